@@ -1,16 +1,20 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use log;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::utils::jsonl::{JsonlReader, MAX_JSONL_LINE_BYTES};
 
 use super::{
     storage::{self, CheckpointStorage},
     Checkpoint, CheckpointMetadata, CheckpointPaths, CheckpointResult, CheckpointStrategy,
-    FileSnapshot, FileState, FileTracker, SessionTimeline,
+    FileSnapshot, FileState, FileTracker, ManagerStats, RestorePreview, SessionTimeline,
 };
 
 /// Manages checkpoint operations for a session
@@ -22,6 +26,20 @@ pub struct CheckpointManager {
     pub storage: Arc<CheckpointStorage>,
     timeline: Arc<RwLock<SessionTimeline>>,
     current_messages: Arc<RwLock<Vec<String>>>, // JSONL messages
+    /// How far the session JSONL has been synced into `current_messages`
+    message_sync: Mutex<MessageSync>,
+    /// Tool uses that triggered an auto-checkpoint since the last checkpoint
+    pending_tool_uses: AtomicU32,
+}
+
+/// Incremental sync state of the session JSONL
+#[derive(Default)]
+struct MessageSync {
+    /// Byte offset just past the last complete line synced; 0 means the next sync re-reads
+    /// the whole file and replaces the tracked messages
+    offset: u64,
+    /// Keys of every tracked message, so tool uses are never tracked twice
+    keys: HashSet<String>,
 }
 
 impl CheckpointManager {
@@ -57,16 +75,75 @@ impl CheckpointManager {
             storage,
             timeline: Arc::new(RwLock::new(timeline)),
             current_messages: Arc::new(RwLock::new(Vec::new())),
+            message_sync: Mutex::new(MessageSync::default()),
+            pending_tool_uses: AtomicU32::new(0),
         })
     }
 
     /// Track a new message in the session
     pub async fn track_message(&self, jsonl_message: String) -> Result<()> {
-        let mut messages = self.current_messages.write().await;
-        messages.push(jsonl_message.clone());
+        self.message_sync
+            .lock()
+            .await
+            .keys
+            .insert(message_key(&jsonl_message));
+        self.current_messages
+            .write()
+            .await
+            .push(jsonl_message.clone());
+        self.track_message_tools(&jsonl_message).await
+    }
+
+    /// Sync the tracked messages with the session JSONL on disk. Only lines appended since the
+    /// last sync are read; the first sync (or one after the file shrank) replaces the tracked
+    /// messages with the whole file. Tool uses are only tracked for messages not seen before,
+    /// so re-syncing never counts them twice. Returns the number of newly tracked messages.
+    pub async fn sync_messages_from_file(&self, session_file: &Path) -> Result<usize> {
+        let mut sync = self.message_sync.lock().await;
+        let file_len = fs::metadata(session_file)
+            .context("Failed to read session file")?
+            .len();
+        if file_len < sync.offset {
+            // Rewritten, e.g. by a restore
+            sync.offset = 0;
+        }
+        let replace = sync.offset == 0;
+
+        let mut file = fs::File::open(session_file).context("Failed to read session file")?;
+        file.seek(SeekFrom::Start(sync.offset))?;
+        let mut reader =
+            JsonlReader::new(BufReader::new(file), MAX_JSONL_LINE_BYTES).complete_lines_only();
+        let mut lines = Vec::new();
+        let mut new_lines = Vec::new();
+        while let Some((_, line)) = reader.next_line()? {
+            if sync.keys.insert(message_key(&line)) {
+                new_lines.push(line.clone());
+            }
+            if replace {
+                lines.push(line);
+            }
+        }
+        sync.offset += reader.complete_bytes();
 
+        {
+            let mut messages = self.current_messages.write().await;
+            if replace {
+                *messages = lines;
+            } else {
+                messages.extend(new_lines.iter().cloned());
+            }
+        }
+
+        for line in &new_lines {
+            self.track_message_tools(line).await?;
+        }
+        Ok(new_lines.len())
+    }
+
+    /// Track file operations from the tool uses of a message
+    async fn track_message_tools(&self, jsonl_message: &str) -> Result<()> {
         // Parse message to check for tool usage
-        if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&jsonl_message) {
+        if let Ok(msg) = serde_json::from_str::<serde_json::Value>(jsonl_message) {
             if let Some(content) = msg.get("message").and_then(|m| m.get("content")) {
                 if let Some(content_array) = content.as_array() {
                     for item in content_array {
@@ -190,6 +267,7 @@ impl CheckpointManager {
         description: Option<String>,
         parent_checkpoint_id: Option<String>,
     ) -> Result<CheckpointResult> {
+        self.pending_tool_uses.store(0, Ordering::Relaxed);
         let messages = self.current_messages.read().await;
         let message_index = messages.len().saturating_sub(1);
 
@@ -456,35 +534,8 @@ impl CheckpointManager {
                 .load_checkpoint(&self.project_id, &self.session_id, checkpoint_id)?;
 
         // First, collect all files currently in the project to handle deletions
-        fn collect_all_project_files(
-            dir: &std::path::Path,
-            base: &std::path::Path,
-            files: &mut Vec<std::path::PathBuf>,
-        ) -> Result<(), std::io::Error> {
-            for entry in std::fs::read_dir(dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_dir() {
-                    // Skip hidden directories like .git
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if name.starts_with('.') {
-                            continue;
-                        }
-                    }
-                    collect_all_project_files(&path, base, files)?;
-                } else if path.is_file() {
-                    // Compute relative path from project root
-                    if let Ok(rel) = path.strip_prefix(base) {
-                        files.push(rel.to_path_buf());
-                    }
-                }
-            }
-            Ok(())
-        }
-
         let mut current_files = Vec::new();
-        let _ =
-            collect_all_project_files(&self.project_path, &self.project_path, &mut current_files);
+        let _ = collect_project_files(&self.project_path, &self.project_path, &mut current_files);
 
         // Create a set of files that should exist after restore
         let mut checkpoint_files = std::collections::HashSet::new();
@@ -563,7 +614,8 @@ impl CheckpointManager {
             }
         }
 
-        // Update current messages
+        // Update current messages; the next file sync re-reads the rewritten session file
+        self.message_sync.lock().await.offset = 0;
         let mut current_messages = self.current_messages.write().await;
         current_messages.clear();
         for line in messages.lines() {
@@ -598,6 +650,131 @@ impl CheckpointManager {
         })
     }
 
+    /// Work out what restoring a checkpoint would change without touching anything.
+    /// `current_message_count` is the length of the session as it is now.
+    pub async fn preview_restore(
+        &self,
+        checkpoint_id: &str,
+        current_message_count: usize,
+    ) -> Result<RestorePreview> {
+        let (checkpoint, file_snapshots, messages) =
+            self.storage
+                .load_checkpoint(&self.project_id, &self.session_id, checkpoint_id)?;
+
+        let mut overwritten_files = Vec::new();
+        let mut created_files = Vec::new();
+        let mut deleted_files = Vec::new();
+        let mut checkpoint_files = std::collections::HashSet::new();
+
+        for snapshot in &file_snapshots {
+            let full_path = self.project_path.join(&snapshot.file_path);
+            if snapshot.is_deleted {
+                if full_path.is_file() {
+                    deleted_files.push(snapshot.file_path.clone());
+                }
+                continue;
+            }
+            checkpoint_files.insert(snapshot.file_path.clone());
+            if !full_path.is_file() {
+                created_files.push(snapshot.file_path.clone());
+            } else {
+                let content = fs::read_to_string(&full_path).unwrap_or_default();
+                if storage::CheckpointStorage::calculate_file_hash(&content) != snapshot.hash {
+                    overwritten_files.push(snapshot.file_path.clone());
+                }
+            }
+        }
+
+        // Same walk as restore_checkpoint: anything else in the project gets deleted
+        let mut current_files = Vec::new();
+        let _ = collect_project_files(&self.project_path, &self.project_path, &mut current_files);
+        for current_file in current_files {
+            if !checkpoint_files.contains(&current_file) && !deleted_files.contains(&current_file) {
+                deleted_files.push(current_file);
+            }
+        }
+
+        overwritten_files.sort();
+        created_files.sort();
+        deleted_files.sort();
+
+        let restored_message_count = messages.lines().filter(|l| !l.trim().is_empty()).count();
+        Ok(RestorePreview {
+            checkpoint,
+            overwritten_files,
+            created_files,
+            deleted_files,
+            current_message_count,
+            restored_message_count,
+            messages_removed: current_message_count.saturating_sub(restored_message_count),
+        })
+    }
+
+    /// Restore only the given files from a checkpoint, leaving the session messages, the
+    /// timeline and all other files untouched. Paths may be relative to the project root or
+    /// absolute inside it; every one must be in the checkpoint or nothing is restored.
+    pub async fn restore_checkpoint_files(
+        &self,
+        checkpoint_id: &str,
+        files: &[PathBuf],
+    ) -> Result<CheckpointResult> {
+        let (checkpoint, file_snapshots, _) =
+            self.storage
+                .load_checkpoint(&self.project_id, &self.session_id, checkpoint_id)?;
+
+        let mut selected = Vec::with_capacity(files.len());
+        let mut missing = Vec::new();
+        for file in files {
+            let relative = file.strip_prefix(&self.project_path).unwrap_or(file);
+            let relative: PathBuf = relative
+                .components()
+                .filter(|c| !matches!(c, std::path::Component::CurDir))
+                .collect();
+            match file_snapshots.iter().find(|s| s.file_path == relative) {
+                Some(snapshot) => selected.push(snapshot),
+                None => missing.push(file.display().to_string()),
+            }
+        }
+        if !missing.is_empty() {
+            anyhow::bail!("Files not in checkpoint: {}", missing.join(", "));
+        }
+
+        let mut warnings = Vec::new();
+        let mut files_processed = 0;
+        let mut tracker = self.file_tracker.write().await;
+        for snapshot in selected {
+            match self.restore_file_snapshot(snapshot).await {
+                Ok(_) => {
+                    files_processed += 1;
+                    if snapshot.is_deleted {
+                        tracker.tracked_files.remove(&snapshot.file_path);
+                    } else {
+                        tracker.tracked_files.insert(
+                            snapshot.file_path.clone(),
+                            FileState {
+                                last_hash: snapshot.hash.clone(),
+                                is_modified: false,
+                                last_modified: Utc::now(),
+                                exists: true,
+                            },
+                        );
+                    }
+                }
+                Err(e) => warnings.push(format!(
+                    "Failed to restore {}: {}",
+                    snapshot.file_path.display(),
+                    e
+                )),
+            }
+        }
+
+        Ok(CheckpointResult {
+            checkpoint,
+            files_processed,
+            warnings,
+        })
+    }
+
     /// Restore a single file from snapshot
     async fn restore_file_snapshot(&self, snapshot: &FileSnapshot) -> Result<()> {
         let full_path = self.project_path.join(&snapshot.file_path);
@@ -679,15 +856,64 @@ impl CheckpointManager {
             .await
     }
 
-    /// Check if auto-checkpoint should be triggered
+    /// Check if auto-checkpoint should be triggered.
+    ///
+    /// Bursts are coalesced: once the strategy matches, the checkpoint is held off until
+    /// enough tool uses accumulated and the minimum interval since the last checkpoint passed.
     pub async fn should_auto_checkpoint(&self, message: &str) -> bool {
         let timeline = self.timeline.read().await;
 
-        if !timeline.auto_checkpoint_enabled {
+        if !timeline.auto_checkpoint_enabled
+            || !Self::matches_strategy(&timeline.checkpoint_strategy, message)
+        {
             return false;
         }
 
-        match timeline.checkpoint_strategy {
+        let tool_uses = Self::count_tool_uses(message).max(1);
+        let pending = self
+            .pending_tool_uses
+            .fetch_add(tool_uses, Ordering::Relaxed)
+            + tool_uses;
+        if pending < timeline.auto_checkpoint_min_tool_uses {
+            return false;
+        }
+
+        if timeline.auto_checkpoint_min_interval_secs > 0 {
+            if let Some(last) = timeline.latest_checkpoint_time() {
+                let min_interval =
+                    chrono::Duration::seconds(timeline.auto_checkpoint_min_interval_secs as i64);
+                if Utc::now() - last < min_interval {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Number of tool_use blocks in a JSONL message
+    fn count_tool_uses(message: &str) -> u32 {
+        serde_json::from_str::<serde_json::Value>(message)
+            .ok()
+            .and_then(|msg| {
+                msg.get("message")
+                    .and_then(|m| m.get("content"))
+                    .and_then(|c| c.as_array())
+                    .map(|content| {
+                        content
+                            .iter()
+                            .filter(|item| {
+                                item.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+                            })
+                            .count() as u32
+                    })
+            })
+            .unwrap_or(0)
+    }
+
+    /// Whether a message matches the auto-checkpoint strategy
+    fn matches_strategy(strategy: &CheckpointStrategy, message: &str) -> bool {
+        match strategy {
             CheckpointStrategy::Manual => false,
             CheckpointStrategy::PerPrompt => {
                 // Check if message is a user prompt
@@ -745,15 +971,23 @@ impl CheckpointManager {
         }
     }
 
-    /// Update checkpoint settings
+    /// Update checkpoint settings; `None` keeps the current debounce limit
     pub async fn update_settings(
         &self,
         auto_checkpoint_enabled: bool,
         checkpoint_strategy: CheckpointStrategy,
+        min_interval_secs: Option<u64>,
+        min_tool_uses: Option<u32>,
     ) -> Result<()> {
         let mut timeline = self.timeline.write().await;
         timeline.auto_checkpoint_enabled = auto_checkpoint_enabled;
         timeline.checkpoint_strategy = checkpoint_strategy;
+        if let Some(secs) = min_interval_secs {
+            timeline.auto_checkpoint_min_interval_secs = secs;
+        }
+        if let Some(count) = min_tool_uses {
+            timeline.auto_checkpoint_min_tool_uses = count;
+        }
 
         // Save updated timeline
         let claude_dir = self.storage.claude_dir.clone();
@@ -764,6 +998,30 @@ impl CheckpointManager {
         Ok(())
     }
 
+    /// Report how much this manager holds in memory
+    pub async fn memory_stats(&self, idle_secs: u64) -> ManagerStats {
+        let messages = self.current_messages.read().await;
+        let tracker = self.file_tracker.read().await;
+
+        let message_bytes: usize = messages.iter().map(|m| m.len()).sum();
+        let file_bytes: usize = tracker
+            .tracked_files
+            .iter()
+            .map(|(path, state)| {
+                path.as_os_str().len() + state.last_hash.len() + std::mem::size_of::<FileState>()
+            })
+            .sum();
+
+        ManagerStats {
+            session_id: self.session_id.clone(),
+            project_id: self.project_id.clone(),
+            tracked_messages: messages.len(),
+            tracked_files: tracker.tracked_files.len(),
+            estimated_bytes: message_bytes + file_bytes,
+            idle_secs,
+        }
+    }
+
     /// Get files modified since a given timestamp
     pub async fn get_files_modified_since(&self, since: DateTime<Utc>) -> Vec<PathBuf> {
         let tracker = self.file_tracker.read().await;
@@ -785,3 +1043,234 @@ impl CheckpointManager {
             .max()
     }
 }
+
+/// Identity of a JSONL message: its `uuid` when present, otherwise the raw line
+fn message_key(jsonl_message: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(jsonl_message)
+        .ok()
+        .and_then(|msg| msg.get("uuid").and_then(|u| u.as_str()).map(str::to_string))
+        .unwrap_or_else(|| jsonl_message.trim().to_string())
+}
+
+/// Collect every file under `dir` relative to `base`, skipping hidden directories like .git
+fn collect_project_files(
+    dir: &std::path::Path,
+    base: &std::path::Path,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), std::io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if name.starts_with('.') {
+                    continue;
+                }
+            }
+            collect_project_files(&path, base, files)?;
+        } else if path.is_file() {
+            if let Ok(rel) = path.strip_prefix(base) {
+                files.push(rel.to_path_buf());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_auto_checkpoint_debounce() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("project");
+        fs::create_dir_all(&project_path).unwrap();
+        let manager = CheckpointManager::new(
+            "test-project".to_string(),
+            "test-session".to_string(),
+            project_path,
+            temp_dir.path().to_path_buf(),
+        )
+        .await
+        .unwrap();
+        manager
+            .update_settings(true, CheckpointStrategy::PerToolUse, Some(0), Some(3))
+            .await
+            .unwrap();
+
+        let tool_use = r#"{"message":{"content":[{"type":"tool_use","name":"Edit","input":{}}]}}"#;
+        assert!(!manager.should_auto_checkpoint(tool_use).await);
+        assert!(!manager.should_auto_checkpoint(tool_use).await);
+        assert!(manager.should_auto_checkpoint(tool_use).await);
+
+        // Right after a checkpoint the interval holds off even a full burst
+        manager.create_checkpoint(None, None).await.unwrap();
+        manager
+            .update_settings(true, CheckpointStrategy::PerToolUse, Some(3600), Some(1))
+            .await
+            .unwrap();
+        assert!(!manager.should_auto_checkpoint(tool_use).await);
+    }
+
+    #[tokio::test]
+    async fn test_restore_checkpoint_files_only_touches_selected() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("project");
+        fs::create_dir_all(&project_path).unwrap();
+        fs::write(project_path.join("a.txt"), "a1").unwrap();
+        fs::write(project_path.join("b.txt"), "b1").unwrap();
+        let manager = CheckpointManager::new(
+            "test-project".to_string(),
+            "test-session".to_string(),
+            project_path.clone(),
+            temp_dir.path().to_path_buf(),
+        )
+        .await
+        .unwrap();
+        let checkpoint_id = manager
+            .create_checkpoint(None, None)
+            .await
+            .unwrap()
+            .checkpoint
+            .id;
+
+        fs::write(project_path.join("a.txt"), "a2").unwrap();
+        fs::write(project_path.join("b.txt"), "b2").unwrap();
+
+        let missing = manager
+            .restore_checkpoint_files(&checkpoint_id, &[PathBuf::from("c.txt")])
+            .await;
+        assert!(missing.is_err());
+
+        let result = manager
+            .restore_checkpoint_files(&checkpoint_id, &[project_path.join("a.txt")])
+            .await
+            .unwrap();
+        assert_eq!(result.files_processed, 1);
+        assert_eq!(
+            fs::read_to_string(project_path.join("a.txt")).unwrap(),
+            "a1"
+        );
+        assert_eq!(
+            fs::read_to_string(project_path.join("b.txt")).unwrap(),
+            "b2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preview_restore_changes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("project");
+        fs::create_dir_all(&project_path).unwrap();
+        fs::write(project_path.join("kept.txt"), "same").unwrap();
+        fs::write(project_path.join("edited.txt"), "v1").unwrap();
+        fs::write(project_path.join("removed.txt"), "gone").unwrap();
+        let manager = CheckpointManager::new(
+            "test-project".to_string(),
+            "test-session".to_string(),
+            project_path.clone(),
+            temp_dir.path().to_path_buf(),
+        )
+        .await
+        .unwrap();
+        manager
+            .track_message(r#"{"type":"user","message":{"content":"hi"}}"#.to_string())
+            .await
+            .unwrap();
+        let checkpoint_id = manager
+            .create_checkpoint(None, None)
+            .await
+            .unwrap()
+            .checkpoint
+            .id;
+
+        fs::write(project_path.join("edited.txt"), "v2").unwrap();
+        fs::remove_file(project_path.join("removed.txt")).unwrap();
+        fs::write(project_path.join("new.txt"), "new").unwrap();
+
+        let preview = manager.preview_restore(&checkpoint_id, 4).await.unwrap();
+        assert_eq!(preview.overwritten_files, vec![PathBuf::from("edited.txt")]);
+        assert_eq!(preview.created_files, vec![PathBuf::from("removed.txt")]);
+        assert_eq!(preview.deleted_files, vec![PathBuf::from("new.txt")]);
+        assert_eq!(preview.restored_message_count, 1);
+        assert_eq!(preview.messages_removed, 3);
+        assert_eq!(
+            fs::read_to_string(project_path.join("edited.txt")).unwrap(),
+            "v2"
+        );
+        assert!(project_path.join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_sync_messages_from_file_skips_tracked() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("project");
+        fs::create_dir_all(&project_path).unwrap();
+        let manager = CheckpointManager::new(
+            "test-project".to_string(),
+            "test-session".to_string(),
+            project_path,
+            temp_dir.path().to_path_buf(),
+        )
+        .await
+        .unwrap();
+
+        // Pushed by the frontend with different formatting than on disk
+        manager
+            .track_message(r#"{"uuid": "a", "type": "user"}"#.to_string())
+            .await
+            .unwrap();
+
+        let session_file = temp_dir.path().join("test-session.jsonl");
+        fs::write(
+            &session_file,
+            "{\"uuid\":\"a\",\"type\":\"user\"}\n{\"uuid\":\"b\",\"type\":\"assistant\"}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manager
+                .sync_messages_from_file(&session_file)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            manager
+                .sync_messages_from_file(&session_file)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(manager.current_messages.read().await.len(), 2);
+
+        // Only the appended complete line is read; the partial one waits for the next sync
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&session_file)
+            .unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            b"{\"uuid\":\"c\",\"type\":\"user\"}\n{\"uuid\":\"d\",",
+        )
+        .unwrap();
+        assert_eq!(
+            manager
+                .sync_messages_from_file(&session_file)
+                .await
+                .unwrap(),
+            1
+        );
+        std::io::Write::write_all(&mut file, b"\"type\":\"assistant\"}\n").unwrap();
+        assert_eq!(
+            manager
+                .sync_messages_from_file(&session_file)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(manager.current_messages.read().await.len(), 4);
+    }
+}
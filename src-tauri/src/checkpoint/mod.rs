@@ -93,6 +93,12 @@ pub struct SessionTimeline {
     pub checkpoint_strategy: CheckpointStrategy,
     /// Total number of checkpoints in timeline
     pub total_checkpoints: usize,
+    /// Minimum seconds between automatic checkpoints (0 disables the limit)
+    #[serde(default)]
+    pub auto_checkpoint_min_interval_secs: u64,
+    /// Tool uses to accumulate before an automatic checkpoint (0 disables the limit)
+    #[serde(default)]
+    pub auto_checkpoint_min_tool_uses: u32,
 }
 
 /// Strategy for automatic checkpoint creation
@@ -140,6 +146,41 @@ pub struct CheckpointResult {
     pub warnings: Vec<String>,
 }
 
+/// What restoring a checkpoint would change, computed without modifying anything
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestorePreview {
+    /// The checkpoint that would be restored
+    pub checkpoint: Checkpoint,
+    /// Files whose current content differs from the checkpoint
+    pub overwritten_files: Vec<PathBuf>,
+    /// Files in the checkpoint that no longer exist
+    pub created_files: Vec<PathBuf>,
+    /// Files that exist now but not in the checkpoint
+    pub deleted_files: Vec<PathBuf>,
+    /// Messages in the session now
+    pub current_message_count: usize,
+    /// Messages the session would revert to
+    pub restored_message_count: usize,
+    /// Messages that would be dropped from the session
+    pub messages_removed: usize,
+}
+
+/// In-memory footprint of an active checkpoint manager
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManagerStats {
+    pub session_id: String,
+    pub project_id: String,
+    /// Session messages held for the next checkpoint
+    pub tracked_messages: usize,
+    /// Files whose state is tracked; snapshot contents are only held while checkpointing
+    pub tracked_files: usize,
+    /// Rough size of the tracked messages and file states
+    pub estimated_bytes: usize,
+    /// Seconds since the session's manager was last accessed
+    pub idle_secs: u64,
+}
+
 /// Diff between two checkpoints
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CheckpointDiff {
@@ -186,7 +227,20 @@ impl SessionTimeline {
             auto_checkpoint_enabled: false,
             checkpoint_strategy: CheckpointStrategy::default(),
             total_checkpoints: 0,
+            auto_checkpoint_min_interval_secs: 0,
+            auto_checkpoint_min_tool_uses: 0,
+        }
+    }
+
+    /// Timestamp of the most recent checkpoint in the tree
+    pub fn latest_checkpoint_time(&self) -> Option<DateTime<Utc>> {
+        fn latest(node: &TimelineNode) -> DateTime<Utc> {
+            node.children
+                .iter()
+                .map(latest)
+                .fold(node.checkpoint.timestamp, DateTime::max)
         }
+        self.root_node.as_ref().map(latest)
     }
 
     /// Find a checkpoint by ID in the timeline tree
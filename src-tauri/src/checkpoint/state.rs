@@ -2,9 +2,17 @@ use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 use super::manager::CheckpointManager;
+use super::ManagerStats;
+
+/// A manager together with when its session was last accessed
+struct ManagerEntry {
+    manager: Arc<CheckpointManager>,
+    last_used: Instant,
+}
 
 /// Manages checkpoint managers for active sessions
 ///
@@ -15,7 +23,7 @@ use super::manager::CheckpointManager;
 pub struct CheckpointState {
     /// Map of session_id to CheckpointManager
     /// Uses Arc<CheckpointManager> to allow sharing across async boundaries
-    managers: Arc<RwLock<HashMap<String, Arc<CheckpointManager>>>>,
+    managers: Arc<RwLock<HashMap<String, ManagerEntry>>>,
     /// The Claude directory path for consistent access
     claude_dir: Arc<RwLock<Option<PathBuf>>>,
 }
@@ -58,8 +66,9 @@ impl CheckpointState {
         let mut managers = self.managers.write().await;
 
         // Check if manager already exists
-        if let Some(manager) = managers.get(&session_id) {
-            return Ok(Arc::clone(manager));
+        if let Some(entry) = managers.get_mut(&session_id) {
+            entry.last_used = Instant::now();
+            return Ok(Arc::clone(&entry.manager));
         }
 
         // Get Claude directory
@@ -76,7 +85,13 @@ impl CheckpointState {
                 .await?;
 
         let manager_arc = Arc::new(manager);
-        managers.insert(session_id, Arc::clone(&manager_arc));
+        managers.insert(
+            session_id,
+            ManagerEntry {
+                manager: Arc::clone(&manager_arc),
+                last_used: Instant::now(),
+            },
+        );
 
         Ok(manager_arc)
     }
@@ -84,10 +99,12 @@ impl CheckpointState {
     /// Gets an existing CheckpointManager for a session
     ///
     /// Returns None if no manager exists for the session
-    #[allow(dead_code)]
     pub async fn get_manager(&self, session_id: &str) -> Option<Arc<CheckpointManager>> {
-        let managers = self.managers.read().await;
-        managers.get(session_id).map(Arc::clone)
+        let mut managers = self.managers.write().await;
+        managers.get_mut(session_id).map(|entry| {
+            entry.last_used = Instant::now();
+            Arc::clone(&entry.manager)
+        })
     }
 
     /// Removes a CheckpointManager for a session
@@ -95,7 +112,7 @@ impl CheckpointState {
     /// This should be called when a session ends to free resources
     pub async fn remove_manager(&self, session_id: &str) -> Option<Arc<CheckpointManager>> {
         let mut managers = self.managers.write().await;
-        managers.remove(session_id)
+        managers.remove(session_id).map(|entry| entry.manager)
     }
 
     /// Clears all managers
@@ -119,6 +136,45 @@ impl CheckpointState {
         managers.keys().cloned().collect()
     }
 
+    /// Reports the in-memory footprint of every active manager
+    pub async fn manager_stats(&self) -> Vec<ManagerStats> {
+        // Snapshot the entries first so the map is not locked while managers are inspected
+        let entries: Vec<(Arc<CheckpointManager>, Instant)> = {
+            let managers = self.managers.read().await;
+            managers
+                .values()
+                .map(|entry| (Arc::clone(&entry.manager), entry.last_used))
+                .collect()
+        };
+
+        let mut stats = Vec::with_capacity(entries.len());
+        for (manager, last_used) in entries {
+            stats.push(manager.memory_stats(last_used.elapsed().as_secs()).await);
+        }
+        stats.sort_by_key(|s| std::cmp::Reverse(s.estimated_bytes));
+        stats
+    }
+
+    /// Drops managers not accessed for `idle` that no caller still holds, and returns
+    /// their session IDs. Sessions in `keep` are never evicted. Evicted managers are
+    /// recreated on demand.
+    pub async fn evict_idle(&self, idle: Duration, keep: &[String]) -> Vec<String> {
+        let mut managers = self.managers.write().await;
+        let evicted: Vec<String> = managers
+            .iter()
+            .filter(|(session_id, entry)| {
+                entry.last_used.elapsed() >= idle
+                    && Arc::strong_count(&entry.manager) == 1
+                    && !keep.contains(session_id)
+            })
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+        for session_id in &evicted {
+            managers.remove(session_id);
+        }
+        evicted
+    }
+
     /// Checks if a session has an active manager
     #[allow(dead_code)]
     pub async fn has_active_manager(&self, session_id: &str) -> bool {
@@ -181,4 +237,40 @@ mod tests {
 
         assert!(!Arc::ptr_eq(&manager1, &manager3));
     }
+
+    #[tokio::test]
+    async fn test_evict_idle_managers() {
+        let state = CheckpointState::new();
+        let temp_dir = TempDir::new().unwrap();
+        state.set_claude_dir(temp_dir.path().to_path_buf()).await;
+        let project_path = temp_dir.path().join("project");
+        std::fs::create_dir_all(&project_path).unwrap();
+
+        for session_id in ["idle", "held", "running"] {
+            state
+                .get_or_create_manager(
+                    session_id.to_string(),
+                    "test-project".to_string(),
+                    project_path.clone(),
+                )
+                .await
+                .unwrap();
+        }
+        let held = state.get_manager("held").await.unwrap();
+
+        let stats = state.manager_stats().await;
+        assert_eq!(stats.len(), 3);
+        assert!(stats.iter().all(|s| s.tracked_messages == 0));
+
+        // Nothing has been idle for an hour
+        let evicted = state.evict_idle(Duration::from_secs(3600), &[]).await;
+        assert!(evicted.is_empty());
+
+        let evicted = state
+            .evict_idle(Duration::ZERO, &["running".to_string()])
+            .await;
+        assert_eq!(evicted, vec!["idle".to_string()]);
+        assert_eq!(state.active_count().await, 2);
+        drop(held);
+    }
 }
@@ -377,6 +377,45 @@ impl CheckpointStorage {
         Ok(removed_count)
     }
 
+    /// Sessions of a project that have checkpoint storage
+    pub fn list_checkpointed_sessions(&self, project_id: &str) -> Result<Vec<String>> {
+        let timelines_dir = self
+            .claude_dir
+            .join("projects")
+            .join(project_id)
+            .join(".timelines");
+        if !timelines_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(&timelines_dir).context("Failed to read timelines directory")? {
+            let path = entry?.path();
+            if path.join("timeline.json").is_file() {
+                if let Some(session_id) = path.file_name().and_then(|n| n.to_str()) {
+                    sessions.push(session_id.to_string());
+                }
+            }
+        }
+        sessions.sort();
+        Ok(sessions)
+    }
+
+    /// Total size in bytes of a session's checkpoint storage
+    pub fn session_storage_size(&self, project_id: &str, session_id: &str) -> u64 {
+        let paths = CheckpointPaths::new(&self.claude_dir, project_id, session_id);
+        let Some(session_dir) = paths.timeline_file.parent() else {
+            return 0;
+        };
+        walkdir::WalkDir::new(session_dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
     /// Collect all checkpoints from the tree in order
     fn collect_checkpoints(node: &TimelineNode, checkpoints: &mut Vec<Checkpoint>) {
         checkpoints.push(node.checkpoint.clone());
@@ -1,13 +1,28 @@
 use anyhow::Result;
 use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 /// Shared module for detecting Claude Code binary installations
 /// Supports NVM installations, aliased paths, and version-based selection
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::Mutex;
 use tauri::Manager;
 
+/// Result of the last successful binary resolution
+#[derive(Debug, Clone)]
+struct CachedClaudeBinary {
+    /// The stored `claude_binary_path` setting this result was resolved against
+    stored_path: Option<String>,
+    /// The resolved binary path
+    path: String,
+}
+
+/// In-memory cache so discovery doesn't run on every execution
+static CLAUDE_BINARY_CACHE: Lazy<Mutex<Option<CachedClaudeBinary>>> =
+    Lazy::new(|| Mutex::new(None));
+
 /// Type of Claude installation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum InstallationType {
@@ -31,8 +46,73 @@ pub struct ClaudeInstallation {
 }
 
 /// Main function to find the Claude binary
-/// Checks database first for stored path and preference, then prioritizes accordingly
+/// Returns the cached path when the stored override hasn't changed since the last resolution,
+/// otherwise runs full discovery and caches the result
 pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let stored_path = get_stored_claude_path(app_handle);
+
+    if let Ok(cache) = CLAUDE_BINARY_CACHE.lock() {
+        if let Some(cached) = cache.as_ref() {
+            if cached.stored_path == stored_path {
+                debug!("Using cached claude binary: {}", cached.path);
+                return Ok(cached.path.clone());
+            }
+        }
+    }
+
+    let path = resolve_claude_binary(app_handle)?;
+
+    if let Ok(mut cache) = CLAUDE_BINARY_CACHE.lock() {
+        *cache = Some(CachedClaudeBinary {
+            stored_path,
+            path: path.clone(),
+        });
+    }
+
+    Ok(path)
+}
+
+/// Clears the cached binary path so the next lookup re-runs discovery
+pub fn invalidate_claude_binary_cache() {
+    if let Ok(mut cache) = CLAUDE_BINARY_CACHE.lock() {
+        if cache.take().is_some() {
+            info!("Invalidated cached claude binary path");
+        }
+    }
+}
+
+/// Invalidates the cache when spawning the cached binary failed because it no longer exists
+pub fn invalidate_on_spawn_error(error: &std::io::Error) {
+    if error.kind() == std::io::ErrorKind::NotFound {
+        warn!("Claude binary not found at spawn time, clearing cached path");
+        invalidate_claude_binary_cache();
+    }
+}
+
+/// Drops the cached path and re-runs discovery (e.g. after installing or updating Claude)
+pub fn refresh_claude_binary_cache(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    invalidate_claude_binary_cache();
+    find_claude_binary(app_handle)
+}
+
+/// Reads the user-configured binary path from the database, if any
+fn get_stored_claude_path(app_handle: &tauri::AppHandle) -> Option<String> {
+    let db_path = app_handle.path().app_data_dir().ok()?.join("agents.db");
+    if !db_path.exists() {
+        return None;
+    }
+
+    let conn = rusqlite::Connection::open(&db_path).ok()?;
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = 'claude_binary_path'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+}
+
+/// Runs the full discovery: stored path and preference first, then system installations
+fn resolve_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, String> {
     info!("Searching for claude binary...");
 
     // First check if we have a stored path and preference in the database
@@ -54,6 +134,8 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
                     #[cfg(not(target_os = "windows"))]
                     let path_buf = PathBuf::from(&stored_path);
 
+                    #[cfg(target_os = "windows")]
+                    let stored_path = translate_msys_path(&stored_path);
                     #[cfg(target_os = "windows")]
                     let mut final_path = stored_path.clone();
                     #[cfg(target_os = "windows")]
@@ -64,6 +146,7 @@ pub fn find_claude_binary(app_handle: &tauri::AppHandle) -> Result<String, Strin
                     if path_buf.exists()
                         && !stored_path.ends_with(".cmd")
                         && !stored_path.ends_with(".exe")
+                        && !is_powershell_shim(&stored_path)
                     {
                         // Test if the current path works by trying to get version
                         if let Err(_) = get_claude_version(&stored_path) {
@@ -245,37 +328,25 @@ fn find_which_installations() -> Vec<ClaudeInstallation> {
                     }
                 }
 
-                // Convert Unix-style path to Windows path if needed
+                // Convert MSYS/Git Bash style paths (/c/Users/...) to native Windows paths
                 #[cfg(target_os = "windows")]
-                let path = {
-                    if path.starts_with("/c/") {
-                        // Convert /c/path to C:\path
-                        let windows_path = path.replace("/c/", "C:\\").replace("/", "\\");
-                        windows_path
-                    } else if path.starts_with("/")
-                        && path.len() > 3
-                        && path.chars().nth(2) == Some('/')
-                    {
-                        // Convert /X/path to X:\path where X is drive letter
-                        let drive = path.chars().nth(1).unwrap();
-                        let rest = &path[3..];
-                        format!("{}:\\{}", drive.to_uppercase(), rest.replace("/", "\\"))
-                    } else {
-                        path
-                    }
-                };
+                let path = translate_msys_path(&path);
 
                 #[cfg(not(target_os = "windows"))]
                 let path = path;
 
                 debug!("'{}' found claude at: {}", command_name, path);
 
-                // On Windows, prefer .cmd files over shell scripts
+                // On Windows, prefer .cmd files over shell scripts, then fall back to .ps1 shims
                 #[cfg(target_os = "windows")]
                 let final_path = {
-                    if !path.ends_with(".cmd") && !path.ends_with(".exe") {
-                        // Check if there's a .cmd file alongside
+                    if !path.ends_with(".cmd")
+                        && !path.ends_with(".exe")
+                        && !is_powershell_shim(&path)
+                    {
+                        // Check if there's a .cmd or .ps1 file alongside
                         let cmd_path = format!("{}.cmd", path);
+                        let ps1_path = format!("{}.ps1", path);
                         if PathBuf::from(&cmd_path).exists() {
                             // Only use .cmd if the original doesn't work
                             if let Err(_) = get_claude_version(&path) {
@@ -283,6 +354,11 @@ fn find_which_installations() -> Vec<ClaudeInstallation> {
                             } else {
                                 path
                             }
+                        } else if !PathBuf::from(&path).exists()
+                            && PathBuf::from(&ps1_path).exists()
+                        {
+                            debug!("Using PowerShell shim for claude: {}", ps1_path);
+                            ps1_path
                         } else {
                             path
                         }
@@ -584,10 +660,57 @@ fn compare_versions(a: &str, b: &str) -> Ordering {
     Ordering::Equal
 }
 
+/// Translates an MSYS/Git Bash style path (`/c/Users/...` or `/cygdrive/c/...`) into a
+/// native Windows path (`C:\Users\...`). Other paths are returned unchanged.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn translate_msys_path(path: &str) -> String {
+    let trimmed = path.strip_prefix("/cygdrive").unwrap_or(path);
+    let bytes = trimmed.as_bytes();
+
+    let is_drive_path = bytes.len() >= 2
+        && bytes[0] == b'/'
+        && bytes[1].is_ascii_alphabetic()
+        && (bytes.len() == 2 || bytes[2] == b'/');
+
+    if !is_drive_path {
+        return path.to_string();
+    }
+
+    let drive = (bytes[1] as char).to_ascii_uppercase();
+    let rest = trimmed.get(3..).unwrap_or("");
+    format!("{}:\\{}", drive, rest.replace('/', "\\"))
+}
+
+/// Whether the path is a PowerShell shim that can't be executed directly
+fn is_powershell_shim(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".ps1")
+}
+
+/// Resolves the program to spawn and any leading arguments it needs
+/// PowerShell shims (`claude.ps1`) are run through `powershell -File`
+pub fn shim_program_and_args(program: &str) -> (String, Vec<String>) {
+    if is_powershell_shim(program) {
+        (
+            "powershell".to_string(),
+            vec![
+                "-NoProfile".to_string(),
+                "-ExecutionPolicy".to_string(),
+                "Bypass".to_string(),
+                "-File".to_string(),
+                program.to_string(),
+            ],
+        )
+    } else {
+        (program.to_string(), Vec::new())
+    }
+}
+
 /// Helper function to create a Command with proper environment variables
 /// This ensures commands like Claude can find Node.js and other dependencies
 pub fn create_command_with_env(program: &str) -> Command {
-    let mut cmd = Command::new(program);
+    let (executable, leading_args) = shim_program_and_args(program);
+    let mut cmd = Command::new(executable);
+    cmd.args(leading_args);
 
     info!("Creating command for: {}", program);
 
@@ -621,6 +744,14 @@ pub fn create_command_with_env(program: &str) -> Command {
         }
     }
 
+    // Point Claude at a relocated config directory (app setting or CLAUDE_CONFIG_DIR)
+    if let Ok((claude_dir, source)) = crate::commands::claude::resolve_claude_dir() {
+        if source != crate::commands::claude::ClaudeDirSource::Default {
+            debug!("Using Claude config dir: {:?}", claude_dir);
+            cmd.env("CLAUDE_CONFIG_DIR", &claude_dir);
+        }
+    }
+
     // Log proxy-related environment variables for debugging
     info!("Command will use proxy settings:");
     if let Ok(http_proxy) = std::env::var("HTTP_PROXY") {
@@ -655,7 +786,7 @@ pub fn create_command_with_env(program: &str) -> Command {
 
 /// Build an enhanced PATH that includes all possible Claude installation locations
 /// This is especially important for DMG/packaged applications where PATH may be limited
-fn build_enhanced_path() -> String {
+pub(crate) fn build_enhanced_path() -> String {
     let mut paths = Vec::new();
 
     // Start with current PATH
@@ -722,3 +853,51 @@ fn build_enhanced_path() -> String {
 
     unique_paths.join(":")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_msys_path() {
+        assert_eq!(
+            translate_msys_path("/c/Users/dev/AppData/Roaming/npm/claude"),
+            "C:\\Users\\dev\\AppData\\Roaming\\npm\\claude"
+        );
+        assert_eq!(
+            translate_msys_path("/d/tools/claude.cmd"),
+            "D:\\tools\\claude.cmd"
+        );
+        assert_eq!(
+            translate_msys_path("/cygdrive/e/bin/claude"),
+            "E:\\bin\\claude"
+        );
+        assert_eq!(translate_msys_path("/c"), "C:\\");
+    }
+
+    #[test]
+    fn test_translate_msys_path_leaves_other_paths() {
+        assert_eq!(
+            translate_msys_path("C:\\Program Files\\claude.exe"),
+            "C:\\Program Files\\claude.exe"
+        );
+        assert_eq!(
+            translate_msys_path("/usr/local/bin/claude"),
+            "/usr/local/bin/claude"
+        );
+        assert_eq!(translate_msys_path("claude"), "claude");
+        assert_eq!(translate_msys_path(""), "");
+    }
+
+    #[test]
+    fn test_shim_program_and_args() {
+        let (program, args) = shim_program_and_args("C:\\npm\\claude.PS1");
+        assert_eq!(program, "powershell");
+        assert_eq!(args.last().map(String::as_str), Some("C:\\npm\\claude.PS1"));
+        assert!(args.contains(&"-File".to_string()));
+
+        let (program, args) = shim_program_and_args("/usr/local/bin/claude");
+        assert_eq!(program, "/usr/local/bin/claude");
+        assert!(args.is_empty());
+    }
+}
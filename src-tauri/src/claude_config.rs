@@ -1,5 +1,4 @@
 use crate::commands::relay_stations::RelayStation;
-use dirs::home_dir;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -75,16 +74,178 @@ pub struct ClaudePermissions {
     pub deny: Vec<String>,
 }
 
+/// 获取 Claude 配置目录（默认 ~/.claude，可通过设置或 CLAUDE_CONFIG_DIR 重定位）
+fn claude_dir() -> Result<PathBuf, String> {
+    crate::commands::claude::get_claude_dir()
+        .map_err(|e| format!("无法获取 Claude 配置目录: {}", e))
+}
+
 /// 获取 Claude 配置文件路径
 pub fn get_claude_config_path() -> Result<PathBuf, String> {
-    let home = home_dir().ok_or_else(|| "无法获取主目录".to_string())?;
-    Ok(home.join(".claude").join("settings.json"))
+    Ok(claude_dir()?.join("settings.json"))
 }
 
 /// 获取配置备份文件路径
 pub fn get_config_backup_path() -> Result<PathBuf, String> {
-    let home = home_dir().ok_or_else(|| "无法获取主目录".to_string())?;
-    Ok(home.join(".claude").join("settings.backup.json"))
+    Ok(claude_dir()?.join("settings.backup.json"))
+}
+
+/// 轮转备份保留的最大数量
+pub const MAX_ROTATING_BACKUPS: usize = 10;
+
+/// 源文件备份在备份列表中的标识
+pub const SOURCE_BACKUP_ID: &str = "source";
+
+/// 配置备份摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeConfigBackup {
+    pub timestamp: String, // 备份标识（时间戳，源文件备份为 "source"）
+    pub created_at: i64,   // 创建时间（毫秒）
+    pub size: u64,
+    pub is_source: bool,              // 是否为首次启用中转站前的源文件备份
+    pub api_base_url: Option<String>, // 备份中的 ANTHROPIC_BASE_URL
+    pub model: Option<String>,        // 备份中的 model
+}
+
+/// 获取轮转备份目录
+pub fn get_config_backups_dir() -> Result<PathBuf, String> {
+    Ok(claude_dir()?.join("settings-backups"))
+}
+
+/// 在覆盖配置前创建带时间戳的轮转备份，并删除超出数量的旧备份
+pub fn create_rotating_backup() -> Result<Option<String>, String> {
+    let config_path = get_claude_config_path()?;
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let backups_dir = get_config_backups_dir()?;
+    fs::create_dir_all(&backups_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    let backup_path = backups_dir.join(format!("settings.{}.json", timestamp));
+    fs::copy(&config_path, &backup_path).map_err(|e| format!("创建轮转备份失败: {}", e))?;
+    log::info!("[CLAUDE_CONFIG] Created rotating backup: {:?}", backup_path);
+
+    // 删除超出保留数量的旧备份（时间戳格式保证按名称排序即按时间排序）
+    let mut timestamps = list_rotating_backup_timestamps()?;
+    if timestamps.len() > MAX_ROTATING_BACKUPS {
+        let excess = timestamps.len() - MAX_ROTATING_BACKUPS;
+        for old in timestamps.drain(..excess) {
+            let old_path = backups_dir.join(format!("settings.{}.json", old));
+            if let Err(e) = fs::remove_file(&old_path) {
+                log::warn!(
+                    "[CLAUDE_CONFIG] Failed to remove old backup {:?}: {}",
+                    old_path,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(Some(timestamp))
+}
+
+/// 列出轮转备份的时间戳（从旧到新）
+fn list_rotating_backup_timestamps() -> Result<Vec<String>, String> {
+    let backups_dir = get_config_backups_dir()?;
+    if !backups_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut timestamps: Vec<String> = fs::read_dir(&backups_dir)
+        .map_err(|e| format!("读取备份目录失败: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix("settings.")
+                .and_then(|rest| rest.strip_suffix(".json"))
+                .map(|ts| ts.to_string())
+        })
+        .collect();
+    timestamps.sort();
+    Ok(timestamps)
+}
+
+/// 获取指定备份的文件路径（防止路径穿越）
+fn backup_path_for(timestamp: &str) -> Result<PathBuf, String> {
+    if timestamp == SOURCE_BACKUP_ID {
+        return get_config_backup_path();
+    }
+    if timestamp.is_empty() || !timestamp.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(format!("无效的备份标识: {}", timestamp));
+    }
+    Ok(get_config_backups_dir()?.join(format!("settings.{}.json", timestamp)))
+}
+
+/// 读取备份摘要
+fn summarize_backup(
+    timestamp: &str,
+    path: &PathBuf,
+    is_source: bool,
+) -> Option<ClaudeConfigBackup> {
+    let metadata = fs::metadata(path).ok()?;
+    let created_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    let config: Option<ClaudeConfig> = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    Some(ClaudeConfigBackup {
+        timestamp: timestamp.to_string(),
+        created_at,
+        size: metadata.len(),
+        is_source,
+        api_base_url: config
+            .as_ref()
+            .and_then(|c| c.env.anthropic_base_url.clone()),
+        model: config.and_then(|c| c.model),
+    })
+}
+
+/// 列出所有配置备份（源文件备份在前，其余从新到旧）
+pub fn list_claude_config_backups() -> Result<Vec<ClaudeConfigBackup>, String> {
+    let mut backups = Vec::new();
+
+    let source_path = get_config_backup_path()?;
+    if let Some(backup) = summarize_backup(SOURCE_BACKUP_ID, &source_path, true) {
+        backups.push(backup);
+    }
+
+    let backups_dir = get_config_backups_dir()?;
+    for timestamp in list_rotating_backup_timestamps()?.into_iter().rev() {
+        let path = backups_dir.join(format!("settings.{}.json", timestamp));
+        if let Some(backup) = summarize_backup(&timestamp, &path, false) {
+            backups.push(backup);
+        }
+    }
+
+    Ok(backups)
+}
+
+/// 恢复指定时间戳的配置备份（恢复前会先备份当前配置）
+pub fn restore_claude_config_at(timestamp: &str) -> Result<(), String> {
+    let backup_path = backup_path_for(timestamp)?;
+    if !backup_path.exists() {
+        return Err(format!("备份不存在: {}", timestamp));
+    }
+
+    // 校验备份内容是有效的 JSON，避免写坏配置
+    let content = fs::read_to_string(&backup_path).map_err(|e| format!("读取备份失败: {}", e))?;
+    serde_json::from_str::<Value>(&content).map_err(|e| format!("备份文件格式无效: {}", e))?;
+
+    create_rotating_backup()?;
+
+    let config_path = get_claude_config_path()?;
+    fs::write(&config_path, content).map_err(|e| format!("恢复配置文件失败: {}", e))?;
+    log::info!("[CLAUDE_CONFIG] Restored config from backup {}", timestamp);
+
+    Ok(())
 }
 
 /// 读取 Claude 配置文件
@@ -199,6 +360,11 @@ pub fn apply_relay_station_to_config(station: &RelayStation) -> Result<(), Strin
         }
     }
 
+    // 覆盖前保留当前配置的轮转备份
+    if let Err(e) = create_rotating_backup() {
+        log::warn!("[CLAUDE_CONFIG] Failed to create rotating backup: {}", e);
+    }
+
     // 第二步：恢复源文件备份（确保使用干净的基准配置）
     if backup_path.exists() {
         log::info!("[CLAUDE_CONFIG] Restoring source config from backup");
@@ -258,6 +424,11 @@ pub fn apply_relay_station_to_config(station: &RelayStation) -> Result<(), Strin
 pub fn clear_relay_station_from_config() -> Result<(), String> {
     log::info!("[CLAUDE_CONFIG] Clearing relay station config");
 
+    // 覆盖前保留当前配置的轮转备份
+    if let Err(e) = create_rotating_backup() {
+        log::warn!("[CLAUDE_CONFIG] Failed to create rotating backup: {}", e);
+    }
+
     // 恢复源文件备份
     let backup_path = get_config_backup_path()?;
     let config_path = get_claude_config_path()?;
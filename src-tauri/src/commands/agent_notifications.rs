@@ -0,0 +1,264 @@
+use log::{info, warn};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use super::agents::{get_agent_run, read_session_jsonl, AgentDb, AgentRunMetrics};
+
+/// Timeout of a single webhook delivery attempt
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Delay before the single retry of a failed webhook delivery
+const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// How an agent reports finished runs
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AgentNotificationConfig {
+    pub system_notification: bool,
+    pub webhook_url: Option<String>,
+}
+
+/// Summary of a finished run sent to webhooks
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentRunSummary {
+    pub run_id: i64,
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub task: String,
+    pub project_path: String,
+    pub session_id: String,
+    pub status: String,
+    pub total_tokens: Option<i64>,
+    pub cost_usd: Option<f64>,
+    pub duration_ms: Option<i64>,
+    pub completed_at: Option<String>,
+}
+
+fn validate_webhook_url(url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(format!("Unsupported webhook URL scheme: {}", scheme)),
+    }
+}
+
+fn load_notification_config(
+    db: &AgentDb,
+    agent_id: i64,
+) -> Result<AgentNotificationConfig, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let config = conn
+        .query_row(
+            "SELECT system_notification, webhook_url FROM agent_notifications WHERE agent_id = ?1",
+            params![agent_id],
+            |row| {
+                Ok(AgentNotificationConfig {
+                    system_notification: row.get(0)?,
+                    webhook_url: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(config.unwrap_or_default())
+}
+
+/// Get the notification settings of an agent
+#[tauri::command]
+pub async fn get_agent_notification_config(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+) -> Result<AgentNotificationConfig, String> {
+    load_notification_config(&db, agent_id)
+}
+
+/// Save the notification settings of an agent
+#[tauri::command]
+pub async fn set_agent_notification_config(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    config: AgentNotificationConfig,
+) -> Result<(), String> {
+    let webhook_url = config
+        .webhook_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &webhook_url {
+        validate_webhook_url(url)?;
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO agent_notifications (agent_id, system_notification, webhook_url) VALUES (?1, ?2, ?3)
+         ON CONFLICT(agent_id) DO UPDATE SET system_notification = excluded.system_notification, webhook_url = excluded.webhook_url",
+        params![agent_id, config.system_notification, webhook_url],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// A failed delivery attempt
+struct WebhookFailure {
+    message: String,
+    /// Timeouts, connection errors and 5xx responses are worth retrying; 4xx responses are not
+    retryable: bool,
+}
+
+/// POST the payload once, treating non-2xx responses as failures
+async fn post_webhook_once(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &serde_json::Value,
+) -> Result<u16, WebhookFailure> {
+    let response = client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| WebhookFailure {
+            retryable: e.is_timeout() || e.is_connect(),
+            // The URL may embed a secret (Slack/Discord tokens live in the path)
+            message: format!("Webhook request failed: {}", e.without_url()),
+        })?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(WebhookFailure {
+            message: format!("Webhook responded with HTTP {}", status),
+            retryable: status.is_server_error(),
+        })
+    }
+}
+
+/// Deliver a webhook with a short timeout and a single retry on transient failures
+async fn deliver_webhook(url: &str, payload: &serde_json::Value) -> Result<u16, String> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    match post_webhook_once(&client, url, payload).await {
+        Ok(status) => Ok(status),
+        Err(failure) if failure.retryable => {
+            // Only log the host: the full URL may carry the webhook secret
+            let host = url::Url::parse(url)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| h.to_string()))
+                .unwrap_or_default();
+            warn!(
+                "Webhook delivery to {} failed, retrying: {}",
+                host, failure.message
+            );
+            tokio::time::sleep(WEBHOOK_RETRY_DELAY).await;
+            post_webhook_once(&client, url, payload)
+                .await
+                .map_err(|failure| failure.message)
+        }
+        Err(failure) => Err(failure.message),
+    }
+}
+
+/// Send a sample payload to a webhook URL so users can check their endpoint
+#[tauri::command]
+pub async fn test_agent_webhook(url: String) -> Result<String, String> {
+    validate_webhook_url(&url)?;
+
+    let payload = serde_json::json!({
+        "event": "agent_run_test",
+        "run": AgentRunSummary {
+            run_id: 0,
+            agent_id: 0,
+            agent_name: "Test agent".to_string(),
+            task: "Webhook test".to_string(),
+            project_path: String::new(),
+            session_id: String::new(),
+            status: "completed".to_string(),
+            total_tokens: Some(0),
+            cost_usd: Some(0.0),
+            duration_ms: Some(0),
+            completed_at: Some(chrono::Utc::now().to_rfc3339()),
+        },
+    });
+
+    let status = deliver_webhook(&url, &payload).await?;
+    Ok(format!("Webhook responded with HTTP {}", status))
+}
+
+async fn build_run_summary(app: &AppHandle, run_id: i64) -> Result<AgentRunSummary, String> {
+    let run = get_agent_run(app.state::<AgentDb>(), run_id).await?;
+    let metrics = if run.session_id.is_empty() {
+        None
+    } else {
+        read_session_jsonl(&run.session_id, &run.project_path)
+            .await
+            .ok()
+            .map(|content| AgentRunMetrics::from_jsonl(&content))
+    };
+
+    Ok(AgentRunSummary {
+        run_id,
+        agent_id: run.agent_id,
+        agent_name: run.agent_name,
+        task: run.task,
+        project_path: run.project_path,
+        session_id: run.session_id,
+        status: run.status,
+        total_tokens: metrics.as_ref().and_then(|m| m.total_tokens),
+        cost_usd: metrics.as_ref().and_then(|m| m.cost_usd),
+        duration_ms: metrics.and_then(|m| m.duration_ms),
+        completed_at: run.completed_at,
+    })
+}
+
+async fn send_run_notifications(app: AppHandle, run_id: i64) -> Result<(), String> {
+    let summary = build_run_summary(&app, run_id).await?;
+    let config = load_notification_config(&app.state::<AgentDb>(), summary.agent_id)?;
+
+    if config.system_notification {
+        let body = match summary.cost_usd {
+            Some(cost) => format!("{} · {} · ${:.4}", summary.status, summary.task, cost),
+            None => format!("{} · {}", summary.status, summary.task),
+        };
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title(format!("Agent \"{}\" finished", summary.agent_name))
+            .body(body)
+            .show()
+        {
+            warn!(
+                "Failed to show notification for agent run {}: {}",
+                run_id, e
+            );
+        }
+    }
+
+    if let Some(url) = &config.webhook_url {
+        let payload = serde_json::json!({ "event": "agent_run_completed", "run": summary });
+        match deliver_webhook(url, &payload).await {
+            Ok(status) => info!(
+                "Delivered webhook for agent run {} (HTTP {})",
+                run_id, status
+            ),
+            Err(e) => warn!("Webhook for agent run {} was not delivered: {}", run_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Notify about a finished run in the background; failures are logged and never affect the run
+pub fn notify_run_finished(app: &AppHandle, run_id: i64) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send_run_notifications(app, run_id).await {
+            warn!(
+                "Failed to send notifications for agent run {}: {}",
+                run_id, e
+            );
+        }
+    });
+}
@@ -0,0 +1,373 @@
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use log::{error, info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::{execute_agent, AgentDb};
+use crate::process::ProcessRegistryState;
+
+/// How often the scheduler checks for due agents
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+
+/// A cron schedule attached to an agent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentSchedule {
+    pub agent_id: i64,
+    pub cron: String,
+    pub project_path: String,
+    pub task: Option<String>, // Falls back to the agent's default task
+    pub paused: bool,
+    pub last_run_at: Option<String>,
+    pub next_run_at: Option<String>,
+}
+
+/// Accepts standard 5-field cron expressions by prepending a seconds field
+fn parse_cron(expr: &str) -> Result<cron::Schedule, String> {
+    let expr = expr.trim();
+    let normalized = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| format!("Invalid cron expression '{}': {}", expr, e))
+}
+
+/// Parses timestamps stored either as RFC 3339 or as SQLite's CURRENT_TIMESTAMP (UTC)
+fn parse_db_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|dt| dt.and_utc())
+        })
+}
+
+/// Next fire time after `after`, evaluated in local time so "nightly" means the user's night
+fn next_fire_time(schedule: &cron::Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    schedule
+        .after(&after.with_timezone(&Local))
+        .next()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+struct ScheduleRow {
+    agent_id: i64,
+    cron: String,
+    project_path: String,
+    task: Option<String>,
+    paused: bool,
+    last_run_at: Option<String>,
+    created_at: String,
+}
+
+impl ScheduleRow {
+    /// Time the next run is measured from: the last run, or when the schedule was set
+    fn reference_time(&self) -> DateTime<Utc> {
+        self.last_run_at
+            .as_deref()
+            .or(Some(self.created_at.as_str()))
+            .and_then(parse_db_timestamp)
+            .unwrap_or_else(Utc::now)
+    }
+
+    fn next_run(&self) -> Option<DateTime<Utc>> {
+        if self.paused {
+            return None;
+        }
+        let schedule = parse_cron(&self.cron).ok()?;
+        next_fire_time(&schedule, self.reference_time())
+    }
+
+    fn into_schedule(self) -> AgentSchedule {
+        let next_run_at = self.next_run().map(|dt| dt.to_rfc3339());
+        AgentSchedule {
+            agent_id: self.agent_id,
+            cron: self.cron,
+            project_path: self.project_path,
+            task: self.task,
+            paused: self.paused,
+            last_run_at: self.last_run_at,
+            next_run_at,
+        }
+    }
+}
+
+const SCHEDULE_COLUMNS: &str =
+    "agent_id, cron, project_path, task, paused, last_run_at, created_at";
+
+fn schedule_from_row(row: &rusqlite::Row) -> rusqlite::Result<ScheduleRow> {
+    Ok(ScheduleRow {
+        agent_id: row.get(0)?,
+        cron: row.get(1)?,
+        project_path: row.get(2)?,
+        task: row.get(3)?,
+        paused: row.get(4)?,
+        last_run_at: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn load_schedule(conn: &Connection, agent_id: i64) -> Result<Option<ScheduleRow>, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM agent_schedules WHERE agent_id = ?1",
+            SCHEDULE_COLUMNS
+        ),
+        params![agent_id],
+        schedule_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Sets (or replaces) the cron schedule of an agent
+#[tauri::command]
+pub async fn set_agent_schedule(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    cron: String,
+    project_path: String,
+    task: Option<String>,
+) -> Result<AgentSchedule, String> {
+    parse_cron(&cron)?;
+    let task = task.filter(|t| !t.trim().is_empty());
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let default_task: Option<String> = conn
+        .query_row(
+            "SELECT default_task FROM agents WHERE id = ?1",
+            params![agent_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+    if task.is_none() && default_task.is_none_or(|t| t.trim().is_empty()) {
+        return Err("A scheduled run needs a task or an agent default task".to_string());
+    }
+
+    // Measure the first run from now, not from a previous schedule's last run
+    conn.execute(
+        "INSERT INTO agent_schedules (agent_id, cron, project_path, task, paused, last_run_at, created_at)
+         VALUES (?1, ?2, ?3, ?4, 0, NULL, CURRENT_TIMESTAMP)
+         ON CONFLICT(agent_id) DO UPDATE SET cron = excluded.cron, project_path = excluded.project_path,
+             task = excluded.task, paused = 0, last_run_at = NULL, created_at = CURRENT_TIMESTAMP",
+        params![agent_id, cron.trim(), project_path, task],
+    )
+    .map_err(|e| e.to_string())?;
+
+    info!("Scheduled agent {} with '{}'", agent_id, cron.trim());
+    load_schedule(&conn, agent_id)?
+        .map(ScheduleRow::into_schedule)
+        .ok_or_else(|| "Failed to load saved schedule".to_string())
+}
+
+/// Gets the schedule of an agent, if any
+#[tauri::command]
+pub async fn get_agent_schedule(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+) -> Result<Option<AgentSchedule>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    Ok(load_schedule(&conn, agent_id)?.map(ScheduleRow::into_schedule))
+}
+
+/// Removes the schedule of an agent
+#[tauri::command]
+pub async fn clear_agent_schedule(db: State<'_, AgentDb>, agent_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM agent_schedules WHERE agent_id = ?1",
+        params![agent_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Pauses an agent's schedule without removing it
+#[tauri::command]
+pub async fn pause_schedule(db: State<'_, AgentDb>, agent_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE agent_schedules SET paused = 1 WHERE agent_id = ?1",
+            params![agent_id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Agent {} has no schedule", agent_id));
+    }
+    info!("Paused schedule of agent {}", agent_id);
+    Ok(())
+}
+
+/// Resumes a paused schedule; runs missed while paused are skipped
+#[tauri::command]
+pub async fn resume_schedule(db: State<'_, AgentDb>, agent_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE agent_schedules SET paused = 0, last_run_at = ?1 WHERE agent_id = ?2",
+            params![Utc::now().to_rfc3339(), agent_id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Agent {} has no schedule", agent_id));
+    }
+    info!("Resumed schedule of agent {}", agent_id);
+    Ok(())
+}
+
+/// Gets the next time an agent's schedule fires (None when unscheduled or paused)
+#[tauri::command]
+pub async fn get_next_run_time(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+) -> Result<Option<String>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    Ok(load_schedule(&conn, agent_id)?
+        .and_then(|schedule| schedule.next_run())
+        .map(|dt| dt.to_rfc3339()))
+}
+
+/// Collects due schedules and marks them as run, so a restart can't fire them twice
+fn claim_due_schedules(db: &AgentDb) -> Result<Vec<ScheduleRow>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM agent_schedules WHERE paused = 0",
+            SCHEDULE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let schedules = stmt
+        .query_map([], schedule_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let now = Utc::now();
+    let mut due = Vec::new();
+    for schedule in schedules {
+        if schedule.next_run().is_none_or(|next| next > now) {
+            continue;
+        }
+
+        // Missed fire times collapse into this single run
+        conn.execute(
+            "UPDATE agent_schedules SET last_run_at = ?1 WHERE agent_id = ?2",
+            params![now.to_rfc3339(), schedule.agent_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        let still_running: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM agent_runs WHERE agent_id = ?1 AND status IN ('pending', 'running'))",
+                params![schedule.agent_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if still_running {
+            warn!(
+                "Skipping scheduled run of agent {}: previous run still executing",
+                schedule.agent_id
+            );
+            continue;
+        }
+
+        due.push(schedule);
+    }
+
+    Ok(due)
+}
+
+async fn run_scheduled_agent(app: &AppHandle, schedule: ScheduleRow) -> Result<i64, String> {
+    let db = app.state::<AgentDb>();
+    let task = match schedule.task {
+        Some(task) => task,
+        None => {
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            conn.query_row(
+                "SELECT default_task FROM agents WHERE id = ?1",
+                params![schedule.agent_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .map_err(|e| e.to_string())?
+            .filter(|t| !t.trim().is_empty())
+            .ok_or("Agent has no default task")?
+        }
+    };
+
+    let run_id = execute_agent(
+        app.clone(),
+        schedule.agent_id,
+        schedule.project_path,
+        task,
+        None,
+        None,
+        app.state::<AgentDb>(),
+        app.state::<ProcessRegistryState>(),
+    )
+    .await?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE agent_runs SET run_trigger = 'scheduled' WHERE id = ?1",
+        params![run_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(run_id)
+}
+
+/// Background loop that starts agents whose schedules are due
+pub async fn run_agent_scheduler(app: AppHandle) {
+    info!("Agent scheduler started");
+    loop {
+        tokio::time::sleep(SCHEDULER_TICK).await;
+
+        let due = match claim_due_schedules(&app.state::<AgentDb>()) {
+            Ok(due) => due,
+            Err(e) => {
+                error!("Agent scheduler failed to read schedules: {}", e);
+                continue;
+            }
+        };
+
+        for schedule in due {
+            let agent_id = schedule.agent_id;
+            match run_scheduled_agent(&app, schedule).await {
+                Ok(run_id) => info!("Started scheduled run {} of agent {}", run_id, agent_id),
+                Err(e) => error!("Scheduled run of agent {} failed to start: {}", agent_id, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parse_cron_accepts_five_field_expressions() {
+        assert!(parse_cron("0 2 * * *").is_ok());
+        assert!(parse_cron("0 0 2 * * *").is_ok());
+        assert!(parse_cron("not a cron").is_err());
+    }
+
+    #[test]
+    fn parse_db_timestamp_reads_both_formats() {
+        let expected = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(parse_db_timestamp("2025-01-02 03:04:05"), Some(expected));
+        assert_eq!(
+            parse_db_timestamp("2025-01-02T03:04:05+00:00"),
+            Some(expected)
+        );
+    }
+}
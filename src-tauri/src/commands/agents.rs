@@ -1,20 +1,23 @@
 use anyhow::Result;
 use chrono;
-use dirs;
 use log::{debug, error, info, warn};
 use reqwest;
 use rusqlite::{params, Connection, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
 use tokio::io::{AsyncBufReadExt, BufReader as TokioBufReader};
 use tokio::process::Command;
 
+use crate::utils::sqlite;
+
 /// Finds the full path to the claude binary
 /// This is necessary because macOS apps have a limited PATH environment
 fn find_claude_binary(app_handle: &AppHandle) -> Result<String, String> {
@@ -36,6 +39,8 @@ pub struct Agent {
     pub hooks: Option<String>, // JSON string of hooks configuration
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub env: HashMap<String, String>, // Extra environment variables for each run
 }
 
 /// Represents an agent execution run
@@ -49,7 +54,7 @@ pub struct AgentRun {
     pub model: String,
     pub project_path: String,
     pub session_id: String, // UUID session ID from Claude Code
-    pub status: String,     // 'pending', 'running', 'completed', 'failed', 'cancelled'
+    pub status: String, // 'pending', 'running', 'completed', 'failed', 'cancelled', 'interrupted'
     pub pid: Option<u32>,
     pub process_started_at: Option<String>,
     pub created_at: String,
@@ -74,6 +79,28 @@ pub struct AgentRunWithMetrics {
     pub output: Option<String>, // Real-time JSONL content
 }
 
+/// Cost and run count for a single day of an agent's runs
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentDailyCost {
+    pub date: String, // YYYY-MM-DD
+    pub cost_usd: f64,
+    pub run_count: i64,
+}
+
+/// Lifetime metrics aggregated across all runs of an agent
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentAggregate {
+    pub agent_id: i64,
+    pub run_count: i64,
+    pub completed_count: i64,
+    pub failed_count: i64,
+    pub success_rate: f64, // completed / finished runs, 0.0 if none finished
+    pub total_tokens: i64,
+    pub total_cost_usd: f64,
+    pub average_duration_ms: Option<f64>,
+    pub daily_costs: Vec<AgentDailyCost>, // Oldest first
+}
+
 /// Agent export format
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AgentExport {
@@ -96,6 +123,34 @@ pub struct AgentData {
 /// Database connection state
 pub struct AgentDb(pub Mutex<Connection>);
 
+impl AgentDb {
+    /// Locks the connection, recovering it if a thread panicked while holding the lock.
+    ///
+    /// The panicking thread may have left a transaction open, so it is rolled back before
+    /// the connection is handed out again. Fails only if that rollback fails, in which case
+    /// `reset_db_connection` reopens the database.
+    pub fn lock(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        match self.0.lock() {
+            Ok(conn) => Ok(conn),
+            Err(poisoned) => {
+                warn!("Database lock was poisoned by a panicked thread, recovering connection");
+                let conn = poisoned.into_inner();
+                if !conn.is_autocommit() {
+                    conn.execute_batch("ROLLBACK").map_err(|e| {
+                        error!("Failed to roll back transaction after lock poisoning: {}", e);
+                        format!(
+                            "Database connection is unusable after an internal error ({}); reset the connection",
+                            e
+                        )
+                    })?;
+                }
+                self.0.clear_poison();
+                Ok(conn)
+            }
+        }
+    }
+}
+
 /// Real-time JSONL reading and processing functions
 impl AgentRunMetrics {
     /// Calculate metrics from JSONL content
@@ -167,11 +222,80 @@ impl AgentRunMetrics {
     }
 }
 
+/// Maximum bytes of output indexed per run, so huge runs do not bloat the database
+const MAX_INDEXED_OUTPUT_BYTES: usize = 2 * 1024 * 1024;
+
+/// Maximum number of results returned by `search_agent_runs`
+const MAX_RUN_SEARCH_RESULTS: u32 = 100;
+
+/// Extracts the readable text (assistant text and final results) from stream-json output.
+/// Lines that are not JSON are kept as they are.
+fn searchable_output_text(output: &str) -> String {
+    let mut text = String::new();
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(json) = serde_json::from_str::<JsonValue>(line) else {
+            text.push_str(line);
+            text.push('\n');
+            continue;
+        };
+
+        match json.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                let blocks = json
+                    .get("message")
+                    .and_then(|m| m.get("content"))
+                    .and_then(|c| c.as_array());
+                for block in blocks.into_iter().flatten() {
+                    if let Some(block_text) = block.get("text").and_then(|t| t.as_str()) {
+                        text.push_str(block_text);
+                        text.push('\n');
+                    }
+                }
+            }
+            Some("result") => {
+                if let Some(result) = json.get("result").and_then(|r| r.as_str()) {
+                    text.push_str(result);
+                    text.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if text.len() > MAX_INDEXED_OUTPUT_BYTES {
+        let mut end = MAX_INDEXED_OUTPUT_BYTES;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
+/// Stores the output of a finished run in the search index, replacing any earlier entry.
+/// The FTS rowid is the run id, so the old entry is found without scanning the unindexed column
+fn index_run_output(conn: &Connection, run_id: i64, output: &str) {
+    let text = searchable_output_text(output);
+    let result = conn
+        .execute(
+            "DELETE FROM agent_run_output_fts WHERE rowid = ?1",
+            params![run_id],
+        )
+        .and_then(|_| {
+            conn.execute(
+                "INSERT INTO agent_run_output_fts (rowid, content, run_id) VALUES (?1, ?2, ?1)",
+                params![run_id, text],
+            )
+        });
+    if let Err(e) = result {
+        warn!("Failed to index output of agent run {}: {}", run_id, e);
+    }
+}
+
 /// Read JSONL content from a session file
 pub async fn read_session_jsonl(session_id: &str, project_path: &str) -> Result<String, String> {
-    let claude_dir = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude")
+    let claude_dir = crate::commands::claude::get_claude_dir()
+        .map_err(|e| e.to_string())?
         .join("projects");
 
     // Encode project path to match Claude Code's directory naming
@@ -214,17 +338,147 @@ pub async fn get_agent_run_with_metrics(run: AgentRun) -> AgentRunWithMetrics {
     }
 }
 
-/// Initialize the agents database
-pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
+/// Path of the agents database, creating the app data dir if needed
+fn agents_db_path(app: &AppHandle) -> PathBuf {
     let app_dir = app
         .path()
         .app_data_dir()
         .expect("Failed to get app data dir");
     std::fs::create_dir_all(&app_dir).expect("Failed to create app data dir");
+    app_dir.join("agents.db")
+}
 
-    let db_path = app_dir.join("agents.db");
-    let conn = Connection::open(db_path)?;
+/// Initialize the agents database
+pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
+    open_database(&agents_db_path(app))
+}
+
+fn open_database(db_path: &Path) -> SqliteResult<Connection> {
+    let conn = sqlite::open_connection(db_path)?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+/// A corrupt agents database that was moved aside at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseRecovery {
+    /// Where the corrupt database file now lives, if it could be moved
+    pub moved_to: Option<String>,
+    pub reason: String,
+    /// Whether the app fell back to a temporary in-memory database
+    pub in_memory: bool,
+}
+
+/// Opens the agents database at startup. A database that fails `PRAGMA integrity_check`
+/// or that SQLite reports as corrupt is moved aside and replaced with an empty one; if even
+/// that fails, an in-memory database keeps the app usable. Any other error (e.g. the file
+/// is locked by another instance or not readable) is returned, leaving the file untouched.
+pub fn init_database_with_recovery(
+    app: &AppHandle,
+) -> Result<(Connection, Option<DatabaseRecovery>), String> {
+    open_database_with_recovery(&agents_db_path(app))
+}
+
+fn open_database_with_recovery(
+    db_path: &Path,
+) -> Result<(Connection, Option<DatabaseRecovery>), String> {
+    let open_error = |e: rusqlite::Error| format!("Failed to open agents database: {}", e);
+    let reason = match check_database_integrity(db_path) {
+        Ok(None) => match open_database(db_path) {
+            Ok(conn) => return Ok((conn, None)),
+            Err(e) if sqlite::is_corruption_error(&e) => e.to_string(),
+            Err(e) => return Err(open_error(e)),
+        },
+        Ok(Some(problems)) => problems,
+        Err(e) if sqlite::is_corruption_error(&e) => e.to_string(),
+        Err(e) => return Err(open_error(e)),
+    };
+
+    error!(
+        "Agents database at {:?} is corrupt, replacing it with an empty database: {}",
+        db_path, reason
+    );
+    let moved_to = match quarantine_database(db_path) {
+        Ok(moved) => {
+            error!("Moved corrupt agents database to {:?}", moved);
+            Some(moved.to_string_lossy().to_string())
+        }
+        Err(e) => {
+            error!("Failed to move corrupt agents database aside: {}", e);
+            None
+        }
+    };
+
+    let (conn, in_memory) = match open_database(db_path) {
+        Ok(conn) => (conn, false),
+        Err(e) => {
+            error!(
+                "Failed to create a fresh agents database, using a temporary in-memory one: {}",
+                e
+            );
+            let conn = Connection::open_in_memory()
+                .and_then(|conn| init_schema(&conn).map(|_| conn))
+                .map_err(open_error)?;
+            (conn, true)
+        }
+    };
+
+    let recovery = DatabaseRecovery {
+        moved_to,
+        reason,
+        in_memory,
+    };
+    Ok((conn, Some(recovery)))
+}
+
+/// Runs `PRAGMA integrity_check` on an existing database file, returning the problems it
+/// reported. Errors opening or reading the file are returned as is for the caller to classify.
+fn check_database_integrity(db_path: &Path) -> SqliteResult<Option<String>> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
 
+    let conn = sqlite::open_connection(db_path)?;
+    let problems: Vec<String> = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    if problems.len() == 1 && problems[0] == "ok" {
+        Ok(None)
+    } else {
+        Ok(Some(
+            problems.into_iter().take(5).collect::<Vec<_>>().join("; "),
+        ))
+    }
+}
+
+/// Renames the database (and its WAL/SHM files) to `agents.db.corrupt-<timestamp>`
+fn quarantine_database(db_path: &Path) -> std::io::Result<PathBuf> {
+    let moved = db_path.with_file_name(format!(
+        "agents.db.corrupt-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::rename(db_path, &moved)?;
+
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = db_path.with_file_name(format!("agents.db{}", suffix));
+        if sidecar.exists() {
+            let target = moved.with_file_name(format!(
+                "{}{}",
+                moved.file_name().unwrap_or_default().to_string_lossy(),
+                suffix
+            ));
+            if let Err(e) = std::fs::rename(&sidecar, &target) {
+                warn!("Failed to move {:?} aside: {}", sidecar, e);
+            }
+        }
+    }
+    Ok(moved)
+}
+
+/// Creates or upgrades every table in the agents database
+fn init_schema(conn: &Connection) -> SqliteResult<()> {
     // Create agents table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS agents (
@@ -238,32 +492,13 @@ pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
             enable_file_write BOOLEAN NOT NULL DEFAULT 1,
             enable_network BOOLEAN NOT NULL DEFAULT 0,
             hooks TEXT,
+            env TEXT,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
         )",
         [],
     )?;
 
-    // Add columns to existing table if they don't exist
-    let _ = conn.execute("ALTER TABLE agents ADD COLUMN default_task TEXT", []);
-    let _ = conn.execute(
-        "ALTER TABLE agents ADD COLUMN model TEXT DEFAULT 'claude-sonnet-4-20250514'",
-        [],
-    );
-    let _ = conn.execute("ALTER TABLE agents ADD COLUMN hooks TEXT", []);
-    let _ = conn.execute(
-        "ALTER TABLE agents ADD COLUMN enable_file_read BOOLEAN DEFAULT 1",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE agents ADD COLUMN enable_file_write BOOLEAN DEFAULT 1",
-        [],
-    );
-    let _ = conn.execute(
-        "ALTER TABLE agents ADD COLUMN enable_network BOOLEAN DEFAULT 0",
-        [],
-    );
-
     // Create agent_runs table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS agent_runs (
@@ -280,36 +515,12 @@ pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
             process_started_at TEXT,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             completed_at TEXT,
+            run_trigger TEXT NOT NULL DEFAULT 'manual',
             FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
         )",
         [],
     )?;
 
-    // Migrate existing agent_runs table if needed
-    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN session_id TEXT", []);
-    let _ = conn.execute(
-        "ALTER TABLE agent_runs ADD COLUMN status TEXT DEFAULT 'pending'",
-        [],
-    );
-    let _ = conn.execute("ALTER TABLE agent_runs ADD COLUMN pid INTEGER", []);
-    let _ = conn.execute(
-        "ALTER TABLE agent_runs ADD COLUMN process_started_at TEXT",
-        [],
-    );
-
-    // Drop old columns that are no longer needed (data is now read from JSONL files)
-    // Note: SQLite doesn't support DROP COLUMN, so we'll ignore errors for existing columns
-    let _ = conn.execute(
-        "UPDATE agent_runs SET session_id = '' WHERE session_id IS NULL",
-        [],
-    );
-    let _ = conn.execute("UPDATE agent_runs SET status = 'completed' WHERE status IS NULL AND completed_at IS NOT NULL", []);
-    let _ = conn.execute("UPDATE agent_runs SET status = 'failed' WHERE status IS NULL AND completed_at IS NOT NULL AND session_id = ''", []);
-    let _ = conn.execute(
-        "UPDATE agent_runs SET status = 'pending' WHERE status IS NULL",
-        [],
-    );
-
     // Create trigger to update the updated_at timestamp
     conn.execute(
         "CREATE TRIGGER IF NOT EXISTS update_agent_timestamp 
@@ -343,6 +554,79 @@ pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
         [],
     )?;
 
+    // Create metrics table caching per-run metrics computed from the session JSONL
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_run_metrics (
+            run_id INTEGER PRIMARY KEY,
+            duration_ms INTEGER,
+            total_tokens INTEGER,
+            cost_usd REAL,
+            message_count INTEGER,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (run_id) REFERENCES agent_runs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create project metadata table (favorites, color tags) keyed by encoded project dir name
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_metadata (
+            project_id TEXT PRIMARY KEY,
+            is_favorite INTEGER NOT NULL DEFAULT 0,
+            color TEXT,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Create project budgets table (spend limits checked before starting sessions)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_budgets (
+            project_id TEXT PRIMARY KEY,
+            daily_usd REAL,
+            monthly_usd REAL,
+            hard_limit INTEGER NOT NULL DEFAULT 1,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Create agent schedules table (cron-triggered runs)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_schedules (
+            agent_id INTEGER PRIMARY KEY,
+            cron TEXT NOT NULL,
+            project_path TEXT NOT NULL,
+            task TEXT,
+            paused INTEGER NOT NULL DEFAULT 0,
+            last_run_at TEXT,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create agent notification settings table (system notification / webhook on completion)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_notifications (
+            agent_id INTEGER PRIMARY KEY,
+            system_notification INTEGER NOT NULL DEFAULT 0,
+            webhook_url TEXT,
+            FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Full-text index over the output of finished agent runs
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS agent_run_output_fts USING fts5(
+            content,
+            run_id UNINDEXED,
+            tokenize = 'unicode61'
+        )",
+        [],
+    )?;
+
     // Create model mappings table for configurable model aliases
     conn.execute(
         "CREATE TABLE IF NOT EXISTS model_mappings (
@@ -374,18 +658,78 @@ pub fn init_database(app: &AppHandle) -> SqliteResult<Connection> {
     }
 
     // Initialize prompt files tables
-    crate::commands::prompt_files::init_prompt_files_tables(&conn)?;
+    crate::commands::prompt_files::init_prompt_files_tables(conn)?;
 
-    Ok(conn)
+    // Bring tables created by older versions up to date
+    crate::commands::schema_migrations::run_schema_migrations(conn)?;
+
+    Ok(())
+}
+
+/// Environment variables that agents may only override when explicitly allowed
+const PROTECTED_ENV_KEYS: &[&str] = &["PATH", "HOME"];
+
+/// Parses the JSON-encoded env column of an agent
+fn parse_agent_env(raw: Option<String>) -> HashMap<String, String> {
+    raw.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Checks that a name is a valid environment variable name
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Validates agent env vars and serializes them for storage
+fn encode_agent_env(
+    env: &HashMap<String, String>,
+    allow_protected_env: bool,
+) -> Result<Option<String>, String> {
+    for key in env.keys() {
+        if !is_valid_env_name(key) {
+            return Err(format!("Invalid environment variable name: {}", key));
+        }
+        if PROTECTED_ENV_KEYS.contains(&key.as_str()) {
+            if !allow_protected_env {
+                return Err(format!(
+                    "Overriding {} may break the agent; allow protected variables to save it anyway",
+                    key
+                ));
+            }
+            warn!("Agent env overrides protected variable {}", key);
+        }
+    }
+
+    if env.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(env)
+        .map(Some)
+        .map_err(|e| format!("Failed to serialize agent env: {}", e))
+}
+
+/// Hides values of variables that look like secrets before they are logged
+fn redact_env_value<'a>(key: &str, value: &'a str) -> &'a str {
+    let upper = key.to_ascii_uppercase();
+    let is_secret = ["KEY", "TOKEN", "SECRET", "PASSWORD", "AUTH", "CREDENTIAL"]
+        .iter()
+        .any(|marker| upper.contains(marker));
+    if is_secret {
+        "***"
+    } else {
+        value
+    }
 }
 
 /// List all agents
 #[tauri::command]
 pub async fn list_agents(db: State<'_, AgentDb>) -> Result<Vec<Agent>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let mut stmt = conn
-        .prepare("SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at FROM agents ORDER BY created_at DESC")
+        .prepare("SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at, env FROM agents ORDER BY created_at DESC")
         .map_err(|e| e.to_string())?;
 
     let agents = stmt
@@ -405,6 +749,7 @@ pub async fn list_agents(db: State<'_, AgentDb>) -> Result<Vec<Agent>, String> {
                 hooks: row.get(9)?,
                 created_at: row.get(10)?,
                 updated_at: row.get(11)?,
+                env: parse_agent_env(row.get(12)?),
             })
         })
         .map_err(|e| e.to_string())?
@@ -427,16 +772,22 @@ pub async fn create_agent(
     enable_file_write: Option<bool>,
     enable_network: Option<bool>,
     hooks: Option<String>,
+    env: Option<HashMap<String, String>>,
+    allow_protected_env: Option<bool>,
 ) -> Result<Agent, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let env = encode_agent_env(
+        &env.unwrap_or_default(),
+        allow_protected_env.unwrap_or(false),
+    )?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
     let model = model.unwrap_or_else(|| "claude-sonnet-4-20250514".to_string());
     let enable_file_read = enable_file_read.unwrap_or(true);
     let enable_file_write = enable_file_write.unwrap_or(true);
     let enable_network = enable_network.unwrap_or(false);
 
     conn.execute(
-        "INSERT INTO agents (name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-        params![name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks],
+        "INSERT INTO agents (name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, env) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, env],
     )
     .map_err(|e| e.to_string())?;
 
@@ -445,7 +796,7 @@ pub async fn create_agent(
     // Fetch the created agent
     let agent = conn
         .query_row(
-            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at FROM agents WHERE id = ?1",
+            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at, env FROM agents WHERE id = ?1",
             params![id],
             |row| {
                 Ok(Agent {
@@ -461,6 +812,7 @@ pub async fn create_agent(
                     hooks: row.get(9)?,
                     created_at: row.get(10)?,
                     updated_at: row.get(11)?,
+                    env: parse_agent_env(row.get(12)?),
                 })
             },
         )
@@ -483,8 +835,13 @@ pub async fn update_agent(
     enable_file_write: Option<bool>,
     enable_network: Option<bool>,
     hooks: Option<String>,
+    env: Option<HashMap<String, String>>,
+    allow_protected_env: Option<bool>,
 ) -> Result<Agent, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let env = env
+        .map(|env| encode_agent_env(&env, allow_protected_env.unwrap_or(false)))
+        .transpose()?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
     let model = model.unwrap_or_else(|| "claude-sonnet-4-20250514".to_string());
 
     // Build dynamic query based on provided parameters
@@ -516,6 +873,11 @@ pub async fn update_agent(
         query.push_str(&format!(", enable_network = ?{}", param_count));
         params_vec.push(Box::new(en));
     }
+    if let Some(env) = env {
+        param_count += 1;
+        query.push_str(&format!(", env = ?{}", param_count));
+        params_vec.push(Box::new(env));
+    }
 
     param_count += 1;
     query.push_str(&format!(" WHERE id = ?{}", param_count));
@@ -530,7 +892,7 @@ pub async fn update_agent(
     // Fetch the updated agent
     let agent = conn
         .query_row(
-            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at FROM agents WHERE id = ?1",
+            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at, env FROM agents WHERE id = ?1",
             params![id],
             |row| {
                 Ok(Agent {
@@ -546,6 +908,7 @@ pub async fn update_agent(
                     hooks: row.get(9)?,
                     created_at: row.get(10)?,
                     updated_at: row.get(11)?,
+                    env: parse_agent_env(row.get(12)?),
                 })
             },
         )
@@ -557,22 +920,78 @@ pub async fn update_agent(
 /// Delete an agent
 #[tauri::command]
 pub async fn delete_agent(db: State<'_, AgentDb>, id: i64) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     conn.execute("DELETE FROM agents WHERE id = ?1", params![id])
         .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM agent_schedules WHERE agent_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM agent_notifications WHERE agent_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
+/// Duplicate an agent under a new name, appending " (copy)" until the name is unique
+#[tauri::command]
+pub async fn duplicate_agent(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+    new_name: String,
+) -> Result<Agent, String> {
+    let new_id = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+
+        let mut name = new_name.trim().to_string();
+        if name.is_empty() {
+            return Err("Agent name is required".to_string());
+        }
+        loop {
+            let existing: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM agents WHERE name = ?1",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if existing == 0 {
+                break;
+            }
+            name.push_str(" (copy)");
+        }
+
+        let inserted = conn
+            .execute(
+                "INSERT INTO agents (name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, env)
+                 SELECT ?1, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, env FROM agents WHERE id = ?2",
+                params![name, agent_id],
+            )
+            .map_err(|e| format!("Failed to duplicate agent: {}", e))?;
+        if inserted == 0 {
+            return Err(format!("Agent not found: {}", agent_id));
+        }
+
+        conn.last_insert_rowid()
+    };
+
+    info!("Duplicated agent {} as {}", agent_id, new_id);
+    get_agent(db, new_id).await
+}
+
 /// Get a single agent by ID
 #[tauri::command]
 pub async fn get_agent(db: State<'_, AgentDb>, id: i64) -> Result<Agent, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let agent = conn
         .query_row(
-            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at FROM agents WHERE id = ?1",
+            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at, env FROM agents WHERE id = ?1",
             params![id],
             |row| {
                 Ok(Agent {
@@ -588,6 +1007,7 @@ pub async fn get_agent(db: State<'_, AgentDb>, id: i64) -> Result<Agent, String>
                     hooks: row.get(9)?,
                     created_at: row.get(10)?,
                     updated_at: row.get(11)?,
+                    env: parse_agent_env(row.get(12)?),
                 })
             },
         )
@@ -602,7 +1022,7 @@ pub async fn list_agent_runs(
     db: State<'_, AgentDb>,
     agent_id: Option<i64>,
 ) -> Result<Vec<AgentRun>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let query = if agent_id.is_some() {
         "SELECT id, agent_id, agent_name, agent_icon, task, model, project_path, session_id, status, pid, process_started_at, created_at, completed_at 
@@ -653,7 +1073,7 @@ pub async fn list_agent_runs(
 /// Get a single agent run by ID
 #[tauri::command]
 pub async fn get_agent_run(db: State<'_, AgentDb>, id: i64) -> Result<AgentRun, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let run = conn
         .query_row(
@@ -683,6 +1103,68 @@ pub async fn get_agent_run(db: State<'_, AgentDb>, id: i64) -> Result<AgentRun,
     Ok(run)
 }
 
+/// A run whose output matched a search query
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentRunSearchResult {
+    pub run_id: i64,
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub agent_icon: String,
+    pub task: String,
+    pub status: String,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub snippet: String,
+}
+
+/// Full-text search over the stored output of finished agent runs
+#[tauri::command]
+pub async fn search_agent_runs(
+    db: State<'_, AgentDb>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<AgentRunSearchResult>, String> {
+    let Some(fts_query) = crate::commands::usage_cache::build_fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(20).clamp(1, MAX_RUN_SEARCH_RESULTS);
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.agent_id, r.agent_name, r.agent_icon, r.task, r.status, r.created_at, r.completed_at,
+                    snippet(agent_run_output_fts, 0, '[', ']', '…', 16)
+             FROM agent_run_output_fts
+             JOIN agent_runs r ON r.id = agent_run_output_fts.rowid
+             WHERE agent_run_output_fts MATCH ?1
+             ORDER BY bm25(agent_run_output_fts)
+             LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let results = stmt
+        .query_map(params![fts_query, limit], |row| {
+            Ok(AgentRunSearchResult {
+                run_id: row.get(0)?,
+                agent_id: row.get(1)?,
+                agent_name: row.get(2)?,
+                agent_icon: row.get(3)?,
+                task: row.get(4)?,
+                status: row
+                    .get::<_, String>(5)
+                    .unwrap_or_else(|_| "pending".to_string()),
+                created_at: row.get(6)?,
+                completed_at: row.get(7)?,
+                snippet: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(results)
+}
+
 /// Get agent run with real-time metrics from JSONL
 #[tauri::command]
 pub async fn get_agent_run_with_real_time_metrics(
@@ -710,6 +1192,144 @@ pub async fn list_agent_runs_with_metrics(
     Ok(runs_with_metrics)
 }
 
+/// Number of days included in the per-day cost series of an agent aggregate
+const AGGREGATE_DAILY_COST_DAYS: i64 = 30;
+
+/// Compute and store metrics for finished runs of an agent that don't have them yet
+async fn backfill_agent_run_metrics(db: &AgentDb, agent_id: i64) -> Result<(), String> {
+    let missing = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT r.id, r.session_id, r.project_path FROM agent_runs r
+                 LEFT JOIN agent_run_metrics m ON m.run_id = r.id
+                 WHERE r.agent_id = ?1 AND r.status != 'running' AND r.session_id != ''
+                   AND m.run_id IS NULL",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![agent_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        rows
+    };
+
+    for (run_id, session_id, project_path) in missing {
+        // Runs whose session file is gone get an empty row so they aren't re-read every time
+        let metrics = match read_session_jsonl(&session_id, &project_path).await {
+            Ok(content) => AgentRunMetrics::from_jsonl(&content),
+            Err(e) => {
+                debug!("No session file for agent run {}: {}", run_id, e);
+                AgentRunMetrics {
+                    duration_ms: None,
+                    total_tokens: None,
+                    cost_usd: None,
+                    message_count: None,
+                }
+            }
+        };
+
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO agent_run_metrics (run_id, duration_ms, total_tokens, cost_usd, message_count)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                run_id,
+                metrics.duration_ms,
+                metrics.total_tokens,
+                metrics.cost_usd,
+                metrics.message_count
+            ],
+        )
+        .map_err(|e| format!("Failed to store metrics for run {}: {}", run_id, e))?;
+    }
+
+    Ok(())
+}
+
+/// Get lifetime metrics aggregated across all runs of an agent
+#[tauri::command]
+pub async fn get_agent_aggregate_metrics(
+    db: State<'_, AgentDb>,
+    agent_id: i64,
+) -> Result<AgentAggregate, String> {
+    backfill_agent_run_metrics(&db, agent_id).await?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    let (mut aggregate, finished_count) = conn
+        .query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(r.status = 'completed'), 0),
+                    COALESCE(SUM(r.status = 'failed'), 0),
+                    COALESCE(SUM(r.status IN ('completed', 'failed', 'cancelled', 'interrupted')), 0),
+                    COALESCE(SUM(m.total_tokens), 0),
+                    COALESCE(SUM(m.cost_usd), 0.0),
+                    AVG(m.duration_ms)
+             FROM agent_runs r
+             LEFT JOIN agent_run_metrics m ON m.run_id = r.id
+             WHERE r.agent_id = ?1",
+            params![agent_id],
+            |row| {
+                Ok((
+                    AgentAggregate {
+                        agent_id,
+                        run_count: row.get(0)?,
+                        completed_count: row.get(1)?,
+                        failed_count: row.get(2)?,
+                        success_rate: 0.0,
+                        total_tokens: row.get(4)?,
+                        total_cost_usd: row.get(5)?,
+                        average_duration_ms: row.get(6)?,
+                        daily_costs: Vec::new(),
+                    },
+                    row.get::<_, i64>(3)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("Failed to aggregate agent runs: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(r.created_at) AS day, COALESCE(SUM(m.cost_usd), 0.0), COUNT(*)
+             FROM agent_runs r
+             LEFT JOIN agent_run_metrics m ON m.run_id = r.id
+             WHERE r.agent_id = ?1 AND r.created_at >= date('now', ?2)
+             GROUP BY day
+             ORDER BY day ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let daily_costs = stmt
+        .query_map(
+            params![agent_id, format!("-{} days", AGGREGATE_DAILY_COST_DAYS)],
+            |row| {
+                Ok(AgentDailyCost {
+                    date: row.get(0)?,
+                    cost_usd: row.get(1)?,
+                    run_count: row.get(2)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    if finished_count > 0 {
+        aggregate.success_rate = aggregate.completed_count as f64 / finished_count as f64;
+    }
+    aggregate.daily_costs = daily_costs;
+
+    Ok(aggregate)
+}
+
 /// Execute a CC agent with streaming output
 #[tauri::command]
 pub async fn execute_agent(
@@ -718,6 +1338,7 @@ pub async fn execute_agent(
     project_path: String,
     task: String,
     model: Option<String>,
+    routing: Option<crate::commands::routing::RoutingOverride>,
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<i64, String> {
@@ -735,6 +1356,24 @@ pub async fn execute_agent(
 
     info!("Resolved model: {} -> {}", execution_model, resolved_model);
 
+    // Per-run routing override is applied through the child's env only
+    let mut env = Vec::new();
+    if let Some(routing) = &routing {
+        env.extend(crate::commands::routing::routing_override_env(
+            &db, routing,
+        )?);
+        info!("Agent run routing override: {:?}", routing);
+    }
+
+    // Agent-defined variables come last so they can override inherited ones
+    for (key, value) in &agent.env {
+        if PROTECTED_ENV_KEYS.contains(&key.as_str()) {
+            warn!("Agent '{}' overrides {}", agent.name, key);
+        }
+        debug!("Agent env: {}={}", key, redact_env_value(key, value));
+        env.push((key.clone(), value.clone()));
+    }
+
     // Create .claude/settings.json with agent hooks if it doesn't exist
     if let Some(hooks_json) = &agent.hooks {
         let claude_dir = std::path::Path::new(&project_path).join(".claude");
@@ -776,7 +1415,7 @@ pub async fn execute_agent(
 
     // Create a new run record
     let run_id = {
-        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let conn = db.lock().map_err(|e| e.to_string())?;
         conn.execute(
             "INSERT INTO agent_runs (agent_id, agent_name, agent_icon, task, model, project_path, session_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![agent_id, agent.name, agent.icon, task, execution_model, project_path, ""],
@@ -817,6 +1456,7 @@ pub async fn execute_agent(
             agent_id,
             agent.name.clone(),
             args,
+            env,
             project_path,
             task,
             resolved_model,
@@ -832,6 +1472,7 @@ pub async fn execute_agent(
             agent.name.clone(),
             claude_path,
             args,
+            env,
             project_path,
             task,
             resolved_model,
@@ -851,6 +1492,7 @@ fn should_use_sidecar(claude_path: &str) -> bool {
 fn create_agent_sidecar_command(
     app: &AppHandle,
     args: Vec<String>,
+    env: &[(String, String)],
     project_path: &str,
 ) -> Result<tauri_plugin_shell::process::Command, String> {
     let mut sidecar_cmd = app
@@ -864,6 +1506,16 @@ fn create_agent_sidecar_command(
     // Set working directory
     sidecar_cmd = sidecar_cmd.current_dir(project_path);
 
+    // Point Claude at a relocated config directory (app setting or CLAUDE_CONFIG_DIR)
+    if let Ok((claude_dir, source)) = crate::commands::claude::resolve_claude_dir() {
+        if source != crate::commands::claude::ClaudeDirSource::Default {
+            sidecar_cmd = sidecar_cmd.env(
+                "CLAUDE_CONFIG_DIR",
+                claude_dir.to_string_lossy().to_string(),
+            );
+        }
+    }
+
     // Pass through proxy environment variables if they exist (only uppercase)
     for (key, value) in std::env::vars() {
         if key == "HTTP_PROXY" || key == "HTTPS_PROXY" || key == "NO_PROXY" || key == "ALL_PROXY" {
@@ -872,6 +1524,11 @@ fn create_agent_sidecar_command(
         }
     }
 
+    // Run-specific variables override anything inherited
+    for (key, value) in env {
+        sidecar_cmd = sidecar_cmd.env(key, value);
+    }
+
     Ok(sidecar_cmd)
 }
 
@@ -879,6 +1536,7 @@ fn create_agent_sidecar_command(
 fn create_agent_system_command(
     claude_path: &str,
     args: Vec<String>,
+    env: &[(String, String)],
     project_path: &str,
 ) -> Command {
     let mut cmd = create_command_with_env(claude_path);
@@ -888,11 +1546,20 @@ fn create_agent_system_command(
         cmd.arg(arg);
     }
 
+    // Run-specific variables override anything inherited
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+
     cmd.current_dir(project_path)
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
+    // Run in its own process group so killing the run also reaches MCP subprocesses
+    #[cfg(unix)]
+    cmd.process_group(0);
+
     cmd
 }
 
@@ -903,6 +1570,7 @@ async fn spawn_agent_sidecar(
     agent_id: i64,
     agent_name: String,
     args: Vec<String>,
+    env: Vec<(String, String)>,
     project_path: String,
     task: String,
     execution_model: String,
@@ -910,7 +1578,7 @@ async fn spawn_agent_sidecar(
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<i64, String> {
     // Build the sidecar command
-    let sidecar_cmd = create_agent_sidecar_command(&app, args, &project_path)?;
+    let sidecar_cmd = create_agent_sidecar_command(&app, args, &env, &project_path)?;
 
     // Spawn the process
     info!("🚀 Spawning Claude sidecar process...");
@@ -929,7 +1597,7 @@ async fn spawn_agent_sidecar(
 
     // Update the database with PID and status
     {
-        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let conn = db.lock().map_err(|e| e.to_string())?;
         conn.execute(
             "UPDATE agent_runs SET status = 'running', pid = ?1, process_started_at = ?2 WHERE id = ?3",
             params![pid as i64, now, run_id],
@@ -1019,10 +1687,17 @@ async fn spawn_agent_sidecar(
                                         info!("🔑 Extracted session ID: {}", sid);
 
                                         // Update database immediately with session ID
-                                        if let Ok(conn) = Connection::open(&db_path_for_sidecar) {
-                                            match conn.execute(
-                                                "UPDATE agent_runs SET session_id = ?1 WHERE id = ?2",
-                                                params![sid, run_id],
+                                        if let Ok(conn) =
+                                            sqlite::open_connection(&db_path_for_sidecar)
+                                        {
+                                            match sqlite::with_busy_retry(
+                                                "session ID update",
+                                                || {
+                                                    conn.execute(
+                                                    "UPDATE agent_runs SET session_id = ?1 WHERE id = ?2",
+                                                    params![sid, run_id],
+                                                )
+                                                },
                                             ) {
                                                 Ok(rows) => {
                                                     if rows > 0 {
@@ -1064,16 +1739,22 @@ async fn spawn_agent_sidecar(
                     };
 
                     // Update database with completion
-                    if let Ok(conn) = Connection::open(&db_path) {
-                        let _ = conn.execute(
-                            "UPDATE agent_runs SET session_id = ?1, status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?2",
-                            params![extracted_session_id, run_id],
-                        );
+                    if let Ok(conn) = sqlite::open_connection(&db_path) {
+                        let _ = sqlite::with_busy_retry("agent run completion", || {
+                            conn.execute(
+                                "UPDATE agent_runs SET session_id = ?1, status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                                params![extracted_session_id, run_id],
+                            )
+                        });
+                        if let Ok(output) = live_output_clone.lock() {
+                            index_run_output(&conn, run_id, &output);
+                        }
                     }
 
                     let success = payload.code.unwrap_or(1) == 0;
                     let _ = app.emit("agent-complete", success);
                     let _ = app.emit(&format!("agent-complete:{}", run_id), success);
+                    crate::commands::agent_notifications::notify_run_finished(&app, run_id);
                     break;
                 }
                 _ => {}
@@ -1097,6 +1778,7 @@ async fn spawn_agent_system(
     agent_name: String,
     claude_path: String,
     args: Vec<String>,
+    env: Vec<(String, String)>,
     project_path: String,
     task: String,
     execution_model: String,
@@ -1104,11 +1786,12 @@ async fn spawn_agent_system(
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<i64, String> {
     // Build the command
-    let mut cmd = create_agent_system_command(&claude_path, args, &project_path);
+    let mut cmd = create_agent_system_command(&claude_path, args, &env, &project_path);
 
     // Spawn the process
     info!("🚀 Spawning Claude system process...");
     let mut child = cmd.spawn().map_err(|e| {
+        crate::claude_binary::invalidate_on_spawn_error(&e);
         error!("❌ Failed to spawn Claude process: {}", e);
         format!("Failed to spawn Claude: {}", e)
     })?;
@@ -1122,7 +1805,7 @@ async fn spawn_agent_system(
 
     // Update the database with PID and status
     {
-        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let conn = db.lock().map_err(|e| e.to_string())?;
         conn.execute(
             "UPDATE agent_runs SET status = 'running', pid = ?1, process_started_at = ?2 WHERE id = ?3",
             params![pid as i64, now, run_id],
@@ -1205,11 +1888,13 @@ async fn spawn_agent_system(
                                 info!("🔑 Extracted session ID: {}", sid);
 
                                 // Update database immediately with session ID
-                                if let Ok(conn) = Connection::open(&db_path_for_stdout) {
-                                    match conn.execute(
-                                        "UPDATE agent_runs SET session_id = ?1 WHERE id = ?2",
-                                        params![sid, run_id],
-                                    ) {
+                                if let Ok(conn) = sqlite::open_connection(&db_path_for_stdout) {
+                                    match sqlite::with_busy_retry("session ID update", || {
+                                        conn.execute(
+                                            "UPDATE agent_runs SET session_id = ?1 WHERE id = ?2",
+                                            params![sid, run_id],
+                                        )
+                                    }) {
                                         Ok(rows) => {
                                             if rows > 0 {
                                                 info!("✅ Updated agent run {} with session ID immediately", run_id);
@@ -1344,15 +2029,18 @@ async fn spawn_agent_system(
                 }
 
                 // Update database
-                if let Ok(conn) = Connection::open(&db_path_for_monitor) {
-                    let _ = conn.execute(
-                        "UPDATE agent_runs SET status = 'failed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
-                        params![run_id],
-                    );
+                if let Ok(conn) = sqlite::open_connection(&db_path_for_monitor) {
+                    let _ = sqlite::with_busy_retry("agent run failure", || {
+                        conn.execute(
+                            "UPDATE agent_runs SET status = 'failed', completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                            params![run_id],
+                        )
+                    });
                 }
 
                 let _ = app.emit("agent-complete", false);
                 let _ = app.emit(&format!("agent-complete:{}", run_id), false);
+                crate::commands::agent_notifications::notify_run_finished(&app, run_id);
                 return;
             }
 
@@ -1378,26 +2066,40 @@ async fn spawn_agent_system(
         info!("✅ Claude process execution monitoring complete");
 
         // Update the run record with session ID and mark as completed - open a new connection
-        if let Ok(conn) = Connection::open(&db_path_for_monitor) {
+        if let Ok(conn) = sqlite::open_connection(&db_path_for_monitor) {
             info!(
                 "🔄 Updating database with extracted session ID: {}",
                 extracted_session_id
             );
-            match conn.execute(
-                "UPDATE agent_runs SET session_id = ?1, status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?2",
-                params![extracted_session_id, run_id],
-            ) {
+            match sqlite::with_busy_retry("agent run completion", || {
+                conn.execute(
+                    "UPDATE agent_runs SET session_id = ?1, status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                    params![extracted_session_id, run_id],
+                )
+            }) {
                 Ok(rows_affected) => {
                     if rows_affected > 0 {
-                        info!("✅ Successfully updated agent run {} with session ID: {}", run_id, extracted_session_id);
+                        info!(
+                            "✅ Successfully updated agent run {} with session ID: {}",
+                            run_id, extracted_session_id
+                        );
                     } else {
-                        warn!("⚠️ No rows affected when updating agent run {} with session ID", run_id);
+                        warn!(
+                            "⚠️ No rows affected when updating agent run {} with session ID",
+                            run_id
+                        );
                     }
                 }
                 Err(e) => {
-                    error!("❌ Failed to update agent run {} with session ID: {}", run_id, e);
+                    error!(
+                        "❌ Failed to update agent run {} with session ID: {}",
+                        run_id, e
+                    );
                 }
             }
+            if let Ok(output) = live_output.lock() {
+                index_run_output(&conn, run_id, &output);
+            }
         } else {
             error!(
                 "❌ Failed to open database to update session ID for run {}",
@@ -1409,6 +2111,7 @@ async fn spawn_agent_system(
 
         let _ = app.emit("agent-complete", true);
         let _ = app.emit(&format!("agent-complete:{}", run_id), true);
+        crate::commands::agent_notifications::notify_run_finished(&app, run_id);
     });
 
     Ok(run_id)
@@ -1420,7 +2123,7 @@ pub async fn list_running_sessions(
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
 ) -> Result<Vec<AgentRun>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // First get all running sessions from the database
     let mut stmt = conn.prepare(
@@ -1485,19 +2188,26 @@ pub async fn kill_agent_session(
     db: State<'_, AgentDb>,
     registry: State<'_, crate::process::ProcessRegistryState>,
     run_id: i64,
+    grace_period_ms: Option<u64>,
 ) -> Result<bool, String> {
     info!("Attempting to kill agent session {}", run_id);
 
-    // First try to kill using the process registry
-    let killed_via_registry = match registry.0.kill_process(run_id).await {
-        Ok(success) => {
-            if success {
-                info!("Successfully killed process {} via registry", run_id);
-                true
-            } else {
-                warn!("Process {} not found in registry", run_id);
-                false
-            }
+    let grace_period = std::time::Duration::from_millis(
+        grace_period_ms.unwrap_or(crate::process::DEFAULT_KILL_GRACE_PERIOD_MS),
+    );
+
+    // First try to kill using the process registry (SIGTERM, then SIGKILL after the grace period)
+    let killed_via_registry = match registry.0.terminate_process(run_id, grace_period).await {
+        Ok(Some(signal)) => {
+            info!(
+                "Successfully killed process {} via registry ({:?})",
+                run_id, signal
+            );
+            true
+        }
+        Ok(None) => {
+            warn!("Process {} not found in registry", run_id);
+            false
         }
         Err(e) => {
             warn!("Failed to kill process {} via registry: {}", run_id, e);
@@ -1508,7 +2218,7 @@ pub async fn kill_agent_session(
     // If registry kill didn't work, try fallback with PID from database
     if !killed_via_registry {
         let pid_result = {
-            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            let conn = db.lock().map_err(|e| e.to_string())?;
             conn.query_row(
                 "SELECT pid FROM agent_runs WHERE id = ?1 AND status = 'running'",
                 params![run_id],
@@ -1524,7 +2234,7 @@ pub async fn kill_agent_session(
     }
 
     // Update the database to mark as cancelled
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
     let updated = conn.execute(
         "UPDATE agent_runs SET status = 'cancelled', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'running'",
         params![run_id],
@@ -1542,7 +2252,7 @@ pub async fn get_session_status(
     db: State<'_, AgentDb>,
     run_id: i64,
 ) -> Result<Option<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     match conn.query_row(
         "SELECT status FROM agent_runs WHERE id = ?1",
@@ -1558,7 +2268,7 @@ pub async fn get_session_status(
 /// Cleanup finished processes and update their status
 #[tauri::command]
 pub async fn cleanup_finished_processes(db: State<'_, AgentDb>) -> Result<Vec<i64>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Get all running processes
     let mut stmt = conn
@@ -1621,6 +2331,57 @@ pub async fn cleanup_finished_processes(db: State<'_, AgentDb>) -> Result<Vec<i6
     Ok(cleaned_up)
 }
 
+/// Mark runs left in the 'running' state by a previous app session as 'interrupted'.
+/// Called on startup, before any run can have been spawned by this session.
+pub fn reconcile_interrupted_runs(conn: &Connection) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, pid FROM agent_runs WHERE status = 'running'")
+        .map_err(|e| e.to_string())?;
+
+    let stale_runs = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Option<i64>>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    drop(stmt);
+
+    let mut interrupted = Vec::new();
+
+    for (run_id, pid) in stale_runs {
+        if let Some(pid) = pid.filter(|p| *p > 0) {
+            if crate::process::is_pid_alive(pid as u32) {
+                // The PID may have been reused, so don't touch the process; the user can
+                // still kill it from the UI via the recorded PID
+                warn!(
+                    "Agent run {} from a previous session still has a live PID {}, leaving it as running",
+                    run_id, pid
+                );
+                continue;
+            }
+        }
+
+        let updated = conn
+            .execute(
+                "UPDATE agent_runs SET status = 'interrupted', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'running'",
+                params![run_id],
+            )
+            .map_err(|e| e.to_string())?;
+
+        if updated > 0 {
+            info!(
+                "Marked agent run {} as interrupted (process no longer exists)",
+                run_id
+            );
+            interrupted.push(run_id);
+        }
+    }
+
+    Ok(interrupted)
+}
+
 /// Get live output from a running process
 #[tauri::command]
 pub async fn get_live_session_output(
@@ -1650,9 +2411,7 @@ pub async fn get_session_output(
     }
 
     // Get the Claude directory
-    let claude_dir = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_dir = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     // Find the correct project directory by searching for the session file
     let projects_dir = claude_dir.join("projects");
@@ -1743,9 +2502,9 @@ pub async fn stream_session_output(
 
     // Spawn a task to monitor the file
     tokio::spawn(async move {
-        let claude_dir = match dirs::home_dir() {
-            Some(home) => home.join(".claude").join("projects"),
-            None => return,
+        let claude_dir = match crate::commands::claude::get_claude_dir() {
+            Ok(dir) => dir.join("projects"),
+            Err(_) => return,
         };
 
         let encoded_project = project_path.replace('/', "-");
@@ -1777,7 +2536,7 @@ pub async fn stream_session_output(
 
             // Check if the session is still running by querying the database
             // If the session is no longer running, stop streaming
-            if let Ok(conn) = rusqlite::Connection::open(
+            if let Ok(conn) = sqlite::open_connection(
                 app.path()
                     .app_data_dir()
                     .expect("Failed to get app data dir")
@@ -1813,7 +2572,7 @@ pub async fn stream_session_output(
 /// Export a single agent to JSON format
 #[tauri::command]
 pub async fn export_agent(db: State<'_, AgentDb>, id: i64) -> Result<String, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Fetch the agent
     let agent = conn
@@ -1864,7 +2623,7 @@ pub async fn export_agent_to_file(
 /// Get the stored Claude binary path from settings
 #[tauri::command]
 pub async fn get_claude_binary_path(db: State<'_, AgentDb>) -> Result<Option<String>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     match conn.query_row(
         "SELECT value FROM app_settings WHERE key = 'claude_binary_path'",
@@ -1880,7 +2639,7 @@ pub async fn get_claude_binary_path(db: State<'_, AgentDb>) -> Result<Option<Str
 /// Set the Claude binary path in settings
 #[tauri::command]
 pub async fn set_claude_binary_path(db: State<'_, AgentDb>, path: String) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Validate that the path exists and is executable
     let path_buf = std::path::PathBuf::from(&path);
@@ -1908,9 +2667,65 @@ pub async fn set_claude_binary_path(db: State<'_, AgentDb>, path: String) -> Res
     )
     .map_err(|e| format!("Failed to save Claude binary path: {}", e))?;
 
+    // Drop any previously discovered path so the new override takes effect immediately
+    crate::claude_binary::invalidate_claude_binary_cache();
+
     Ok(())
 }
 
+/// Re-run Claude binary discovery, e.g. after installing or updating Claude Code
+#[tauri::command]
+pub async fn refresh_claude_binary_cache(app: AppHandle) -> Result<String, String> {
+    crate::claude_binary::refresh_claude_binary_cache(&app)
+}
+
+/// Smallest accepted live output cap, so a typo can't make the buffer useless
+const MIN_LIVE_OUTPUT_LIMIT_BYTES: usize = 64 * 1024;
+
+/// Read the stored per-run live output cap, if one has been configured
+pub fn load_live_output_limit(conn: &Connection) -> Option<usize> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = 'live_output_limit_bytes'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.parse::<usize>().ok())
+}
+
+/// Get the per-run live output cap in bytes
+#[tauri::command]
+pub async fn get_live_output_limit(
+    registry: State<'_, crate::process::ProcessRegistryState>,
+) -> Result<usize, String> {
+    Ok(registry.0.live_output_limit())
+}
+
+/// Set the per-run live output cap in bytes; older lines are dropped once a run exceeds it
+#[tauri::command]
+pub async fn set_live_output_limit(
+    db: State<'_, AgentDb>,
+    registry: State<'_, crate::process::ProcessRegistryState>,
+    limit_bytes: usize,
+) -> Result<(), String> {
+    if limit_bytes < MIN_LIVE_OUTPUT_LIMIT_BYTES {
+        return Err(format!(
+            "Live output limit must be at least {} bytes",
+            MIN_LIVE_OUTPUT_LIMIT_BYTES
+        ));
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES ('live_output_limit_bytes', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = ?1",
+        params![limit_bytes.to_string()],
+    )
+    .map_err(|e| format!("Failed to save live output limit: {}", e))?;
+
+    registry.0.set_live_output_limit(limit_bytes)
+}
+
 /// List all available Claude installations on the system
 #[tauri::command]
 pub async fn list_claude_installations(
@@ -1931,8 +2746,10 @@ fn create_command_with_env(program: &str) -> Command {
     // Convert std::process::Command to tokio::process::Command
     let _std_cmd = crate::claude_binary::create_command_with_env(program);
 
-    // Create a new tokio Command from the program path
-    let mut tokio_cmd = Command::new(program);
+    // Create a new tokio Command from the program path (PowerShell shims run via powershell -File)
+    let (executable, leading_args) = crate::claude_binary::shim_program_and_args(program);
+    let mut tokio_cmd = Command::new(executable);
+    tokio_cmd.args(leading_args);
 
     // Copy over all environment variables from the std::process::Command
     // This is a workaround since we can't directly convert between the two types
@@ -1999,7 +2816,7 @@ pub async fn import_agent(db: State<'_, AgentDb>, json_data: String) -> Result<A
     }
 
     let agent_data = export_data.agent;
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Check if an agent with the same name already exists
     let existing_count: i64 = conn
@@ -2036,7 +2853,7 @@ pub async fn import_agent(db: State<'_, AgentDb>, json_data: String) -> Result<A
     // Fetch the created agent
     let agent = conn
         .query_row(
-            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at FROM agents WHERE id = ?1",
+            "SELECT id, name, icon, system_prompt, default_task, model, enable_file_read, enable_file_write, enable_network, hooks, created_at, updated_at, env FROM agents WHERE id = ?1",
             params![id],
             |row| {
                 Ok(Agent {
@@ -2052,6 +2869,7 @@ pub async fn import_agent(db: State<'_, AgentDb>, json_data: String) -> Result<A
                     hooks: row.get(9)?,
                     created_at: row.get(10)?,
                     updated_at: row.get(11)?,
+                    env: parse_agent_env(row.get(12)?),
                 })
             },
         )
@@ -2212,9 +3030,7 @@ pub async fn load_agent_session_history(
 ) -> Result<Vec<serde_json::Value>, String> {
     log::info!("Loading agent session history for session: {}", session_id);
 
-    let claude_dir = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_dir = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     let projects_dir = claude_dir.join("projects");
 
@@ -2283,7 +3099,7 @@ pub struct ModelMapping {
 /// Get all model mappings
 #[tauri::command]
 pub async fn get_model_mappings(db: State<'_, AgentDb>) -> Result<Vec<ModelMapping>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let mut stmt = conn
         .prepare("SELECT alias, model_name, updated_at FROM model_mappings ORDER BY alias")
@@ -2311,7 +3127,7 @@ pub async fn update_model_mapping(
     alias: String,
     model_name: String,
 ) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     conn.execute(
         "INSERT OR REPLACE INTO model_mappings (alias, model_name, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
@@ -2324,7 +3140,7 @@ pub async fn update_model_mapping(
 
 /// Get model name by alias (with fallback)
 fn get_model_by_alias(db: &AgentDb, alias: &str) -> Result<String, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // If alias looks like a full model name (contains 'claude-'), return it directly
     if alias.starts_with("claude-") {
@@ -2339,3 +3155,71 @@ fn get_model_by_alias(db: &AgentDb, alias: &str) -> Result<String, String> {
     )
     .map_err(|_| format!("Model alias '{}' not found in mappings", alias))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_keeps_healthy_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("agents.db");
+        open_database(&db_path)
+            .unwrap()
+            .execute(
+                "INSERT INTO agents (name, icon, system_prompt, model) VALUES ('a', 'bot', 'p', 'sonnet')",
+                [],
+            )
+            .unwrap();
+
+        let (conn, recovery) = open_database_with_recovery(&db_path).unwrap();
+        assert!(recovery.is_none());
+        let agents: i64 = conn
+            .query_row("SELECT COUNT(*) FROM agents", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(agents, 1);
+    }
+
+    #[test]
+    fn test_recovery_quarantines_corrupt_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("agents.db");
+        std::fs::write(&db_path, vec![0xAB; 8192]).unwrap();
+
+        let (conn, recovery) = open_database_with_recovery(&db_path).unwrap();
+        let recovery = recovery.expect("corrupt database should be recovered");
+        assert!(!recovery.in_memory);
+        let moved_to = PathBuf::from(recovery.moved_to.unwrap());
+        assert_eq!(std::fs::read(&moved_to).unwrap(), vec![0xAB; 8192]);
+        let agents: i64 = conn
+            .query_row("SELECT COUNT(*) FROM agents", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(agents, 0);
+    }
+
+    #[test]
+    fn test_recovery_surfaces_busy_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("agents.db");
+        let lock = Connection::open(&db_path).unwrap();
+        lock.execute_batch(
+            "CREATE TABLE t (id INTEGER); BEGIN EXCLUSIVE; INSERT INTO t VALUES (1);",
+        )
+        .unwrap();
+
+        let error = open_database_with_recovery(&db_path).unwrap_err();
+        assert!(error.contains("locked"), "{}", error);
+        lock.execute_batch("COMMIT").unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains("corrupt"))
+            .collect();
+        assert!(files.is_empty());
+        let rows: i64 = lock
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+    }
+}
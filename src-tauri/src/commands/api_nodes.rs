@@ -7,6 +7,7 @@ use uuid::Uuid;
 // 导入公共模块
 use crate::types::node_test::NodeTestResult;
 use crate::utils::node_tester;
+use crate::utils::sqlite;
 
 /// API 节点数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,7 +44,7 @@ pub struct UpdateApiNodeRequest {
 /// 获取数据库连接
 fn get_connection() -> Result<Connection> {
     let db_path = get_nodes_db_path()?;
-    let conn = Connection::open(&db_path)
+    let conn = sqlite::open_connection(&db_path)
         .context(format!("Failed to open database at {:?}", db_path))?;
     Ok(conn)
 }
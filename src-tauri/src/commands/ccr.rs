@@ -1,14 +1,30 @@
 use log::{debug, error, info};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::net::TcpStream;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::Mutex;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::AsyncBufReadExt;
+
+use crate::commands::agents::AgentDb;
+use crate::commands::relay_stations::load_enabled_relay_station;
+use crate::i18n;
 
 // 全局变量存储找到的 CCR 路径
 static CCR_PATH: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
 
+// 最近一次未找到 CCR 的时间，用于在短时间内缓存"未找到"结果
+static CCR_PATH_MISS: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+/// "未找到"结果的缓存时长
+const CCR_NOT_FOUND_TTL: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CcrServiceStatus {
     pub is_running: bool,
@@ -308,7 +324,7 @@ fn find_ccr_via_shell() -> Option<String> {
     None
 }
 
-/// 查找实际的 CCR 路径
+/// 查找实际的 CCR 路径（优先使用缓存，未找到的结果在短时间内同样会被缓存）
 fn find_ccr_path() -> Option<String> {
     // 先检查缓存
     if let Ok(cached) = CCR_PATH.lock() {
@@ -317,6 +333,48 @@ fn find_ccr_path() -> Option<String> {
         }
     }
 
+    // 最近刚查找失败过，避免重复执行耗时的查找
+    if let Ok(miss) = CCR_PATH_MISS.lock() {
+        if miss.is_some_and(|at| at.elapsed() < CCR_NOT_FOUND_TTL) {
+            return None;
+        }
+    }
+
+    let found = discover_ccr_path();
+    if let Ok(mut miss) = CCR_PATH_MISS.lock() {
+        *miss = if found.is_some() {
+            None
+        } else {
+            Some(Instant::now())
+        };
+    }
+    found
+}
+
+/// 清除 CCR 路径缓存（包括未找到的缓存）
+fn clear_ccr_path_cache() {
+    if let Ok(mut cached) = CCR_PATH.lock() {
+        *cached = None;
+    }
+    if let Ok(mut miss) = CCR_PATH_MISS.lock() {
+        *miss = None;
+    }
+}
+
+/// 查找 CCR 路径，并确认缓存的路径仍然存在（CCR 被卸载或移动后重新查找）
+fn find_verified_ccr_path() -> Option<String> {
+    let path = find_ccr_path()?;
+    let path_obj = std::path::Path::new(&path);
+    if path_obj.is_absolute() && !path_obj.exists() {
+        info!("Cached CCR path no longer exists, re-discovering: {}", path);
+        clear_ccr_path_cache();
+        return find_ccr_path();
+    }
+    Some(path)
+}
+
+/// 在所有候选位置中查找 CCR（找到后写入缓存）
+fn discover_ccr_path() -> Option<String> {
     // 硬编码检查最常见的路径（针对打包应用的特殊处理）
     let home = std::env::var("HOME").unwrap_or_default();
     let mut hardcoded_paths: Vec<String> = Vec::new();
@@ -551,6 +609,15 @@ pub async fn check_ccr_installation() -> Result<bool, String> {
     Ok(path.is_some())
 }
 
+/// 清除 CCR 路径缓存并重新查找（CCR 重新安装或移动后使用）
+#[tauri::command]
+pub async fn refresh_ccr_path() -> Result<Option<String>, String> {
+    clear_ccr_path_cache();
+    let path = find_ccr_path();
+    info!("Refreshed CCR path: {:?}", path);
+    Ok(path)
+}
+
 /// 获取 CCR 版本信息
 #[tauri::command]
 pub async fn get_ccr_version() -> Result<String, String> {
@@ -591,6 +658,76 @@ pub async fn get_ccr_version() -> Result<String, String> {
     Err("Unable to get CCR version".to_string())
 }
 
+/// 构造 CCR 子命令（Node.js 安装的路径通过 shell 执行）
+fn ccr_status_command(ccr_path: &str, args: &[&str]) -> Command {
+    let mut cmd = if ccr_path.contains("node_modules") || ccr_path.contains(".nvm") {
+        // 如果是 Node.js 安装的路径，可能需要使用 node 来执行
+        let mut c = Command::new("sh");
+        c.arg("-c").arg(format!("{} {}", ccr_path, args.join(" ")));
+        c
+    } else {
+        let mut c = Command::new(ccr_path);
+        c.args(args);
+        c
+    };
+    cmd.env("PATH", get_extended_path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd
+}
+
+/// `ccr status --json` 解析结果
+#[derive(Debug, PartialEq)]
+struct ParsedCcrStatus {
+    is_running: bool,
+    port: Option<u16>,
+    endpoint: Option<String>,
+    process_id: Option<u32>,
+}
+
+/// 解析 `ccr status --json` 的输出，输出不是 JSON 时返回 None
+fn parse_ccr_status_json(output: &str) -> Option<ParsedCcrStatus> {
+    // 部分版本会在 JSON 前后打印横幅，只截取对象部分
+    let start = output.find('{')?;
+    let end = output.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(output.get(start..=end)?).ok()?;
+    let obj = value.as_object()?;
+
+    let field = |names: &[&str]| names.iter().find_map(|name| obj.get(*name));
+    let as_number = |value: &serde_json::Value| {
+        value
+            .as_u64()
+            .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+    };
+
+    let is_running = match field(&["running", "isRunning"]) {
+        Some(value) => value.as_bool()?,
+        None => field(&["status"])?
+            .as_str()?
+            .eq_ignore_ascii_case("running"),
+    };
+    let port = field(&["port"])
+        .and_then(as_number)
+        .and_then(|n| u16::try_from(n).ok());
+    let process_id = field(&["pid", "processId"])
+        .and_then(as_number)
+        .and_then(|n| u32::try_from(n).ok());
+    let endpoint = field(&["endpoint", "apiEndpoint", "url"])
+        .and_then(|value| value.as_str())
+        .map(|s| s.to_string())
+        .or_else(|| {
+            port.filter(|_| is_running)
+                .map(|port| format!("http://127.0.0.1:{}", port))
+        });
+
+    Some(ParsedCcrStatus {
+        is_running,
+        port,
+        endpoint,
+        process_id,
+    })
+}
+
 /// 检查 CCR 服务状态
 #[tauri::command]
 pub async fn get_ccr_service_status() -> Result<CcrServiceStatus, String> {
@@ -734,24 +871,28 @@ pub async fn get_ccr_service_status() -> Result<CcrServiceStatus, String> {
     // 获取 CCR 路径
     let ccr_path = find_ccr_path().ok_or("CCR not found")?;
 
+    // 优先使用结构化输出，不支持时回退到文本解析
+    if let Ok(output) = ccr_status_command(&ccr_path, &["status", "--json"]).output() {
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Some(parsed) = parse_ccr_status_json(&stdout) {
+                info!("Parsed CCR JSON status: {:?}", parsed);
+                return Ok(CcrServiceStatus {
+                    is_running: parsed.is_running,
+                    port: parsed.port,
+                    endpoint: parsed.endpoint,
+                    has_ccr_binary,
+                    ccr_version,
+                    process_id: parsed.process_id,
+                    raw_output: Some(stdout.to_string()),
+                });
+            }
+        }
+        debug!("CCR status --json not supported, falling back to text parsing");
+    }
+
     // 检查服务状态
-    let mut cmd = if ccr_path.contains("node_modules") || ccr_path.contains(".nvm") {
-        // 如果是 Node.js 安装的路径，可能需要使用 node 来执行
-        let mut c = Command::new("sh");
-        c.arg("-c")
-            .arg(format!("{} status", ccr_path))
-            .env("PATH", get_extended_path())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-        c
-    } else {
-        let mut c = Command::new(&ccr_path);
-        c.arg("status")
-            .env("PATH", get_extended_path())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-        c
-    };
+    let mut cmd = ccr_status_command(&ccr_path, &["status"]);
 
     info!("Executing ccr status command at path: {}", ccr_path);
     let output = cmd.output();
@@ -929,15 +1070,30 @@ pub async fn get_ccr_service_status() -> Result<CcrServiceStatus, String> {
 }
 
 /// 启动 CCR 服务
+/// 已有中转站接管 Claude 配置时拒绝启动，除非 `force` 为 true
 #[tauri::command]
-pub async fn start_ccr_service() -> Result<CcrServiceInfo, String> {
+pub async fn start_ccr_service(
+    force: Option<bool>,
+    db: State<'_, AgentDb>,
+) -> Result<CcrServiceInfo, String> {
     // 先检查是否已安装
     if !check_ccr_installation().await.unwrap_or(false) {
         return Err("CCR is not installed. Please install claude-code-router first.".to_string());
     }
 
+    // 中转站与 CCR 会互相覆盖 Claude 配置，需要用户明确选择
+    if !force.unwrap_or(false) {
+        if let Some(station) = load_enabled_relay_station(&db)? {
+            return Err(format!(
+                "{} ({})",
+                i18n::t("ccr.relay_station_conflict"),
+                station.name
+            ));
+        }
+    }
+
     // 获取 CCR 路径
-    let ccr_path = find_ccr_path().ok_or("CCR not found")?;
+    let ccr_path = find_verified_ccr_path().ok_or("CCR not found")?;
 
     // 检查当前状态
     let current_status = get_ccr_service_status().await?;
@@ -1038,7 +1194,7 @@ pub async fn restart_ccr_service() -> Result<CcrServiceInfo, String> {
 
 /// 打开 CCR UI
 #[tauri::command]
-pub async fn open_ccr_ui() -> Result<String, String> {
+pub async fn open_ccr_ui(db: State<'_, AgentDb>) -> Result<String, String> {
     if !check_ccr_installation().await.unwrap_or(false) {
         return Err("CCR is not installed".to_string());
     }
@@ -1047,7 +1203,7 @@ pub async fn open_ccr_ui() -> Result<String, String> {
     let status = get_ccr_service_status().await?;
     if !status.is_running {
         // 如果服务未运行，尝试启动
-        let _start_result = start_ccr_service().await?;
+        let _start_result = start_ccr_service(None, db).await?;
         // 再等待一下
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     }
@@ -1065,12 +1221,466 @@ pub async fn open_ccr_ui() -> Result<String, String> {
     Ok("CCR UI opening...".to_string())
 }
 
+/// CCR 配置文件路径
+fn ccr_config_file() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join(".claude-code-router").join("config.json"))
+}
+
+/// 让 Claude 子进程经由本地 CCR 转发所需的环境变量（与 `ccr code` 设置的一致）
+pub fn ccr_client_env(port: u16) -> Vec<(String, String)> {
+    let api_key = ccr_config_file()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|config| {
+            config
+                .get("APIKEY")
+                .and_then(|key| key.as_str())
+                .map(|key| key.to_string())
+        })
+        .filter(|key| !key.is_empty())
+        .unwrap_or_else(|| "test".to_string());
+
+    vec![
+        (
+            "ANTHROPIC_BASE_URL".to_string(),
+            format!("http://127.0.0.1:{}", port),
+        ),
+        ("ANTHROPIC_AUTH_TOKEN".to_string(), api_key),
+    ]
+}
+
 /// 获取 CCR 配置路径
 #[tauri::command]
 pub async fn get_ccr_config_path() -> Result<String, String> {
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-
-    let config_path = home_dir.join(".claude-code-router").join("config.json");
+    let config_path = ccr_config_file()?;
 
     Ok(config_path.to_string_lossy().to_string())
 }
+
+/// 校验 CCR 配置结构（Providers 数组与 Router 映射），返回具体的错误位置
+fn validate_ccr_config(config: &serde_json::Value) -> Result<(), String> {
+    let obj = config
+        .as_object()
+        .ok_or("CCR config must be a JSON object")?;
+
+    let mut provider_names = Vec::new();
+    if let Some(providers) = obj.get("Providers") {
+        let providers = providers
+            .as_array()
+            .ok_or("\"Providers\" must be an array")?;
+        for (i, provider) in providers.iter().enumerate() {
+            let provider = provider
+                .as_object()
+                .ok_or_else(|| format!("Providers[{}] must be an object", i))?;
+
+            let name = provider
+                .get("name")
+                .and_then(|v| v.as_str())
+                .filter(|name| !name.trim().is_empty())
+                .ok_or_else(|| format!("Providers[{}].name must be a non-empty string", i))?;
+            if provider_names.contains(&name) {
+                return Err(format!("Providers[{}].name \"{}\" is duplicated", i, name));
+            }
+
+            if provider
+                .get("api_base_url")
+                .and_then(|v| v.as_str())
+                .is_none_or(|url| url.trim().is_empty())
+            {
+                return Err(format!(
+                    "Providers[{}].api_base_url must be a non-empty string",
+                    i
+                ));
+            }
+
+            let models = provider
+                .get("models")
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| format!("Providers[{}].models must be an array", i))?;
+            if let Some(j) = models.iter().position(|m| !m.is_string()) {
+                return Err(format!("Providers[{}].models[{}] must be a string", i, j));
+            }
+
+            provider_names.push(name);
+        }
+    }
+
+    if let Some(router) = obj.get("Router") {
+        let router = router.as_object().ok_or("\"Router\" must be an object")?;
+        for (key, value) in router {
+            // 路由项格式为 "provider,model"，非字符串的值（如阈值）不做校验
+            let Some(route) = value.as_str() else {
+                continue;
+            };
+            if route.is_empty() {
+                continue;
+            }
+            let (provider, model) = route
+                .split_once(',')
+                .ok_or_else(|| format!("Router.{} must use the \"provider,model\" format", key))?;
+            if model.trim().is_empty() {
+                return Err(format!("Router.{} is missing a model name", key));
+            }
+            if !provider_names.contains(&provider.trim()) {
+                return Err(format!(
+                    "Router.{} refers to unknown provider \"{}\"",
+                    key,
+                    provider.trim()
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取 CCR 配置（文件不存在时返回空配置骨架）
+#[tauri::command]
+pub async fn read_ccr_config() -> Result<serde_json::Value, String> {
+    let config_path = ccr_config_file()?;
+    if !config_path.exists() {
+        return Ok(serde_json::json!({ "Providers": [], "Router": {} }));
+    }
+
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read CCR config: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse CCR config: {}", e))
+}
+
+/// 校验并写入 CCR 配置（先备份为 config.json.bak，再原子替换）
+/// `restart` 为 true 且服务正在运行时自动重启 CCR 使配置生效
+#[tauri::command]
+pub async fn write_ccr_config(
+    config: serde_json::Value,
+    restart: Option<bool>,
+) -> Result<String, String> {
+    validate_ccr_config(&config)?;
+
+    let config_path = ccr_config_file()?;
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create CCR config directory: {}", e))?;
+    }
+
+    if config_path.exists() {
+        std::fs::copy(&config_path, config_path.with_extension("json.bak"))
+            .map_err(|e| format!("Failed to back up CCR config: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize CCR config: {}", e))?;
+    let tmp_path = config_path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content).map_err(|e| format!("Failed to write CCR config: {}", e))?;
+    std::fs::rename(&tmp_path, &config_path)
+        .map_err(|e| format!("Failed to replace CCR config: {}", e))?;
+
+    info!("Saved CCR config to {:?}", config_path);
+
+    if restart.unwrap_or(false) {
+        let status = get_ccr_service_status().await?;
+        if status.is_running {
+            restart_ccr_service().await?;
+            return Ok("CCR config saved and service restarted".to_string());
+        }
+    }
+
+    Ok("CCR config saved".to_string())
+}
+
+/// CCR 的 npm 包名
+const CCR_NPM_PACKAGE: &str = "@musistudio/claude-code-router";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CcrInstallResult {
+    pub success: bool,
+    pub message: String,
+    pub ccr_path: Option<String>, // 安装后重新查找到的 CCR 路径
+}
+
+/// 通过 npm 全局安装 CCR，安装输出逐行通过 `ccr-install-output` 事件发送
+/// 这是一次全局安装，前端需在用户确认后以 `confirmed = true` 调用
+#[tauri::command]
+pub async fn install_ccr(app: AppHandle, confirmed: bool) -> Result<CcrInstallResult, String> {
+    if !confirmed {
+        return Err(
+            "Installing CCR runs a global npm install and must be confirmed first".to_string(),
+        );
+    }
+
+    let extended_path = get_extended_path();
+    let npm_path = which::which_in("npm", Some(&extended_path), std::env::temp_dir()).map_err(|_| {
+        "npm was not found. Install Node.js (which includes npm) from https://nodejs.org and try again.".to_string()
+    })?;
+
+    info!("Installing {} with npm at {:?}", CCR_NPM_PACKAGE, npm_path);
+    let mut child = tokio::process::Command::new(&npm_path)
+        .args(["install", "-g", CCR_NPM_PACKAGE])
+        .env("PATH", &extended_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run npm: {}", e))?;
+
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(emit_install_output(app.clone(), stdout));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(emit_install_output(app.clone(), stderr));
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to wait for npm: {}", e))?;
+    for reader in readers {
+        let _ = reader.await;
+    }
+
+    // 无论成功与否都清除缓存，确保下次状态检查重新查找
+    clear_ccr_path_cache();
+
+    if !status.success() {
+        error!("npm install for CCR failed: {:?}", status.code());
+        return Ok(CcrInstallResult {
+            success: false,
+            message: format!(
+                "npm install failed (exit code {}). See the install output for details.",
+                status
+                    .code()
+                    .map_or_else(|| "unknown".to_string(), |c| c.to_string())
+            ),
+            ccr_path: None,
+        });
+    }
+
+    let ccr_path = find_ccr_path();
+    info!("CCR installed, discovered path: {:?}", ccr_path);
+    Ok(CcrInstallResult {
+        success: true,
+        message: "CCR installed successfully".to_string(),
+        ccr_path,
+    })
+}
+
+/// 逐行读取安装输出并发送事件
+fn emit_install_output<R>(app: AppHandle, reader: R) -> tokio::task::JoinHandle<()>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = app.emit("ccr-install-output", &line);
+        }
+    })
+}
+
+/// 日志快照最多读取的字节数
+const CCR_LOG_TAIL_BYTES: u64 = 64 * 1024;
+
+/// 跟踪日志时的轮询间隔
+const CCR_LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// 当前日志跟踪任务的停止标志
+static CCR_LOG_FOLLOWER: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CcrLogSnapshot {
+    pub path: String,
+    pub is_running: bool,
+    pub content: String, // 日志末尾内容（服务未运行时可用于排查启动失败原因）
+}
+
+/// 定位 CCR 日志文件：优先使用 logs/ 目录下最新的日志，其次是旧版本的单一日志文件
+fn find_ccr_log_path() -> Result<PathBuf, String> {
+    let ccr_dir = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claude-code-router");
+
+    let latest = std::fs::read_dir(ccr_dir.join("logs"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path);
+
+    Ok(latest.unwrap_or_else(|| ccr_dir.join("claude-code-router.log")))
+}
+
+/// 读取日志末尾内容，截断时丢弃不完整的首行
+fn read_log_tail(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open CCR log: {}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read CCR log metadata: {}", e))?
+        .len();
+    let start = len.saturating_sub(CCR_LOG_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to seek CCR log: {}", e))?;
+
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read CCR log: {}", e))?;
+    let content = String::from_utf8_lossy(&buf).into_owned();
+
+    if start > 0 {
+        if let Some(pos) = content.find('\n') {
+            return Ok(content[pos + 1..].to_string());
+        }
+    }
+    Ok(content)
+}
+
+/// 停止当前的日志跟踪任务（如有）
+fn stop_log_follower() -> bool {
+    if let Ok(mut follower) = CCR_LOG_FOLLOWER.lock() {
+        if let Some(stop) = follower.take() {
+            stop.store(true, Ordering::SeqCst);
+            return true;
+        }
+    }
+    false
+}
+
+/// 获取 CCR 日志文件路径
+#[tauri::command]
+pub async fn get_ccr_log_path() -> Result<String, String> {
+    Ok(find_ccr_log_path()?.to_string_lossy().to_string())
+}
+
+/// 开始跟踪 CCR 日志，新增的每一行通过 `ccr-log` 事件发送
+/// 返回日志末尾内容，服务未运行时可直接用于查看启动失败原因
+#[tauri::command]
+pub async fn follow_ccr_logs(app: AppHandle) -> Result<CcrLogSnapshot, String> {
+    let path = find_ccr_log_path()?;
+    let is_running = get_ccr_service_status()
+        .await
+        .map(|status| status.is_running)
+        .unwrap_or(false);
+
+    let content = if path.exists() {
+        read_log_tail(&path)?
+    } else {
+        String::new()
+    };
+    let mut offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    stop_log_follower();
+    let stop = Arc::new(AtomicBool::new(false));
+    if let Ok(mut follower) = CCR_LOG_FOLLOWER.lock() {
+        *follower = Some(stop.clone());
+    }
+
+    let follow_path = path.clone();
+    tokio::spawn(async move {
+        let mut pending = String::new();
+        while !stop.load(Ordering::SeqCst) {
+            tokio::time::sleep(CCR_LOG_POLL_INTERVAL).await;
+
+            let len = match std::fs::metadata(&follow_path) {
+                Ok(metadata) => metadata.len(),
+                Err(_) => continue,
+            };
+            if len < offset {
+                // 日志被截断或轮转，从头开始读取
+                offset = 0;
+                pending.clear();
+            }
+            if len == offset {
+                continue;
+            }
+
+            let mut file = match File::open(&follow_path) {
+                Ok(file) => file,
+                Err(e) => {
+                    debug!("Failed to open CCR log for following: {}", e);
+                    continue;
+                }
+            };
+            let mut buf = Vec::new();
+            if file.seek(SeekFrom::Start(offset)).is_err() || file.read_to_end(&mut buf).is_err() {
+                continue;
+            }
+            offset += buf.len() as u64;
+
+            pending.push_str(&String::from_utf8_lossy(&buf));
+            while let Some(pos) = pending.find('\n') {
+                let line: String = pending.drain(..=pos).collect();
+                let _ = app.emit("ccr-log", line.trim_end_matches(['\r', '\n']));
+            }
+        }
+        debug!("Stopped following CCR log: {:?}", follow_path);
+    });
+
+    info!("Following CCR log: {:?}", path);
+    Ok(CcrLogSnapshot {
+        path: path.to_string_lossy().to_string(),
+        is_running,
+        content,
+    })
+}
+
+/// 停止跟踪 CCR 日志
+#[tauri::command]
+pub async fn stop_following_ccr_logs() -> Result<bool, String> {
+    Ok(stop_log_follower())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_ccr_config_reports_precise_errors() {
+        let valid = serde_json::json!({
+            "Providers": [{ "name": "openrouter", "api_base_url": "https://openrouter.ai/api/v1/chat/completions", "models": ["claude-sonnet-4"] }],
+            "Router": { "default": "openrouter,claude-sonnet-4", "longContextThreshold": 60000 }
+        });
+        assert!(validate_ccr_config(&valid).is_ok());
+
+        let missing_models = serde_json::json!({
+            "Providers": [{ "name": "a", "api_base_url": "https://a" }]
+        });
+        assert_eq!(
+            validate_ccr_config(&missing_models).unwrap_err(),
+            "Providers[0].models must be an array"
+        );
+
+        let unknown_provider = serde_json::json!({
+            "Providers": [],
+            "Router": { "default": "missing,model" }
+        });
+        assert_eq!(
+            validate_ccr_config(&unknown_provider).unwrap_err(),
+            "Router.default refers to unknown provider \"missing\""
+        );
+    }
+
+    #[test]
+    fn parse_ccr_status_json_reads_structured_fields() {
+        let output = "banner\n{\"running\":true,\"port\":3456,\"pid\":\"4242\"}\n";
+        assert_eq!(
+            parse_ccr_status_json(output),
+            Some(ParsedCcrStatus {
+                is_running: true,
+                port: Some(3456),
+                endpoint: Some("http://127.0.0.1:3456".to_string()),
+                process_id: Some(4242),
+            })
+        );
+
+        let stopped = parse_ccr_status_json("{\"status\":\"stopped\"}").unwrap();
+        assert!(!stopped.is_running);
+        assert!(stopped.endpoint.is_none());
+
+        assert_eq!(parse_ccr_status_json("📊 Status: Running"), None);
+    }
+}
@@ -1,15 +1,26 @@
 use anyhow::{Context, Result};
+use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::SystemTime;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 
+use crate::commands::agents::AgentDb;
+use crate::commands::filesystem::{symlink_target, SymlinkPolicy};
+use crate::commands::project_budgets::check_project_budget;
+use crate::commands::search_ignore::{
+    load_search_ignore_settings, IgnoreMatcher, SearchIgnoreSettings,
+};
+use crate::commands::session_compare::session_prompts;
+use crate::commands::session_export::session_file;
+use crate::utils::jsonl::{self, JsonlReader};
+
 /// Global state to track current Claude process
 pub struct ClaudeProcessState {
     pub current_process: Arc<Mutex<Option<Child>>>,
@@ -36,6 +47,12 @@ pub struct Project {
     pub created_at: u64,
     /// Unix timestamp of the most recent session (last modified time of newest JSONL file)
     pub last_session_time: u64,
+    /// Whether the user pinned this project as a favorite
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Optional user-chosen color tag (e.g. "#3b82f6")
+    #[serde(default)]
+    pub color: Option<String>,
 }
 
 /// Represents a session with its metadata
@@ -126,6 +143,62 @@ pub struct FileEntry {
     pub size: u64,
     /// File extension (if applicable)
     pub extension: Option<String>,
+    /// Whether the name starts with a dot
+    #[serde(default)]
+    pub is_hidden: bool,
+    /// Whether the entry itself is a symbolic link
+    #[serde(default)]
+    pub is_symlink: bool,
+    /// Resolved target of a symbolic link
+    #[serde(default)]
+    pub symlink_target: Option<String>,
+}
+
+/// Result of running a Claude CLI diagnostic command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticResult {
+    /// The command line that was executed
+    pub command: String,
+    /// Captured standard output
+    pub stdout: String,
+    /// Captured standard error
+    pub stderr: String,
+    /// Process exit code (None if killed or timed out)
+    pub exit_code: Option<i32>,
+    /// Whether the command was killed after exceeding the timeout
+    pub timed_out: bool,
+    /// Wall-clock duration in milliseconds
+    pub duration_ms: u64,
+}
+
+/// Default timeout for diagnostic commands
+const DIAGNOSTIC_TIMEOUT_SECS: u64 = 30;
+
+/// Output format requested from the Claude CLI
+///
+/// `stream-json` is the default and is required for session ID extraction, checkpoint
+/// message tracking and live token metrics. `text` emits raw lines on the
+/// `claude-text:{session_id}` channel and only reports completion via the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    StreamJson,
+    Text,
+}
+
+impl OutputFormat {
+    /// CLI arguments selecting this output format
+    fn cli_args(&self) -> Vec<String> {
+        match self {
+            OutputFormat::StreamJson => vec![
+                "--output-format".to_string(),
+                "stream-json".to_string(),
+                "--verbose".to_string(),
+            ],
+            OutputFormat::Text => vec!["--output-format".to_string(), "text".to_string()],
+        }
+    }
 }
 
 /// Finds the full path to the claude binary
@@ -134,42 +207,183 @@ fn find_claude_binary(app_handle: &AppHandle) -> Result<String, String> {
     crate::claude_binary::find_claude_binary(app_handle)
 }
 
-/// Gets the path to the ~/.claude directory
-fn get_claude_dir() -> Result<PathBuf> {
-    dirs::home_dir()
-        .context("Could not find home directory")?
-        .join(".claude")
-        .canonicalize()
-        .context("Could not find ~/.claude directory")
+/// App setting holding a user-chosen Claude config directory
+pub const CLAUDE_DIR_SETTING_KEY: &str = "claude_config_dir";
+
+/// Override from the app settings, loaded at startup; takes precedence over `CLAUDE_CONFIG_DIR`
+static CLAUDE_DIR_OVERRIDE: once_cell::sync::Lazy<std::sync::RwLock<Option<PathBuf>>> =
+    once_cell::sync::Lazy::new(|| std::sync::RwLock::new(None));
+
+/// Where the Claude config directory location came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaudeDirSource {
+    Setting,
+    Env,
+    Default,
 }
 
-/// Gets the actual project path by reading the cwd from the first JSONL entry
-fn get_project_path_from_sessions(project_dir: &PathBuf) -> Result<String, String> {
-    // Try to read any JSONL file in the directory
-    let entries = fs::read_dir(project_dir)
-        .map_err(|e| format!("Failed to read project directory: {}", e))?;
+/// Sets (or clears) the app setting override of the Claude config directory
+pub(crate) fn set_claude_dir_override(dir: Option<PathBuf>) {
+    if let Ok(mut current) = CLAUDE_DIR_OVERRIDE.write() {
+        *current = dir;
+    }
+}
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                // Read the first line of the JSONL file
-                if let Ok(file) = fs::File::open(&path) {
-                    let reader = BufReader::new(file);
-                    if let Some(Ok(first_line)) = reader.lines().next() {
-                        // Parse the JSON and extract cwd
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&first_line) {
-                            if let Some(cwd) = json.get("cwd").and_then(|v| v.as_str()) {
-                                return Ok(cwd.to_string());
-                            }
-                        }
-                    }
-                }
+/// Loads the Claude config directory override from the app settings
+pub fn load_claude_dir_override(conn: &rusqlite::Connection) {
+    let dir = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![CLAUDE_DIR_SETTING_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(PathBuf::from);
+    set_claude_dir_override(dir);
+}
+
+/// Resolves the Claude config directory without touching the filesystem:
+/// the app setting, then `CLAUDE_CONFIG_DIR`, then `~/.claude`
+pub(crate) fn resolve_claude_dir() -> Result<(PathBuf, ClaudeDirSource)> {
+    if let Some(dir) = CLAUDE_DIR_OVERRIDE.read().ok().and_then(|d| d.clone()) {
+        return Ok((dir, ClaudeDirSource::Setting));
+    }
+    if let Some(dir) = std::env::var_os("CLAUDE_CONFIG_DIR").filter(|d| !d.is_empty()) {
+        return Ok((PathBuf::from(dir), ClaudeDirSource::Env));
+    }
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    Ok((home.join(".claude"), ClaudeDirSource::Default))
+}
+
+/// Subdirectories Claude creates on first run; created up front so a fresh install looks empty
+/// rather than broken
+const CLAUDE_DIR_SUBDIRS: &[&str] = &["projects", "todos"];
+
+/// Gets the path to the Claude config directory (~/.claude unless relocated), creating it if missing
+pub(crate) fn get_claude_dir() -> Result<PathBuf> {
+    let (dir, _) = resolve_claude_dir()?;
+    if !dir.exists() {
+        log::info!("Creating Claude directory at {:?}", dir);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create Claude directory {}", dir.display()))?;
+    }
+    for subdir in CLAUDE_DIR_SUBDIRS {
+        let path = dir.join(subdir);
+        if !path.exists() {
+            if let Err(e) = fs::create_dir_all(&path) {
+                log::warn!("Failed to create {:?}: {}", path, e);
+            }
+        }
+    }
+    dir.canonicalize()
+        .with_context(|| format!("Could not resolve Claude directory {}", dir.display()))
+}
+
+/// Checks that a directory exists and that files can be created in it
+pub(crate) fn validate_writable_dir(dir: &std::path::Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
+    }
+    let probe = dir.join(format!(".claudia-write-test-{}", std::process::id()));
+    fs::write(&probe, b"")
+        .map_err(|e| format!("Directory is not writable: {} ({})", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// The Claude config directory in use and where its location came from
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeDirInfo {
+    pub path: String,
+    pub source: ClaudeDirSource,
+    pub exists: bool,
+}
+
+/// Gets the Claude config directory in use
+#[tauri::command]
+pub async fn get_claude_config_dir() -> Result<ClaudeDirInfo, String> {
+    let (dir, source) = resolve_claude_dir().map_err(|e| e.to_string())?;
+    Ok(ClaudeDirInfo {
+        path: dir.to_string_lossy().to_string(),
+        source,
+        exists: dir.is_dir(),
+    })
+}
+
+/// Overrides the Claude config directory; `None` falls back to `CLAUDE_CONFIG_DIR` or ~/.claude
+#[tauri::command]
+pub async fn set_claude_config_dir(
+    db: State<'_, AgentDb>,
+    checkpoint_state: State<'_, crate::checkpoint::state::CheckpointState>,
+    path: Option<String>,
+) -> Result<ClaudeDirInfo, String> {
+    let dir = path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        match &dir {
+            Some(dir) => {
+                validate_writable_dir(dir)?;
+                conn.execute(
+                    "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+                     ON CONFLICT(key) DO UPDATE SET value = ?2",
+                    params![CLAUDE_DIR_SETTING_KEY, dir.to_string_lossy().to_string()],
+                )
             }
+            None => conn.execute(
+                "DELETE FROM app_settings WHERE key = ?1",
+                params![CLAUDE_DIR_SETTING_KEY],
+            ),
         }
+        .map_err(|e| format!("Failed to save Claude directory setting: {}", e))?;
     }
 
-    Err("Could not determine project path from session files".to_string())
+    set_claude_dir_override(dir);
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    checkpoint_state.set_claude_dir(claude_dir).await;
+    get_claude_config_dir().await
+}
+
+/// Reads the cwd from the first line of a session JSONL file
+fn read_session_cwd(path: &Path) -> Result<String, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open: {}", e))?;
+    let first_line = BufReader::new(file)
+        .lines()
+        .next()
+        .ok_or_else(|| "File is empty".to_string())?
+        .map_err(|e| format!("Failed to read first line: {}", e))?;
+    let json: serde_json::Value = serde_json::from_str(&first_line)
+        .map_err(|e| format!("First line is not valid JSON: {}", e))?;
+    json.get("cwd")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .ok_or_else(|| "First line has no cwd".to_string())
+}
+
+/// Session JSONL files of a project directory, in directory order
+fn session_files(project_dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(project_dir)
+        .map_err(|e| format!("Failed to read project directory: {}", e))?;
+
+    Ok(entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .collect())
+}
+
+/// Gets the actual project path by reading the cwd from the first JSONL entry
+fn get_project_path_from_sessions(project_dir: &PathBuf) -> Result<String, String> {
+    // Use the first session file that records a cwd
+    session_files(project_dir)?
+        .iter()
+        .find_map(|path| read_session_cwd(path).ok())
+        .ok_or_else(|| "Could not determine project path from session files".to_string())
 }
 
 /// Decodes a project directory name back to its original path
@@ -182,36 +396,34 @@ fn decode_project_path(encoded: &str) -> String {
     encoded.replace('-', "/")
 }
 
+/// Whether a user message is a caveat or local-command wrapper inserted by Claude Code
+/// rather than something the user typed
+pub(crate) fn is_command_wrapper_message(content: &str) -> bool {
+    content.contains(
+        "Caveat: The messages below were generated by the user while running local commands",
+    ) || content.starts_with("<command-name>")
+        || content.starts_with("<local-command-stdout>")
+}
+
 /// Extracts the first valid user message from a JSONL file
-fn extract_first_user_message(jsonl_path: &PathBuf) -> (Option<String>, Option<String>) {
-    let file = match fs::File::open(jsonl_path) {
-        Ok(file) => file,
+pub(crate) fn extract_first_user_message(jsonl_path: &Path) -> (Option<String>, Option<String>) {
+    let mut reader = match JsonlReader::open(jsonl_path) {
+        Ok(reader) => reader,
         Err(_) => return (None, None),
     };
 
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if let Ok(entry) = serde_json::from_str::<JsonlEntry>(&line) {
-                if let Some(message) = entry.message {
-                    if message.role.as_deref() == Some("user") {
-                        if let Some(content) = message.content {
-                            // Skip if it contains the caveat message
-                            if content.contains("Caveat: The messages below were generated by the user while running local commands") {
-                                continue;
-                            }
-
-                            // Skip if it starts with command tags
-                            if content.starts_with("<command-name>")
-                                || content.starts_with("<local-command-stdout>")
-                            {
-                                continue;
-                            }
-
-                            // Found a valid user message
-                            return (Some(content), entry.timestamp);
+    while let Ok(Some((_, value))) = reader.next_value() {
+        if let Ok(entry) = serde_json::from_value::<JsonlEntry>(value) {
+            if let Some(message) = entry.message {
+                if message.role.as_deref() == Some("user") {
+                    if let Some(content) = message.content {
+                        // Skip caveat and command wrapper messages
+                        if is_command_wrapper_message(&content) {
+                            continue;
                         }
+
+                        // Found a valid user message
+                        return (Some(content), entry.timestamp);
                     }
                 }
             }
@@ -227,8 +439,10 @@ fn create_command_with_env(program: &str) -> Command {
     // Convert std::process::Command to tokio::process::Command
     let _std_cmd = crate::claude_binary::create_command_with_env(program);
 
-    // Create a new tokio Command from the program path
-    let mut tokio_cmd = Command::new(program);
+    // Create a new tokio Command from the program path (PowerShell shims run via powershell -File)
+    let (executable, leading_args) = crate::claude_binary::shim_program_and_args(program);
+    let mut tokio_cmd = Command::new(executable);
+    tokio_cmd.args(leading_args);
 
     // Copy over all environment variables
     for (key, value) in std::env::vars() {
@@ -278,34 +492,62 @@ fn create_system_command(claude_path: &str, args: Vec<String>, project_path: &st
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
+    // Run in its own process group so cancellation also reaches MCP subprocesses
+    #[cfg(unix)]
+    cmd.process_group(0);
+
     cmd
 }
 
-/// Starts watching the Claude projects directory for the specific project
+/// Starts watching the Claude projects directory for the specific project. With `check`,
+/// the directory is first tested for native file events and polled if they don't fire.
+/// Returns the watching mode in use.
 #[tauri::command]
 pub async fn watch_claude_project_directory(
     project_path: String,
+    check: Option<bool>,
     app_handle: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<crate::file_watcher::WatchMode, String> {
     use crate::file_watcher::FileWatcherState;
     let project_path_buf = PathBuf::from(&project_path);
 
     // 支持直接传入位于 ~/.claude 或 ~/.claudia 下的特殊目录（例如智能会话）
-    if (project_path.contains("/.claude/") || project_path.contains("/.claudia/"))
+    let (root, project_id) = if (project_path.contains("/.claude/")
+        || project_path.contains("/.claudia/"))
         && project_path_buf.exists()
     {
-        let file_watcher_state = app_handle.state::<FileWatcherState>();
-        let path_str = project_path_buf.to_string_lossy().to_string();
-        return file_watcher_state
-            .with_manager(|manager| manager.watch_path(&path_str, false))
-            .map_err(|e| format!("Failed to watch Claude project directory: {}", e));
-    }
+        (project_path_buf, None)
+    } else {
+        log::info!(
+            "Starting to watch Claude project directory for project: {}",
+            project_path
+        );
+        find_claude_project_dir(&project_path)?
+    };
 
-    log::info!(
-        "Starting to watch Claude project directory for project: {}",
-        project_path
-    );
+    let prefer_polling = if check.unwrap_or(false) {
+        let probe_root = root.clone();
+        let capability = tokio::task::spawn_blocking(move || {
+            crate::file_watcher::probe_watch_capability(&probe_root)
+        })
+        .await
+        .map_err(|e| format!("Failed to check watch capability: {}", e))?;
+        capability.recommended_mode == crate::file_watcher::WatchMode::Polling
+    } else {
+        false
+    };
 
+    let file_watcher_state = app_handle.state::<FileWatcherState>();
+    let path_str = root.to_string_lossy().to_string();
+    file_watcher_state
+        .with_manager(|manager| {
+            manager.watch_path_for(&path_str, false, project_id.as_deref(), prefer_polling)
+        })
+        .map_err(|e| format!("Failed to watch Claude project directory: {}", e))
+}
+
+/// Finds the session directory of a project and its project ID
+fn find_claude_project_dir(project_path: &str) -> Result<(PathBuf, Option<String>), String> {
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let projects_dir = claude_dir.join("projects");
 
@@ -315,25 +557,14 @@ pub async fn watch_claude_project_directory(
 
     // 找到对应项目的目录
     if let Ok(entries) = std::fs::read_dir(&projects_dir) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    // 检查是否是当前项目的目录
-                    if let Ok(found_project_path) = get_project_path_from_sessions(&path) {
-                        if found_project_path == project_path {
-                            // 找到了对应的项目目录，开始监控
-                            let file_watcher_state = app_handle.state::<FileWatcherState>();
-                            let path_str = path.to_string_lossy().to_string();
-
-                            return file_watcher_state
-                                .with_manager(|manager| manager.watch_path(&path_str, false))
-                                .map_err(|e| {
-                                    format!("Failed to watch Claude project directory: {}", e)
-                                });
-                        }
-                    }
-                }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // 检查是否是当前项目的目录
+            if path.is_dir()
+                && get_project_path_from_sessions(&path).is_ok_and(|found| found == project_path)
+            {
+                let project_id = entry.file_name().to_string_lossy().to_string();
+                return Ok((path, Some(project_id)));
             }
         }
     }
@@ -402,11 +633,150 @@ pub async fn unwatch_claude_project_directory(
     Ok(())
 }
 
+/// Watches the session directories of several projects at once, e.g. everything the
+/// dashboard displays. Each call takes a reference on every directory, so release them with
+/// `unwatch_projects` rather than stopping watches other views still need.
+#[tauri::command]
+pub async fn watch_projects(
+    project_ids: Vec<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<crate::file_watcher::WatchedPath>, String> {
+    use crate::file_watcher::FileWatcherState;
+
+    let projects_dir = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects");
+    let mut roots = Vec::with_capacity(project_ids.len());
+    for project_id in &project_ids {
+        validate_project_id(project_id)?;
+        let project_dir = projects_dir.join(project_id);
+        if !project_dir.is_dir() {
+            return Err(format!("Project not found: {}", project_id));
+        }
+        roots.push((project_id, project_dir.to_string_lossy().to_string()));
+    }
+
+    let file_watcher_state = app_handle.state::<FileWatcherState>();
+    file_watcher_state.with_manager(|manager| {
+        for (i, (project_id, root)) in roots.iter().enumerate() {
+            if let Err(e) = manager.watch_path_for(root, false, Some(project_id.as_str()), false) {
+                // Release the references this call already took
+                for (_, watched) in &roots[..i] {
+                    let _ = manager.unwatch_path(watched);
+                }
+                return Err(format!("Failed to watch project {}: {}", project_id, e));
+            }
+        }
+        Ok(manager.get_watched_paths())
+    })
+}
+
+/// Releases the references `watch_projects` took; a directory stops being watched once no
+/// other view needs it
+#[tauri::command]
+pub async fn unwatch_projects(
+    project_ids: Vec<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    use crate::file_watcher::FileWatcherState;
+
+    let projects_dir = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects");
+    let file_watcher_state = app_handle.state::<FileWatcherState>();
+    file_watcher_state.with_manager(|manager| {
+        for project_id in &project_ids {
+            validate_project_id(project_id)?;
+            let root = projects_dir.join(project_id).to_string_lossy().to_string();
+            if let Err(e) = manager.unwatch_path(&root) {
+                log::debug!("Project {} was not watched: {}", project_id, e);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Loads favorite/color metadata keyed by the encoded project directory name
+fn load_project_metadata(
+    db: &AgentDb,
+) -> Result<std::collections::HashMap<String, (bool, Option<String>)>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT project_id, is_favorite, color FROM project_metadata")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (row.get::<_, bool>(1)?, row.get::<_, Option<String>>(2)?),
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    let metadata = rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?;
+    Ok(metadata)
+}
+
+/// Validates a color tag in `#RGB` or `#RRGGBB` form
+fn is_valid_project_color(color: &str) -> bool {
+    color.strip_prefix('#').is_some_and(|hex| {
+        (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// Marks or unmarks a project as favorite (favorites are listed first)
+#[tauri::command]
+pub async fn set_project_favorite(
+    db: State<'_, AgentDb>,
+    project_id: String,
+    is_favorite: bool,
+) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO project_metadata (project_id, is_favorite) VALUES (?1, ?2)
+         ON CONFLICT(project_id) DO UPDATE SET is_favorite = excluded.is_favorite, updated_at = CURRENT_TIMESTAMP",
+        params![project_id, is_favorite],
+    )
+    .map_err(|e| format!("Failed to update project favorite: {}", e))?;
+    Ok(())
+}
+
+/// Sets or clears (with `None`) the color tag of a project
+#[tauri::command]
+pub async fn set_project_color(
+    db: State<'_, AgentDb>,
+    project_id: String,
+    color: Option<String>,
+) -> Result<(), String> {
+    if let Some(color) = &color {
+        if !is_valid_project_color(color) {
+            return Err(format!(
+                "Invalid color '{}', expected #RGB or #RRGGBB",
+                color
+            ));
+        }
+    }
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO project_metadata (project_id, color) VALUES (?1, ?2)
+         ON CONFLICT(project_id) DO UPDATE SET color = excluded.color, updated_at = CURRENT_TIMESTAMP",
+        params![project_id, color],
+    )
+    .map_err(|e| format!("Failed to update project color: {}", e))?;
+    Ok(())
+}
+
 /// Lists all projects in the ~/.claude/projects directory
 #[tauri::command]
-pub async fn list_projects() -> Result<Vec<Project>, String> {
+pub async fn list_projects(db: State<'_, AgentDb>) -> Result<Vec<Project>, String> {
     log::info!("Listing projects from ~/.claude/projects");
 
+    // Metadata is keyed by the encoded directory name so it survives path re-resolution
+    let project_metadata = load_project_metadata(&db).unwrap_or_else(|e| {
+        log::warn!("Failed to load project metadata: {}", e);
+        Default::default()
+    });
+
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
     let projects_dir = claude_dir.join("projects");
 
@@ -485,18 +855,26 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
                 }
             }
 
+            let (is_favorite, color) = project_metadata.get(dir_name).cloned().unwrap_or_default();
+
             projects.push(Project {
                 id: dir_name.to_string(),
                 path: project_path,
                 sessions,
                 created_at,
                 last_session_time,
+                is_favorite,
+                color,
             });
         }
     }
 
-    // Sort projects by last session time (newest first)
-    projects.sort_by(|a, b| b.last_session_time.cmp(&a.last_session_time));
+    // Sort favorites first, then by last session time (newest first) within each group
+    projects.sort_by(|a, b| {
+        b.is_favorite
+            .cmp(&a.is_favorite)
+            .then_with(|| b.last_session_time.cmp(&a.last_session_time))
+    });
 
     log::info!("Found {} projects", projects.len());
     Ok(projects)
@@ -512,7 +890,8 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
     let todos_dir = claude_dir.join("todos");
 
     if !project_dir.exists() {
-        return Err(format!("Project directory not found: {}", project_id));
+        log::warn!("Project directory not found: {:?}", project_dir);
+        return Ok(Vec::new());
     }
 
     // Get the actual project path from JSONL files
@@ -589,6 +968,320 @@ pub async fn get_project_sessions(project_id: String) -> Result<Vec<Session>, St
     Ok(sessions)
 }
 
+/// Project directories that resolve to the same working directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateProjectGroup {
+    /// The working directory shared by every project in the group
+    pub path: String,
+    /// Encoded project directory names, sorted
+    pub project_ids: Vec<String>,
+}
+
+/// Outcome of merging project directories into one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeProjectsResult {
+    pub target_id: String,
+    /// Session IDs moved into the target directory
+    pub moved_sessions: Vec<String>,
+    /// Source directories that were empty after the move and have been removed
+    pub removed_projects: Vec<String>,
+}
+
+/// Rejects project IDs that would escape the projects directory
+pub(crate) fn validate_project_id(project_id: &str) -> Result<(), String> {
+    if project_id.is_empty()
+        || project_id == "."
+        || project_id == ".."
+        || project_id.contains('/')
+        || project_id.contains('\\')
+    {
+        return Err(format!("Invalid project ID: {}", project_id));
+    }
+    Ok(())
+}
+
+/// Session IDs (JSONL file stems) stored in a project directory
+fn project_session_ids(project_dir: &PathBuf) -> Result<Vec<String>, String> {
+    Ok(session_files(project_dir)?
+        .iter()
+        .filter_map(|path| path.file_stem().and_then(|s| s.to_str()).map(String::from))
+        .collect())
+}
+
+/// Rewrites every `projectId` equal to `from` in a checkpoint JSON document
+fn replace_checkpoint_project_id(value: &mut serde_json::Value, from: &str, to: &str) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "projectId" && child.as_str() == Some(from) {
+                    *child = serde_json::Value::String(to.to_string());
+                } else {
+                    replace_checkpoint_project_id(child, from, to);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                replace_checkpoint_project_id(item, from, to);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Points the timeline and checkpoint metadata of a moved session at its new project
+fn retarget_session_checkpoints(timeline_dir: &Path, from: &str, to: &str) -> Result<(), String> {
+    let mut files = vec![timeline_dir.join("timeline.json")];
+    if let Ok(entries) = fs::read_dir(timeline_dir.join("checkpoints")) {
+        files.extend(entries.flatten().map(|e| e.path().join("metadata.json")));
+    }
+
+    for file in files.into_iter().filter(|f| f.is_file()) {
+        let content = fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        let mut json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", file.display(), e))?;
+        replace_checkpoint_project_id(&mut json, from, to);
+        let updated = serde_json::to_string_pretty(&json)
+            .map_err(|e| format!("Failed to serialize {}: {}", file.display(), e))?;
+        fs::write(&file, updated)
+            .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Finds project directories whose sessions report the same working directory.
+///
+/// Claude Code derives the directory name from the path, so the same project can end up
+/// under several names (e.g. after a symlink or case change). Only groups with more than
+/// one directory are returned.
+#[tauri::command]
+pub async fn find_duplicate_projects() -> Result<Vec<DuplicateProjectGroup>, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let projects_dir = claude_dir.join("projects");
+
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+    let mut by_path: std::collections::BTreeMap<String, Vec<String>> = Default::default();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        // Directories without sessions can't be resolved reliably, so they are never grouped
+        if let Ok(project_path) = get_project_path_from_sessions(&path) {
+            by_path
+                .entry(project_path)
+                .or_default()
+                .push(dir_name.to_string());
+        }
+    }
+
+    let groups: Vec<DuplicateProjectGroup> = by_path
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(path, mut project_ids)| {
+            project_ids.sort();
+            DuplicateProjectGroup { path, project_ids }
+        })
+        .collect();
+
+    log::info!("Found {} duplicate project groups", groups.len());
+    Ok(groups)
+}
+
+/// Moves the sessions and checkpoint timelines of `source_ids` into `target_id`.
+///
+/// Fails without moving anything if a session ID exists in more than one of the
+/// directories. Todos are stored per session in `~/.claude/todos`, so they follow the
+/// sessions without being moved. Source directories left empty are removed.
+#[tauri::command]
+pub async fn merge_projects(
+    checkpoint_state: State<'_, crate::checkpoint::state::CheckpointState>,
+    target_id: String,
+    source_ids: Vec<String>,
+) -> Result<MergeProjectsResult, String> {
+    log::info!("Merging projects {:?} into {}", source_ids, target_id);
+
+    validate_project_id(&target_id)?;
+    for source_id in &source_ids {
+        validate_project_id(source_id)?;
+        if *source_id == target_id {
+            return Err("A project cannot be merged into itself".to_string());
+        }
+    }
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let projects_dir = claude_dir.join("projects");
+    let target_dir = projects_dir.join(&target_id);
+    if !target_dir.is_dir() {
+        return Err(format!("Project not found: {}", target_id));
+    }
+
+    // Collect everything up front so a collision is reported before any file moves
+    let mut seen: std::collections::HashMap<String, String> = project_session_ids(&target_dir)?
+        .into_iter()
+        .map(|session_id| (session_id, target_id.clone()))
+        .collect();
+    let mut plan = Vec::new();
+    let mut collisions = Vec::new();
+    for source_id in &source_ids {
+        let source_dir = projects_dir.join(source_id);
+        if !source_dir.is_dir() {
+            return Err(format!("Project not found: {}", source_id));
+        }
+        for session_id in project_session_ids(&source_dir)? {
+            if let Some(owner) = seen.get(&session_id) {
+                collisions.push(format!("{} ({} and {})", session_id, owner, source_id));
+            } else {
+                seen.insert(session_id.clone(), source_id.clone());
+                plan.push((source_id.clone(), session_id));
+            }
+        }
+    }
+    if !collisions.is_empty() {
+        return Err(format!(
+            "Session IDs exist in more than one project, nothing was merged: {}",
+            collisions.join(", ")
+        ));
+    }
+
+    let mut moved_sessions = Vec::new();
+    for (source_id, session_id) in plan {
+        let source_dir = projects_dir.join(&source_id);
+        let file_name = format!("{}.jsonl", session_id);
+        fs::rename(source_dir.join(&file_name), target_dir.join(&file_name))
+            .map_err(|e| format!("Failed to move session {}: {}", session_id, e))?;
+
+        let source_timeline = source_dir.join(".timelines").join(&session_id);
+        if source_timeline.is_dir() {
+            let target_timeline = target_dir.join(".timelines").join(&session_id);
+            fs::create_dir_all(target_dir.join(".timelines"))
+                .map_err(|e| format!("Failed to create timelines directory: {}", e))?;
+            fs::rename(&source_timeline, &target_timeline)
+                .map_err(|e| format!("Failed to move checkpoints of {}: {}", session_id, e))?;
+            retarget_session_checkpoints(&target_timeline, &source_id, &target_id)?;
+        }
+
+        // A cached manager would keep writing checkpoints under the old project
+        checkpoint_state.remove_manager(&session_id).await;
+        moved_sessions.push(session_id);
+    }
+
+    let mut removed_projects = Vec::new();
+    for source_id in &source_ids {
+        let source_dir = projects_dir.join(source_id);
+        let _ = fs::remove_dir(source_dir.join(".timelines"));
+        match fs::remove_dir(&source_dir) {
+            Ok(()) => removed_projects.push(source_id.clone()),
+            Err(e) => log::warn!("Keeping project directory {}: {}", source_id, e),
+        }
+    }
+
+    log::info!(
+        "Merged {} sessions into {}",
+        moved_sessions.len(),
+        target_id
+    );
+    Ok(MergeProjectsResult {
+        target_id,
+        moved_sessions,
+        removed_projects,
+    })
+}
+
+/// The cwd recorded by one session file of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCwdInfo {
+    pub session_id: String,
+    /// The cwd from the first JSONL line, if it could be read
+    pub cwd: Option<String>,
+    /// Why no cwd could be read from this file
+    pub error: Option<String>,
+}
+
+/// How a project directory name was resolved to a path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectResolutionDebug {
+    /// The encoded directory name
+    pub project_id: String,
+    /// Result of the lossy `decode_project_path` fallback
+    pub decoded_path: String,
+    /// Every session file in directory order, which is the order resolution tries them in
+    pub sessions: Vec<SessionCwdInfo>,
+    /// The session whose cwd was used, if any
+    pub chosen_session_id: Option<String>,
+    /// The path `list_projects` reports for this directory
+    pub resolved_path: String,
+    /// Whether the sessions disagree about the cwd
+    pub conflicting_cwds: bool,
+}
+
+/// Explains how a project directory resolves to its path, for diagnosing wrong project paths
+#[tauri::command]
+pub async fn debug_project_resolution(
+    project_id: String,
+) -> Result<ProjectResolutionDebug, String> {
+    validate_project_id(&project_id)?;
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let project_dir = claude_dir.join("projects").join(&project_id);
+    if !project_dir.is_dir() {
+        return Err(format!("Project not found: {}", project_id));
+    }
+
+    let sessions: Vec<SessionCwdInfo> = session_files(&project_dir)?
+        .iter()
+        .map(|path| {
+            let session_id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            match read_session_cwd(path) {
+                Ok(cwd) => SessionCwdInfo {
+                    session_id,
+                    cwd: Some(cwd),
+                    error: None,
+                },
+                Err(e) => SessionCwdInfo {
+                    session_id,
+                    cwd: None,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect();
+
+    let decoded_path = decode_project_path(&project_id);
+    let chosen = sessions.iter().find(|s| s.cwd.is_some());
+    let chosen_session_id = chosen.map(|s| s.session_id.clone());
+    let resolved_path = chosen
+        .and_then(|s| s.cwd.clone())
+        .unwrap_or_else(|| decoded_path.clone());
+    let conflicting_cwds = sessions
+        .iter()
+        .filter_map(|s| s.cwd.as_deref())
+        .any(|cwd| cwd != resolved_path);
+
+    Ok(ProjectResolutionDebug {
+        project_id,
+        decoded_path,
+        sessions,
+        chosen_session_id,
+        resolved_path,
+        conflicting_cwds,
+    })
+}
+
 /// Reads the Claude settings file
 #[tauri::command]
 pub async fn get_claude_settings() -> Result<ClaudeSettings, String> {
@@ -782,7 +1475,11 @@ pub async fn save_claude_settings_backup(settings: serde_json::Value) -> Result<
 
 /// Recursively finds all CLAUDE.md files in a project directory
 #[tauri::command]
-pub async fn find_claude_md_files(project_path: String) -> Result<Vec<ClaudeMdFile>, String> {
+pub async fn find_claude_md_files(
+    db: State<'_, AgentDb>,
+    project_path: String,
+    follow_symlinks: Option<bool>,
+) -> Result<Vec<ClaudeMdFile>, String> {
     log::info!("Finding CLAUDE.md files in project: {}", project_path);
 
     let path = PathBuf::from(&project_path);
@@ -790,8 +1487,10 @@ pub async fn find_claude_md_files(project_path: String) -> Result<Vec<ClaudeMdFi
         return Err(format!("Project path does not exist: {}", project_path));
     }
 
+    let ignore = IgnoreMatcher::new(&load_search_ignore_settings(&db), &path);
+    let mut symlinks = SymlinkPolicy::new(follow_symlinks.unwrap_or(false), &path);
     let mut claude_files = Vec::new();
-    find_claude_md_recursive(&path, &path, &mut claude_files)?;
+    find_claude_md_recursive(&path, &path, &ignore, &mut symlinks, &mut claude_files)?;
 
     // Sort by relative path
     claude_files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
@@ -800,10 +1499,13 @@ pub async fn find_claude_md_files(project_path: String) -> Result<Vec<ClaudeMdFi
     Ok(claude_files)
 }
 
-/// Helper function to recursively find CLAUDE.md files
+/// Helper function to recursively find CLAUDE.md files; symlinked directories are only
+/// entered when `symlinks` follows them
 fn find_claude_md_recursive(
     current_path: &PathBuf,
     project_root: &PathBuf,
+    ignore: &IgnoreMatcher,
+    symlinks: &mut SymlinkPolicy,
     claude_files: &mut Vec<ClaudeMdFile>,
 ) -> Result<(), String> {
     let entries = fs::read_dir(current_path)
@@ -821,17 +1523,17 @@ fn find_claude_md_recursive(
         }
 
         if path.is_dir() {
-            // Skip common directories that shouldn't be searched
-            if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                if matches!(
-                    dir_name,
-                    "node_modules" | "target" | ".git" | "dist" | "build" | ".next" | "__pycache__"
-                ) {
-                    continue;
-                }
+            // Skip dependency and build directories
+            if ignore.is_ignored(path.strip_prefix(project_root).unwrap_or(&path), true) {
+                continue;
             }
 
-            find_claude_md_recursive(&path, project_root, claude_files)?;
+            let is_symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+            if !symlinks.should_descend(&path, is_symlink) {
+                continue;
+            }
+
+            find_claude_md_recursive(&path, project_root, ignore, symlinks, claude_files)?;
         } else if path.is_file() {
             // Check if it's a CLAUDE.md file (case insensitive)
             if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
@@ -897,9 +1599,22 @@ pub async fn save_claude_md_file(file_path: String, content: String) -> Result<S
     Ok("File saved successfully".to_string())
 }
 
+/// Payload of the `session-history-malformed` events emitted when loading skipped lines
+#[derive(Debug, Clone, Serialize)]
+pub struct MalformedSessionLines {
+    pub session_id: String,
+    pub project_id: String,
+    pub malformed_lines: usize,
+    /// Lines dropped for exceeding the line length limit
+    pub oversized_lines: usize,
+}
+
 /// Loads the JSONL history for a specific session
+/// Lines that are not valid JSON or exceed the line length limit are skipped and reported
+/// via `session-history-malformed`
 #[tauri::command]
 pub async fn load_session_history(
+    app: AppHandle,
     session_id: String,
     project_id: String,
 ) -> Result<Vec<serde_json::Value>, String> {
@@ -919,21 +1634,216 @@ pub async fn load_session_history(
         return Err(format!("Session file not found: {}", session_id));
     }
 
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {}", e))?;
-
-    let reader = BufReader::new(file);
     let mut messages = Vec::new();
+    let stats = jsonl::for_each_json_line_in_file(&session_path, |_, json| messages.push(json))
+        .map_err(|e| format!("Failed to read session file: {}", e))?;
+
+    if stats.lines_skipped > 0 || stats.lines_oversized > 0 {
+        log::warn!(
+            "Skipped {} malformed and {} oversized lines in session {}",
+            stats.lines_skipped,
+            stats.lines_oversized,
+            session_id
+        );
+        let payload = MalformedSessionLines {
+            session_id: session_id.clone(),
+            project_id,
+            malformed_lines: stats.lines_skipped,
+            oversized_lines: stats.lines_oversized,
+        };
+        let _ = app.emit(
+            &format!("session-history-malformed:{}", session_id),
+            &payload,
+        );
+        let _ = app.emit("session-history-malformed", &payload);
+    }
 
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                messages.push(json);
-            }
+    Ok(messages)
+}
+
+/// A line of a session file that is not valid JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct CorruptJsonlLine {
+    /// 1-based line number
+    pub line_number: usize,
+    /// Start of the line, for display
+    pub preview: String,
+    pub error: String,
+}
+
+/// Result of scanning (and optionally repairing) a session file
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRepairReport {
+    pub total_lines: usize,
+    pub corrupt_lines: Vec<CorruptJsonlLine>,
+    /// Whether the last line is corrupt, typically a write cut short by a crash
+    pub trailing_line_incomplete: bool,
+    /// Whether the incomplete trailing line was removed
+    pub repaired: bool,
+    pub backup_path: Option<String>,
+}
+
+/// Scans a session file for lines that are not valid JSON and, if `remove_trailing` is set,
+/// removes an incomplete last line after backing the file up next to the original.
+/// Refused while the session is running, since Claude may still be appending to it
+#[tauri::command]
+pub async fn repair_session_jsonl(
+    registry: State<'_, crate::process::ProcessRegistryState>,
+    session_id: String,
+    project_id: String,
+    remove_trailing: Option<bool>,
+) -> Result<SessionRepairReport, String> {
+    const PREVIEW_CHARS: usize = 200;
+
+    let session_path = session_file(&session_id, &project_id)?;
+    // A running session's last line may simply be a write in progress
+    if registry.0.get_claude_session_by_id(&session_id)?.is_some() {
+        return Err(format!(
+            "Cannot repair session {} while it is running",
+            session_id
+        ));
+    }
+
+    let mut reader = JsonlReader::open(&session_path)
+        .map_err(|e| format!("Failed to read session file: {}", e))?;
+
+    let mut total_lines = 0;
+    let mut corrupt_lines = Vec::new();
+    // Byte offset where the last non-empty line starts, and whether it is corrupt
+    let mut last_line: Option<(u64, bool)> = None;
+
+    while let Some(line) = reader
+        .next_checked()
+        .map_err(|e| format!("Failed to read session file: {}", e))?
+    {
+        total_lines += 1;
+        let corrupt = line.error.is_some();
+        if let Some(error) = line.error {
+            corrupt_lines.push(CorruptJsonlLine {
+                line_number: line.index + 1,
+                preview: line.text.chars().take(PREVIEW_CHARS).collect(),
+                error,
+            });
         }
+        last_line = Some((line.offset, corrupt));
     }
+    drop(reader);
+
+    let trailing_line_incomplete = matches!(last_line, Some((_, true)));
+    let mut report = SessionRepairReport {
+        total_lines,
+        corrupt_lines,
+        trailing_line_incomplete,
+        repaired: false,
+        backup_path: None,
+    };
 
-    Ok(messages)
+    let Some((trailing_start, true)) = last_line else {
+        return Ok(report);
+    };
+    if !remove_trailing.unwrap_or(false) {
+        return Ok(report);
+    }
+
+    let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let backup_path = session_path.with_extension(format!("jsonl.bak-{}", timestamp));
+    fs::copy(&session_path, &backup_path)
+        .map_err(|e| format!("Failed to back up session file: {}", e))?;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&session_path)
+        .and_then(|file| file.set_len(trailing_start))
+        .map_err(|e| format!("Failed to truncate session file: {}", e))?;
+
+    log::info!(
+        "Removed incomplete trailing line from session {} (backup: {:?})",
+        session_id,
+        backup_path
+    );
+    report.corrupt_lines.pop();
+    report.total_lines -= 1;
+    report.repaired = true;
+    report.backup_path = Some(backup_path.to_string_lossy().to_string());
+    Ok(report)
+}
+
+/// Validates diagnostic arguments against the allowlist of non-interactive subcommands
+/// Only `--version`, `doctor`, `mcp list`, `config list` and `config get <key>` are permitted
+fn validate_diagnostic_args(args: &[String]) -> Result<(), String> {
+    let parts: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+
+    let allowed = match parts.as_slice() {
+        ["--version"] | ["-v"] | ["version"] | ["doctor"] => true,
+        ["mcp", "list"] | ["config", "list"] => true,
+        ["config", "get", key] => !key.starts_with('-'),
+        _ => false,
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!(
+            "Diagnostic command not allowed: claude {}. Allowed: --version, doctor, mcp list, config list, config get <key>",
+            parts.join(" ")
+        ))
+    }
+}
+
+/// Runs an allowlisted Claude CLI command and captures its full output for diagnostics
+#[tauri::command]
+pub async fn run_claude_diagnostic(
+    app: AppHandle,
+    args: Vec<String>,
+    timeout_secs: Option<u64>,
+) -> Result<DiagnosticResult, String> {
+    validate_diagnostic_args(&args)?;
+
+    let claude_path = find_claude_binary(&app)?;
+    let command_line = format!("{} {}", claude_path, args.join(" "));
+    log::info!("Running Claude diagnostic: {}", command_line);
+
+    let mut cmd = create_command_with_env(&claude_path);
+    cmd.args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(DIAGNOSTIC_TIMEOUT_SECS));
+    let start = std::time::Instant::now();
+
+    let child = cmd.spawn().map_err(|e| {
+        crate::claude_binary::invalidate_on_spawn_error(&e);
+        format!("Failed to spawn Claude: {}", e)
+    })?;
+
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => Ok(DiagnosticResult {
+            command: command_line,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output.status.code(),
+            timed_out: false,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }),
+        Ok(Err(e)) => Err(format!("Failed to run diagnostic command: {}", e)),
+        Err(_) => {
+            log::warn!(
+                "Claude diagnostic timed out after {}s: {}",
+                timeout.as_secs(),
+                command_line
+            );
+            Ok(DiagnosticResult {
+                command: command_line,
+                stdout: String::new(),
+                stderr: format!("Command timed out after {} seconds", timeout.as_secs()),
+                exit_code: None,
+                timed_out: true,
+                duration_ms: start.elapsed().as_millis() as u64,
+            })
+        }
+    }
 }
 
 /// Execute a new interactive Claude Code session with streaming output
@@ -943,6 +1853,7 @@ pub async fn execute_claude_code(
     project_path: String,
     prompt: String,
     model: String,
+    output_format: Option<OutputFormat>,
 ) -> Result<(), String> {
     log::info!(
         "Starting new Claude Code session in: {} with model: {}",
@@ -950,6 +1861,7 @@ pub async fn execute_claude_code(
         model
     );
 
+    check_project_budget(&app, &project_path).await?;
     let claude_path = find_claude_binary(&app)?;
 
     // Map opus-plan to the appropriate Claude CLI parameter
@@ -958,19 +1870,28 @@ pub async fn execute_claude_code(
         _ => model.clone(),
     };
 
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
         claude_model,
-        "--output-format".to_string(),
-        "stream-json".to_string(),
-        "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    let output_format = output_format.unwrap_or_default();
+    args.extend(output_format.cli_args());
 
     let cmd = create_system_command(&claude_path, args, &project_path);
-    spawn_claude_process(app, cmd, prompt, model, project_path).await
+    spawn_claude_process(
+        app,
+        cmd,
+        prompt,
+        model,
+        project_path,
+        output_format,
+        None,
+        None,
+    )
+    .await
 }
 
 /// Continue an existing Claude Code conversation with streaming output
@@ -980,6 +1901,7 @@ pub async fn continue_claude_code(
     project_path: String,
     prompt: String,
     model: String,
+    output_format: Option<OutputFormat>,
 ) -> Result<(), String> {
     log::info!(
         "Continuing Claude Code conversation in: {} with model: {}",
@@ -987,6 +1909,7 @@ pub async fn continue_claude_code(
         model
     );
 
+    check_project_budget(&app, &project_path).await?;
     let claude_path = find_claude_binary(&app)?;
 
     // Map opus-plan to the appropriate Claude CLI parameter
@@ -995,20 +1918,29 @@ pub async fn continue_claude_code(
         _ => model.clone(),
     };
 
-    let args = vec![
+    let mut args = vec![
         "-c".to_string(), // Continue flag
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
         claude_model,
-        "--output-format".to_string(),
-        "stream-json".to_string(),
-        "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    let output_format = output_format.unwrap_or_default();
+    args.extend(output_format.cli_args());
 
     let cmd = create_system_command(&claude_path, args, &project_path);
-    spawn_claude_process(app, cmd, prompt, model, project_path).await
+    spawn_claude_process(
+        app,
+        cmd,
+        prompt,
+        model,
+        project_path,
+        output_format,
+        None,
+        None,
+    )
+    .await
 }
 
 /// Resume an existing Claude Code session by ID with streaming output
@@ -1019,6 +1951,7 @@ pub async fn resume_claude_code(
     session_id: String,
     prompt: String,
     model: String,
+    output_format: Option<OutputFormat>,
 ) -> Result<(), String> {
     log::info!(
         "Resuming Claude Code session: {} in: {} with model: {}",
@@ -1027,6 +1960,7 @@ pub async fn resume_claude_code(
         model
     );
 
+    check_project_budget(&app, &project_path).await?;
     let claude_path = find_claude_binary(&app)?;
 
     // Map opus-plan to the appropriate Claude CLI parameter
@@ -1035,21 +1969,138 @@ pub async fn resume_claude_code(
         _ => model.clone(),
     };
 
-    let args = vec![
+    let mut args = vec![
         "--resume".to_string(),
         session_id.clone(),
         "-p".to_string(),
         prompt.clone(),
         "--model".to_string(),
         claude_model,
-        "--output-format".to_string(),
-        "stream-json".to_string(),
-        "--verbose".to_string(),
         "--dangerously-skip-permissions".to_string(),
     ];
+    let output_format = output_format.unwrap_or_default();
+    args.extend(output_format.cli_args());
 
     let cmd = create_system_command(&claude_path, args, &project_path);
-    spawn_claude_process(app, cmd, prompt, model, project_path).await
+    spawn_claude_process(
+        app,
+        cmd,
+        prompt,
+        model,
+        project_path,
+        output_format,
+        Some(session_id),
+        None,
+    )
+    .await
+}
+
+/// Progress of a session replay, emitted as `replay-progress` before each prompt is sent
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayProgress {
+    pub source_session_id: String,
+    /// The new session; `None` until the first prompt has started it
+    pub session_id: Option<String>,
+    /// 1-based index of the prompt being sent
+    pub step: usize,
+    pub total_steps: usize,
+}
+
+/// Replays the user prompts of a session as a fresh session, sending each prompt once the
+/// previous one has completed. Tool results and command wrapper messages are skipped.
+/// Every step is registered like a normal session, so `cancel_claude_execution` stops the
+/// replay. Returns the ID of the new session.
+#[tauri::command]
+pub async fn replay_session(
+    app: AppHandle,
+    source_session_id: String,
+    project_id: String,
+    project_path: String,
+    model: String,
+) -> Result<String, String> {
+    let source = session_file(&source_session_id, &project_id)?;
+    let prompts = tokio::task::spawn_blocking(move || session_prompts(&source))
+        .await
+        .map_err(|e| format!("Failed to read session: {}", e))??;
+    if prompts.is_empty() {
+        return Err(format!(
+            "Session {} has no user prompts to replay",
+            source_session_id
+        ));
+    }
+    log::info!(
+        "Replaying {} prompts of session {} in: {} with model: {}",
+        prompts.len(),
+        source_session_id,
+        project_path,
+        model
+    );
+
+    let claude_path = find_claude_binary(&app)?;
+
+    // Map opus-plan to the appropriate Claude CLI parameter
+    let claude_model = match model.as_str() {
+        "opus-plan" => "opusplan".to_string(),
+        _ => model.clone(),
+    };
+
+    let total_steps = prompts.len();
+    let mut session_id: Option<String> = None;
+    for (index, prompt) in prompts.into_iter().enumerate() {
+        check_project_budget(&app, &project_path).await?;
+        let _ = app.emit(
+            "replay-progress",
+            ReplayProgress {
+                source_session_id: source_session_id.clone(),
+                session_id: session_id.clone(),
+                step: index + 1,
+                total_steps,
+            },
+        );
+
+        // The first prompt starts the session, the rest resume it
+        let mut args = Vec::new();
+        if let Some(sid) = &session_id {
+            args.extend(["--resume".to_string(), sid.clone()]);
+        }
+        args.extend([
+            "-p".to_string(),
+            prompt.clone(),
+            "--model".to_string(),
+            claude_model.clone(),
+            "--dangerously-skip-permissions".to_string(),
+        ]);
+        args.extend(OutputFormat::StreamJson.cli_args());
+
+        let cmd = create_system_command(&claude_path, args, &project_path);
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+        spawn_claude_process(
+            app.clone(),
+            cmd,
+            prompt,
+            model.clone(),
+            project_path.clone(),
+            OutputFormat::StreamJson,
+            session_id.clone(),
+            Some(done_tx),
+        )
+        .await?;
+
+        let (step_session_id, success) = done_rx.await.unwrap_or((None, false));
+        if step_session_id.is_some() {
+            session_id = step_session_id;
+        }
+        if !success {
+            return Err(format!(
+                "Replay stopped at prompt {} of {} (session {})",
+                index + 1,
+                total_steps,
+                session_id.as_deref().unwrap_or("not started")
+            ));
+        }
+    }
+
+    session_id.ok_or_else(|| "Claude did not report a session ID".to_string())
 }
 
 /// Cancel the currently running Claude Code execution
@@ -1057,12 +2108,17 @@ pub async fn resume_claude_code(
 pub async fn cancel_claude_execution(
     app: AppHandle,
     session_id: Option<String>,
+    grace_period_ms: Option<u64>,
 ) -> Result<(), String> {
     log::info!(
         "Cancelling Claude Code execution for session: {:?}",
         session_id
     );
 
+    let grace_period = std::time::Duration::from_millis(
+        grace_period_ms.unwrap_or(crate::process::DEFAULT_KILL_GRACE_PERIOD_MS),
+    );
+
     let mut killed = false;
     let mut attempted_methods = Vec::new();
 
@@ -1077,14 +2133,17 @@ pub async fn cancel_claude_execution(
                     process_info.run_id,
                     process_info.pid
                 );
-                match registry.0.kill_process(process_info.run_id).await {
-                    Ok(success) => {
-                        if success {
-                            log::info!("Successfully killed process via registry");
-                            killed = true;
-                        } else {
-                            log::warn!("Registry kill returned false");
-                        }
+                match registry
+                    .0
+                    .terminate_process(process_info.run_id, grace_period)
+                    .await
+                {
+                    Ok(Some(signal)) => {
+                        log::info!("Successfully killed process via registry ({:?})", signal);
+                        killed = true;
+                    }
+                    Ok(None) => {
+                        log::warn!("Registry kill returned false");
                     }
                     Err(e) => {
                         log::warn!("Failed to kill via registry: {}", e);
@@ -1114,44 +2173,30 @@ pub async fn cancel_claude_execution(
                 pid
             );
 
-            // Kill the process
-            match child.kill().await {
-                Ok(_) => {
-                    log::info!("Successfully killed Claude process via ClaudeProcessState");
-                    killed = true;
-                }
-                Err(e) => {
-                    log::error!(
-                        "Failed to kill Claude process via ClaudeProcessState: {}",
-                        e
-                    );
-
-                    // Method 3: If we have a PID, try system kill as last resort
-                    if let Some(pid) = pid {
-                        log::info!("Attempting system kill as last resort for PID: {}", pid);
-                        let kill_result = if cfg!(target_os = "windows") {
-                            std::process::Command::new("taskkill")
-                                .args(["/F", "/PID", &pid.to_string()])
-                                .output()
-                        } else {
-                            std::process::Command::new("kill")
-                                .args(["-KILL", &pid.to_string()])
-                                .output()
-                        };
-
-                        match kill_result {
-                            Ok(output) if output.status.success() => {
-                                log::info!("Successfully killed process via system command");
-                                killed = true;
-                            }
-                            Ok(output) => {
-                                let stderr = String::from_utf8_lossy(&output.stderr);
-                                log::error!("System kill failed: {}", stderr);
-                            }
-                            Err(e) => {
-                                log::error!("Failed to execute system kill command: {}", e);
-                            }
-                        }
+            if let Some(pid) = pid {
+                // SIGTERM the process group first, escalating to SIGKILL after the grace period
+                let signal = crate::process::terminate_with_escalation(pid, grace_period, || {
+                    matches!(child.try_wait(), Ok(None))
+                })
+                .await;
+                log::info!(
+                    "Claude process {} terminated via ClaudeProcessState ({:?})",
+                    pid,
+                    signal
+                );
+                killed = true;
+            } else {
+                // No PID means the process has already been reaped; make sure the handle is dropped
+                match child.kill().await {
+                    Ok(_) => {
+                        log::info!("Successfully killed Claude process via ClaudeProcessState");
+                        killed = true;
+                    }
+                    Err(e) => {
+                        log::error!(
+                            "Failed to kill Claude process via ClaudeProcessState: {}",
+                            e
+                        );
                     }
                 }
             }
@@ -1208,21 +2253,231 @@ pub async fn get_claude_session_output(
     }
 }
 
-/// Helper function to spawn Claude process and handle streaming
+/// A `claude-output` line tagged with its position in the session's live output buffer,
+/// emitted on `claude-output-seq:{session_id}`
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeOutputLine {
+    pub cursor: usize,
+    pub line: String,
+}
+
+/// Get the session's output lines from `cursor` onwards, so a reconnecting UI can fetch
+/// exactly what it missed. Subscribe to `claude-output-seq:{session_id}` first, then fetch
+/// from the last cursor seen and ignore events whose cursor is below `next_cursor`.
+#[tauri::command]
+pub async fn get_claude_output_since(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+    session_id: String,
+    cursor: usize,
+) -> Result<crate::process::LiveOutputChunk, String> {
+    let process_info = registry
+        .0
+        .get_claude_session_by_id(&session_id)?
+        .ok_or_else(|| format!("Session {} is not running", session_id))?;
+    registry
+        .0
+        .get_live_output_since(process_info.run_id, cursor)?
+        .ok_or_else(|| format!("Session {} is not running", session_id))
+}
+
+/// Re-attach to a running session: returns its buffered output and process info, and resumes
+/// its output events if they were paused. Errors if the session is not running, in which case
+/// the UI should read the session JSONL instead.
+/// Lines can arrive both in the backlog and as events, so subscribe to
+/// `claude-output-seq:{session_id}` first and drop cursors below `output.next_cursor`.
+#[tauri::command]
+pub async fn attach_session(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+    session_id: String,
+) -> Result<crate::process::SessionAttachment, String> {
+    registry
+        .0
+        .attach_claude_session(&session_id)?
+        .ok_or_else(|| format!("Session {} is not running", session_id))
+}
+
+/// Emit output held back while a session was paused, preceded by a buffered-lines marker.
+/// Stream-json lines are also emitted on `claude-output-seq:{session_id}` with their cursors.
+fn flush_paused_output(app: &AppHandle, session_id: &str, paused: crate::process::PausedOutput) {
+    let Some(event) = paused.event.as_deref() else {
+        return;
+    };
+    let scoped_event = format!("{}:{}", event, session_id);
+    let marker = paused.marker();
+    let _ = app.emit(&scoped_event, &marker);
+    let _ = app.emit(event, &marker);
+
+    for held in paused.lines {
+        let _ = app.emit(&scoped_event, &held.line);
+        if let (Some(cursor), "claude-output") = (held.cursor, event) {
+            let _ = app.emit(
+                &format!("claude-output-seq:{}", session_id),
+                &ClaudeOutputLine {
+                    cursor,
+                    line: held.line.clone(),
+                },
+            );
+        }
+        let _ = app.emit(event, &held.line);
+    }
+}
+
+/// Pause or resume emitting a session's output to the frontend. The process keeps running and
+/// its output is still captured; on resume the held back lines (or their tail) are emitted.
+#[tauri::command]
+pub async fn set_session_output_paused(
+    app: AppHandle,
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+    session_id: String,
+    paused: bool,
+) -> Result<(), String> {
+    if let Some(held_back) = registry.0.set_output_paused(&session_id, paused)? {
+        flush_paused_output(&app, &session_id, held_back);
+    }
+    Ok(())
+}
+
+/// Running token and cost totals of a streaming session, emitted as `claude-metrics:{session_id}`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionMetrics {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub estimated_cost_usd: f64,
+    /// Set once the final `result` message arrived; the cost is then the one Claude reported
+    pub is_final: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TokenUsage {
+    input: u64,
+    output: u64,
+    cache_creation: u64,
+    cache_read: u64,
+}
+
+impl TokenUsage {
+    /// Reads a `usage` object, treating absent or malformed fields as zero
+    fn from_json(usage: &serde_json::Value) -> Self {
+        let field = |name: &str| usage.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+        Self {
+            input: field("input_tokens"),
+            output: field("output_tokens"),
+            cache_creation: field("cache_creation_input_tokens"),
+            cache_read: field("cache_read_input_tokens"),
+        }
+    }
+}
+
+/// Accumulates usage from stream-json lines. Claude repeats the usage of a message on every
+/// line of that message, so usage is keyed by message ID and the latest value wins.
+/// Running totals are adjusted as messages arrive so each update is constant time.
+#[derive(Debug, Default)]
+struct SessionMetricsTracker {
+    model: String,
+    by_message: std::collections::HashMap<String, (TokenUsage, f64)>,
+    totals: TokenUsage,
+    estimated_cost: f64,
+    reported_cost: Option<f64>,
+}
+
+impl SessionMetricsTracker {
+    fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Updates the totals from one stream-json message, returning the new totals if they changed
+    fn observe(&mut self, msg: &serde_json::Value) -> Option<SessionMetrics> {
+        match msg.get("type").and_then(|t| t.as_str()) {
+            Some("system") => {
+                if let Some(model) = msg.get("model").and_then(|m| m.as_str()) {
+                    self.model = model.to_string();
+                }
+                None
+            }
+            Some("assistant") => {
+                let message = msg.get("message")?;
+                let usage = TokenUsage::from_json(message.get("usage")?);
+                let model = message
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or(&self.model);
+                let cost = crate::commands::usage::estimate_cost(
+                    model,
+                    usage.input,
+                    usage.output,
+                    usage.cache_creation,
+                    usage.cache_read,
+                );
+
+                if let Some(id) = message.get("id").and_then(|id| id.as_str()) {
+                    if let Some((previous, previous_cost)) =
+                        self.by_message.insert(id.to_string(), (usage, cost))
+                    {
+                        if previous == usage {
+                            return None;
+                        }
+                        self.totals.input -= previous.input;
+                        self.totals.output -= previous.output;
+                        self.totals.cache_creation -= previous.cache_creation;
+                        self.totals.cache_read -= previous.cache_read;
+                        self.estimated_cost -= previous_cost;
+                    }
+                }
+                self.totals.input += usage.input;
+                self.totals.output += usage.output;
+                self.totals.cache_creation += usage.cache_creation;
+                self.totals.cache_read += usage.cache_read;
+                self.estimated_cost += cost;
+                Some(self.snapshot())
+            }
+            Some("result") => {
+                self.reported_cost = msg.get("total_cost_usd").and_then(|c| c.as_f64());
+                let mut metrics = self.snapshot();
+                metrics.is_final = true;
+                Some(metrics)
+            }
+            _ => None,
+        }
+    }
+
+    fn snapshot(&self) -> SessionMetrics {
+        SessionMetrics {
+            input_tokens: self.totals.input,
+            output_tokens: self.totals.output,
+            cache_creation_tokens: self.totals.cache_creation,
+            cache_read_tokens: self.totals.cache_read,
+            estimated_cost_usd: self.reported_cost.unwrap_or(self.estimated_cost),
+            is_final: false,
+        }
+    }
+}
+
+/// Helper function to spawn Claude process and handle streaming.
+/// `on_complete` receives the session ID and whether the process succeeded once it exited.
+#[allow(clippy::too_many_arguments)]
 async fn spawn_claude_process(
     app: AppHandle,
     mut cmd: Command,
     prompt: String,
     model: String,
     project_path: String,
+    output_format: OutputFormat,
+    known_session_id: Option<String>,
+    on_complete: Option<tokio::sync::oneshot::Sender<(Option<String>, bool)>>,
 ) -> Result<(), String> {
     use std::sync::Mutex;
     use tokio::io::{AsyncBufReadExt, BufReader};
 
     // Spawn the process
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn Claude: {}", e))?;
+    let mut child = cmd.spawn().map_err(|e| {
+        crate::claude_binary::invalidate_on_spawn_error(&e);
+        format!("Failed to spawn Claude: {}", e)
+    })?;
 
     // Get stdout and stderr
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
@@ -1261,11 +2516,54 @@ async fn spawn_claude_process(
     let project_path_clone = project_path.clone();
     let prompt_clone = prompt.clone();
     let model_clone = model.clone();
+
+    // Text output has no init message, so key the session by the resumed ID (or a generated
+    // one) and register it up front
+    let is_text_output = output_format == OutputFormat::Text;
+    if is_text_output {
+        let stream_id = known_session_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        match registry.0.register_claude_session(
+            stream_id.clone(),
+            pid,
+            project_path.clone(),
+            prompt.clone(),
+            model.clone(),
+        ) {
+            Ok(run_id) => {
+                *run_id_holder.lock().unwrap() = Some(run_id);
+            }
+            Err(e) => {
+                log::error!("Failed to register Claude text session: {}", e);
+            }
+        }
+        *session_id_holder.lock().unwrap() = Some(stream_id.clone());
+        let _ = app.emit("claude-text-started", &stream_id);
+    }
+
     let stdout_task = tokio::spawn(async move {
         let mut lines = stdout_reader.lines();
+        let mut metrics_tracker = SessionMetricsTracker::new(&model_clone);
         while let Ok(Some(line)) = lines.next_line().await {
             log::debug!("Claude stdout: {}", line);
 
+            if is_text_output {
+                let run_id = *run_id_holder_clone.lock().unwrap();
+                let session_id = session_id_holder_clone.lock().unwrap().clone();
+                let _ = registry_clone.append_session_output(
+                    run_id,
+                    session_id.as_deref(),
+                    "claude-text",
+                    &line,
+                    |_| {
+                        if let Some(ref session_id) = session_id {
+                            let _ = app_handle.emit(&format!("claude-text:{}", session_id), &line);
+                        }
+                        let _ = app_handle.emit("claude-text", &line);
+                    },
+                );
+                continue;
+            }
+
             // Parse the line to check for init message with session ID
             if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
                 if msg["type"] == "system" && msg["subtype"] == "init" {
@@ -1275,13 +2573,15 @@ async fn spawn_claude_process(
                             *session_id_guard = Some(claude_session_id.to_string());
                             log::info!("Extracted Claude session ID: {}", claude_session_id);
 
-                            // Now register with ProcessRegistry using Claude's session ID
+                            // Now register with ProcessRegistry using Claude's session ID and
+                            // the resolved model from the init message (the request may use an alias)
+                            let resolved_model = msg["model"].as_str().unwrap_or(&model_clone);
                             match registry_clone.register_claude_session(
                                 claude_session_id.to_string(),
                                 pid,
                                 project_path_clone.clone(),
                                 prompt_clone.clone(),
-                                model_clone.clone(),
+                                resolved_model.to_string(),
                             ) {
                                 Ok(run_id) => {
                                     log::info!("Registered Claude session with run_id: {}", run_id);
@@ -1295,19 +2595,52 @@ async fn spawn_claude_process(
                         }
                     }
                 }
+
+                if let Some(metrics) = metrics_tracker.observe(&msg) {
+                    if let Some(run_id) = *run_id_holder_clone.lock().unwrap() {
+                        let _ = registry_clone.update_usage(
+                            run_id,
+                            metrics.input_tokens
+                                + metrics.cache_creation_tokens
+                                + metrics.cache_read_tokens,
+                            metrics.output_tokens,
+                            metrics.estimated_cost_usd,
+                        );
+                    }
+                    if let Some(ref session_id) = *session_id_holder_clone.lock().unwrap() {
+                        let _ =
+                            app_handle.emit(&format!("claude-metrics:{}", session_id), &metrics);
+                    }
+                }
             }
 
-            // Store live output in registry if we have a run_id
-            if let Some(run_id) = *run_id_holder_clone.lock().unwrap() {
-                let _ = registry_clone.append_live_output(run_id, &line);
-            }
-
-            // Emit the line to the frontend with session isolation if we have session ID
-            if let Some(ref session_id) = *session_id_holder_clone.lock().unwrap() {
-                let _ = app_handle.emit(&format!("claude-output:{}", session_id), &line);
-            }
-            // Also emit to the generic event for backward compatibility
-            let _ = app_handle.emit("claude-output", &line);
+            // Store live output in registry if we have a run_id, then emit it unless the
+            // session's output is paused
+            let run_id = *run_id_holder_clone.lock().unwrap();
+            let session_id = session_id_holder_clone.lock().unwrap().clone();
+            let _ = registry_clone.append_session_output(
+                run_id,
+                session_id.as_deref(),
+                "claude-output",
+                &line,
+                |cursor| {
+                    // Emit the line to the frontend with session isolation if we have session ID
+                    if let Some(ref session_id) = session_id {
+                        let _ = app_handle.emit(&format!("claude-output:{}", session_id), &line);
+                        if let Some(cursor) = cursor {
+                            let _ = app_handle.emit(
+                                &format!("claude-output-seq:{}", session_id),
+                                &ClaudeOutputLine {
+                                    cursor,
+                                    line: line.clone(),
+                                },
+                            );
+                        }
+                    }
+                    // Also emit to the generic event for backward compatibility
+                    let _ = app_handle.emit("claude-output", &line);
+                },
+            );
         }
     });
 
@@ -1336,12 +2669,22 @@ async fn spawn_claude_process(
         let _ = stdout_task.await;
         let _ = stderr_task.await;
 
-        // Get the child from the state to wait on it
+        // Flush output still held back so the frontend sees the end of the session
+        let finished_session_id = session_id_holder_clone3.lock().unwrap().clone();
+        if let Some(ref session_id) = finished_session_id {
+            if let Ok(Some(held_back)) = registry_clone2.set_output_paused(session_id, false) {
+                flush_paused_output(&app_handle_wait, session_id, held_back);
+            }
+        }
+
+        // Get the child from the state to wait on it; a cancelled process is already gone
+        let mut success = false;
         let mut current_process = claude_state_wait.lock().await;
         if let Some(mut child) = current_process.take() {
             match child.wait().await {
                 Ok(status) => {
                     log::info!("Claude process exited with status: {}", status);
+                    success = status.success();
                     // Add a small delay to ensure all messages are processed
                     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                     if let Some(ref session_id) = *session_id_holder_clone3.lock().unwrap() {
@@ -1372,14 +2715,26 @@ async fn spawn_claude_process(
 
         // Clear the process from state
         *current_process = None;
+        drop(current_process);
+
+        if let Some(on_complete) = on_complete {
+            let _ = on_complete.send((finished_session_id, success));
+        }
     });
 
     Ok(())
 }
 
-/// Lists files and directories in a given path
+/// Lists files and directories in a given path.
+///
+/// Hidden entries other than `.claude` are skipped unless `show_hidden` is set; even then,
+/// hidden entries matching the search ignore patterns (such as `.git`) stay hidden.
 #[tauri::command]
-pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileEntry>, String> {
+pub async fn list_directory_contents(
+    db: State<'_, AgentDb>,
+    directory_path: String,
+    show_hidden: Option<bool>,
+) -> Result<Vec<FileEntry>, String> {
     log::info!("Listing directory contents: '{}'", directory_path);
 
     // Check if path is empty
@@ -1401,6 +2756,8 @@ pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileE
         return Err(format!("Path is not a directory: {}", directory_path));
     }
 
+    let show_hidden = show_hidden.unwrap_or(false);
+    let ignore = show_hidden.then(|| IgnoreMatcher::new(&load_search_ignore_settings(&db), &path));
     let mut entries = Vec::new();
 
     let dir_entries =
@@ -1413,18 +2770,22 @@ pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileE
             .metadata()
             .map_err(|e| format!("Failed to read metadata: {}", e))?;
 
-        // Skip hidden files/directories unless they are .claude directories
-        if let Some(name) = entry_path.file_name().and_then(|n| n.to_str()) {
-            if name.starts_with('.') && name != ".claude" {
-                continue;
-            }
-        }
-
         let name = entry_path
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("")
             .to_string();
+        let is_hidden = name.starts_with('.');
+        let is_symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+
+        // Skip hidden files/directories unless they are .claude directories
+        if is_hidden && name != ".claude" {
+            match &ignore {
+                None => continue,
+                Some(ignore) if ignore.is_ignored(Path::new(&name), metadata.is_dir()) => continue,
+                Some(_) => {}
+            }
+        }
 
         let extension = if metadata.is_file() {
             entry_path
@@ -1441,6 +2802,13 @@ pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileE
             is_directory: metadata.is_dir(),
             size: metadata.len(),
             extension,
+            is_hidden,
+            is_symlink,
+            symlink_target: if is_symlink {
+                symlink_target(&entry_path)
+            } else {
+                None
+            },
         });
     }
 
@@ -1454,10 +2822,59 @@ pub async fn list_directory_contents(directory_path: String) -> Result<Vec<FileE
     Ok(entries)
 }
 
+/// Default directory depth searched below the base path
+const DEFAULT_SEARCH_DEPTH: usize = 5;
+/// Default number of entries returned by `search_files`
+const DEFAULT_SEARCH_RESULTS: usize = 50;
+
+/// Search results along with how the search went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchFilesResult {
+    /// Matching entries, most relevant first, at most `max_results`
+    pub entries: Vec<FileEntry>,
+    /// Number of matches found before the search stopped, at most `max_results + 1`
+    pub total_matched: usize,
+    /// Whether the search stopped early because more than `max_results` entries matched
+    pub truncated: bool,
+    /// Number of directories read
+    pub dirs_scanned: usize,
+    /// Whether some directories were not searched because of `max_depth`
+    pub depth_limit_reached: bool,
+    pub elapsed_ms: u64,
+}
+
+/// State shared across the recursive search
+struct FileSearch<'a> {
+    base_path: &'a PathBuf,
+    query: &'a str,
+    ignore: &'a IgnoreMatcher,
+    symlinks: SymlinkPolicy,
+    max_depth: usize,
+    max_results: usize,
+    results: Vec<FileEntry>,
+    dirs_scanned: usize,
+    depth_limit_reached: bool,
+}
+
+impl FileSearch<'_> {
+    /// One match beyond `max_results` is kept to tell whether the results were capped
+    fn is_full(&self) -> bool {
+        self.results.len() > self.max_results
+    }
+}
+
 /// Search for files and directories matching a pattern
 #[tauri::command]
-pub async fn search_files(base_path: String, query: String) -> Result<Vec<FileEntry>, String> {
+pub async fn search_files(
+    db: State<'_, AgentDb>,
+    base_path: String,
+    query: String,
+    max_depth: Option<usize>,
+    max_results: Option<usize>,
+    follow_symlinks: Option<bool>,
+) -> Result<SearchFilesResult, String> {
     log::info!("Searching files in '{}' for: '{}'", base_path, query);
+    let started = std::time::Instant::now();
 
     // Check if path is empty
     if base_path.trim().is_empty() {
@@ -1468,7 +2885,14 @@ pub async fn search_files(base_path: String, query: String) -> Result<Vec<FileEn
     // Check if query is empty
     if query.trim().is_empty() {
         log::warn!("Search query is empty, returning empty results");
-        return Ok(Vec::new());
+        return Ok(SearchFilesResult {
+            entries: Vec::new(),
+            total_matched: 0,
+            truncated: false,
+            dirs_scanned: 0,
+            depth_limit_reached: false,
+            elapsed_ms: 0,
+        });
     }
 
     let path = PathBuf::from(&base_path);
@@ -1480,9 +2904,21 @@ pub async fn search_files(base_path: String, query: String) -> Result<Vec<FileEn
     }
 
     let query_lower = query.to_lowercase();
-    let mut results = Vec::new();
+    let ignore = IgnoreMatcher::new(&load_search_ignore_settings(&db), &path);
+    let mut search = FileSearch {
+        base_path: &path,
+        query: &query_lower,
+        ignore: &ignore,
+        symlinks: SymlinkPolicy::new(follow_symlinks.unwrap_or(false), &path),
+        max_depth: max_depth.unwrap_or(DEFAULT_SEARCH_DEPTH),
+        max_results: max_results.unwrap_or(DEFAULT_SEARCH_RESULTS),
+        results: Vec::new(),
+        dirs_scanned: 0,
+        depth_limit_reached: false,
+    };
 
-    search_files_recursive(&path, &path, &query_lower, &mut results, 0)?;
+    search_files_recursive(&mut search, &path, 0)?;
+    let mut results = search.results;
 
     // Sort by relevance: exact matches first, then by name
     results.sort_by(|a, b| {
@@ -1497,29 +2933,58 @@ pub async fn search_files(base_path: String, query: String) -> Result<Vec<FileEn
     });
 
     // Limit results to prevent overwhelming the UI
-    results.truncate(50);
-
-    Ok(results)
+    let total_matched = results.len();
+    results.truncate(search.max_results);
+
+    Ok(SearchFilesResult {
+        truncated: results.len() < total_matched,
+        entries: results,
+        total_matched,
+        dirs_scanned: search.dirs_scanned,
+        depth_limit_reached: search.depth_limit_reached,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
 }
 
 fn search_files_recursive(
+    search: &mut FileSearch,
     current_path: &PathBuf,
-    base_path: &PathBuf,
-    query: &str,
-    results: &mut Vec<FileEntry>,
     depth: usize,
 ) -> Result<(), String> {
+    // Stop once enough results were found
+    if search.is_full() {
+        return Ok(());
+    }
+
     // Limit recursion depth to prevent excessive searching
-    if depth > 5 || results.len() >= 50 {
+    if depth > search.max_depth {
+        search.depth_limit_reached = true;
         return Ok(());
     }
 
     let entries = fs::read_dir(current_path)
         .map_err(|e| format!("Failed to read directory {:?}: {}", current_path, e))?;
+    search.dirs_scanned += 1;
 
     for entry in entries {
+        if search.is_full() {
+            return Ok(());
+        }
+
         let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
         let entry_path = entry.path();
+        let is_dir = entry_path.is_dir();
+        let is_symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+
+        // Skip ignored entries (dependency and build directories by default)
+        if search.ignore.is_ignored(
+            entry_path
+                .strip_prefix(search.base_path)
+                .unwrap_or(&entry_path),
+            is_dir,
+        ) {
+            continue;
+        }
 
         // Skip hidden files/directories
         if let Some(name) = entry_path.file_name().and_then(|n| n.to_str()) {
@@ -1528,9 +2993,10 @@ fn search_files_recursive(
             }
 
             // Check if name matches query
-            if name.to_lowercase().contains(query) {
-                let metadata = entry
-                    .metadata()
+            if name.to_lowercase().contains(search.query) {
+                // Describe what a symlink points to; broken links fall back to the link itself
+                let metadata = fs::metadata(&entry_path)
+                    .or_else(|_| entry.metadata())
                     .map_err(|e| format!("Failed to read metadata: {}", e))?;
 
                 let extension = if metadata.is_file() {
@@ -1542,29 +3008,286 @@ fn search_files_recursive(
                     None
                 };
 
-                results.push(FileEntry {
+                search.results.push(FileEntry {
                     name: name.to_string(),
                     path: entry_path.to_string_lossy().to_string(),
                     is_directory: metadata.is_dir(),
                     size: metadata.len(),
                     extension,
+                    is_hidden: false,
+                    is_symlink,
+                    symlink_target: if is_symlink {
+                        symlink_target(&entry_path)
+                    } else {
+                        None
+                    },
                 });
             }
         }
 
         // Recurse into directories
-        if entry_path.is_dir() {
-            // Skip common directories that shouldn't be searched
-            if let Some(dir_name) = entry_path.file_name().and_then(|n| n.to_str()) {
-                if matches!(
-                    dir_name,
-                    "node_modules" | "target" | ".git" | "dist" | "build" | ".next" | "__pycache__"
-                ) {
-                    continue;
+        if is_dir && search.symlinks.should_descend(&entry_path, is_symlink) {
+            search_files_recursive(search, &entry_path, depth + 1)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Default number of matching lines returned by `grep_files`
+const DEFAULT_GREP_MATCHES: usize = 500;
+/// Files larger than this are not searched
+const MAX_GREP_FILE_SIZE: u64 = 2 * 1024 * 1024;
+/// Matched lines are cut to this many characters
+const MAX_GREP_LINE_CHARS: usize = 500;
+/// Directory depth searched below the base path
+const MAX_GREP_DEPTH: usize = 20;
+/// The search stops after reading this many files
+const MAX_GREP_FILES: usize = 20_000;
+
+/// Options for `grep_files`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrepOptions {
+    pub case_insensitive: bool,
+    /// Stop after this many matching lines (default 500)
+    pub max_matches: Option<usize>,
+    /// Only search files whose name or relative path matches one of these globs
+    pub include: Vec<String>,
+    /// Skip files whose name or relative path matches one of these globs
+    pub exclude: Vec<String>,
+    /// Enter symlinked directories, each real directory at most once
+    pub follow_symlinks: bool,
+}
+
+/// A matching line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrepLineMatch {
+    /// 1-based line number
+    pub line_number: usize,
+    /// The line text, cut to a maximum length
+    pub line: String,
+}
+
+/// All matching lines in one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrepFileMatches {
+    pub path: String,
+    pub relative_path: String,
+    pub matches: Vec<GrepLineMatch>,
+}
+
+/// Result of `grep_files`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrepResult {
+    pub files: Vec<GrepFileMatches>,
+    pub total_matches: usize,
+    /// Whether more lines matched than `max_matches` and were left out
+    pub truncated: bool,
+    pub files_scanned: usize,
+    /// Whether some files were not searched because of the depth or file count limit
+    pub scan_limit_reached: bool,
+    pub elapsed_ms: u64,
+}
+
+struct GrepSearch<'a> {
+    base_path: &'a PathBuf,
+    regex: regex::Regex,
+    ignore: &'a IgnoreMatcher,
+    symlinks: SymlinkPolicy,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+    max_matches: usize,
+    files: Vec<GrepFileMatches>,
+    total_matches: usize,
+    files_scanned: usize,
+    truncated: bool,
+    scan_limit_reached: bool,
+}
+
+impl GrepSearch<'_> {
+    fn is_done(&self) -> bool {
+        self.truncated || self.scan_limit_reached
+    }
+
+    fn wants_file(&self, relative: &str, name: &str) -> bool {
+        let matches = |p: &glob::Pattern| p.matches(name) || p.matches(relative);
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+fn compile_globs(patterns: &[String]) -> Result<Vec<glob::Pattern>, String> {
+    patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).map_err(|e| format!("Invalid glob '{}': {}", p, e)))
+        .collect()
+}
+
+/// Searches file contents below `base_path` for a regex, skipping hidden and ignored
+/// entries, binary files and files over 2 MB. Stops at the first match past `max_matches`,
+/// 20 directories deep or after reading 20,000 files.
+#[tauri::command]
+pub async fn grep_files(
+    db: State<'_, AgentDb>,
+    base_path: String,
+    pattern: String,
+    options: Option<GrepOptions>,
+) -> Result<GrepResult, String> {
+    log::info!("Grepping files in '{}' for: '{}'", base_path, pattern);
+    let started = std::time::Instant::now();
+    let options = options.unwrap_or_default();
+
+    if pattern.is_empty() {
+        return Err("Search pattern cannot be empty".to_string());
+    }
+    let path = PathBuf::from(&base_path);
+    if !path.is_dir() {
+        return Err(format!("Path does not exist: {}", base_path));
+    }
+
+    let regex = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(options.case_insensitive)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))?;
+    let settings = load_search_ignore_settings(&db);
+
+    // Reading every file is slow on large trees, so keep it off the async runtime
+    tokio::task::spawn_blocking(move || -> Result<GrepResult, String> {
+        let mut result = grep_directory(&path, regex, &settings, &options)?;
+        result.elapsed_ms = started.elapsed().as_millis() as u64;
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Grep task failed: {}", e))?
+}
+
+fn grep_directory(
+    path: &PathBuf,
+    regex: regex::Regex,
+    settings: &SearchIgnoreSettings,
+    options: &GrepOptions,
+) -> Result<GrepResult, String> {
+    let ignore = IgnoreMatcher::new(settings, path);
+    let mut search = GrepSearch {
+        base_path: path,
+        regex,
+        ignore: &ignore,
+        symlinks: SymlinkPolicy::new(options.follow_symlinks, path),
+        include: compile_globs(&options.include)?,
+        exclude: compile_globs(&options.exclude)?,
+        max_matches: options.max_matches.unwrap_or(DEFAULT_GREP_MATCHES),
+        files: Vec::new(),
+        total_matches: 0,
+        files_scanned: 0,
+        truncated: false,
+        scan_limit_reached: false,
+    };
+    grep_files_recursive(&mut search, path, 0)?;
+
+    Ok(GrepResult {
+        files: search.files,
+        total_matches: search.total_matches,
+        truncated: search.truncated,
+        files_scanned: search.files_scanned,
+        scan_limit_reached: search.scan_limit_reached,
+        elapsed_ms: 0,
+    })
+}
+
+fn grep_files_recursive(
+    search: &mut GrepSearch,
+    current_path: &PathBuf,
+    depth: usize,
+) -> Result<(), String> {
+    if depth > MAX_GREP_DEPTH {
+        search.scan_limit_reached = true;
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(current_path)
+        .map_err(|e| format!("Failed to read directory {:?}: {}", current_path, e))?;
+
+    for entry in entries.flatten() {
+        if search.is_done() {
+            return Ok(());
+        }
+
+        let entry_path = entry.path();
+        let name = match entry_path.file_name().and_then(|n| n.to_str()) {
+            Some(name) if !name.starts_with('.') => name.to_string(),
+            _ => continue,
+        };
+        let relative = entry_path
+            .strip_prefix(search.base_path)
+            .unwrap_or(&entry_path)
+            .to_path_buf();
+        let is_dir = entry_path.is_dir();
+        if search.ignore.is_ignored(&relative, is_dir) {
+            continue;
+        }
+
+        if is_dir {
+            let is_symlink = entry.file_type().is_ok_and(|t| t.is_symlink());
+            if !search.symlinks.should_descend(&entry_path, is_symlink) {
+                continue;
+            }
+            // Unreadable subdirectories shouldn't abort the whole search
+            if let Err(e) = grep_files_recursive(search, &entry_path, depth + 1) {
+                log::debug!("Skipping directory during grep: {}", e);
+            }
+            continue;
+        }
+
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if !search.wants_file(&relative, &name) {
+            continue;
+        }
+        // Files behind symlinks are only read when following symlinks
+        let metadata = if search.symlinks.follows() {
+            fs::metadata(&entry_path)
+        } else {
+            entry.metadata()
+        };
+        if metadata.map_or(true, |m| !m.is_file() || m.len() > MAX_GREP_FILE_SIZE) {
+            continue;
+        }
+        if search.files_scanned >= MAX_GREP_FILES {
+            search.scan_limit_reached = true;
+            return Ok(());
+        }
+        let Ok(bytes) = fs::read(&entry_path) else {
+            continue;
+        };
+        // Treat files with NUL bytes near the start as binary
+        if bytes.iter().take(8192).any(|&b| b == 0) {
+            continue;
+        }
+        search.files_scanned += 1;
+
+        let content = String::from_utf8_lossy(&bytes);
+        let mut matches = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if search.regex.is_match(line) {
+                // Only a match that has to be dropped marks the results as truncated
+                if search.total_matches >= search.max_matches {
+                    search.truncated = true;
+                    break;
                 }
+                search.total_matches += 1;
+                matches.push(GrepLineMatch {
+                    line_number: index + 1,
+                    line: line.chars().take(MAX_GREP_LINE_CHARS).collect(),
+                });
             }
+        }
 
-            search_files_recursive(&entry_path, base_path, query, results, depth + 1)?;
+        if !matches.is_empty() {
+            search.files.push(GrepFileMatches {
+                path: entry_path.to_string_lossy().to_string(),
+                relative_path: relative,
+                matches,
+            });
         }
     }
 
@@ -1680,6 +3403,77 @@ pub async fn restore_checkpoint(
     Ok(result)
 }
 
+/// Previews what `restore_checkpoint` would change: the files it would overwrite, create or
+/// delete and the message count the session would revert to. Nothing is modified.
+#[tauri::command]
+pub async fn preview_restore_checkpoint(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    checkpoint_id: String,
+    session_id: String,
+    project_id: String,
+    project_path: String,
+) -> Result<crate::checkpoint::RestorePreview, String> {
+    log::info!(
+        "Previewing restore of checkpoint: {} for session: {}",
+        checkpoint_id,
+        session_id
+    );
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let session_path = claude_dir
+        .join("projects")
+        .join(&project_id)
+        .join(format!("{}.jsonl", session_id));
+    // A missing session file is what restore would create, so it counts as empty
+    let current_message_count = match fs::read_to_string(&session_path) {
+        Ok(content) => content.lines().filter(|l| !l.trim().is_empty()).count(),
+        Err(_) => 0,
+    };
+
+    let manager = app
+        .get_or_create_manager(session_id, project_id, PathBuf::from(&project_path))
+        .await
+        .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
+
+    manager
+        .preview_restore(&checkpoint_id, current_message_count)
+        .await
+        .map_err(|e| format!("Failed to preview restore: {}", e))
+}
+
+/// Restores only the given files from a checkpoint, without rewinding the session or
+/// touching any other file
+#[tauri::command]
+pub async fn restore_checkpoint_files(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    checkpoint_id: String,
+    session_id: String,
+    project_id: String,
+    project_path: String,
+    files: Vec<String>,
+) -> Result<crate::checkpoint::CheckpointResult, String> {
+    log::info!(
+        "Restoring {} files from checkpoint: {} for session: {}",
+        files.len(),
+        checkpoint_id,
+        session_id
+    );
+    if files.is_empty() {
+        return Err("No files selected to restore".to_string());
+    }
+
+    let manager = app
+        .get_or_create_manager(session_id, project_id, PathBuf::from(&project_path))
+        .await
+        .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
+
+    let files: Vec<PathBuf> = files.into_iter().map(PathBuf::from).collect();
+    manager
+        .restore_checkpoint_files(&checkpoint_id, &files)
+        .await
+        .map_err(|e| format!("Failed to restore files: {}", e))
+}
+
 /// Lists all checkpoints for a session
 #[tauri::command]
 pub async fn list_checkpoints(
@@ -1783,6 +3577,8 @@ pub async fn update_checkpoint_settings(
     project_path: String,
     auto_checkpoint_enabled: bool,
     checkpoint_strategy: String,
+    min_interval_secs: Option<u64>,
+    min_tool_uses: Option<u32>,
 ) -> Result<(), String> {
     use crate::checkpoint::CheckpointStrategy;
 
@@ -1807,7 +3603,12 @@ pub async fn update_checkpoint_settings(
         .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
 
     manager
-        .update_settings(auto_checkpoint_enabled, strategy)
+        .update_settings(
+            auto_checkpoint_enabled,
+            strategy,
+            min_interval_secs,
+            min_tool_uses,
+        )
         .await
         .map_err(|e| format!("Failed to update settings: {}", e))
 }
@@ -1970,6 +3771,105 @@ pub async fn cleanup_old_checkpoints(
         .map_err(|e| format!("Failed to cleanup checkpoints: {}", e))
 }
 
+/// Checkpoint cleanup result of one session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionCheckpointCleanup {
+    pub session_id: String,
+    pub removed: usize,
+    pub bytes_freed: u64,
+    /// Set when cleaning up this session failed; the other sessions are still cleaned
+    pub error: Option<String>,
+}
+
+/// Checkpoint cleanup result of a whole project
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectCheckpointCleanup {
+    pub sessions: Vec<SessionCheckpointCleanup>,
+    pub total_removed: usize,
+    pub total_bytes_freed: u64,
+}
+
+/// Prunes the checkpoints of every session of a project to the most recent
+/// `keep_per_session`, collecting unreferenced file content. Refuses while any of those
+/// sessions is running. Cached checkpoint managers of the cleaned sessions are dropped so
+/// their timelines are reloaded from disk.
+#[tauri::command]
+pub async fn cleanup_project_checkpoints(
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+    checkpoint_state: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    project_id: String,
+    project_path: String,
+    keep_per_session: usize,
+) -> Result<ProjectCheckpointCleanup, String> {
+    log::info!(
+        "Cleaning up checkpoints of project: {} ({}), keeping {} per session",
+        project_id,
+        project_path,
+        keep_per_session
+    );
+    validate_project_id(&project_id)?;
+
+    let storage = crate::checkpoint::storage::CheckpointStorage::new(
+        get_claude_dir().map_err(|e| e.to_string())?,
+    );
+    let session_ids = storage
+        .list_checkpointed_sessions(&project_id)
+        .map_err(|e| format!("Failed to list checkpointed sessions: {}", e))?;
+
+    let mut active = Vec::new();
+    for session_id in &session_ids {
+        if registry.0.get_claude_session_by_id(session_id)?.is_some() {
+            active.push(session_id.as_str());
+        }
+    }
+    if !active.is_empty() {
+        return Err(format!(
+            "Cannot clean up checkpoints while sessions are running: {}",
+            active.join(", ")
+        ));
+    }
+
+    let cleanup = tokio::task::spawn_blocking(move || {
+        let sessions: Vec<SessionCheckpointCleanup> = session_ids
+            .into_iter()
+            .map(|session_id| {
+                let size_before = storage.session_storage_size(&project_id, &session_id);
+                let (removed, error) = match storage.cleanup_old_checkpoints(
+                    &project_id,
+                    &session_id,
+                    keep_per_session,
+                ) {
+                    Ok(removed) => (removed, None),
+                    Err(e) => {
+                        log::warn!("Failed to clean up checkpoints of {}: {}", session_id, e);
+                        (0, Some(e.to_string()))
+                    }
+                };
+                let size_after = storage.session_storage_size(&project_id, &session_id);
+                SessionCheckpointCleanup {
+                    session_id,
+                    removed,
+                    bytes_freed: size_before.saturating_sub(size_after),
+                    error,
+                }
+            })
+            .collect();
+
+        ProjectCheckpointCleanup {
+            total_removed: sessions.iter().map(|s| s.removed).sum(),
+            total_bytes_freed: sessions.iter().map(|s| s.bytes_freed).sum(),
+            sessions,
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to clean up checkpoints: {}", e))?;
+
+    for session in &cleanup.sessions {
+        checkpoint_state.remove_manager(&session.session_id).await;
+    }
+    Ok(cleanup)
+}
+
 /// Gets checkpoint settings for a session
 #[tauri::command]
 pub async fn get_checkpoint_settings(
@@ -1992,6 +3892,8 @@ pub async fn get_checkpoint_settings(
         "checkpoint_strategy": timeline.checkpoint_strategy,
         "total_checkpoints": timeline.total_checkpoints,
         "current_checkpoint_id": timeline.current_checkpoint_id,
+        "min_interval_secs": timeline.auto_checkpoint_min_interval_secs,
+        "min_tool_uses": timeline.auto_checkpoint_min_tool_uses,
     }))
 }
 
@@ -2014,13 +3916,42 @@ pub async fn get_checkpoint_state_stats(
 ) -> Result<serde_json::Value, String> {
     let active_count = app.active_count().await;
     let active_sessions = app.list_active_sessions().await;
+    let managers = app.manager_stats().await;
+    let estimated_bytes: usize = managers.iter().map(|m| m.estimated_bytes).sum();
 
     Ok(serde_json::json!({
         "active_managers": active_count,
         "active_sessions": active_sessions,
+        "estimated_bytes": estimated_bytes,
+        "managers": managers,
     }))
 }
 
+/// Drops checkpoint managers whose sessions have not been accessed for `idle_secs`.
+/// Managers of running sessions are kept; evicted ones are recreated on demand.
+#[tauri::command]
+pub async fn evict_idle_managers(
+    app: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    registry: tauri::State<'_, crate::process::ProcessRegistryState>,
+    idle_secs: u64,
+) -> Result<Vec<String>, String> {
+    let running: Vec<String> = registry
+        .0
+        .get_running_claude_sessions()?
+        .into_iter()
+        .filter_map(|info| match info.process_type {
+            crate::process::ProcessType::ClaudeSession { session_id } => Some(session_id),
+            _ => None,
+        })
+        .collect();
+
+    let evicted = app
+        .evict_idle(std::time::Duration::from_secs(idle_secs), &running)
+        .await;
+    log::info!("Evicted {} idle checkpoint managers", evicted.len());
+    Ok(evicted)
+}
+
 /// Gets files modified in the last N minutes for a session
 #[tauri::command]
 pub async fn get_recently_modified_files(
@@ -2091,6 +4022,44 @@ pub async fn track_session_messages(
     Ok(())
 }
 
+/// Syncs checkpoint message tracking with the session JSONL on disk, which is
+/// authoritative over messages pushed by the frontend. Returns the number of new messages.
+#[tauri::command]
+pub async fn sync_checkpoint_tracking_from_file(
+    state: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    session_id: String,
+    project_id: String,
+    project_path: String,
+) -> Result<usize, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let session_path = claude_dir
+        .join("projects")
+        .join(&project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !session_path.exists() {
+        return Err(format!(
+            "Session file not found: {}",
+            session_path.display()
+        ));
+    }
+
+    let manager = state
+        .get_or_create_manager(session_id.clone(), project_id, PathBuf::from(&project_path))
+        .await
+        .map_err(|e| format!("Failed to get checkpoint manager: {}", e))?;
+
+    let synced = manager
+        .sync_messages_from_file(&session_path)
+        .await
+        .map_err(|e| format!("Failed to sync session messages: {}", e))?;
+    log::info!(
+        "Synced {} new messages from file for session {}",
+        synced,
+        session_id
+    );
+    Ok(synced)
+}
+
 /// Gets hooks configuration from settings at specified scope
 #[tauri::command]
 pub async fn get_hooks_config(
@@ -2225,3 +4194,136 @@ pub async fn validate_hook_command(command: String) -> Result<serde_json::Value,
         Err(e) => Err(format!("Failed to validate command: {}", e)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grep_fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello\nworld\nhello again\n").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "// Hello\n").unwrap();
+        fs::create_dir(dir.path().join("node_modules")).unwrap();
+        fs::write(dir.path().join("node_modules/dep.js"), "hello").unwrap();
+        fs::write(dir.path().join(".hidden"), "hello").unwrap();
+        fs::write(dir.path().join("data.bin"), b"hello\0world").unwrap();
+        dir
+    }
+
+    fn grep(dir: &tempfile::TempDir, pattern: &str, options: GrepOptions) -> GrepResult {
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(options.case_insensitive)
+            .build()
+            .unwrap();
+        grep_directory(
+            &dir.path().to_path_buf(),
+            regex,
+            &SearchIgnoreSettings::default(),
+            &options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_grep_files_matches_lines() {
+        let dir = grep_fixture();
+        let result = grep(&dir, "hello", GrepOptions::default());
+        assert_eq!(result.total_matches, 2);
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.files[0].relative_path, "a.txt");
+        let lines: Vec<usize> = result.files[0]
+            .matches
+            .iter()
+            .map(|m| m.line_number)
+            .collect();
+        assert_eq!(lines, vec![1, 3]);
+        assert!(!result.truncated);
+
+        let result = grep(
+            &dir,
+            "hello",
+            GrepOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(result.total_matches, 3);
+    }
+
+    #[test]
+    fn test_grep_files_truncates_only_when_a_match_is_dropped() {
+        let dir = grep_fixture();
+        let exact = grep(
+            &dir,
+            "hello",
+            GrepOptions {
+                max_matches: Some(2),
+                ..Default::default()
+            },
+        );
+        assert_eq!(exact.total_matches, 2);
+        assert!(!exact.truncated);
+
+        let capped = grep(
+            &dir,
+            "hello",
+            GrepOptions {
+                max_matches: Some(1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(capped.total_matches, 1);
+        assert!(capped.truncated);
+    }
+
+    #[test]
+    fn test_grep_files_skips_ignored_paths() {
+        let dir = grep_fixture();
+        let result = grep(
+            &dir,
+            "(?i)hello",
+            GrepOptions {
+                exclude: vec!["src/*".into()],
+                ..Default::default()
+            },
+        );
+        let paths: Vec<&str> = result
+            .files
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["a.txt"]);
+        assert_eq!(result.files_scanned, 1);
+        assert!(!result.scan_limit_reached);
+    }
+
+    #[test]
+    fn test_session_metrics_tracker_replaces_repeated_usage() {
+        let assistant = |id: Option<&str>, output: u64| {
+            let mut message = serde_json::json!({
+                "usage": { "input_tokens": 10, "output_tokens": output }
+            });
+            if let Some(id) = id {
+                message["id"] = serde_json::json!(id);
+            }
+            serde_json::json!({ "type": "assistant", "message": message })
+        };
+
+        let mut tracker = SessionMetricsTracker::new("sonnet");
+        tracker.observe(&assistant(Some("m1"), 5)).unwrap();
+        assert!(tracker.observe(&assistant(Some("m1"), 5)).is_none());
+        tracker.observe(&assistant(Some("m1"), 20)).unwrap();
+        let metrics = tracker.observe(&assistant(None, 1)).unwrap();
+        assert_eq!(metrics.input_tokens, 20);
+        assert_eq!(metrics.output_tokens, 21);
+        assert!(!metrics.is_final);
+
+        let metrics = tracker
+            .observe(&serde_json::json!({ "type": "result", "total_cost_usd": 0.5 }))
+            .unwrap();
+        assert!(metrics.is_final);
+        assert_eq!(metrics.estimated_cost_usd, 0.5);
+        assert_eq!(metrics.output_tokens, 21);
+    }
+}
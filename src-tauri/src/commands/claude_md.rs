@@ -0,0 +1,382 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use super::agents::AgentDb;
+use super::claude::{find_claude_md_files, get_claude_dir, save_claude_md_file, ClaudeMdFile};
+use crate::file_watcher::{FileWatcherManager, FileWatcherState};
+use crate::i18n;
+
+/// Bundled CLAUDE.md templates as (name, English content, Chinese content)
+const BUILTIN_TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "rust",
+        include_str!("../templates/claude_md/rust.md"),
+        include_str!("../templates/claude_md/rust.zh-CN.md"),
+    ),
+    (
+        "node",
+        include_str!("../templates/claude_md/node.md"),
+        include_str!("../templates/claude_md/node.zh-CN.md"),
+    ),
+    (
+        "python",
+        include_str!("../templates/claude_md/python.md"),
+        include_str!("../templates/claude_md/python.zh-CN.md"),
+    ),
+    (
+        "monorepo",
+        include_str!("../templates/claude_md/monorepo.md"),
+        include_str!("../templates/claude_md/monorepo.zh-CN.md"),
+    ),
+];
+
+/// A CLAUDE.md template that can be scaffolded into a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeMdTemplate {
+    /// Template name used to scaffold it (e.g. "rust")
+    pub name: String,
+    /// Short description in the current locale
+    pub description: String,
+    /// "builtin" or "user"
+    pub source: String,
+    /// Markdown content in the current locale
+    pub content: String,
+}
+
+/// Directory where users can drop additional templates (~/.claudia/templates)
+fn user_templates_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claudia").join("templates"))
+}
+
+/// Splits a template file stem into its name and optional locale, e.g. "rust.zh-CN"
+fn split_template_stem(stem: &str) -> (&str, Option<&str>) {
+    match stem.split_once('.') {
+        Some((name, locale)) if !name.is_empty() && !locale.is_empty() => (name, Some(locale)),
+        _ => (stem, None),
+    }
+}
+
+/// Uses the first Markdown heading as the description of a user template
+fn describe_user_template(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Loads bundled templates, then user templates which override bundled ones of the same name.
+/// Locale-specific files (`name.<locale>.md`) take precedence over `name.md`.
+fn load_templates() -> BTreeMap<String, ClaudeMdTemplate> {
+    let locale = i18n::get_current_locale();
+    let mut templates = BTreeMap::new();
+
+    for (name, en, zh) in BUILTIN_TEMPLATES {
+        let content = if locale == "zh-CN" { zh } else { en };
+        templates.insert(
+            name.to_string(),
+            ClaudeMdTemplate {
+                name: name.to_string(),
+                description: i18n::t(&format!("claude_md_template.{}", name)),
+                source: "builtin".to_string(),
+                content: content.to_string(),
+            },
+        );
+    }
+
+    let Some(dir) = user_templates_dir() else {
+        return templates;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return templates;
+    };
+
+    // name -> (is locale specific, content)
+    let mut user_templates: BTreeMap<String, (bool, String)> = BTreeMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        let (name, file_locale) = split_template_stem(stem);
+        let localized = match file_locale {
+            Some(file_locale) if file_locale == locale => true,
+            Some(_) => continue,
+            None => false,
+        };
+        if user_templates
+            .get(name)
+            .is_some_and(|(existing_localized, _)| *existing_localized && !localized)
+        {
+            continue;
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                user_templates.insert(name.to_string(), (localized, content));
+            }
+            Err(e) => log::warn!("Failed to read CLAUDE.md template {:?}: {}", path, e),
+        }
+    }
+
+    for (name, (_, content)) in user_templates {
+        templates.insert(
+            name.clone(),
+            ClaudeMdTemplate {
+                name,
+                description: describe_user_template(&content),
+                source: "user".to_string(),
+                content,
+            },
+        );
+    }
+
+    templates
+}
+
+/// Lists bundled CLAUDE.md templates plus those found in ~/.claudia/templates
+#[tauri::command]
+pub async fn list_claude_md_templates() -> Result<Vec<ClaudeMdTemplate>, String> {
+    log::info!("Listing CLAUDE.md templates");
+    Ok(load_templates().into_values().collect())
+}
+
+/// Writes a CLAUDE.md template into the project root, or into `.claude/` when
+/// `in_claude_dir` is set. Refuses to replace an existing file unless `overwrite` is true.
+#[tauri::command]
+pub async fn scaffold_claude_md(
+    project_path: String,
+    template_name: String,
+    overwrite: Option<bool>,
+    in_claude_dir: Option<bool>,
+) -> Result<String, String> {
+    log::info!(
+        "Scaffolding CLAUDE.md template {} into {}",
+        template_name,
+        project_path
+    );
+
+    let project = PathBuf::from(&project_path);
+    if !project.is_dir() {
+        return Err(format!("Project path does not exist: {}", project_path));
+    }
+
+    let template = load_templates()
+        .remove(&template_name)
+        .ok_or_else(|| format!("CLAUDE.md template not found: {}", template_name))?;
+
+    let target = if in_claude_dir.unwrap_or(false) {
+        project.join(".claude").join("CLAUDE.md")
+    } else {
+        project.join("CLAUDE.md")
+    };
+
+    if target.exists() && !overwrite.unwrap_or(false) {
+        return Err(format!(
+            "CLAUDE.md already exists at {}; enable overwrite to replace it",
+            target.display()
+        ));
+    }
+
+    let target_path = target.to_string_lossy().to_string();
+    save_claude_md_file(target_path.clone(), template.content).await?;
+    Ok(target_path)
+}
+
+/// File names Claude Code reads as memory inside each directory, in load order
+const MEMORY_FILE_NAMES: &[&str] = &["CLAUDE.md", ".claude/CLAUDE.md", "CLAUDE.local.md"];
+
+/// A CLAUDE.md file that applies to a directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveClaudeMdFile {
+    /// "user" for ~/.claude/CLAUDE.md, "project" for files in the project tree
+    pub scope: String,
+    /// Absolute path to the file
+    pub absolute_path: String,
+    /// Directory relative to the project root ("" for the root itself)
+    pub relative_dir: String,
+    /// File content
+    pub content: String,
+}
+
+/// The chain of CLAUDE.md files applying to a directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveClaudeMd {
+    /// Files in precedence order: the closest directory first, the user-level file last
+    pub files: Vec<EffectiveClaudeMdFile>,
+    /// All files concatenated in load order, so later sections override earlier ones
+    pub merged: String,
+}
+
+/// Finds the project root for `dir`: the nearest ancestor containing `.git`, or `dir` itself
+fn detect_project_root(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// Lists the directories from `project_root` down to `dir`, root first
+fn directory_chain(dir: &Path, project_root: &Path) -> Result<Vec<PathBuf>, String> {
+    if !dir.starts_with(project_root) {
+        return Err(format!(
+            "{} is not inside project {}",
+            dir.display(),
+            project_root.display()
+        ));
+    }
+
+    let mut chain: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|ancestor| ancestor.starts_with(project_root))
+        .map(Path::to_path_buf)
+        .collect();
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Concatenates files (given in load order) into a merged preview
+fn merge_claude_md(files: &[EffectiveClaudeMdFile]) -> String {
+    files
+        .iter()
+        .map(|file| {
+            format!(
+                "<!-- {}: {} -->\n{}",
+                file.scope,
+                file.absolute_path,
+                file.content.trim_end()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Resolves which CLAUDE.md files apply to `file_dir`, from the user-level
+/// ~/.claude/CLAUDE.md down through each directory between the project root and `file_dir`
+#[tauri::command]
+pub async fn get_effective_claude_md(
+    file_dir: String,
+    project_path: Option<String>,
+) -> Result<EffectiveClaudeMd, String> {
+    log::info!("Resolving effective CLAUDE.md for {}", file_dir);
+
+    let dir = fs::canonicalize(&file_dir)
+        .map_err(|e| format!("Failed to resolve directory {}: {}", file_dir, e))?;
+    let project_root = match project_path {
+        Some(path) => fs::canonicalize(&path)
+            .map_err(|e| format!("Failed to resolve project path {}: {}", path, e))?,
+        None => detect_project_root(&dir),
+    };
+
+    // Load order: user-level first, then from the project root down to `dir`
+    let mut load_order = Vec::new();
+
+    let user_claude_md = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("CLAUDE.md");
+    if let Ok(content) = fs::read_to_string(&user_claude_md) {
+        load_order.push(EffectiveClaudeMdFile {
+            scope: "user".to_string(),
+            absolute_path: user_claude_md.to_string_lossy().to_string(),
+            relative_dir: String::new(),
+            content,
+        });
+    }
+
+    for directory in directory_chain(&dir, &project_root)? {
+        let relative_dir = directory
+            .strip_prefix(&project_root)
+            .unwrap_or(&directory)
+            .to_string_lossy()
+            .to_string();
+
+        for name in MEMORY_FILE_NAMES {
+            let path = directory.join(name);
+            if !path.is_file() {
+                continue;
+            }
+            match fs::read_to_string(&path) {
+                Ok(content) => load_order.push(EffectiveClaudeMdFile {
+                    scope: "project".to_string(),
+                    absolute_path: path.to_string_lossy().to_string(),
+                    relative_dir: relative_dir.clone(),
+                    content,
+                }),
+                Err(e) => log::warn!("Failed to read {:?}: {}", path, e),
+            }
+        }
+    }
+
+    let merged = merge_claude_md(&load_order);
+    load_order.reverse();
+
+    Ok(EffectiveClaudeMd {
+        files: load_order,
+        merged,
+    })
+}
+
+/// Watches every CLAUDE.md in the project, including ones created later, and emits
+/// `claude-md-changed` plus `claude-md-changed:{path}` with the new content after a short debounce.
+/// Characters not allowed in event names are replaced with `_` in the scoped event.
+#[tauri::command]
+pub async fn watch_claude_md_files(
+    project_path: String,
+    app_handle: AppHandle,
+) -> Result<Vec<ClaudeMdFile>, String> {
+    let files =
+        find_claude_md_files(app_handle.state::<AgentDb>(), project_path.clone(), None).await?;
+
+    app_handle
+        .state::<FileWatcherState>()
+        .with_manager(|manager| manager.watch_claude_md(&project_path))
+        .map_err(|e| format!("Failed to watch CLAUDE.md files: {}", e))?;
+
+    Ok(files)
+}
+
+/// Stops watching CLAUDE.md files in the project
+#[tauri::command]
+pub async fn unwatch_claude_md_files(
+    project_path: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let key = FileWatcherManager::claude_md_watch_key(&project_path);
+    app_handle
+        .state::<FileWatcherState>()
+        .with_manager(|manager| manager.unwatch_path(&key))
+        .map_err(|e| format!("Failed to stop watching CLAUDE.md files: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_template_stem_reads_locale_suffix() {
+        assert_eq!(split_template_stem("rust"), ("rust", None));
+        assert_eq!(split_template_stem("rust.zh-CN"), ("rust", Some("zh-CN")));
+    }
+
+    #[test]
+    fn directory_chain_runs_from_root_to_dir() {
+        let chain = directory_chain(Path::new("/repo/crates/core"), Path::new("/repo")).unwrap();
+        assert_eq!(
+            chain,
+            vec![
+                PathBuf::from("/repo"),
+                PathBuf::from("/repo/crates"),
+                PathBuf::from("/repo/crates/core"),
+            ]
+        );
+        assert!(directory_chain(Path::new("/other"), Path::new("/repo")).is_err());
+    }
+}
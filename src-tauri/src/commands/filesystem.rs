@@ -1,7 +1,17 @@
-use crate::file_watcher::FileWatcherState;
+use crate::commands::agents::AgentDb;
+use crate::commands::claude::get_claude_dir;
+use crate::commands::search_ignore::{load_search_ignore_settings, IgnoreMatcher};
+use crate::file_watcher::{
+    probe_watch_capability, FileWatcherState, WatchCapability, WatchMode, WatchedPath,
+    WatcherModeStatus,
+};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
 use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,6 +30,50 @@ pub struct FileSystemChange {
     pub change_type: String, // "created", "modified", "deleted"
 }
 
+/// 递归遍历目录时的符号链接策略
+///
+/// 默认不进入符号链接目录，避免循环和跳出项目目录；开启跟随后按规范路径记录已访问目录，
+/// 同一目录只进入一次
+pub(crate) struct SymlinkPolicy {
+    follow: bool,
+    visited: HashSet<PathBuf>,
+}
+
+impl SymlinkPolicy {
+    pub(crate) fn new(follow: bool, root: &Path) -> Self {
+        let mut visited = HashSet::new();
+        if follow {
+            if let Ok(root) = fs::canonicalize(root) {
+                visited.insert(root);
+            }
+        }
+        Self { follow, visited }
+    }
+
+    pub(crate) fn follows(&self) -> bool {
+        self.follow
+    }
+
+    /// 是否进入该子目录；`is_symlink` 应取自不跟随链接的元数据（如 `DirEntry::file_type`）
+    pub(crate) fn should_descend(&mut self, dir: &Path, is_symlink: bool) -> bool {
+        if !self.follow {
+            return !is_symlink;
+        }
+        match fs::canonicalize(dir) {
+            Ok(canonical) => self.visited.insert(canonical),
+            Err(_) => false,
+        }
+    }
+}
+
+/// 符号链接指向的目标：能解析时返回规范路径，断开的链接返回链接内容
+pub(crate) fn symlink_target(path: &Path) -> Option<String> {
+    fs::canonicalize(path)
+        .or_else(|_| fs::read_link(path))
+        .ok()
+        .map(|target| target.to_string_lossy().to_string())
+}
+
 /// 读取文件内容
 #[tauri::command]
 pub async fn read_file(path: String) -> Result<String, String> {
@@ -33,11 +87,14 @@ pub async fn write_file(path: String, content: String) -> Result<(), String> {
 }
 
 /// 读取目录树结构
+///
+/// 默认跳过符号链接；`follow_symlinks` 为 true 时跟随，并防止循环
 #[tauri::command]
 pub async fn read_directory_tree(
     path: String,
     max_depth: Option<u32>,
     ignore_patterns: Option<Vec<String>>,
+    follow_symlinks: Option<bool>,
 ) -> Result<FileNode, String> {
     let path = Path::new(&path);
     if !path.exists() {
@@ -59,7 +116,9 @@ pub async fn read_directory_tree(
         ]
     });
 
-    read_directory_recursive(path, 0, max_depth, &ignore_patterns).map_err(|e| e.to_string())
+    let mut symlinks = SymlinkPolicy::new(follow_symlinks.unwrap_or(false), path);
+    read_directory_recursive(path, 0, max_depth, &ignore_patterns, &mut symlinks)
+        .map_err(|e| e.to_string())
 }
 
 fn read_directory_recursive(
@@ -67,6 +126,7 @@ fn read_directory_recursive(
     current_depth: u32,
     max_depth: u32,
     ignore_patterns: &[String],
+    symlinks: &mut SymlinkPolicy,
 ) -> std::io::Result<FileNode> {
     let name = path
         .file_name()
@@ -91,14 +151,22 @@ fn read_directory_recursive(
                     let entry = entry?;
                     let child_path = entry.path();
 
-                    // Skip symlinks to avoid infinite loops
-                    if let Ok(meta) = entry.metadata() {
-                        if !meta.file_type().is_symlink() {
+                    // Symlinks are skipped unless followed; followed directories are
+                    // entered once to avoid infinite loops
+                    if let Ok(file_type) = entry.file_type() {
+                        let is_symlink = file_type.is_symlink();
+                        let include = if child_path.is_dir() {
+                            symlinks.should_descend(&child_path, is_symlink)
+                        } else {
+                            !is_symlink || symlinks.follow
+                        };
+                        if include {
                             if let Ok(child_node) = read_directory_recursive(
                                 &child_path,
                                 current_depth + 1,
                                 max_depth,
                                 ignore_patterns,
+                                symlinks,
                             ) {
                                 children.push(child_node);
                             }
@@ -145,6 +213,172 @@ fn read_directory_recursive(
     Ok(node)
 }
 
+/// 目录占用空间
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct DirectorySize {
+    pub bytes: u64,
+    pub file_count: u64,
+}
+
+impl std::ops::AddAssign for DirectorySize {
+    fn add_assign(&mut self, other: Self) {
+        self.bytes += other.bytes;
+        self.file_count += other.file_count;
+    }
+}
+
+/// 目录大小缓存的有效期
+const DIR_SIZE_TTL: Duration = Duration::from_secs(60);
+
+/// 目录大小缓存项：(目录 mtime, 计算时间, 结果)
+type DirSizeEntry = (Option<SystemTime>, Instant, DirectorySize);
+
+/// 目录大小缓存，按路径索引
+static DIR_SIZE_CACHE: Lazy<Mutex<HashMap<PathBuf, DirSizeEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 递归统计目录大小，不跟随符号链接；`ignore` 命中的条目不计入
+pub(crate) fn directory_size(
+    path: &Path,
+    root: &Path,
+    ignore: Option<&IgnoreMatcher>,
+) -> DirectorySize {
+    let mut size = DirectorySize::default();
+    let Ok(entries) = fs::read_dir(path) else {
+        return size;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let entry_path = entry.path();
+        if let Some(ignore) = ignore {
+            let relative = entry_path.strip_prefix(root).unwrap_or(&entry_path);
+            if ignore.is_ignored(relative, metadata.is_dir()) {
+                continue;
+            }
+        }
+
+        if metadata.is_dir() {
+            size += directory_size(&entry_path, root, ignore);
+        } else if metadata.is_file() {
+            size.bytes += metadata.len();
+            size.file_count += 1;
+        }
+    }
+    size
+}
+
+/// 计算目录占用空间（跳过搜索忽略规则中的目录）
+///
+/// 结果按路径缓存一分钟；目录本身的 mtime 变化时立即重新计算
+#[tauri::command]
+pub async fn get_directory_size(
+    db: State<'_, AgentDb>,
+    path: String,
+) -> Result<DirectorySize, String> {
+    let path = PathBuf::from(&path);
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+
+    let mtime = fs::metadata(&path).and_then(|m| m.modified()).ok();
+    if let Ok(cache) = DIR_SIZE_CACHE.lock() {
+        if let Some((cached_mtime, at, size)) = cache.get(&path) {
+            if *cached_mtime == mtime && at.elapsed() < DIR_SIZE_TTL {
+                return Ok(*size);
+            }
+        }
+    }
+
+    let settings = load_search_ignore_settings(&db);
+    let root = path.clone();
+    let size = tokio::task::spawn_blocking(move || {
+        let ignore = IgnoreMatcher::new(&settings, &root);
+        directory_size(&root, &root, Some(&ignore))
+    })
+    .await
+    .map_err(|e| format!("Failed to compute directory size: {}", e))?;
+
+    if let Ok(mut cache) = DIR_SIZE_CACHE.lock() {
+        cache.retain(|_, (_, at, _)| at.elapsed() < DIR_SIZE_TTL);
+        cache.insert(path, (mtime, Instant::now(), size));
+    }
+    Ok(size)
+}
+
+/// ~/.claude 及 Claudia 缓存的空间占用明细
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClaudeDiskUsage {
+    /// 项目会话文件（不含检查点）
+    pub projects: DirectorySize,
+    /// 各项目 .timelines 下的检查点数据
+    pub checkpoints: DirectorySize,
+    pub todos: DirectorySize,
+    /// ~/.claude 中的其他内容
+    pub other: DirectorySize,
+    /// ~/.claudia/cache
+    pub claudia_cache: DirectorySize,
+    pub total: DirectorySize,
+}
+
+/// 统计 ~/.claude 各部分及 Claudia 缓存占用的空间
+#[tauri::command]
+pub async fn get_claude_disk_usage() -> Result<ClaudeDiskUsage, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let claudia_cache = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".claudia")
+        .join("cache");
+
+    tokio::task::spawn_blocking(move || {
+        let projects_dir = claude_dir.join("projects");
+        let mut projects = DirectorySize::default();
+        let mut checkpoints = DirectorySize::default();
+        for entry in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
+            let project_dir = entry.path();
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let all = directory_size(&project_dir, &project_dir, None);
+            let timelines = project_dir.join(".timelines");
+            let timeline_size = directory_size(&timelines, &timelines, None);
+            checkpoints += timeline_size;
+            // 两次遍历之间可能有新的检查点写入，相减时不能下溢
+            projects += DirectorySize {
+                bytes: all.bytes.saturating_sub(timeline_size.bytes),
+                file_count: all.file_count.saturating_sub(timeline_size.file_count),
+            };
+        }
+
+        let todos_dir = claude_dir.join("todos");
+        let todos = directory_size(&todos_dir, &todos_dir, None);
+        let everything = directory_size(&claude_dir, &claude_dir, None);
+        let mut counted = projects;
+        counted += checkpoints;
+        counted += todos;
+        let other = DirectorySize {
+            bytes: everything.bytes.saturating_sub(counted.bytes),
+            file_count: everything.file_count.saturating_sub(counted.file_count),
+        };
+        let claudia_cache = directory_size(&claudia_cache, &claudia_cache, None);
+
+        let mut total = everything;
+        total += claudia_cache;
+        ClaudeDiskUsage {
+            projects,
+            checkpoints,
+            todos,
+            other,
+            claudia_cache,
+            total,
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to compute disk usage: {}", e))
+}
+
 /// 搜索文件
 #[tauri::command]
 pub async fn search_files_by_name(
@@ -248,16 +482,34 @@ pub async fn get_file_info(path: String) -> Result<FileNode, String> {
     })
 }
 
-/// 监听文件系统变化
+/// 监听文件系统变化，返回实际使用的监听方式。
+/// `check` 为 true 时先检测原生事件能否触发，不能则对该路径使用轮询
 #[tauri::command]
 pub async fn watch_directory(
     watcher_state: State<'_, FileWatcherState>,
     path: String,
     recursive: Option<bool>,
-) -> Result<(), String> {
+    check: Option<bool>,
+) -> Result<WatchMode, String> {
     let recursive = recursive.unwrap_or(false);
 
-    watcher_state.with_manager(|manager| manager.watch_path(&path, recursive))
+    let prefer_polling = if check.unwrap_or(false) {
+        can_watch_path(path.clone()).await?.recommended_mode == WatchMode::Polling
+    } else {
+        false
+    };
+
+    watcher_state
+        .with_manager(|manager| manager.watch_path_for(&path, recursive, None, prefer_polling))
+}
+
+/// 检测路径是否支持原生监听：临时监听并写入、删除一个标记文件，报告事件是否触发，
+/// 不触发时建议使用轮询
+#[tauri::command]
+pub async fn can_watch_path(path: String) -> Result<WatchCapability, String> {
+    tokio::task::spawn_blocking(move || probe_watch_capability(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Failed to check watch capability: {}", e))
 }
 
 /// 停止监听指定路径
@@ -269,14 +521,88 @@ pub async fn unwatch_directory(
     watcher_state.with_manager(|manager| manager.unwatch_path(&path))
 }
 
-/// 获取当前监听的路径列表
+/// 获取当前监听的路径列表，包括每个路径对应的项目和引用计数
 #[tauri::command]
 pub async fn get_watched_paths(
     watcher_state: State<'_, FileWatcherState>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<WatchedPath>, String> {
     watcher_state.with_manager(|manager| Ok(manager.get_watched_paths()))
 }
 
+/// 停止所有监听（忽略引用计数），返回停止的路径数量
+#[tauri::command]
+pub async fn unwatch_all(watcher_state: State<'_, FileWatcherState>) -> Result<usize, String> {
+    watcher_state.with_manager(|manager| Ok(manager.unwatch_all()))
+}
+
+/// 设置文件变更事件的防抖间隔（毫秒），同一路径在间隔内的变更合并为一次通知
+#[tauri::command]
+pub async fn set_file_watcher_debounce(
+    watcher_state: State<'_, FileWatcherState>,
+    debounce_ms: u64,
+) -> Result<(), String> {
+    watcher_state.with_manager(|manager| manager.set_debounce_ms(debounce_ms))
+}
+
+/// 获取文件变更事件的防抖间隔（毫秒）
+#[tauri::command]
+pub async fn get_file_watcher_debounce(
+    watcher_state: State<'_, FileWatcherState>,
+) -> Result<u64, String> {
+    watcher_state.with_manager(|manager| Ok(manager.debounce_ms()))
+}
+
+/// 读取保存的轮询设置：(是否强制轮询, 轮询间隔毫秒)
+pub fn load_file_watcher_polling(conn: &rusqlite::Connection) -> (bool, Option<u64>) {
+    let setting = |key: &str| {
+        conn.query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            [key],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+    };
+    let force = setting("file_watcher_force_polling").is_some_and(|v| v == "true");
+    let interval_ms = setting("file_watcher_poll_interval_ms").and_then(|v| v.parse().ok());
+    (force, interval_ms)
+}
+
+/// 设置是否强制使用轮询监听（适用于 NFS、WSL 等原生事件不可用的文件系统），
+/// 已有的监听会按新设置重建；原生监听失败时无论设置如何都会自动退回轮询
+#[tauri::command]
+pub async fn set_file_watcher_polling(
+    db: State<'_, AgentDb>,
+    watcher_state: State<'_, FileWatcherState>,
+    force: bool,
+    interval_ms: Option<u64>,
+) -> Result<WatcherModeStatus, String> {
+    watcher_state.with_manager(|manager| manager.set_polling(force, interval_ms))?;
+
+    let conn = db.lock()?;
+    let mut settings = vec![("file_watcher_force_polling", force.to_string())];
+    if let Some(interval_ms) = interval_ms {
+        settings.push(("file_watcher_poll_interval_ms", interval_ms.to_string()));
+    }
+    for (key, value) in settings {
+        conn.execute(
+            "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = ?2",
+            [key, value.as_str()],
+        )
+        .map_err(|e| format!("Failed to save file watcher setting: {}", e))?;
+    }
+
+    watcher_state.with_manager(|manager| Ok(manager.mode_status()))
+}
+
+/// 获取当前的监听方式（原生事件或轮询）及各路径实际使用的方式
+#[tauri::command]
+pub async fn get_file_watcher_mode(
+    watcher_state: State<'_, FileWatcherState>,
+) -> Result<WatcherModeStatus, String> {
+    watcher_state.with_manager(|manager| Ok(manager.mode_status()))
+}
+
 /// 获取文件树（简化版，供文件浏览器使用）
 #[tauri::command]
 pub async fn get_file_tree(project_path: String) -> Result<Vec<FileNode>, String> {
@@ -298,9 +624,33 @@ pub async fn get_file_tree(project_path: String) -> Result<Vec<FileNode>, String
     ];
 
     // 增加最大深度为 10，以支持更深的文件夹结构
-    let root_node =
-        read_directory_recursive(path, 0, 10, &ignore_patterns).map_err(|e| e.to_string())?;
+    let mut symlinks = SymlinkPolicy::new(false, path);
+    let root_node = read_directory_recursive(path, 0, 10, &ignore_patterns, &mut symlinks)
+        .map_err(|e| e.to_string())?;
 
     // Return children of root node if it has any
     Ok(root_node.children.unwrap_or_default())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symlink_policy_skips_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("sub");
+        fs::create_dir(&sub).unwrap();
+        let link = sub.join("loop");
+        std::os::unix::fs::symlink(dir.path(), &link).unwrap();
+
+        let mut policy = SymlinkPolicy::new(false, dir.path());
+        assert!(policy.should_descend(&sub, false));
+        assert!(!policy.should_descend(&link, true));
+
+        let mut policy = SymlinkPolicy::new(true, dir.path());
+        assert!(policy.should_descend(&sub, false));
+        assert!(!policy.should_descend(&link, true));
+        assert!(!policy.should_descend(&sub, false));
+    }
+}
@@ -117,6 +117,72 @@ pub async fn get_git_status(path: String) -> Result<GitStatus, String> {
     })
 }
 
+/// 与上游分支的同步状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitRemoteStatus {
+    pub branch: String,
+    /// 上游分支，如 "origin/main"；未配置时为 `None`
+    pub upstream: Option<String>,
+    pub has_upstream: bool,
+    /// 本地有而上游没有的提交数（待推送）
+    pub ahead: u32,
+    /// 上游有而本地没有的提交数
+    pub behind: u32,
+    /// 上游所在远程的 URL，没有上游时取 origin
+    pub remote_url: Option<String>,
+    /// 工作区是否有未提交的改动（含未跟踪文件）
+    pub is_dirty: bool,
+}
+
+/// 成功时返回命令输出（去除首尾空白），失败或输出为空时返回 `None`
+fn git_output_line(repo: &Path, args: &[&str]) -> Option<String> {
+    run_git(repo, args)
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|line| !line.is_empty())
+}
+
+/// 获取与远程的同步状态：领先/落后上游的提交数、远程 URL 及工作区是否干净
+///
+/// 没有配置上游时 `has_upstream` 为 false，领先/落后计数为 0
+#[tauri::command]
+pub async fn get_git_remote_status(repo_path: String) -> Result<GitRemoteStatus, String> {
+    let repo = open_repo(&repo_path).map_err(|e| e.to_string())?;
+    let branch = current_branch(repo).map_err(|e| e.to_string())?;
+
+    let upstream = git_output_line(
+        repo,
+        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
+    );
+    let (ahead, behind) = if upstream.is_some() {
+        get_tracking_info(repo)?
+    } else {
+        (0, 0)
+    };
+
+    let remote_name = git_output_line(repo, &["config", &format!("branch.{}.remote", branch)])
+        .filter(|remote| remote != ".")
+        .unwrap_or_else(|| "origin".to_string());
+    let remote_url = git_output_line(repo, &["remote", "get-url", &remote_name]);
+
+    let status = run_git(repo, &["status", "--porcelain=v1"]).map_err(|e| e.to_string())?;
+    if !status.status.success() {
+        return Err(command_output(&status));
+    }
+    let is_dirty = !status.stdout.iter().all(u8::is_ascii_whitespace);
+
+    Ok(GitRemoteStatus {
+        branch,
+        has_upstream: upstream.is_some(),
+        upstream,
+        ahead,
+        behind,
+        remote_url,
+        is_dirty,
+    })
+}
+
 fn get_tracking_info(path: &Path) -> Result<(u32, u32), String> {
     // Get ahead/behind counts
     let ahead_output = Command::new("git")
@@ -431,6 +497,628 @@ pub async fn get_git_diff(
     Ok(String::from_utf8_lossy(&diff_output.stdout).to_string())
 }
 
+/// Git 写操作失败的原因，前端可按 `kind` 区分处理
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GitError {
+    NotARepository {
+        path: String,
+    },
+    InvalidInput {
+        message: String,
+    },
+    /// 暂存区没有可提交的改动
+    NothingToCommit,
+    /// pre-commit / commit-msg 等钩子拒绝了提交，`output` 为钩子的输出
+    HookRejected {
+        output: String,
+    },
+    /// 工作区有未提交的改动，切换会被拒绝
+    UncommittedChanges {
+        paths: Vec<String>,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitError::NotARepository { path } => write!(f, "Not a git repository: {}", path),
+            GitError::InvalidInput { message } | GitError::Failed { message } => {
+                write!(f, "{}", message)
+            }
+            GitError::NothingToCommit => write!(f, "Nothing to commit"),
+            GitError::HookRejected { output } => write!(f, "Commit rejected by hook: {}", output),
+            GitError::UncommittedChanges { paths } => {
+                write!(f, "{} files have uncommitted changes", paths.len())
+            }
+        }
+    }
+}
+
+/// 提交选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitCommitOptions {
+    /// 追加 `Signed-off-by` 行
+    #[serde(default)]
+    pub sign_off: bool,
+    /// 覆盖作者名称，未设置时使用 git 配置
+    pub author_name: Option<String>,
+    /// 覆盖作者邮箱，未设置时使用 git 配置
+    pub author_email: Option<String>,
+}
+
+/// 在仓库中执行 git 命令，仅在无法启动 git 时返回错误
+fn run_git(repo: &Path, args: &[&str]) -> Result<std::process::Output, GitError> {
+    Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .map_err(|e| GitError::Failed {
+            message: format!("Failed to execute git command: {}", e),
+        })
+}
+
+/// 合并 stdout 与 stderr，钩子的输出可能在任意一个里
+fn command_output(output: &std::process::Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    format!("{}\n{}", stdout.trim(), stderr.trim())
+        .trim()
+        .to_string()
+}
+
+/// 校验路径是 git 仓库
+fn open_repo(repo_path: &str) -> Result<&Path, GitError> {
+    let path = Path::new(repo_path);
+    let output = run_git(path, &["rev-parse", "--git-dir"]).ok();
+    if !path.exists() || !output.is_some_and(|o| o.status.success()) {
+        return Err(GitError::NotARepository {
+            path: repo_path.to_string(),
+        });
+    }
+    Ok(path)
+}
+
+/// 校验待暂存/取消暂存的路径列表
+fn validate_paths(paths: &[String]) -> Result<(), GitError> {
+    if paths.is_empty() {
+        return Err(GitError::InvalidInput {
+            message: "No paths given".to_string(),
+        });
+    }
+    if paths.iter().any(|p| p.trim().is_empty()) {
+        return Err(GitError::InvalidInput {
+            message: "Empty path".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// HEAD 是否指向一个提交（新仓库在首次提交前没有）
+fn has_head(repo: &Path) -> bool {
+    run_git(repo, &["rev-parse", "--verify", "--quiet", "HEAD"])
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// 暂存区是否有改动
+fn has_staged_changes(repo: &Path) -> Result<bool, GitError> {
+    let output = run_git(repo, &["diff", "--cached", "--quiet"])?;
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => Err(GitError::Failed {
+            message: command_output(&output),
+        }),
+    }
+}
+
+/// 仓库中是否启用了会影响提交的钩子（遵循 `core.hooksPath`）
+fn has_commit_hooks(repo: &Path) -> bool {
+    let Ok(output) = run_git(repo, &["rev-parse", "--git-path", "hooks"]) else {
+        return false;
+    };
+    let hooks_dir = repo.join(String::from_utf8_lossy(&output.stdout).trim());
+    ["pre-commit", "prepare-commit-msg", "commit-msg"]
+        .iter()
+        .any(|hook| is_executable(&hooks_dir.join(hook)))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// 将失败的 `git commit` 归类
+fn classify_commit_failure(output: &str, hooks_enabled: bool) -> GitError {
+    if output.contains("nothing to commit") || output.contains("no changes added to commit") {
+        GitError::NothingToCommit
+    } else if hooks_enabled {
+        // Git prints nothing of its own when a hook fails, only the hook's output
+        GitError::HookRejected {
+            output: output.to_string(),
+        }
+    } else {
+        GitError::Failed {
+            message: output.to_string(),
+        }
+    }
+}
+
+/// 暂存指定路径（包括删除的文件）
+#[tauri::command]
+pub async fn git_stage(repo_path: String, paths: Vec<String>) -> Result<(), GitError> {
+    let repo = open_repo(&repo_path)?;
+    validate_paths(&paths)?;
+
+    let mut args = vec!["add", "-A", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let output = run_git(repo, &args)?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    Ok(())
+}
+
+/// 取消暂存指定路径，工作区的改动保持不变
+#[tauri::command]
+pub async fn git_unstage(repo_path: String, paths: Vec<String>) -> Result<(), GitError> {
+    let repo = open_repo(&repo_path)?;
+    validate_paths(&paths)?;
+
+    // Before the first commit there is no HEAD to reset the index to
+    let mut args = if has_head(repo) {
+        vec!["reset", "-q", "HEAD", "--"]
+    } else {
+        vec!["rm", "--cached", "-r", "-q", "--ignore-unmatch", "--"]
+    };
+    args.extend(paths.iter().map(String::as_str));
+    let output = run_git(repo, &args)?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    Ok(())
+}
+
+/// 提交暂存区并返回新提交的哈希
+///
+/// `amend` 时修改上一个提交，消息为空则沿用原消息
+#[tauri::command]
+pub async fn git_commit(
+    repo_path: String,
+    message: String,
+    amend: bool,
+    options: Option<GitCommitOptions>,
+) -> Result<String, GitError> {
+    let repo = open_repo(&repo_path)?;
+    let options = options.unwrap_or_default();
+    let message = message.trim();
+
+    if amend {
+        if !has_head(repo) {
+            return Err(GitError::InvalidInput {
+                message: "There is no commit to amend".to_string(),
+            });
+        }
+    } else {
+        if message.is_empty() {
+            return Err(GitError::InvalidInput {
+                message: "Commit message cannot be empty".to_string(),
+            });
+        }
+        if !has_staged_changes(repo)? {
+            return Err(GitError::NothingToCommit);
+        }
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg("commit").current_dir(repo);
+    if amend {
+        cmd.arg("--amend");
+    }
+    if message.is_empty() {
+        cmd.arg("--no-edit");
+    } else {
+        cmd.args(["-m", message]);
+    }
+    if options.sign_off {
+        cmd.arg("--signoff");
+    }
+    // Environment overrides keep the configured value for whichever field isn't given
+    if let Some(name) = options
+        .author_name
+        .as_deref()
+        .filter(|n| !n.trim().is_empty())
+    {
+        cmd.env("GIT_AUTHOR_NAME", name.trim());
+    }
+    if let Some(email) = options
+        .author_email
+        .as_deref()
+        .filter(|e| !e.trim().is_empty())
+    {
+        cmd.env("GIT_AUTHOR_EMAIL", email.trim());
+    }
+
+    let output = cmd.output().map_err(|e| GitError::Failed {
+        message: format!("Failed to execute git command: {}", e),
+    })?;
+    if !output.status.success() {
+        let error = classify_commit_failure(&command_output(&output), has_commit_hooks(repo));
+        log::warn!("git commit failed in {}: {}", repo_path, error);
+        return Err(error);
+    }
+
+    let head = run_git(repo, &["rev-parse", "HEAD"])?;
+    if !head.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&head),
+        });
+    }
+    Ok(String::from_utf8_lossy(&head.stdout).trim().to_string())
+}
+
+/// 校验分支名或引用，拒绝会被 git 当作选项的值
+fn validate_ref_arg(value: &str, what: &str) -> Result<(), GitError> {
+    let value = value.trim();
+    if value.is_empty() || value.starts_with('-') || value.chars().any(char::is_whitespace) {
+        return Err(GitError::InvalidInput {
+            message: format!("Invalid {}: '{}'", what, value),
+        });
+    }
+    Ok(())
+}
+
+/// 当前分支名；分离 HEAD 时返回提交的短哈希
+fn current_branch(repo: &Path) -> Result<String, GitError> {
+    let output = run_git(repo, &["symbolic-ref", "--quiet", "--short", "HEAD"])?;
+    let output = if output.status.success() {
+        output
+    } else {
+        run_git(repo, &["rev-parse", "--short", "HEAD"])?
+    };
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 有未提交改动的已跟踪文件，未跟踪文件不影响切换
+fn uncommitted_paths(repo: &Path) -> Result<Vec<String>, GitError> {
+    let output = run_git(repo, &["status", "--porcelain=v1", "--untracked-files=no"])?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.len() > 3)
+        .map(|line| line[3..].trim().to_string())
+        .collect())
+}
+
+/// 创建分支并切换过去，返回切换后的当前分支
+///
+/// 未提交的改动会带到新分支上；`from` 为空时从 HEAD 创建
+#[tauri::command]
+pub async fn git_create_branch(
+    repo_path: String,
+    name: String,
+    from: Option<String>,
+) -> Result<String, GitError> {
+    let repo = open_repo(&repo_path)?;
+    let name = name.trim();
+    validate_ref_arg(name, "branch name")?;
+    let format_check = run_git(repo, &["check-ref-format", "--branch", name])?;
+    if !format_check.status.success() {
+        return Err(GitError::InvalidInput {
+            message: format!("Invalid branch name: '{}'", name),
+        });
+    }
+    let existing = format!("refs/heads/{}", name);
+    if run_git(repo, &["rev-parse", "--verify", "--quiet", &existing])?
+        .status
+        .success()
+    {
+        return Err(GitError::InvalidInput {
+            message: format!("Branch '{}' already exists", name),
+        });
+    }
+
+    let mut args = vec!["checkout", "-b", name];
+    if let Some(from) = from.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+        validate_ref_arg(from, "start point")?;
+        args.push(from);
+    }
+    let output = run_git(repo, &args)?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    current_branch(repo)
+}
+
+/// 切换到分支或提交，返回切换后的当前分支
+///
+/// 有未提交的改动时拒绝切换，除非 `force`（丢弃改动）或 `stash`（先暂存到 stash）
+#[tauri::command]
+pub async fn git_checkout(
+    repo_path: String,
+    reference: String,
+    force: bool,
+    stash: Option<bool>,
+) -> Result<String, GitError> {
+    let repo = open_repo(&repo_path)?;
+    let reference = reference.trim();
+    validate_ref_arg(reference, "reference")?;
+
+    if !force {
+        let dirty = uncommitted_paths(repo)?;
+        if !dirty.is_empty() {
+            if !stash.unwrap_or(false) {
+                return Err(GitError::UncommittedChanges { paths: dirty });
+            }
+            let message = format!("Before checkout of {}", reference);
+            let output = run_git(repo, &["stash", "push", "-m", &message])?;
+            if !output.status.success() {
+                return Err(GitError::Failed {
+                    message: command_output(&output),
+                });
+            }
+            log::info!("Stashed {} changed files in {}", dirty.len(), repo_path);
+        }
+    }
+
+    let mut args = vec!["checkout"];
+    if force {
+        args.push("--force");
+    }
+    // The trailing "--" makes git treat the argument as a ref, never as a path
+    args.extend([reference, "--"]);
+    let output = run_git(repo, &args)?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    current_branch(repo)
+}
+
+/// 隔离会话的起点：新分支及其上的检查点
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IsolatedSessionStart {
+    pub branch: String,
+    pub checkpoint: crate::checkpoint::CheckpointResult,
+}
+
+/// 为会话创建并切换到新分支，再创建检查点，供"隔离会话"一步完成
+#[tauri::command]
+pub async fn start_isolated_session(
+    checkpoints: tauri::State<'_, crate::checkpoint::state::CheckpointState>,
+    project_path: String,
+    branch_name: String,
+    from: Option<String>,
+    session_id: String,
+    project_id: String,
+) -> Result<IsolatedSessionStart, String> {
+    let branch = git_create_branch(project_path.clone(), branch_name, from)
+        .await
+        .map_err(|e| e.to_string())?;
+    let checkpoint = super::claude::create_checkpoint(
+        checkpoints,
+        session_id,
+        project_id,
+        project_path,
+        None,
+        Some(format!("Start of branch {}", branch)),
+    )
+    .await?;
+    Ok(IsolatedSessionStart { branch, checkpoint })
+}
+
+/// 单个文件的统一 diff
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitFileDiff {
+    pub path: String,
+    pub status: String, // "added", "modified", "deleted", "renamed"
+    pub insertions: u32,
+    pub deletions: u32,
+    /// 该文件的统一 diff，包含 `diff --git` 头
+    pub patch: String,
+}
+
+/// 自某一时间点以来的工作区改动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitDiffSince {
+    /// 该时间点的 HEAD；仓库当时还没有提交时为 `None`，此时与空树比较
+    pub base_commit: Option<String>,
+    /// "reflog"（按 HEAD 的移动记录）或 "history"（按提交时间）
+    pub base_source: String,
+    pub files: Vec<GitFileDiff>,
+}
+
+/// git 的空树对象，用于与"无提交"比较
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// 从 `git reflog --date=unix --format=%H%x09%gd` 的输出中找出 `since` 时刻的 HEAD
+///
+/// reflog 按时间从新到旧排列；最早的记录仍晚于 `since` 时返回 `None`
+fn head_at_from_reflog(reflog: &str, since: i64) -> Option<String> {
+    reflog.lines().find_map(|line| {
+        let (hash, selector) = line.split_once('\t')?;
+        let timestamp: i64 = selector
+            .rsplit_once("@{")?
+            .1
+            .trim_end_matches('}')
+            .parse()
+            .ok()?;
+        (timestamp <= since).then(|| hash.to_string())
+    })
+}
+
+/// 将多文件的 `git diff` 输出按文件拆分
+fn split_unified_diff(diff_text: &str) -> Vec<GitFileDiff> {
+    let mut files: Vec<GitFileDiff> = Vec::new();
+    let mut in_hunk = false;
+
+    for line in diff_text.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            // The header is "a/<path> b/<path>"; the +++/--- lines below refine it
+            let path = header
+                .trim_end()
+                .rsplit_once(" b/")
+                .map(|(_, path)| path)
+                .unwrap_or(header.trim_end());
+            in_hunk = false;
+            files.push(GitFileDiff {
+                path: path.to_string(),
+                status: "modified".to_string(),
+                insertions: 0,
+                deletions: 0,
+                patch: String::new(),
+            });
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        file.patch.push_str(line);
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.starts_with("@@") {
+            in_hunk = true;
+        } else if in_hunk {
+            if content.starts_with('+') {
+                file.insertions += 1;
+            } else if content.starts_with('-') {
+                file.deletions += 1;
+            }
+        } else if content.starts_with("new file mode") {
+            file.status = "added".to_string();
+        } else if content.starts_with("deleted file mode") {
+            file.status = "deleted".to_string();
+        } else if let Some(path) = content.strip_prefix("rename to ") {
+            file.status = "renamed".to_string();
+            file.path = path.to_string();
+        } else if let Some(path) = content.strip_prefix("+++ b/") {
+            file.path = path.to_string();
+        }
+    }
+
+    files
+}
+
+/// 时间点之后新建的未跟踪文件（按修改时间判断）
+fn untracked_files_since(repo: &Path, since: i64) -> Result<Vec<String>, GitError> {
+    let output = run_git(repo, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .filter(|path| {
+            std::fs::metadata(repo.join(path))
+                .and_then(|m| m.modified())
+                .map(|modified| {
+                    chrono::DateTime::<chrono::Utc>::from(modified).timestamp() >= since
+                })
+                .unwrap_or(false)
+        })
+        .map(str::to_string)
+        .collect())
+}
+
+/// 获取自某一时间点（如检查点的时间戳）以来的改动，按文件拆分
+///
+/// 以该时刻的 HEAD（优先从 reflog 查找，否则按提交时间）为基准与工作区比较，
+/// 并包含该时刻之后新建的未跟踪文件，因此未提交的改动也能对应到会话的某一段
+#[tauri::command]
+pub async fn git_diff_since(
+    repo_path: String,
+    since_timestamp: String,
+) -> Result<GitDiffSince, GitError> {
+    let repo = open_repo(&repo_path)?;
+    let since = chrono::DateTime::parse_from_rfc3339(since_timestamp.trim())
+        .map_err(|e| GitError::InvalidInput {
+            message: format!("Invalid timestamp '{}': {}", since_timestamp, e),
+        })?
+        .timestamp();
+
+    let reflog = run_git(
+        repo,
+        &[
+            "reflog",
+            "show",
+            "HEAD",
+            "--date=unix",
+            "--format=%H%x09%gd",
+        ],
+    )?;
+    let from_reflog = reflog
+        .status
+        .success()
+        .then(|| head_at_from_reflog(&String::from_utf8_lossy(&reflog.stdout), since))
+        .flatten();
+    let (base_commit, base_source) = match from_reflog {
+        Some(commit) => (Some(commit), "reflog"),
+        None => {
+            // The reflog doesn't reach back that far; use the last commit made before then
+            let before = format!("--before={}", since);
+            let output = run_git(repo, &["rev-list", "-1", &before, "HEAD"])?;
+            let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (
+                (output.status.success() && !commit.is_empty()).then_some(commit),
+                "history",
+            )
+        }
+    };
+
+    let base = base_commit.as_deref().unwrap_or(EMPTY_TREE);
+    let output = run_git(repo, &["diff", "--no-color", base, "--"])?;
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            message: command_output(&output),
+        });
+    }
+    let mut files = split_unified_diff(&String::from_utf8_lossy(&output.stdout));
+
+    for path in untracked_files_since(repo, since)? {
+        // --no-index exits with 1 when the files differ, which they always do here
+        let output = run_git(
+            repo,
+            &["diff", "--no-color", "--no-index", "--", "/dev/null", &path],
+        )?;
+        files.extend(split_unified_diff(&String::from_utf8_lossy(&output.stdout)));
+    }
+
+    Ok(GitDiffSince {
+        base_commit,
+        base_source: base_source.to_string(),
+        files,
+    })
+}
+
 /// 获取 Git 提交列表（简化版）
 #[tauri::command]
 pub async fn get_git_commits(project_path: String, limit: usize) -> Result<Vec<GitCommit>, String> {
@@ -461,4 +1149,58 @@ mod tests {
         assert_eq!(modified.len(), 1);
         assert_eq!(modified[0].path, "modified-file.txt");
     }
+
+    #[test]
+    fn test_head_at_from_reflog() {
+        let reflog = "ccc\tHEAD@{300}\nbbb\tHEAD@{200}\naaa\tHEAD@{100}\n";
+        assert_eq!(head_at_from_reflog(reflog, 250), Some("bbb".to_string()));
+        assert_eq!(head_at_from_reflog(reflog, 300), Some("ccc".to_string()));
+        assert_eq!(head_at_from_reflog(reflog, 50), None);
+    }
+
+    #[test]
+    fn test_split_unified_diff() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\nindex 1..2 100644\n\
+                    --- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1,2 @@\n-old\n+new\n+more\n\
+                    diff --git a/gone.txt b/gone.txt\ndeleted file mode 100644\n\
+                    --- a/gone.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-bye\n";
+        let files = split_unified_diff(diff);
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/a.rs");
+        assert_eq!((files[0].insertions, files[0].deletions), (2, 1));
+        assert!(files[0].patch.starts_with("diff --git a/src/a.rs"));
+        assert_eq!(files[1].path, "gone.txt");
+        assert_eq!(files[1].status, "deleted");
+        assert_eq!(files[1].deletions, 1);
+    }
+
+    #[test]
+    fn test_validate_ref_arg() {
+        assert!(validate_ref_arg("feature/isolated", "branch name").is_ok());
+        assert!(validate_ref_arg("--orphan", "branch name").is_err());
+        assert!(validate_ref_arg("two words", "branch name").is_err());
+        assert!(validate_ref_arg("  ", "reference").is_err());
+    }
+
+    #[test]
+    fn test_classify_commit_failure() {
+        assert_eq!(
+            classify_commit_failure(
+                "On branch main\nnothing to commit, working tree clean",
+                true
+            ),
+            GitError::NothingToCommit
+        );
+        assert_eq!(
+            classify_commit_failure("lint failed: 2 errors", true),
+            GitError::HookRejected {
+                output: "lint failed: 2 errors".to_string()
+            }
+        );
+        assert!(matches!(
+            classify_commit_failure("fatal: unable to auto-detect email address", false),
+            GitError::Failed { .. }
+        ));
+    }
 }
@@ -106,7 +106,10 @@ fn execute_claude_mcp_command(app_handle: &AppHandle, args: Vec<&str>) -> Result
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to execute claude command")?;
+    let output = cmd
+        .output()
+        .inspect_err(crate::claude_binary::invalidate_on_spawn_error)
+        .context("Failed to execute claude command")?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
@@ -633,6 +636,7 @@ pub async fn mcp_serve(app: AppHandle) -> Result<String, String> {
             Ok("Claude Code MCP server started".to_string())
         }
         Err(e) => {
+            crate::claude_binary::invalidate_on_spawn_error(&e);
             error!("Failed to start MCP server: {}", e);
             Err(e.to_string())
         }
@@ -1,16 +1,29 @@
+pub mod agent_notifications;
+pub mod agent_schedules;
 pub mod agents;
 pub mod api_nodes;
 pub mod ccr;
 pub mod claude;
+pub mod claude_md;
+pub mod diagnostics;
 pub mod filesystem;
 pub mod git;
 pub mod language;
 pub mod mcp;
 pub mod packycode_nodes;
+pub mod project_archive;
+pub mod project_budgets;
 pub mod prompt_files;
 pub mod proxy;
 pub mod relay_adapters;
 pub mod relay_stations;
+pub mod routing;
+pub mod schema_migrations;
+pub mod search_ignore;
+pub mod session_compare;
+pub mod session_export;
+pub mod session_tools;
+pub mod settings_migrations;
 pub mod slash_commands;
 pub mod smart_sessions;
 pub mod storage;
@@ -18,4 +31,5 @@ pub mod system;
 pub mod terminal;
 pub mod usage;
 pub mod usage_cache;
+pub mod usage_currency;
 pub mod usage_index;
@@ -1,11 +1,33 @@
 use anyhow::Result;
+use once_cell::sync::Lazy;
+use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use tauri::command;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{command, State};
 
 // 导入公共模块
+use crate::claude_config;
+use crate::commands::agents::AgentDb;
+use crate::commands::relay_stations::{load_enabled_relay_station, RelayStationAdapter};
+use crate::i18n;
 use crate::types::node_test::NodeTestResult;
 use crate::utils::node_tester;
 
+/// app_settings 中用户固定节点（节点 ID，即节点 URL）的键
+pub const PACKYCODE_PINNED_NODE_KEY: &str = "packycode_pinned_node";
+
+/// 最近一次测速结果（按节点 ID 缓存）
+#[derive(Debug, Clone)]
+struct NodeProbe {
+    response_time_ms: Option<u64>,
+    reachable: bool,
+    measured_at: i64,
+}
+
+static NODE_PROBES: Lazy<Mutex<HashMap<String, NodeProbe>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// PackyCode 节点类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -26,6 +48,65 @@ pub struct PackycodeNode {
     pub available: Option<bool>,    // 是否可用
 }
 
+/// 当前节点的选择方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeSelection {
+    Auto,   // 自动选择
+    Pinned, // 用户固定
+}
+
+/// 节点状态（含最近测速结果、是否正在使用、是否被固定）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackycodeNodeStatus {
+    pub node_id: String,
+    pub node: PackycodeNode,
+    pub latency_ms: Option<u64>,          // 最近一次测速的延迟（毫秒）
+    pub reachable: Option<bool>,          // 最近一次测速是否可达，未测速时为空
+    pub measured_at: Option<i64>,         // 最近一次测速时间（Unix 秒）
+    pub active: bool,                     // 是否为当前启用的 PackyCode 中转站所用节点
+    pub pinned: bool,                     // 是否为用户固定的节点
+    pub selection: Option<NodeSelection>, // 当前使用节点的选择方式，非当前节点为空
+}
+
+/// 节点 ID：去掉末尾斜杠的节点 URL
+fn to_node_id(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+/// 把测速结果写入缓存
+fn record_probes(results: &[NodeTestResult]) {
+    let now = chrono::Utc::now().timestamp();
+    if let Ok(mut probes) = NODE_PROBES.lock() {
+        for result in results {
+            probes.insert(
+                to_node_id(&result.url),
+                NodeProbe {
+                    response_time_ms: result.response_time_ms,
+                    reachable: result.status.is_success(),
+                    measured_at: now,
+                },
+            );
+        }
+    }
+}
+
+/// 读取用户固定的节点 ID
+fn load_pinned_node(db: &AgentDb) -> Result<Option<String>, String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        params![PACKYCODE_PINNED_NODE_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .optional()
+    .map(|value| value.filter(|id| !id.trim().is_empty()))
+    .map_err(|e| format!("读取固定节点失败: {}", e))
+}
+
 /// 获取所有 PackyCode 节点
 pub fn get_all_nodes() -> Vec<PackycodeNode> {
     vec![
@@ -145,6 +226,8 @@ pub async fn test_all_packycode_nodes() -> Result<Vec<NodeTestResult>, String> {
         }
     }
 
+    record_probes(&results);
+
     // 按响应时间排序（成功的节点优先）
     node_tester::sort_by_response_time(&mut results);
 
@@ -152,10 +235,19 @@ pub async fn test_all_packycode_nodes() -> Result<Vec<NodeTestResult>, String> {
 }
 
 /// 自动选择最快的节点（仅从直连和备用中选择，不需要 token）
+/// 用户固定了节点时直接返回该节点，不再测速选择
 #[command]
-pub async fn auto_select_best_node() -> Result<PackycodeNode, String> {
+pub async fn auto_select_best_node(db: State<'_, AgentDb>) -> Result<PackycodeNode, String> {
     let nodes = get_all_nodes();
 
+    if let Some(pinned) = load_pinned_node(&db)? {
+        if let Some(node) = nodes.iter().find(|n| to_node_id(&n.url) == pinned) {
+            log::info!("使用用户固定的节点: {}", node.name);
+            return Ok(node.clone());
+        }
+        log::warn!("固定的节点 {} 已不存在，改为自动选择", pinned);
+    }
+
     // 只测试直连和备用节点，过滤掉紧急节点
     let test_nodes: Vec<_> = nodes
         .into_iter()
@@ -172,6 +264,7 @@ pub async fn auto_select_best_node() -> Result<PackycodeNode, String> {
 
     // 使用公共批量测试
     let results = node_tester::test_nodes_batch(urls, 3000).await;
+    record_probes(&results);
 
     // 查找最快的节点
     if let Some(fastest) = node_tester::find_fastest_node(&results) {
@@ -202,3 +295,127 @@ pub async fn auto_select_best_node() -> Result<PackycodeNode, String> {
 pub fn get_packycode_nodes() -> Vec<PackycodeNode> {
     get_all_nodes()
 }
+
+/// 获取所有节点及其最近测速结果、使用状态（不会触发测速）
+#[command]
+pub async fn get_packycode_nodes_with_status(
+    db: State<'_, AgentDb>,
+) -> Result<Vec<PackycodeNodeStatus>, String> {
+    let pinned = load_pinned_node(&db)?;
+    let active = load_enabled_relay_station(&db)?
+        .filter(|station| matches!(station.adapter, RelayStationAdapter::Packycode))
+        .map(|station| to_node_id(&station.api_url));
+    let probes = NODE_PROBES
+        .lock()
+        .map(|probes| probes.clone())
+        .unwrap_or_default();
+
+    Ok(get_all_nodes()
+        .into_iter()
+        .map(|node| {
+            let id = to_node_id(&node.url);
+            let probe = probes.get(&id);
+            let is_active = active.as_deref() == Some(id.as_str());
+            let is_pinned = pinned.as_deref() == Some(id.as_str());
+            PackycodeNodeStatus {
+                latency_ms: probe.and_then(|p| p.response_time_ms),
+                reachable: probe.map(|p| p.reachable),
+                measured_at: probe.map(|p| p.measured_at),
+                active: is_active,
+                pinned: is_pinned,
+                selection: is_active.then_some(if is_pinned {
+                    NodeSelection::Pinned
+                } else {
+                    NodeSelection::Auto
+                }),
+                node_id: id,
+                node,
+            }
+        })
+        .collect())
+}
+
+/// 保存或清除用户固定的节点
+fn save_pinned_node(db: &AgentDb, node_id: Option<&str>) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+    match node_id {
+        Some(id) => conn.execute(
+            "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = ?2",
+            params![PACKYCODE_PINNED_NODE_KEY, id],
+        ),
+        None => conn.execute(
+            "DELETE FROM app_settings WHERE key = ?1",
+            params![PACKYCODE_PINNED_NODE_KEY],
+        ),
+    }
+    .map_err(|e| format!("保存固定节点失败: {}", e))?;
+    Ok(())
+}
+
+/// 把节点地址写入当前启用的 PackyCode 中转站并同步到 Claude 配置，
+/// 没有启用的 PackyCode 中转站时返回 false
+fn apply_node_to_enabled_station(db: &AgentDb, node: &PackycodeNode) -> Result<bool, String> {
+    let Some(mut station) = load_enabled_relay_station(db)?
+        .filter(|station| matches!(station.adapter, RelayStationAdapter::Packycode))
+    else {
+        return Ok(false);
+    };
+    if to_node_id(&station.api_url) == to_node_id(&node.url) {
+        return Ok(true);
+    }
+
+    {
+        let conn = db.lock().map_err(|e| {
+            log::error!("Failed to acquire database lock: {}", e);
+            i18n::t("database.lock_failed")
+        })?;
+        conn.execute(
+            "UPDATE relay_stations SET api_url = ?1, updated_at = ?2 WHERE id = ?3",
+            params![node.url, chrono::Utc::now().timestamp(), station.id],
+        )
+        .map_err(|e| format!("更新中转站地址失败: {}", e))?;
+    }
+
+    station.api_url = node.url.clone();
+    claude_config::apply_relay_station_to_config(&station)
+        .map_err(|e| format!("配置同步失败: {}", e))?;
+    log::info!(
+        "已将中转站 {} 切换到节点 {} ({})",
+        station.name,
+        node.name,
+        node.url
+    );
+    Ok(true)
+}
+
+/// 固定使用指定节点（`Some`）并立即应用到当前 PackyCode 中转站，之后自动选择直接返回该节点；
+/// `None` 取消固定，恢复自动选择
+#[command]
+pub async fn set_packycode_node_override(
+    node_id: Option<String>,
+    db: State<'_, AgentDb>,
+) -> Result<Option<PackycodeNode>, String> {
+    let Some(node_id) = node_id else {
+        save_pinned_node(&db, None)?;
+        log::info!("已取消固定 PackyCode 节点，恢复自动选择");
+        return Ok(None);
+    };
+
+    let id = to_node_id(&node_id);
+    let node = get_all_nodes()
+        .into_iter()
+        .find(|n| to_node_id(&n.url) == id)
+        .ok_or_else(|| format!("未找到节点: {}", node_id))?;
+
+    save_pinned_node(&db, Some(&id))?;
+    log::info!("已固定 PackyCode 节点: {}", node.name);
+
+    if !apply_node_to_enabled_station(&db, &node)? {
+        log::info!("没有启用的 PackyCode 中转站，固定节点将在启用后生效");
+    }
+    Ok(Some(node))
+}
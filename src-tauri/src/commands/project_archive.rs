@@ -0,0 +1,446 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::State;
+use walkdir::WalkDir;
+
+use super::claude::{get_claude_dir, validate_project_id};
+use crate::checkpoint::state::CheckpointState;
+
+/// Name of the manifest entry at the root of a project archive
+const MANIFEST_NAME: &str = "manifest.json";
+/// Prefix of the project directory entries (sessions and `.timelines` checkpoints)
+const PROJECT_PREFIX: &str = "project";
+/// Prefix of the todo entries
+const TODOS_PREFIX: &str = "todos";
+/// Bumped when the archive layout changes
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Describes the contents of a project archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveManifest {
+    version: u32,
+    project_id: String,
+    session_ids: Vec<String>,
+    created_at: String,
+}
+
+/// Outcome of archiving a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveProjectResult {
+    pub archive_path: String,
+    pub session_count: usize,
+    pub file_count: usize,
+    /// Total size of the archived files
+    pub original_bytes: u64,
+    /// Size of the archive on disk
+    pub archive_bytes: u64,
+    pub removed_originals: bool,
+}
+
+/// Outcome of restoring a project archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnarchiveProjectResult {
+    pub project_id: String,
+    pub session_count: usize,
+    pub file_count: usize,
+    /// Total size of the restored files
+    pub restored_bytes: u64,
+    pub archive_bytes: u64,
+}
+
+/// Todo files belonging to the given sessions (`<session>.json` and `<session>-agent-*.json`)
+fn session_todo_files(todos_dir: &Path, session_ids: &[String]) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(todos_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            path.is_file()
+                && session_ids.iter().any(|id| {
+                    name == format!("{}.json", id) || name.starts_with(&format!("{}-agent-", id))
+                })
+        })
+        .collect()
+}
+
+/// Archive entry name with forward slashes, as zip requires
+fn entry_name(prefix: &str, relative: &Path) -> String {
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    format!("{}/{}", prefix, parts.join("/"))
+}
+
+/// Maps an archive entry to the path it restores to, rejecting anything that could escape
+/// the project or todos directory
+fn restore_target(
+    name: &str,
+    project_dir: &Path,
+    todos_dir: &Path,
+) -> Result<Option<PathBuf>, String> {
+    if name == MANIFEST_NAME {
+        return Ok(None);
+    }
+
+    let path = Path::new(name);
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!("Archive entry has an unsafe path: {}", name));
+    }
+
+    let mut components = path.components();
+    let prefix = components.next().and_then(|c| c.as_os_str().to_str());
+    let rest = components.as_path();
+    let depth = rest.components().count();
+    match prefix {
+        Some(PROJECT_PREFIX) if depth > 0 => Ok(Some(project_dir.join(rest))),
+        Some(TODOS_PREFIX) if depth == 1 => Ok(Some(todos_dir.join(rest))),
+        _ => Err(format!("Unexpected archive entry: {}", name)),
+    }
+}
+
+/// Zips a project's sessions, checkpoints and todos into one archive.
+///
+/// `dest_path` may be a directory, in which case the archive is named after the project and
+/// the current time. With `remove_originals`, the project directory and its todos are
+/// deleted once the archive has been written and read back, which is refused while any
+/// of the project's sessions is running.
+#[tauri::command]
+pub async fn archive_project(
+    checkpoint_state: State<'_, CheckpointState>,
+    registry: State<'_, crate::process::ProcessRegistryState>,
+    project_id: String,
+    dest_path: String,
+    remove_originals: Option<bool>,
+) -> Result<ArchiveProjectResult, String> {
+    log::info!("Archiving project {} to {}", project_id, dest_path);
+    validate_project_id(&project_id)?;
+
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let project_dir = claude_dir.join("projects").join(&project_id);
+    let todos_dir = claude_dir.join("todos");
+    if !project_dir.is_dir() {
+        return Err(format!("Project not found: {}", project_id));
+    }
+
+    let mut archive_path = PathBuf::from(&dest_path);
+    if archive_path.is_dir() {
+        archive_path = archive_path.join(format!(
+            "{}-{}.zip",
+            project_id,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+    }
+    if archive_path.exists() {
+        return Err(format!(
+            "Archive already exists: {}",
+            archive_path.display()
+        ));
+    }
+
+    let remove_originals = remove_originals.unwrap_or(false);
+    let archive_dir = project_dir.clone();
+    let archive_project_id = project_id.clone();
+    let output = archive_path.clone();
+    let (manifest, file_count, original_bytes) = tokio::task::spawn_blocking(move || {
+        write_archive(&archive_project_id, &archive_dir, &todos_dir, &output)
+    })
+    .await
+    .map_err(|e| format!("Archive task failed: {}", e))?
+    .inspect_err(|_| {
+        let _ = fs::remove_file(&archive_path);
+    })?;
+
+    let archive_bytes = fs::metadata(&archive_path).map(|m| m.len()).unwrap_or(0);
+
+    if remove_originals {
+        // Only delete once the archive is known to be readable and complete
+        let archived = zip::ZipArchive::new(
+            fs::File::open(&archive_path)
+                .map_err(|e| format!("Failed to reopen archive: {}", e))?,
+        )
+        .map_err(|e| format!("Archive could not be read back, originals kept: {}", e))?;
+        if archived.len() != file_count + 1 {
+            return Err("Archive is incomplete, originals kept".to_string());
+        }
+
+        let mut active = Vec::new();
+        for session_id in &manifest.session_ids {
+            if registry.0.get_claude_session_by_id(session_id)?.is_some() {
+                active.push(session_id.as_str());
+            }
+        }
+        if !active.is_empty() {
+            return Err(format!(
+                "Archive written, but originals kept while sessions are running: {}",
+                active.join(", ")
+            ));
+        }
+
+        for session_id in &manifest.session_ids {
+            checkpoint_state.remove_manager(session_id).await;
+        }
+        for todo in session_todo_files(&claude_dir.join("todos"), &manifest.session_ids) {
+            if let Err(e) = fs::remove_file(&todo) {
+                log::warn!("Failed to remove archived todo {:?}: {}", todo, e);
+            }
+        }
+        fs::remove_dir_all(&project_dir)
+            .map_err(|e| format!("Archive written, but removing the project failed: {}", e))?;
+    }
+
+    log::info!(
+        "Archived {} files of project {} ({} -> {} bytes)",
+        file_count,
+        project_id,
+        original_bytes,
+        archive_bytes
+    );
+    Ok(ArchiveProjectResult {
+        archive_path: archive_path.to_string_lossy().to_string(),
+        session_count: manifest.session_ids.len(),
+        file_count,
+        original_bytes,
+        archive_bytes,
+        removed_originals: remove_originals,
+    })
+}
+
+/// Writes the archive; returns the manifest, the number of archived files and their size
+fn write_archive(
+    project_id: &str,
+    project_dir: &Path,
+    todos_dir: &Path,
+    output: &Path,
+) -> Result<(ArchiveManifest, usize, u64), String> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(project_dir).follow_links(false) {
+        let entry = entry.map_err(|e| format!("Failed to read project directory: {}", e))?;
+        if entry.file_type().is_file() {
+            let relative = entry
+                .path()
+                .strip_prefix(project_dir)
+                .map_err(|e| e.to_string())?;
+            files.push((
+                entry_name(PROJECT_PREFIX, relative),
+                entry.path().to_path_buf(),
+            ));
+        }
+    }
+
+    let mut session_ids: Vec<String> = files
+        .iter()
+        .map(|(_, path)| path)
+        .filter(|path| {
+            path.parent() == Some(project_dir)
+                && path.extension().and_then(|e| e.to_str()) == Some("jsonl")
+        })
+        .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+        .collect();
+    session_ids.sort();
+
+    for todo in session_todo_files(todos_dir, &session_ids) {
+        let relative = todo.strip_prefix(todos_dir).map_err(|e| e.to_string())?;
+        files.push((entry_name(TODOS_PREFIX, relative), todo.clone()));
+    }
+
+    let manifest = ArchiveManifest {
+        version: ARCHIVE_VERSION,
+        project_id: project_id.to_string(),
+        session_ids,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create archive directory: {}", e))?;
+    }
+    let file = fs::File::create(output).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_NAME, options)
+        .and_then(|_| zip.write_all(&manifest_json).map_err(Into::into))
+        .map_err(|e| format!("Failed to write archive manifest: {}", e))?;
+
+    let mut original_bytes = 0;
+    for (name, path) in &files {
+        let mut source =
+            fs::File::open(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+        original_bytes += std::io::copy(&mut source, &mut zip)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+
+    Ok((manifest, files.len(), original_bytes))
+}
+
+/// Restores a project archive created by `archive_project`.
+///
+/// Every entry is validated before anything is written. An existing project directory is
+/// only written into when `overwrite` is set.
+#[tauri::command]
+pub async fn unarchive_project(
+    archive_path: String,
+    overwrite: Option<bool>,
+) -> Result<UnarchiveProjectResult, String> {
+    log::info!("Restoring project archive {}", archive_path);
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let overwrite = overwrite.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        restore_archive(Path::new(&archive_path), &claude_dir, overwrite)
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+}
+
+fn restore_archive(
+    archive_path: &Path,
+    claude_dir: &Path,
+    overwrite: bool,
+) -> Result<UnarchiveProjectResult, String> {
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let archive_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Not a valid archive: {}", e))?;
+
+    let manifest: ArchiveManifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| "Archive has no manifest, it was not created by Claudia".to_string())?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read archive manifest: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Invalid archive manifest: {}", e))?
+    };
+    if manifest.version > ARCHIVE_VERSION {
+        return Err(format!(
+            "Archive version {} is newer than supported ({})",
+            manifest.version, ARCHIVE_VERSION
+        ));
+    }
+    validate_project_id(&manifest.project_id)?;
+
+    let project_dir = claude_dir.join("projects").join(&manifest.project_id);
+    let todos_dir = claude_dir.join("todos");
+    if project_dir.exists() && !overwrite {
+        return Err(format!(
+            "Project {} already exists; restore with overwrite to replace its files",
+            manifest.project_id
+        ));
+    }
+
+    // Validate every entry before touching the disk
+    let mut targets = Vec::with_capacity(zip.len());
+    for index in 0..zip.len() {
+        let entry = zip.by_index(index).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        if entry.enclosed_name().is_none() {
+            return Err(format!(
+                "Archive entry has an unsafe path: {}",
+                entry.name()
+            ));
+        }
+        if let Some(target) = restore_target(entry.name(), &project_dir, &todos_dir)? {
+            targets.push((index, target));
+        }
+    }
+
+    let mut restored_bytes = 0;
+    for (index, target) in &targets {
+        let mut entry = zip.by_index(*index).map_err(|e| e.to_string())?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut out = fs::File::create(target)
+            .map_err(|e| format!("Failed to restore {:?}: {}", target, e))?;
+        restored_bytes += std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to restore {:?}: {}", target, e))?;
+    }
+
+    log::info!(
+        "Restored {} files of project {}",
+        targets.len(),
+        manifest.project_id
+    );
+    Ok(UnarchiveProjectResult {
+        project_id: manifest.project_id,
+        session_count: manifest.session_ids.len(),
+        file_count: targets.len(),
+        restored_bytes,
+        archive_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_target_rejects_escaping_paths() {
+        let project = Path::new("/claude/projects/p");
+        let todos = Path::new("/claude/todos");
+
+        assert_eq!(
+            restore_target("project/s.jsonl", project, todos).unwrap(),
+            Some(project.join("s.jsonl"))
+        );
+        assert_eq!(
+            restore_target("todos/s.json", project, todos).unwrap(),
+            Some(todos.join("s.json"))
+        );
+        assert_eq!(restore_target(MANIFEST_NAME, project, todos).unwrap(), None);
+        assert!(restore_target("project/../../etc/passwd", project, todos).is_err());
+        assert!(restore_target("/etc/passwd", project, todos).is_err());
+        assert!(restore_target("todos/nested/s.json", project, todos).is_err());
+        assert!(restore_target("other/file", project, todos).is_err());
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let claude = tempfile::tempdir().unwrap();
+        let project_dir = claude.path().join("projects").join("-tmp-demo");
+        let todos_dir = claude.path().join("todos");
+        fs::create_dir_all(project_dir.join(".timelines").join("s1")).unwrap();
+        fs::create_dir_all(&todos_dir).unwrap();
+        fs::write(project_dir.join("s1.jsonl"), "{\"cwd\":\"/tmp/demo\"}\n").unwrap();
+        fs::write(project_dir.join(".timelines/s1/timeline.json"), "{}").unwrap();
+        fs::write(todos_dir.join("s1.json"), "[]").unwrap();
+        fs::write(todos_dir.join("other.json"), "[]").unwrap();
+
+        let output = claude.path().join("demo.zip");
+        let (manifest, file_count, _) =
+            write_archive("-tmp-demo", &project_dir, &todos_dir, &output).unwrap();
+        assert_eq!(manifest.session_ids, vec!["s1".to_string()]);
+        assert_eq!(file_count, 3);
+
+        assert!(restore_archive(&output, claude.path(), false).is_err());
+        fs::remove_dir_all(&project_dir).unwrap();
+        fs::remove_file(todos_dir.join("s1.json")).unwrap();
+
+        let restored = restore_archive(&output, claude.path(), false).unwrap();
+        assert_eq!(restored.file_count, 3);
+        assert!(project_dir.join(".timelines/s1/timeline.json").is_file());
+        assert!(todos_dir.join("s1.json").is_file());
+    }
+}
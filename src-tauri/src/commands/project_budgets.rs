@@ -0,0 +1,306 @@
+use chrono::{Datelike, Local, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::agents::AgentDb;
+use super::usage_cache::{usage_scan_update, UsageCacheState};
+
+/// Spend limits of one project, keyed by the encoded project directory name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBudget {
+    pub project_id: String,
+    pub daily_usd: Option<f64>,
+    pub monthly_usd: Option<f64>,
+    /// Refuse to start sessions when over budget; otherwise only warn
+    pub hard_limit: bool,
+}
+
+/// A budget together with the spend it is checked against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBudgetStatus {
+    pub budget: ProjectBudget,
+    pub spent_today_usd: f64,
+    pub spent_this_month_usd: f64,
+}
+
+/// Payload of the `budget-exceeded` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetExceeded {
+    pub project_id: String,
+    pub project_path: String,
+    /// "daily" or "monthly"
+    pub period: String,
+    pub limit_usd: f64,
+    pub spent_usd: f64,
+    /// Whether the session was refused
+    pub enforced: bool,
+}
+
+fn validate_limit(limit: Option<f64>) -> Result<Option<f64>, String> {
+    match limit {
+        Some(usd) if !usd.is_finite() || usd < 0.0 => Err(format!(
+            "Invalid budget {}, expected a non-negative amount",
+            usd
+        )),
+        _ => Ok(limit),
+    }
+}
+
+fn load_budget(conn: &Connection, project_id: &str) -> rusqlite::Result<Option<ProjectBudget>> {
+    conn.query_row(
+        "SELECT project_id, daily_usd, monthly_usd, hard_limit FROM project_budgets
+         WHERE project_id = ?1",
+        params![project_id],
+        |row| {
+            Ok(ProjectBudget {
+                project_id: row.get(0)?,
+                daily_usd: row.get(1)?,
+                monthly_usd: row.get(2)?,
+                hard_limit: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Cost of usage entries of a project at or after `since` (a local timestamp prefix).
+/// Entries record the session cwd, or the encoded directory name when it was unknown.
+fn project_spend_since(
+    conn: &Connection,
+    project_path: &str,
+    project_id: &str,
+    since: &str,
+) -> rusqlite::Result<f64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(cost), 0.0) FROM usage_entries
+         WHERE project_path IN (?1, ?2) AND timestamp >= ?3",
+        params![project_path, project_id, since],
+        |row| row.get(0),
+    )
+}
+
+/// The usage cache is rescanned before a budget check once its last scan is older than this
+const SPEND_RESCAN_AFTER_MS: i64 = 60_000;
+
+/// Whether the usage cache was never scanned or its last scan is older than the threshold
+fn spend_is_stale(usage: &UsageCacheState) -> Result<bool, String> {
+    let last_scan = *usage.last_scan_time.lock().map_err(|e| e.to_string())?;
+    Ok(last_scan
+        .is_none_or(|scanned| Utc::now().timestamp_millis() - scanned >= SPEND_RESCAN_AFTER_MS))
+}
+
+/// Today's and this month's spend of a project from the usage cache
+async fn project_spend(
+    usage: State<'_, UsageCacheState>,
+    project_path: &str,
+    project_id: &str,
+) -> Result<(f64, f64), String> {
+    // Cached spend is used as is unless the last scan is stale; the scan is incremental
+    if spend_is_stale(&usage)? {
+        usage_scan_update(usage.clone()).await?;
+    }
+
+    let today = Local::now().date_naive();
+    let day_start = today.format("%Y-%m-%d").to_string();
+    let month_start = today
+        .with_day(1)
+        .unwrap_or(today)
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let conn_guard = usage.conn.lock().map_err(|e| e.to_string())?;
+    let conn = conn_guard.as_ref().ok_or("Usage cache not initialized")?;
+    let spent_today = project_spend_since(conn, project_path, project_id, &day_start)
+        .map_err(|e| e.to_string())?;
+    let spent_this_month = project_spend_since(conn, project_path, project_id, &month_start)
+        .map_err(|e| e.to_string())?;
+    Ok((spent_today, spent_this_month))
+}
+
+/// Limits of `budget` that the spend has reached, as (period, limit, spent)
+fn exceeded_limits(
+    budget: &ProjectBudget,
+    spent_today: f64,
+    spent_this_month: f64,
+) -> Vec<(&'static str, f64, f64)> {
+    [
+        ("daily", budget.daily_usd, spent_today),
+        ("monthly", budget.monthly_usd, spent_this_month),
+    ]
+    .into_iter()
+    .filter_map(|(period, limit, spent)| {
+        limit
+            .filter(|limit| spent >= *limit)
+            .map(|limit| (period, limit, spent))
+    })
+    .collect()
+}
+
+/// Checks the budget of the project at `project_path` before a session is started.
+/// Emits `budget-exceeded` for every limit reached, and fails when the budget is a hard limit.
+pub(crate) async fn check_project_budget(
+    app: &AppHandle,
+    project_path: &str,
+) -> Result<(), String> {
+    let project_id = project_path.replace('/', "-");
+    let budget = {
+        let db = app.state::<AgentDb>();
+        let conn = db.lock()?;
+        load_budget(&conn, &project_id).map_err(|e| e.to_string())?
+    };
+    let Some(budget) = budget else {
+        return Ok(());
+    };
+
+    let (spent_today, spent_this_month) =
+        match project_spend(app.state::<UsageCacheState>(), project_path, &project_id).await {
+            Ok(spend) => spend,
+            Err(e) => {
+                log::warn!("Skipping budget check for {}: {}", project_path, e);
+                return Ok(());
+            }
+        };
+
+    let exceeded = exceeded_limits(&budget, spent_today, spent_this_month);
+    for (period, limit_usd, spent_usd) in &exceeded {
+        log::warn!(
+            "Project {} is over its {} budget: ${:.2} of ${:.2}",
+            project_path,
+            period,
+            spent_usd,
+            limit_usd
+        );
+        let _ = app.emit(
+            "budget-exceeded",
+            BudgetExceeded {
+                project_id: project_id.clone(),
+                project_path: project_path.to_string(),
+                period: period.to_string(),
+                limit_usd: *limit_usd,
+                spent_usd: *spent_usd,
+                enforced: budget.hard_limit,
+            },
+        );
+    }
+
+    match exceeded.first() {
+        Some((period, limit_usd, spent_usd)) if budget.hard_limit => Err(format!(
+            "Project {} budget exceeded: ${:.2} spent of ${:.2}",
+            period, spent_usd, limit_usd
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Sets the daily and monthly spend limits of a project; clearing both removes the budget.
+/// With `hard_limit` (the default) sessions are refused when over budget, otherwise only
+/// a `budget-exceeded` warning is emitted.
+#[tauri::command]
+pub async fn set_project_budget(
+    db: State<'_, AgentDb>,
+    project_id: String,
+    daily_usd: Option<f64>,
+    monthly_usd: Option<f64>,
+    hard_limit: Option<bool>,
+) -> Result<(), String> {
+    let daily_usd = validate_limit(daily_usd)?;
+    let monthly_usd = validate_limit(monthly_usd)?;
+
+    let conn = db.lock()?;
+    if daily_usd.is_none() && monthly_usd.is_none() {
+        conn.execute(
+            "DELETE FROM project_budgets WHERE project_id = ?1",
+            params![project_id],
+        )
+        .map_err(|e| format!("Failed to remove project budget: {}", e))?;
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO project_budgets (project_id, daily_usd, monthly_usd, hard_limit)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(project_id) DO UPDATE SET daily_usd = excluded.daily_usd,
+            monthly_usd = excluded.monthly_usd, hard_limit = excluded.hard_limit,
+            updated_at = CURRENT_TIMESTAMP",
+        params![
+            project_id,
+            daily_usd,
+            monthly_usd,
+            hard_limit.unwrap_or(true)
+        ],
+    )
+    .map_err(|e| format!("Failed to save project budget: {}", e))?;
+    Ok(())
+}
+
+/// Gets the budget of a project with its current spend, or `None` when it has no budget
+#[tauri::command]
+pub async fn get_project_budget(
+    db: State<'_, AgentDb>,
+    usage: State<'_, UsageCacheState>,
+    project_id: String,
+    project_path: String,
+) -> Result<Option<ProjectBudgetStatus>, String> {
+    let budget = {
+        let conn = db.lock()?;
+        load_budget(&conn, &project_id).map_err(|e| e.to_string())?
+    };
+    let Some(budget) = budget else {
+        return Ok(None);
+    };
+
+    let (spent_today_usd, spent_this_month_usd) =
+        project_spend(usage, &project_path, &project_id).await?;
+    Ok(Some(ProjectBudgetStatus {
+        budget,
+        spent_today_usd,
+        spent_this_month_usd,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_spend_and_exceeded_limits() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE usage_entries (timestamp TEXT, cost REAL, project_path TEXT);
+             INSERT INTO usage_entries VALUES ('2025-03-01 09:00:00.000', 1.0, '/work/app');
+             INSERT INTO usage_entries VALUES ('2025-03-05 10:00:00.000', 2.0, '-work-app');
+             INSERT INTO usage_entries VALUES ('2025-03-05 11:00:00.000', 4.0, '/work/other');",
+        )
+        .unwrap();
+
+        let today = project_spend_since(&conn, "/work/app", "-work-app", "2025-03-05").unwrap();
+        let month = project_spend_since(&conn, "/work/app", "-work-app", "2025-03-01").unwrap();
+        assert_eq!((today, month), (2.0, 3.0));
+
+        let budget = ProjectBudget {
+            project_id: "-work-app".to_string(),
+            daily_usd: Some(5.0),
+            monthly_usd: Some(3.0),
+            hard_limit: true,
+        };
+        assert_eq!(
+            exceeded_limits(&budget, today, month),
+            vec![("monthly", 3.0, 3.0)]
+        );
+        assert!(validate_limit(Some(-1.0)).is_err());
+    }
+
+    #[test]
+    fn test_spend_is_stale_only_after_threshold() {
+        let usage = UsageCacheState::default();
+        assert!(spend_is_stale(&usage).unwrap());
+
+        let now = Utc::now().timestamp_millis();
+        *usage.last_scan_time.lock().unwrap() = Some(now);
+        assert!(!spend_is_stale(&usage).unwrap());
+
+        *usage.last_scan_time.lock().unwrap() = Some(now - SPEND_RESCAN_AFTER_MS);
+        assert!(spend_is_stale(&usage).unwrap());
+    }
+}
@@ -1,5 +1,4 @@
 use chrono::Utc;
-use dirs;
 use log::{error, info};
 use rusqlite::{params, Connection, Result as SqliteResult, Row};
 use serde::{Deserialize, Serialize};
@@ -100,7 +99,7 @@ pub fn init_prompt_files_tables(conn: &Connection) -> SqliteResult<()> {
 /// 列出所有提示词文件
 #[command]
 pub async fn prompt_files_list(db: State<'_, AgentDb>) -> Result<Vec<PromptFile>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let mut stmt = conn
         .prepare(
@@ -123,7 +122,7 @@ pub async fn prompt_files_list(db: State<'_, AgentDb>) -> Result<Vec<PromptFile>
 /// 获取单个提示词文件
 #[command]
 pub async fn prompt_file_get(id: String, db: State<'_, AgentDb>) -> Result<PromptFile, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let file = conn
         .query_row(
@@ -148,7 +147,7 @@ pub async fn prompt_file_create(
     info!("Creating prompt file: {}", request.name);
 
     let id = {
-        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let conn = db.lock().map_err(|e| e.to_string())?;
 
         // 检查名称是否已存在
         let exists: bool = conn
@@ -207,7 +206,7 @@ pub async fn prompt_file_update(
     info!("Updating prompt file: {}", request.id);
 
     let id = {
-        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let conn = db.lock().map_err(|e| e.to_string())?;
 
         // 检查文件是否存在
         let exists: bool = conn
@@ -267,7 +266,7 @@ pub async fn prompt_file_update(
 pub async fn prompt_file_delete(id: String, db: State<'_, AgentDb>) -> Result<(), String> {
     info!("Deleting prompt file: {}", id);
 
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     let deleted = conn
         .execute("DELETE FROM prompt_files WHERE id = ?1", params![id])
@@ -280,21 +279,10 @@ pub async fn prompt_file_delete(id: String, db: State<'_, AgentDb>) -> Result<()
     Ok(())
 }
 
-/// 获取 Claude 配置目录路径（~/.claude）
+/// 获取 Claude 配置目录路径（默认 ~/.claude，不存在时自动创建）
 fn get_claude_config_dir() -> Result<PathBuf, String> {
-    let home_dir = dirs::home_dir()
-        .ok_or_else(|| "无法获取主目录".to_string())?;
-    
-    let claude_dir = home_dir.join(".claude");
-    
-    // 确保目录存在
-    if !claude_dir.exists() {
-        fs::create_dir_all(&claude_dir)
-            .map_err(|e| format!("创建 .claude 目录失败: {}", e))?;
-        info!("创建 Claude 配置目录: {:?}", claude_dir);
-    }
-    
-    Ok(claude_dir)
+    crate::commands::claude::get_claude_dir()
+        .map_err(|e| format!("获取 Claude 配置目录失败: {}", e))
 }
 
 /// 应用提示词文件（替换本地 CLAUDE.md 或指定目标路径）
@@ -357,7 +345,7 @@ pub async fn prompt_file_apply(
         .map_err(|e| format!("写入文件失败: {}", e))?;
 
     // 5. 更新数据库状态
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // 将所有文件的 is_active 设为 0
     conn.execute("UPDATE prompt_files SET is_active = 0", [])
@@ -380,7 +368,7 @@ pub async fn prompt_file_apply(
 pub async fn prompt_file_deactivate(db: State<'_, AgentDb>) -> Result<(), String> {
     info!("Deactivating all prompt files");
 
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     conn.execute("UPDATE prompt_files SET is_active = 0", [])
         .map_err(|e| format!("取消激活失败: {}", e))?;
@@ -506,7 +494,7 @@ pub async fn prompt_files_update_order(
 ) -> Result<(), String> {
     info!("Updating prompt files order");
 
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     for (index, id) in ids.iter().enumerate() {
         conn.execute(
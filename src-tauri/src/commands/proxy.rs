@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -28,8 +28,12 @@ impl Default for ProxySettings {
 /// Get proxy settings from the database
 #[tauri::command]
 pub async fn get_proxy_settings(db: State<'_, AgentDb>) -> Result<ProxySettings, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    Ok(load_proxy_settings(&conn))
+}
 
+/// Read proxy settings from app_settings, falling back to defaults for missing keys
+pub fn load_proxy_settings(conn: &Connection) -> ProxySettings {
     let mut settings = ProxySettings::default();
 
     // Query each proxy setting
@@ -58,7 +62,7 @@ pub async fn get_proxy_settings(db: State<'_, AgentDb>) -> Result<ProxySettings,
         }
     }
 
-    Ok(settings)
+    settings
 }
 
 /// Save proxy settings to the database
@@ -67,7 +71,7 @@ pub async fn save_proxy_settings(
     db: State<'_, AgentDb>,
     settings: ProxySettings,
 ) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Save each setting
     let values = vec![
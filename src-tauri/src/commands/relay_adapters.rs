@@ -1,15 +1,41 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use tauri::{command, State};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{command, AppHandle, Emitter, State};
 
 use crate::commands::agents::AgentDb;
 use crate::commands::relay_stations::{RelayStation, RelayStationAdapter};
 use crate::http_client;
 use crate::i18n;
 
+/// app_settings 中全局默认总超时（毫秒）的键
+pub const RELAY_TIMEOUT_SETTING_KEY: &str = "relay_timeout_ms";
+/// app_settings 中全局默认连接超时（毫秒）的键
+pub const RELAY_CONNECT_TIMEOUT_SETTING_KEY: &str = "relay_connect_timeout_ms";
+/// app_settings 中 PackyCode 预算提醒阈值（已用/预算的比例）的键
+pub const PACKYCODE_BUDGET_THRESHOLD_KEY: &str = "packycode_budget_warning_threshold";
+/// 未配置时的预算提醒阈值
+const DEFAULT_PACKYCODE_BUDGET_THRESHOLD: f64 = 0.8;
+/// 未配置连接超时时的默认值，让不可达的主机尽快失败
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 5000;
+
+/// 全局默认超时（毫秒），0 表示未配置
+static GLOBAL_TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+static GLOBAL_CONNECT_TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+
+/// 中转站请求的全局默认超时设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelayTimeoutSettings {
+    /// 总超时（毫秒），为空时使用各请求的内置默认值
+    pub timeout_ms: Option<u64>,
+    /// 连接超时（毫秒），为空时为 5000
+    pub connect_timeout_ms: Option<u64>,
+}
+
 /// 中转站信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StationInfo {
@@ -42,6 +68,7 @@ pub struct ConnectionTestResult {
     pub response_time: u64, // 响应时间（毫秒）
     pub message: String,
     pub details: Option<String>,
+    pub attempts: u32, // 实际请求次数（含重试）
 }
 
 /// Token 信息
@@ -75,6 +102,134 @@ pub struct TokenPaginationResponse {
     pub pagination: PaginationInfo,
 }
 
+/// 模型可用性测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelAvailabilityResult {
+    pub available_models: Vec<String>, // 中转站提供的全部模型
+    pub present: Vec<String>,          // 所需模型中已提供的
+    pub missing: Vec<String>,          // 所需模型中缺失的
+    pub all_present: bool,
+}
+
+/// 构建 OpenAI 风格的模型列表地址（兼容以 /v1 结尾的 API 地址）
+fn models_endpoint(api_url: &str) -> String {
+    let base = api_url.trim_end_matches('/');
+    if base.ends_with("/v1") {
+        format!("{}/models", base)
+    } else {
+        format!("{}/v1/models", base)
+    }
+}
+
+/// 从 `/v1/models` 响应中提取模型 ID（支持 `data` 与 `models` 两种格式）
+fn parse_model_ids(body: &Value) -> Vec<String> {
+    let entries = body
+        .get("data")
+        .or_else(|| body.get("models"))
+        .and_then(|v| v.as_array());
+
+    let mut ids: Vec<String> = entries
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    entry
+                        .get("id")
+                        .or_else(|| entry.get("name"))
+                        .and_then(|id| id.as_str())
+                        .or_else(|| entry.as_str())
+                        .map(|id| id.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// 读取 adapter_config 中的非负整数配置（兼容数字与字符串）
+fn adapter_config_u64(station: &RelayStation, key: &str) -> Option<u64> {
+    let value = station.adapter_config.as_ref()?.get(key)?;
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn global_timeout(value: &AtomicU64) -> Option<u64> {
+    Some(value.load(Ordering::Relaxed)).filter(|ms| *ms > 0)
+}
+
+/// 为中转站创建 HTTP 客户端：超时依次取 adapter_config 的 `timeout_ms`/`connect_timeout_ms`、
+/// app_settings 中的全局默认值，最后才是 `base` 自带的超时
+fn station_client(
+    station: &RelayStation,
+    base: http_client::ClientConfig,
+) -> Result<reqwest::Client> {
+    let mut config = base;
+    if let Some(ms) = adapter_config_u64(station, "timeout_ms")
+        .filter(|ms| *ms > 0)
+        .or_else(|| global_timeout(&GLOBAL_TIMEOUT_MS))
+    {
+        config = config.timeout_ms(ms);
+    }
+    let connect_ms = adapter_config_u64(station, "connect_timeout_ms")
+        .filter(|ms| *ms > 0)
+        .or_else(|| global_timeout(&GLOBAL_CONNECT_TIMEOUT_MS))
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT_MS);
+    config = config.connect_timeout_ms(connect_ms);
+
+    http_client::create_client(config).map_err(|e| anyhow::anyhow!("创建 HTTP 客户端失败: {}", e))
+}
+
+/// 从 app_settings 加载全局默认超时（启动时调用）
+pub fn load_relay_timeout_defaults(conn: &rusqlite::Connection) {
+    let setting = |key: &str| -> u64 {
+        conn.query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![key],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+    };
+    GLOBAL_TIMEOUT_MS.store(setting(RELAY_TIMEOUT_SETTING_KEY), Ordering::Relaxed);
+    GLOBAL_CONNECT_TIMEOUT_MS.store(
+        setting(RELAY_CONNECT_TIMEOUT_SETTING_KEY),
+        Ordering::Relaxed,
+    );
+}
+
+/// 中转站的重试策略，可通过 adapter_config 的 `max_attempts`、`retry_base_delay_ms` 覆盖
+fn retry_policy(station: &RelayStation) -> http_client::RetryPolicy {
+    let mut policy = http_client::RetryPolicy::new();
+    if let Some(attempts) = adapter_config_u64(station, "max_attempts") {
+        policy = policy.max_attempts(attempts.min(10) as u32);
+    }
+    if let Some(delay_ms) = adapter_config_u64(station, "retry_base_delay_ms") {
+        policy = policy.base_delay_ms(delay_ms);
+    }
+    policy
+}
+
+/// 带重试发送幂等的 GET 请求，返回响应与实际请求次数
+async fn send_idempotent(
+    station: &RelayStation,
+    request: reqwest::RequestBuilder,
+) -> (Result<reqwest::Response>, u32) {
+    let outcome = http_client::send_with_retry(request, &retry_policy(station)).await;
+    let attempts = outcome.attempts;
+    let result = outcome.result.map_err(|e| {
+        if attempts > 1 {
+            anyhow::anyhow!("{} (已尝试 {} 次)", e, attempts)
+        } else {
+            e.into()
+        }
+    });
+    (result, attempts)
+}
+
 /// 中转站适配器 trait
 #[async_trait]
 pub trait StationAdapter: Send + Sync {
@@ -123,25 +278,52 @@ pub trait StationAdapter: Send + Sync {
 
     /// 删除 Token
     async fn delete_token(&self, station: &RelayStation, token_id: &str) -> Result<String>;
+
+    /// 列出中转站提供的模型（默认请求 OpenAI 风格的 `/v1/models`）
+    async fn list_models(&self, station: &RelayStation) -> Result<Vec<String>> {
+        let client = station_client(station, http_client::ClientConfig::new().timeout(10))?;
+
+        let request = client
+            .get(models_endpoint(&station.api_url))
+            .header("Authorization", format!("Bearer {}", station.system_token))
+            .header("x-api-key", &station.system_token)
+            .header("anthropic-version", "2023-06-01");
+        let (response, _) = send_idempotent(station, request).await;
+        let response = response?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "{}: HTTP {}",
+                i18n::t("relay_adapter.api_error"),
+                response.status()
+            ));
+        }
+
+        let body: Value = response.json().await?;
+        Ok(parse_model_ids(&body))
+    }
 }
 
 /// PackyCode 适配器（默认使用 API Key 认证）
 pub struct PackycodeAdapter;
 
-#[async_trait]
-impl StationAdapter for PackycodeAdapter {
-    async fn get_station_info(&self, station: &RelayStation) -> Result<StationInfo> {
+impl PackycodeAdapter {
+    /// 请求健康检查端点，返回站点信息与实际请求次数
+    async fn check_health(&self, station: &RelayStation) -> (Result<StationInfo>, u32) {
         // PackyCode 使用简单的健康检查端点
         let url = format!("{}/health", station.api_url.trim_end_matches('/'));
 
-        let client = http_client::default_client()
-            .map_err(|e| anyhow::anyhow!("创建 HTTP 客户端失败: {}", e))?;
-        let response = client
-            .get(&url)
-            .header("X-API-Key", &station.system_token)
-            .send()
-            .await?;
+        let client = match station_client(station, http_client::ClientConfig::default()) {
+            Ok(client) => client,
+            Err(e) => return (Err(e), 0),
+        };
+        let request = client.get(&url).header("X-API-Key", &station.system_token);
+        let (response, attempts) = send_idempotent(station, request).await;
+        let result = response.and_then(|response| Self::station_info(station, &response));
+        (result, attempts)
+    }
 
+    fn station_info(station: &RelayStation, response: &reqwest::Response) -> Result<StationInfo> {
         if response.status().is_success() {
             Ok(StationInfo {
                 name: station.name.clone(),
@@ -160,21 +342,28 @@ impl StationAdapter for PackycodeAdapter {
                 quota_per_unit: Some(1),
             })
         } else {
-            Err(anyhow::anyhow!("PackyCode service unavailable"))
+            Err(anyhow::anyhow!(
+                "PackyCode service unavailable (HTTP {})",
+                response.status()
+            ))
         }
     }
+}
+
+#[async_trait]
+impl StationAdapter for PackycodeAdapter {
+    async fn get_station_info(&self, station: &RelayStation) -> Result<StationInfo> {
+        self.check_health(station).await.0
+    }
 
     async fn get_user_info(&self, station: &RelayStation, _user_id: &str) -> Result<UserInfo> {
         // PackyCode 用户信息获取
         let url = format!("{}/user/info", station.api_url.trim_end_matches('/'));
 
-        let client = http_client::default_client()
-            .map_err(|e| anyhow::anyhow!("创建 HTTP 客户端失败: {}", e))?;
-        let response = client
-            .get(&url)
-            .header("X-API-Key", &station.system_token)
-            .send()
-            .await?;
+        let client = station_client(station, http_client::ClientConfig::default())?;
+        let request = client.get(&url).header("X-API-Key", &station.system_token);
+        let (response, _) = send_idempotent(station, request).await;
+        let response = response?;
 
         let data: Value = response.json().await?;
 
@@ -204,7 +393,8 @@ impl StationAdapter for PackycodeAdapter {
     async fn test_connection(&self, station: &RelayStation) -> Result<ConnectionTestResult> {
         let start_time = std::time::Instant::now();
 
-        match self.get_station_info(station).await {
+        let (result, attempts) = self.check_health(station).await;
+        match result {
             Ok(info) => {
                 let response_time = start_time.elapsed().as_millis() as u64;
                 Ok(ConnectionTestResult {
@@ -215,6 +405,7 @@ impl StationAdapter for PackycodeAdapter {
                         "服务版本: {}",
                         info.version.unwrap_or_else(|| "Unknown".to_string())
                     )),
+                    attempts,
                 })
             }
             Err(e) => {
@@ -224,6 +415,7 @@ impl StationAdapter for PackycodeAdapter {
                     response_time,
                     message: format!("连接失败: {}", e),
                     details: None,
+                    attempts,
                 })
             }
         }
@@ -323,14 +515,11 @@ impl StationAdapter for CustomAdapter {
         let start_time = std::time::Instant::now();
 
         // 尝试简单的 GET 请求测试连接
-        let client = http_client::create_client(
-            http_client::ClientConfig::new().timeout(5)
-        ).map_err(|e| anyhow::anyhow!("创建 HTTP 客户端失败: {}", e))?;
-        let response = client
+        let client = station_client(station, http_client::ClientConfig::new().timeout(5))?;
+        let request = client
             .get(&station.api_url)
-            .header("Authorization", format!("Bearer {}", station.system_token))
-            .send()
-            .await;
+            .header("Authorization", format!("Bearer {}", station.system_token));
+        let (response, attempts) = send_idempotent(station, request).await;
 
         let response_time = start_time.elapsed().as_millis() as u64;
 
@@ -344,12 +533,14 @@ impl StationAdapter for CustomAdapter {
                     format!("HTTP {}: 服务器响应错误", resp.status())
                 },
                 details: Some(format!("响应状态: {}", resp.status())),
+                attempts,
             }),
             Err(e) => Ok(ConnectionTestResult {
                 success: false,
                 response_time,
                 message: format!("连接失败: {}", e),
                 details: None,
+                attempts,
             }),
         }
     }
@@ -428,7 +619,7 @@ pub async fn relay_station_get_info(
     db: State<'_, AgentDb>,
 ) -> Result<StationInfo, String> {
     // 获取中转站配置
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
 
     // 创建适配器
     let adapter = create_adapter(&station.adapter);
@@ -447,7 +638,7 @@ pub async fn relay_station_get_user_info(
     user_id: String,
     db: State<'_, AgentDb>,
 ) -> Result<UserInfo, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter
@@ -465,7 +656,7 @@ pub async fn relay_station_test_connection(
     station_id: String,
     db: State<'_, AgentDb>,
 ) -> Result<ConnectionTestResult, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter.test_connection(&station).await.map_err(|e| {
@@ -474,6 +665,45 @@ pub async fn relay_station_test_connection(
     })
 }
 
+/// 测试中转站是否提供所需模型
+#[command]
+pub async fn relay_station_test_models(
+    station_id: String,
+    required_models: Vec<String>,
+    db: State<'_, AgentDb>,
+) -> Result<ModelAvailabilityResult, String> {
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
+    let adapter = create_adapter(&station.adapter);
+
+    let available_models = adapter.list_models(&station).await.map_err(|e| {
+        log::error!("Failed to list models for station {}: {}", station.name, e);
+        i18n::t("relay_adapter.list_models_failed")
+    })?;
+
+    // 模型名称比较忽略大小写
+    let (present, missing): (Vec<String>, Vec<String>) =
+        required_models.into_iter().partition(|required| {
+            available_models
+                .iter()
+                .any(|model| model.eq_ignore_ascii_case(required))
+        });
+
+    if !missing.is_empty() {
+        log::warn!(
+            "Relay station {} does not offer models: {:?}",
+            station.name,
+            missing
+        );
+    }
+
+    Ok(ModelAvailabilityResult {
+        all_present: missing.is_empty(),
+        available_models,
+        present,
+        missing,
+    })
+}
+
 /// 获取使用日志
 #[command]
 pub async fn relay_station_get_usage_logs(
@@ -483,7 +713,7 @@ pub async fn relay_station_get_usage_logs(
     size: Option<usize>,
     db: State<'_, AgentDb>,
 ) -> Result<Value, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter
@@ -503,7 +733,7 @@ pub async fn relay_station_list_tokens(
     size: Option<usize>,
     db: State<'_, AgentDb>,
 ) -> Result<TokenPaginationResponse, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter
@@ -523,7 +753,7 @@ pub async fn relay_station_create_token(
     quota: Option<i64>,
     db: State<'_, AgentDb>,
 ) -> Result<TokenInfo, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter
@@ -544,7 +774,7 @@ pub async fn relay_station_update_token(
     quota: Option<i64>,
     db: State<'_, AgentDb>,
 ) -> Result<TokenInfo, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter
@@ -563,7 +793,7 @@ pub async fn relay_station_delete_token(
     token_id: String,
     db: State<'_, AgentDb>,
 ) -> Result<String, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db).await?;
+    let station = crate::commands::relay_stations::load_relay_station(&db, &station_id)?;
     let adapter = create_adapter(&station.adapter);
 
     adapter
@@ -575,6 +805,49 @@ pub async fn relay_station_delete_token(
         })
 }
 
+/// 获取中转站请求的全局默认超时
+#[command]
+pub async fn relay_get_timeout_settings() -> Result<RelayTimeoutSettings, String> {
+    Ok(RelayTimeoutSettings {
+        timeout_ms: global_timeout(&GLOBAL_TIMEOUT_MS),
+        connect_timeout_ms: global_timeout(&GLOBAL_CONNECT_TIMEOUT_MS),
+    })
+}
+
+/// 保存中转站请求的全局默认超时（为空时删除对应设置）
+#[command]
+pub async fn relay_set_timeout_settings(
+    settings: RelayTimeoutSettings,
+    db: State<'_, AgentDb>,
+) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+
+    for (key, value) in [
+        (RELAY_TIMEOUT_SETTING_KEY, settings.timeout_ms),
+        (
+            RELAY_CONNECT_TIMEOUT_SETTING_KEY,
+            settings.connect_timeout_ms,
+        ),
+    ] {
+        match value.filter(|ms| *ms > 0) {
+            Some(ms) => conn.execute(
+                "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = ?2",
+                params![key, ms.to_string()],
+            ),
+            None => conn.execute("DELETE FROM app_settings WHERE key = ?1", params![key]),
+        }
+        .map_err(|e| format!("保存超时设置失败: {}", e))?;
+    }
+
+    load_relay_timeout_defaults(&conn);
+    log::info!("Updated relay timeout defaults: {:?}", settings);
+    Ok(())
+}
+
 /// PackyCode 用户额度信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackycodeUserQuota {
@@ -591,55 +864,175 @@ pub struct PackycodeUserQuota {
     pub opus_enabled: Option<bool>,      // 是否启用Opus模型
 }
 
-/// 获取 PackyCode 用户额度（专用）
-#[command]
-pub async fn packycode_get_user_quota(
-    station_id: String,
-    db: State<'_, AgentDb>,
-) -> Result<PackycodeUserQuota, String> {
-    let station = crate::commands::relay_stations::relay_station_get(station_id, db)
-        .await
-        .map_err(|e| format!("Failed to get station: {}", e))?;
+/// PackyCode 服务类型，对应不同的后台地址
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackycodeServiceType {
+    Bus,   // 公交车
+    Share, // 滴滴车
+}
 
-    if station.adapter.as_str() != "packycode" {
-        return Err("此功能仅支持 PackyCode 中转站".to_string());
+impl PackycodeServiceType {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "bus" => Some(Self::Bus),
+            "share" => Some(Self::Share),
+            _ => None,
+        }
     }
 
-    // 根据服务类型构建不同的 URL
-    let url =
-        if station.api_url.contains("share-api") || station.api_url.contains("share.packycode") {
-            // 滴滴车服务
-            "https://share.packycode.com/api/backend/users/info"
-        } else {
-            // 公交车服务
-            "https://www.packycode.com/api/backend/users/info"
-        };
+    fn users_info_url(self) -> &'static str {
+        match self {
+            Self::Bus => "https://www.packycode.com/api/backend/users/info",
+            Self::Share => "https://share.packycode.com/api/backend/users/info",
+        }
+    }
 
-    let client = http_client::secure_client()
-        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+    fn label(self) -> &'static str {
+        match self {
+            Self::Bus => "公交车",
+            Self::Share => "滴滴车",
+        }
+    }
+
+    fn config_value(self) -> &'static str {
+        match self {
+            Self::Bus => "bus",
+            Self::Share => "share",
+        }
+    }
 
+    fn other(self) -> Self {
+        match self {
+            Self::Bus => Self::Share,
+            Self::Share => Self::Bus,
+        }
+    }
+}
+
+/// 中转站的 PackyCode 服务类型：优先使用 adapter_config 的 `service_type`，
+/// 未设置时才根据 API 地址推断
+fn packycode_service_type(station: &RelayStation) -> Result<PackycodeServiceType, String> {
+    let configured = station
+        .adapter_config
+        .as_ref()
+        .and_then(|config| config.get("service_type"))
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.trim().is_empty());
+
+    if let Some(value) = configured {
+        return PackycodeServiceType::parse(value).ok_or_else(|| {
+            format!(
+                "无效的 service_type: {}（应为 \"bus\" 或 \"share\"）",
+                value
+            )
+        });
+    }
+
+    if station.api_url.contains("share-api") || station.api_url.contains("share.packycode") {
+        Ok(PackycodeServiceType::Share)
+    } else {
+        Ok(PackycodeServiceType::Bus)
+    }
+}
+
+/// 请求指定服务的用户信息接口（幂等 GET，失败时按策略重试）
+async fn request_packycode_users_info(
+    client: &reqwest::Client,
+    station: &RelayStation,
+    service: PackycodeServiceType,
+) -> Result<reqwest::Response, String> {
+    let url = service.users_info_url();
     log::info!("正在请求 PackyCode 用户信息: {}", url);
 
-    let response = client
+    let request = client
         .get(url)
         .header("Authorization", format!("Bearer {}", station.system_token))
         .header("User-Agent", "Claudia")
-        .header("Accept", "*/*")
-        .send()
-        .await
-        .map_err(|e| {
-            log::error!("请求 PackyCode API 失败: {}", e);
-            if e.is_connect() {
-                format!("网络连接失败: {}", e)
-            } else if e.is_timeout() {
-                format!("请求超时: {}", e)
-            } else {
-                format!("请求失败: {}", e)
-            }
-        })?;
+        .header("Accept", "*/*");
+    let outcome = http_client::send_with_retry(request, &retry_policy(station)).await;
+    let attempts = outcome.attempts;
+    outcome.result.map_err(|e| {
+        log::error!("请求 PackyCode API 失败（尝试 {} 次）: {}", attempts, e);
+        if e.is_connect() {
+            format!("网络连接失败: {}", e)
+        } else if e.is_timeout() {
+            format!("请求超时: {}", e)
+        } else {
+            format!("请求失败: {}", e)
+        }
+    })
+}
+
+/// 预算提醒（`budget-warning` 事件的载荷）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackycodeBudgetWarning {
+    pub station_id: String,
+    pub station_name: String,
+    /// daily 或 monthly
+    pub period: String,
+    pub spent_usd: f64,
+    pub budget_usd: f64,
+    /// 已用/预算
+    pub ratio: f64,
+    /// 触发提醒的阈值
+    pub threshold: f64,
+}
+
+/// 加载 PackyCode 中转站，非 PackyCode 中转站返回错误
+fn load_packycode_station(db: &AgentDb, station_id: &str) -> Result<RelayStation, String> {
+    let station = crate::commands::relay_stations::load_relay_station(db, station_id)
+        .map_err(|e| format!("Failed to get station: {}", e))?;
+
+    if station.adapter.as_str() != "packycode" {
+        return Err("此功能仅支持 PackyCode 中转站".to_string());
+    }
+    Ok(station)
+}
+
+/// 获取 PackyCode 用户额度（专用）
+#[command]
+pub async fn packycode_get_user_quota(
+    station_id: String,
+    db: State<'_, AgentDb>,
+) -> Result<PackycodeUserQuota, String> {
+    let station = load_packycode_station(&db, &station_id)?;
+    fetch_packycode_quota(&station).await
+}
+
+/// 请求 PackyCode 后台接口获取用户额度
+async fn fetch_packycode_quota(station: &RelayStation) -> Result<PackycodeUserQuota, String> {
+    let service = packycode_service_type(station)?;
+
+    // 30 秒超时且不走代理，但允许按中转站覆盖超时
+    let client =
+        station_client(station, http_client::secure_config()).map_err(|e| e.to_string())?;
+
+    let response = request_packycode_users_info(&client, station, service).await?;
 
     if !response.status().is_success() {
         let status = response.status();
+        // Token 被拒绝时检查它是否属于另一种服务，给出明确的配置提示
+        if matches!(status.as_u16(), 401 | 403) {
+            let other = service.other();
+            if let Ok(probe) = request_packycode_users_info(&client, station, other).await {
+                if probe.status().is_success() {
+                    log::warn!(
+                        "PackyCode token of station {} belongs to the {:?} service, not {:?}",
+                        station.name,
+                        other,
+                        service
+                    );
+                    return Err(format!(
+                        "Token 属于{}服务，但中转站使用的是{}服务，请在适配器配置中设置 \"service_type\": \"{}\"",
+                        other.label(),
+                        service.label(),
+                        other.config_value()
+                    ));
+                }
+            }
+        }
+
         let error_text = response.text().await.unwrap_or_default();
         return Err(match status.as_u16() {
             401 => "Token 无效或已过期".to_string(),
@@ -698,3 +1091,105 @@ pub async fn packycode_get_user_quota(
         opus_enabled: data.get("opus_enabled").and_then(|v| v.as_bool()),
     })
 }
+
+/// 读取预算提醒阈值，未配置或无效时使用默认值
+fn load_budget_threshold(db: &AgentDb) -> Result<f64, String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+    let threshold = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![PACKYCODE_BUDGET_THRESHOLD_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|value| *value > 0.0 && *value <= 1.0)
+        .unwrap_or(DEFAULT_PACKYCODE_BUDGET_THRESHOLD);
+    Ok(threshold)
+}
+
+/// 计算超过阈值的日/月预算（预算为 0 视为未设置）
+fn budget_warnings(
+    station: &RelayStation,
+    quota: &PackycodeUserQuota,
+    threshold: f64,
+) -> Vec<PackycodeBudgetWarning> {
+    [
+        ("daily", quota.daily_spent_usd, quota.daily_budget_usd),
+        ("monthly", quota.monthly_spent_usd, quota.monthly_budget_usd),
+    ]
+    .into_iter()
+    .filter(|(_, _, budget)| *budget > 0.0)
+    .map(|(period, spent, budget)| (period, spent, budget, spent / budget))
+    .filter(|(_, _, _, ratio)| *ratio >= threshold)
+    .map(|(period, spent, budget, ratio)| PackycodeBudgetWarning {
+        station_id: station.id.clone(),
+        station_name: station.name.clone(),
+        period: period.to_string(),
+        spent_usd: spent,
+        budget_usd: budget,
+        ratio,
+        threshold,
+    })
+    .collect()
+}
+
+/// 检查 PackyCode 日/月预算，超过阈值时发出 `budget-warning` 事件并返回提醒列表
+#[command]
+pub async fn check_packycode_budget(
+    app: AppHandle,
+    station_id: String,
+    db: State<'_, AgentDb>,
+) -> Result<Vec<PackycodeBudgetWarning>, String> {
+    let station = load_packycode_station(&db, &station_id)?;
+    let threshold = load_budget_threshold(&db)?;
+    let quota = fetch_packycode_quota(&station).await?;
+
+    let warnings = budget_warnings(&station, &quota, threshold);
+    for warning in &warnings {
+        log::warn!(
+            "PackyCode {} budget of station {} at {:.0}% (${:.2} / ${:.2})",
+            warning.period,
+            station.name,
+            warning.ratio * 100.0,
+            warning.spent_usd,
+            warning.budget_usd
+        );
+        if let Err(e) = app.emit("budget-warning", warning) {
+            log::error!("Failed to emit budget-warning event: {}", e);
+        }
+    }
+    Ok(warnings)
+}
+
+/// 获取 PackyCode 预算提醒阈值（0~1）
+#[command]
+pub async fn get_packycode_budget_threshold(db: State<'_, AgentDb>) -> Result<f64, String> {
+    load_budget_threshold(&db)
+}
+
+/// 设置 PackyCode 预算提醒阈值（0~1，如 0.8 表示已用 80% 时提醒）
+#[command]
+pub async fn set_packycode_budget_threshold(
+    threshold: f64,
+    db: State<'_, AgentDb>,
+) -> Result<(), String> {
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        return Err(format!("预算提醒阈值必须在 0 到 1 之间: {}", threshold));
+    }
+
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = ?2",
+        params![PACKYCODE_BUDGET_THRESHOLD_KEY, threshold.to_string()],
+    )
+    .map_err(|e| format!("保存预算提醒阈值失败: {}", e))?;
+    Ok(())
+}
@@ -8,7 +8,10 @@ use uuid::Uuid;
 
 use crate::claude_config;
 use crate::commands::agents::AgentDb;
+use crate::commands::relay_adapters::{self, create_adapter};
+use crate::commands::routing::is_ccr_running;
 use crate::i18n;
+use crate::utils::sqlite;
 
 /// 中转站适配器类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +151,12 @@ pub struct TokenPaginationResponse {
 }
 
 impl RelayStation {
+    /// 返回令牌脱敏后的副本（发送到前端时使用）
+    pub fn masked(mut self) -> Self {
+        self.system_token = mask_token(&self.system_token);
+        self
+    }
+
     pub fn from_row(row: &Row) -> Result<Self, rusqlite::Error> {
         let adapter_str: String = row.get("adapter")?;
         let auth_method_str: String = row.get("auth_method")?;
@@ -193,7 +202,7 @@ impl RelayStation {
             api_url: row.get("api_url")?,
             adapter,
             auth_method,
-            system_token: row.get("system_token")?,
+            system_token: resolve_token(row.get("system_token")?),
             user_id: row.get("user_id")?,
             adapter_config,
             enabled: row.get::<_, i32>("enabled")? == 1,
@@ -252,7 +261,7 @@ pub fn init_relay_stations_tables(conn: &Connection) -> Result<()> {
 /// 获取所有中转站
 #[command]
 pub async fn relay_stations_list(db: State<'_, AgentDb>) -> Result<Vec<RelayStation>, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
@@ -263,12 +272,6 @@ pub async fn relay_stations_list(db: State<'_, AgentDb>) -> Result<Vec<RelayStat
         i18n::t("database.init_failed")
     })?;
 
-    // 添加 display_order 列（如果不存在）
-    let _ = conn.execute(
-        "ALTER TABLE relay_stations ADD COLUMN display_order INTEGER NOT NULL DEFAULT 0",
-        [],
-    );
-
     let mut stmt = conn
         .prepare("SELECT * FROM relay_stations ORDER BY display_order ASC, created_at DESC")
         .map_err(|e| {
@@ -289,33 +292,57 @@ pub async fn relay_stations_list(db: State<'_, AgentDb>) -> Result<Vec<RelayStat
         })?;
 
     log::info!("Retrieved {} relay stations", stations.len());
-    Ok(stations)
+    Ok(stations.into_iter().map(RelayStation::masked).collect())
 }
 
-/// 获取单个中转站
+/// 获取单个中转站（令牌已脱敏，获取完整令牌请使用 relay_station_reveal_token）
 #[command]
 pub async fn relay_station_get(id: String, db: State<'_, AgentDb>) -> Result<RelayStation, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let station = load_relay_station(&db, &id)?;
+
+    log::info!("Retrieved relay station: {}", id);
+    Ok(station.masked())
+}
+
+/// 获取中转站完整令牌（仅在用户明确要求查看时调用）
+#[command]
+pub async fn relay_station_reveal_token(
+    id: String,
+    db: State<'_, AgentDb>,
+) -> Result<String, String> {
+    let station = load_relay_station(&db, &id)?;
+
+    log::info!("Revealed token of relay station: {}", id);
+    Ok(station.system_token)
+}
+
+/// 读取包含完整令牌的中转站配置（仅供后端内部使用，不要直接返回给前端）
+pub fn load_relay_station(db: &AgentDb, id: &str) -> Result<RelayStation, String> {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
 
-    let mut stmt = conn
-        .prepare("SELECT * FROM relay_stations WHERE id = ?1")
-        .map_err(|e| {
-            log::error!("Failed to prepare statement: {}", e);
-            i18n::t("database.query_failed")
-        })?;
+    relay_station_get_internal(&conn, id)
+}
 
-    let station = stmt
-        .query_row(params![id], |row| RelayStation::from_row(row))
-        .map_err(|e| {
-            log::error!("Failed to get relay station {}: {}", id, e);
-            i18n::t("relay_station.not_found")
-        })?;
+/// 读取当前启用的中转站（包含完整令牌，仅供后端内部使用）
+pub fn load_enabled_relay_station(db: &AgentDb) -> Result<Option<RelayStation>, String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
 
-    log::info!("Retrieved relay station: {}", id);
-    Ok(station)
+    conn.query_row(
+        "SELECT * FROM relay_stations WHERE enabled = 1 LIMIT 1",
+        [],
+        RelayStation::from_row,
+    )
+    .optional()
+    .map_err(|e| {
+        log::error!("Failed to query enabled relay station: {}", e);
+        i18n::t("database.query_failed")
+    })
 }
 
 /// 创建中转站
@@ -324,7 +351,7 @@ pub async fn relay_station_create(
     request: CreateRelayStationRequest,
     db: State<'_, AgentDb>,
 ) -> Result<RelayStation, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
@@ -372,6 +399,7 @@ pub async fn relay_station_create(
             })?;
     }
 
+    let stored_token = store_token(&conn, &id, &request.system_token);
     conn.execute(
         r#"
         INSERT INTO relay_stations 
@@ -385,7 +413,7 @@ pub async fn relay_station_create(
             request.api_url,
             adapter_str,
             auth_method_str,
-            request.system_token,
+            stored_token,
             request.user_id,
             adapter_config_str,
             if request.enabled { 1 } else { 0 },
@@ -394,6 +422,7 @@ pub async fn relay_station_create(
         ],
     ).map_err(|e| {
         log::error!("Failed to create relay station: {}", e);
+        delete_stored_token(&stored_token);
         i18n::t("relay_station.create_failed")
     })?;
 
@@ -415,20 +444,26 @@ pub async fn relay_station_create(
 
     log::info!("[CREATE] Created relay station: {} ({})", station.name, id);
     log::info!("[CREATE] Final station.adapter_config: {:?}", station.adapter_config);
-    Ok(station)
+    Ok(station.masked())
 }
 
 /// 更新中转站
 #[command]
 pub async fn relay_station_update(
-    request: UpdateRelayStationRequest,
+    mut request: UpdateRelayStationRequest,
     db: State<'_, AgentDb>,
 ) -> Result<RelayStation, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
 
+    // 前端拿到的是脱敏令牌，未修改时保留数据库中的原令牌
+    let existing = relay_station_get_internal(&conn, &request.id)?;
+    if request.system_token == mask_token(&existing.system_token) {
+        request.system_token = existing.system_token;
+    }
+
     // 验证输入
     validate_relay_station_request(&request.name, &request.api_url, &request.system_token)?;
 
@@ -483,7 +518,7 @@ pub async fn relay_station_update(
                 request.api_url,
                 adapter_str,
                 auth_method_str,
-                request.system_token,
+                store_token(&conn, &request.id, &request.system_token),
                 request.user_id,
                 adapter_config_str,
                 if request.enabled { 1 } else { 0 },
@@ -517,17 +552,29 @@ pub async fn relay_station_update(
 
     log::info!("[UPDATE] Updated relay station: {} ({})", station.name, request.id);
     log::info!("[UPDATE] Final station.adapter_config: {:?}", station.adapter_config);
-    Ok(station)
+    Ok(station.masked())
 }
 
 /// 删除中转站
 #[command]
 pub async fn relay_station_delete(id: String, db: State<'_, AgentDb>) -> Result<String, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
 
+    let stored_token: Option<String> = conn
+        .query_row(
+            "SELECT system_token FROM relay_stations WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| {
+            log::error!("Failed to query relay station token: {}", e);
+            i18n::t("database.query_failed")
+        })?;
+
     let rows_affected = conn
         .execute("DELETE FROM relay_stations WHERE id = ?1", params![id])
         .map_err(|e| {
@@ -539,18 +586,88 @@ pub async fn relay_station_delete(id: String, db: State<'_, AgentDb>) -> Result<
         return Err(i18n::t("relay_station.not_found"));
     }
 
+    if let Some(stored_token) = stored_token {
+        delete_stored_token(&stored_token);
+    }
+
     log::info!("Deleted relay station: {}", id);
     Ok(i18n::t("relay_station.delete_success"))
 }
 
+/// 切换中转站启用状态的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayToggleResult {
+    pub applied: bool,                                             // 是否已切换并写入配置
+    pub message: String,                                           // 结果消息
+    pub test_result: Option<relay_adapters::ConnectionTestResult>, // 启用前的连接测试结果
+}
+
 /// 切换中转站启用状态（确保只有一个中转站启用）
+/// 启用前会检查 CCR 是否运行并测试连接，存在冲突或测试失败时不写入配置，除非 `force` 为 true；禁用总是直接生效
 #[command]
 pub async fn relay_station_toggle_enable(
     id: String,
     enabled: bool,
+    force: Option<bool>,
     db: State<'_, AgentDb>,
-) -> Result<String, String> {
-    let conn = db.0.lock().map_err(|e| {
+) -> Result<RelayToggleResult, String> {
+    // CCR 运行时会接管 Claude 配置，启用中转站前需要用户明确选择
+    if enabled && !force.unwrap_or(false) && is_ccr_running().await {
+        log::warn!("CCR service is running, not enabling relay station {}", id);
+        return Ok(RelayToggleResult {
+            applied: false,
+            message: i18n::t("relay_station.ccr_conflict"),
+            test_result: None,
+        });
+    }
+
+    // 启用前测试连接（不持有数据库锁）
+    let mut test_result = None;
+    if enabled {
+        let station = {
+            let conn = db.lock().map_err(|e| {
+                log::error!("Failed to acquire database lock: {}", e);
+                i18n::t("database.lock_failed")
+            })?;
+            relay_station_get_internal(&conn, &id)?
+        };
+
+        let adapter = create_adapter(&station.adapter);
+        let result = match adapter.test_connection(&station).await {
+            Ok(result) => result,
+            Err(e) => relay_adapters::ConnectionTestResult {
+                success: false,
+                response_time: 0,
+                message: e.to_string(),
+                details: None,
+                attempts: 0,
+            },
+        };
+
+        if !result.success {
+            if force.unwrap_or(false) {
+                log::warn!(
+                    "Connection test for relay station {} failed, applying anyway (forced): {}",
+                    station.name,
+                    result.message
+                );
+            } else {
+                log::warn!(
+                    "Connection test for relay station {} failed, not applying: {}",
+                    station.name,
+                    result.message
+                );
+                return Ok(RelayToggleResult {
+                    applied: false,
+                    message: i18n::t("relay_station.test_failed_not_applied"),
+                    test_result: Some(result),
+                });
+            }
+        }
+        test_result = Some(result);
+    }
+
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
@@ -601,10 +718,14 @@ pub async fn relay_station_toggle_enable(
     }
 
     log::info!("Toggled relay station enable status: {} -> {}", id, enabled);
-    Ok(if enabled {
-        i18n::t("relay_station.enabled_success")
-    } else {
-        i18n::t("relay_station.disabled_success")
+    Ok(RelayToggleResult {
+        applied: true,
+        message: if enabled {
+            i18n::t("relay_station.enabled_success")
+        } else {
+            i18n::t("relay_station.disabled_success")
+        },
+        test_result,
     })
 }
 
@@ -680,20 +801,215 @@ fn validate_relay_station_request(
     Ok(())
 }
 
-/// Token 脱敏显示
-#[allow(dead_code)]
+/// Token 脱敏显示（按字符截取，避免多字节字符边界导致 panic）
 pub fn mask_token(token: &str) -> String {
-    if token.len() <= 8 {
-        "*".repeat(token.len())
+    let count = token.chars().count();
+    if count <= 8 {
+        "*".repeat(count)
     } else {
-        format!("{}...{}", &token[..4], &token[token.len() - 4..])
+        let head: String = token.chars().take(4).collect();
+        let tail: String = token.chars().skip(count - 4).collect();
+        format!("{}...{}", head, tail)
     }
 }
 
+/// 系统钥匙串中保存中转站令牌所用的服务名
+const KEYRING_SERVICE: &str = "claudia.relay_stations";
+
+/// 数据库中指向钥匙串条目的令牌引用前缀，后接中转站 ID
+const KEYRING_REF_PREFIX: &str = "keyring:";
+
+/// 是否将令牌保存到系统钥匙串的设置项，默认关闭（明文保存在数据库中）
+const KEYCHAIN_SETTING_KEY: &str = "relay_station_token_keychain";
+
+fn keyring_entry(id: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, id)
+}
+
+/// 读取是否启用钥匙串保存令牌
+pub fn load_token_keychain_enabled(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        params![KEYCHAIN_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|value| value == "true")
+    .unwrap_or(false)
+}
+
+/// 返回应写入数据库的令牌值：启用钥匙串时写入钥匙串并返回引用，否则返回明文
+/// 没有可用的钥匙串后端时回退为明文存储
+fn store_token(conn: &Connection, id: &str, token: &str) -> String {
+    if !load_token_keychain_enabled(conn) {
+        return token.to_string();
+    }
+
+    match keyring_entry(id).and_then(|entry| entry.set_password(token)) {
+        Ok(()) => format!("{}{}", KEYRING_REF_PREFIX, id),
+        Err(e) => {
+            log::warn!(
+                "OS keychain unavailable, storing token of relay station {} in plaintext: {}",
+                id,
+                e
+            );
+            token.to_string()
+        }
+    }
+}
+
+/// 解析数据库中的令牌值，钥匙串引用会被替换为实际令牌
+fn resolve_token(stored: String) -> String {
+    let Some(id) = stored.strip_prefix(KEYRING_REF_PREFIX) else {
+        return stored;
+    };
+
+    match keyring_entry(id).and_then(|entry| entry.get_password()) {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!(
+                "Failed to read token of relay station {} from OS keychain: {}",
+                id,
+                e
+            );
+            stored
+        }
+    }
+}
+
+/// 删除数据库令牌值对应的钥匙串条目（明文令牌无需处理）
+fn delete_stored_token(stored: &str) {
+    let Some(id) = stored.strip_prefix(KEYRING_REF_PREFIX) else {
+        return;
+    };
+
+    match keyring_entry(id).and_then(|entry| entry.delete_credential()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => log::warn!(
+            "Failed to delete token of relay station {} from OS keychain: {}",
+            id,
+            e
+        ),
+    }
+}
+
+/// 事务中已写入钥匙串的令牌，事务未提交（出错提前返回或回滚）时删除这些钥匙串条目
+#[derive(Default)]
+struct PendingKeychainTokens {
+    stored: Vec<String>,
+    committed: bool,
+}
+
+impl PendingKeychainTokens {
+    /// 记录写入数据库的令牌值，明文令牌无需处理
+    fn push(&mut self, stored: &str) {
+        if stored.starts_with(KEYRING_REF_PREFIX) {
+            self.stored.push(stored.to_string());
+        }
+    }
+
+    /// 该令牌对应的行没有写入数据库，立即删除其钥匙串条目
+    fn discard(&mut self, stored: &str) {
+        self.stored.retain(|s| s != stored);
+        delete_stored_token(stored);
+    }
+
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for PendingKeychainTokens {
+    fn drop(&mut self) {
+        if !self.committed {
+            for stored in &self.stored {
+                delete_stored_token(stored);
+            }
+        }
+    }
+}
+
+/// 将数据库中仍为明文的令牌迁移到系统钥匙串，返回迁移数量
+/// 未启用钥匙串时不做任何事；钥匙串不可用时保留明文并停止迁移
+pub fn migrate_tokens_to_keychain(conn: &Connection) -> Result<usize, String> {
+    if !load_token_keychain_enabled(conn) {
+        return Ok(0);
+    }
+
+    init_relay_stations_tables(conn).map_err(|e| e.to_string())?;
+
+    let plaintext: Vec<(String, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, system_token FROM relay_stations WHERE system_token NOT LIKE ?1")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![format!("{}%", KEYRING_REF_PREFIX)], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut migrated = 0;
+    for (id, token) in plaintext {
+        let stored = store_token(conn, &id, &token);
+        if stored == token {
+            // 钥匙串不可用，其余令牌也无法迁移
+            break;
+        }
+
+        if let Err(e) = conn.execute(
+            "UPDATE relay_stations SET system_token = ?1 WHERE id = ?2",
+            params![stored, id],
+        ) {
+            delete_stored_token(&stored);
+            return Err(e.to_string());
+        }
+        migrated += 1;
+    }
+
+    Ok(migrated)
+}
+
+/// 获取是否启用系统钥匙串保存中转站令牌
+#[command]
+pub async fn relay_station_get_token_keychain(db: State<'_, AgentDb>) -> Result<bool, String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+    Ok(load_token_keychain_enabled(&conn))
+}
+
+/// 启用或停用系统钥匙串保存中转站令牌，启用时立即迁移现有明文令牌并返回迁移数量
+/// 停用只影响之后保存的令牌，已在钥匙串中的令牌仍可正常读取
+#[command]
+pub async fn relay_station_set_token_keychain(
+    enabled: bool,
+    db: State<'_, AgentDb>,
+) -> Result<usize, String> {
+    let conn = db.lock().map_err(|e| {
+        log::error!("Failed to acquire database lock: {}", e);
+        i18n::t("database.lock_failed")
+    })?;
+
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = ?2",
+        params![KEYCHAIN_SETTING_KEY, enabled.to_string()],
+    )
+    .map_err(|e| {
+        log::error!("Failed to save relay station keychain setting: {}", e);
+        i18n::t("database.update_failed")
+    })?;
+
+    migrate_tokens_to_keychain(&conn)
+}
+
 /// 手动同步中转站配置到 Claude 配置文件
 #[command]
 pub async fn relay_station_sync_config(db: State<'_, AgentDb>) -> Result<String, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
@@ -746,6 +1062,23 @@ pub async fn relay_station_restore_config() -> Result<String, String> {
     Ok("已从备份恢复 Claude 配置".to_string())
 }
 
+/// 列出 Claude 配置备份
+#[command]
+pub async fn list_claude_config_backups() -> Result<Vec<claude_config::ClaudeConfigBackup>, String>
+{
+    claude_config::list_claude_config_backups().map_err(|e| format!("获取备份列表失败: {}", e))
+}
+
+/// 恢复指定时间戳的 Claude 配置备份（"source" 为首次启用中转站前的源配置）
+#[command]
+pub async fn restore_claude_config_at(timestamp: String) -> Result<String, String> {
+    claude_config::restore_claude_config_at(&timestamp)
+        .map_err(|e| format!("恢复配置失败: {}", e))?;
+
+    log::info!("Restored Claude config from backup {}", timestamp);
+    Ok(format!("已从备份 {} 恢复 Claude 配置", timestamp))
+}
+
 /// 获取当前 Claude 配置中的 API 信息
 #[command]
 pub async fn relay_station_get_current_config() -> Result<HashMap<String, Option<String>>, String> {
@@ -772,7 +1105,7 @@ pub async fn relay_station_get_current_config() -> Result<HashMap<String, Option
 /// 导出所有中转站配置
 #[command]
 pub async fn relay_stations_export(db: State<'_, AgentDb>) -> Result<Vec<RelayStation>, String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
@@ -828,7 +1161,7 @@ pub async fn relay_stations_import(
     request: ImportRelayStationsRequest,
     db: State<'_, AgentDb>,
 ) -> Result<ImportResult, String> {
-    let mut conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
@@ -840,11 +1173,31 @@ pub async fn relay_stations_import(
     })?;
 
     // 开始事务
-    let tx = conn.transaction().map_err(|e| {
+    let tx = sqlite::begin_immediate(&conn, "relay station import").map_err(|e| {
         log::error!("Failed to start transaction: {}", e);
         i18n::t("database.transaction_failed")
     })?;
 
+    // 清除前记录旧令牌，事务提交后再删除对应的钥匙串条目
+    let cleared_tokens: Vec<String> = if request.clear_existing {
+        let mut stmt = tx
+            .prepare("SELECT system_token FROM relay_stations")
+            .map_err(|e| {
+                log::error!("Failed to prepare statement: {}", e);
+                i18n::t("database.query_failed")
+            })?;
+        let tokens = stmt
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| {
+                log::error!("Failed to query existing relay station tokens: {}", e);
+                i18n::t("database.query_failed")
+            })?;
+        tokens
+    } else {
+        Vec::new()
+    };
+
     // 如果需要清除现有配置
     if request.clear_existing {
         tx.execute("DELETE FROM relay_stations", []).map_err(|e| {
@@ -865,7 +1218,10 @@ pub async fn relay_stations_import(
 
         let stations_iter = stmt
             .query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                Ok((
+                    row.get::<_, String>(0)?,
+                    resolve_token(row.get::<_, String>(1)?),
+                ))
             })
             .map_err(|e| {
                 log::error!("Failed to query existing stations: {}", e);
@@ -888,7 +1244,8 @@ pub async fn relay_stations_import(
         Vec::new()
     };
 
-    // 导入新的中转站
+    // 导入新的中转站；事务未提交时删除已写入钥匙串的令牌
+    let mut pending_tokens = PendingKeychainTokens::default();
     let total = request.stations.len();
     let mut imported_count = 0;
     let mut skipped_count = 0;
@@ -941,6 +1298,8 @@ pub async fn relay_stations_import(
             .transpose()
             .map_err(|_| i18n::t("relay_station.invalid_config"))?;
 
+        let stored_token = store_token(&tx, &id, &station_request.system_token);
+        pending_tokens.push(&stored_token);
         match tx.execute(
             r#"
             INSERT INTO relay_stations 
@@ -954,7 +1313,7 @@ pub async fn relay_stations_import(
                 station_request.api_url,
                 adapter_str,
                 auth_method_str,
-                station_request.system_token,
+                stored_token,
                 station_request.user_id,
                 adapter_config_str,
                 if station_request.enabled { 1 } else { 0 },
@@ -965,6 +1324,7 @@ pub async fn relay_stations_import(
             Ok(_) => imported_count += 1,
             Err(e) => {
                 log::error!("Failed to import relay station: {}", e);
+                pending_tokens.discard(&stored_token);
                 failed_count += 1;
             }
         }
@@ -975,6 +1335,11 @@ pub async fn relay_stations_import(
         log::error!("Failed to commit transaction: {}", e);
         i18n::t("database.transaction_failed")
     })?;
+    pending_tokens.commit();
+
+    for stored_token in &cleared_tokens {
+        delete_stored_token(stored_token);
+    }
 
     let message = format!(
         "导入完成：总计 {} 个，成功 {} 个，跳过 {} 个（重复），失败 {} 个",
@@ -999,13 +1364,13 @@ pub async fn relay_station_update_order(
     station_ids: Vec<String>,
     db: State<'_, AgentDb>,
 ) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.lock().map_err(|e| {
         log::error!("Failed to acquire database lock: {}", e);
         i18n::t("database.lock_failed")
     })?;
 
     // 开始事务
-    let tx = conn.unchecked_transaction().map_err(|e| {
+    let tx = sqlite::begin_immediate(&conn, "relay station reorder").map_err(|e| {
         log::error!("Failed to start transaction: {}", e);
         i18n::t("database.transaction_failed")
     })?;
@@ -1034,3 +1399,16 @@ pub async fn relay_station_update_order(
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_token_handles_non_ascii() {
+        assert_eq!(mask_token("sk-abcdefghijkl"), "sk-a...ijkl");
+        assert_eq!(mask_token("短令牌"), "***");
+        assert_eq!(mask_token("令牌abcdefgh令牌"), "令牌ab...gh令牌");
+        assert_eq!(mask_token("sk-é1234567890ü"), "sk-é...890ü");
+    }
+}
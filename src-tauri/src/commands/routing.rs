@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::claude_config;
+use crate::commands::agents::AgentDb;
+use crate::commands::ccr::{ccr_client_env, get_ccr_service_status};
+use crate::commands::relay_stations::{load_enabled_relay_station, load_relay_station, mask_token};
+use crate::i18n;
+
+/// Claude 请求的实际路由方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingMode {
+    Direct,       // 未设置 base URL，直连 Anthropic
+    RelayStation, // 指向当前启用的中转站
+    Ccr,          // 指向本地 CCR 服务
+    Custom,       // 指向其他自定义地址
+}
+
+/// 当前路由状态（用于前端路由状态徽标）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingStatus {
+    pub mode: RoutingMode,                  // 实际生效的路由方式
+    pub relay_station_id: Option<String>,   // 已启用的中转站 ID
+    pub relay_station_name: Option<String>, // 已启用的中转站名称
+    pub ccr_running: bool,                  // CCR 服务是否运行
+    pub ccr_port: Option<u16>,              // CCR 服务端口
+    pub base_url: Option<String>,           // Claude 配置中的 base URL
+    pub masked_token: Option<String>,       // Claude 配置中的令牌（已脱敏）
+}
+
+/// 判断 URL 是否指向本机指定端口
+fn is_local_endpoint(base_url: &str, port: u16) -> bool {
+    let Ok(url) = url::Url::parse(base_url) else {
+        return false;
+    };
+
+    let is_local = matches!(
+        url.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]") | Some("0.0.0.0")
+    );
+    is_local && url.port_or_known_default() == Some(port)
+}
+
+/// 比较两个 URL 是否指向同一地址（忽略末尾斜杠和大小写）
+fn same_url(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/')
+        .eq_ignore_ascii_case(b.trim_end_matches('/'))
+}
+
+/// 根据 Claude 配置中的 base URL 判断路由方式
+fn classify_routing(
+    base_url: Option<&str>,
+    relay_api_url: Option<&str>,
+    ccr_port: Option<u16>,
+) -> RoutingMode {
+    let Some(base_url) = base_url.filter(|url| !url.trim().is_empty()) else {
+        return RoutingMode::Direct;
+    };
+
+    if let Some(port) = ccr_port {
+        if is_local_endpoint(base_url, port) {
+            return RoutingMode::Ccr;
+        }
+    }
+
+    match relay_api_url {
+        Some(relay_url) if same_url(base_url, relay_url) => RoutingMode::RelayStation,
+        _ => RoutingMode::Custom,
+    }
+}
+
+/// 路由冲突检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingConflict {
+    pub has_conflict: bool,                 // CCR 与中转站是否同时生效
+    pub ccr_running: bool,                  // CCR 服务是否运行（启用中转站前需注意）
+    pub relay_station_enabled: bool,        // 是否有启用的中转站（启动 CCR 前需注意）
+    pub relay_station_name: Option<String>, // 已启用的中转站名称
+    pub mode: RoutingMode,                  // Claude 配置当前实际指向
+    pub message: Option<String>,            // 冲突提示
+}
+
+impl RoutingConflict {
+    fn from_status(status: &RoutingStatus) -> Self {
+        let relay_station_enabled = status.relay_station_id.is_some();
+        let has_conflict = status.ccr_running && relay_station_enabled;
+
+        RoutingConflict {
+            has_conflict,
+            ccr_running: status.ccr_running,
+            relay_station_enabled,
+            relay_station_name: status.relay_station_name.clone(),
+            mode: status.mode,
+            message: has_conflict.then(|| i18n::t("routing.conflict")),
+        }
+    }
+}
+
+/// 单次运行的路由覆盖（仅作用于该子进程，不修改全局 Claude 配置）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingOverride {
+    pub relay_station_id: Option<String>, // 经由指定中转站
+    pub ccr_port: Option<u16>,            // 经由指定端口的本地 CCR
+}
+
+/// 将路由覆盖解析为子进程环境变量（ANTHROPIC_BASE_URL / ANTHROPIC_AUTH_TOKEN）
+pub fn routing_override_env(
+    db: &AgentDb,
+    routing: &RoutingOverride,
+) -> Result<Vec<(String, String)>, String> {
+    match (&routing.relay_station_id, routing.ccr_port) {
+        (Some(_), Some(_)) => {
+            Err("Choose either a relay station or a CCR port, not both".to_string())
+        }
+        (Some(station_id), None) => {
+            let station = load_relay_station(db, station_id)?;
+            Ok(vec![
+                ("ANTHROPIC_BASE_URL".to_string(), station.api_url),
+                ("ANTHROPIC_AUTH_TOKEN".to_string(), station.system_token),
+            ])
+        }
+        (None, Some(port)) => Ok(ccr_client_env(port)),
+        (None, None) => Ok(Vec::new()),
+    }
+}
+
+/// 汇总中转站、CCR 与 Claude 配置，得到当前路由状态
+pub async fn active_routing(db: &AgentDb) -> Result<RoutingStatus, String> {
+    let relay_station = load_enabled_relay_station(db)?;
+
+    let ccr_status = match get_ccr_service_status().await {
+        Ok(status) => Some(status),
+        Err(e) => {
+            log::warn!("Failed to get CCR service status: {}", e);
+            None
+        }
+    };
+    let ccr_running = ccr_status.as_ref().is_some_and(|s| s.is_running);
+    let ccr_port = ccr_status.and_then(|s| s.port);
+
+    let base_url = claude_config::get_current_api_url()?;
+    let masked_token = claude_config::get_current_api_token()?.map(|token| mask_token(&token));
+
+    let mode = classify_routing(
+        base_url.as_deref(),
+        relay_station.as_ref().map(|s| s.api_url.as_str()),
+        ccr_port.filter(|_| ccr_running),
+    );
+
+    Ok(RoutingStatus {
+        mode,
+        relay_station_id: relay_station.as_ref().map(|s| s.id.clone()),
+        relay_station_name: relay_station.map(|s| s.name),
+        ccr_running,
+        ccr_port,
+        base_url,
+        masked_token,
+    })
+}
+
+/// 检查 CCR 服务是否正在运行（获取状态失败时视为未运行）
+pub async fn is_ccr_running() -> bool {
+    get_ccr_service_status()
+        .await
+        .map(|status| status.is_running)
+        .unwrap_or(false)
+}
+
+/// 获取当前 Claude 请求的路由状态（CCR / 中转站 / 直连）
+#[tauri::command]
+pub async fn get_active_routing(db: State<'_, AgentDb>) -> Result<RoutingStatus, String> {
+    active_routing(&db).await
+}
+
+/// 检查 CCR 与中转站是否会争夺 Claude 配置，供前端在切换前提示
+#[tauri::command]
+pub async fn check_routing_conflict(db: State<'_, AgentDb>) -> Result<RoutingConflict, String> {
+    let status = active_routing(&db).await?;
+    Ok(RoutingConflict::from_status(&status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_routing_detects_each_mode() {
+        assert_eq!(classify_routing(None, None, None), RoutingMode::Direct);
+        assert_eq!(
+            classify_routing(Some(""), None, Some(3456)),
+            RoutingMode::Direct
+        );
+        assert_eq!(
+            classify_routing(Some("http://127.0.0.1:3456"), None, Some(3456)),
+            RoutingMode::Ccr
+        );
+        assert_eq!(
+            classify_routing(
+                Some("https://relay.example.com/"),
+                Some("https://relay.example.com"),
+                Some(3456)
+            ),
+            RoutingMode::RelayStation
+        );
+        assert_eq!(
+            classify_routing(Some("http://localhost:8080"), None, Some(3456)),
+            RoutingMode::Custom
+        );
+    }
+
+    #[test]
+    fn conflict_requires_both_ccr_and_relay_station() {
+        let mut status = RoutingStatus {
+            mode: RoutingMode::Ccr,
+            relay_station_id: None,
+            relay_station_name: None,
+            ccr_running: true,
+            ccr_port: Some(3456),
+            base_url: Some("http://127.0.0.1:3456".to_string()),
+            masked_token: None,
+        };
+        assert!(!RoutingConflict::from_status(&status).has_conflict);
+
+        status.relay_station_id = Some("station".to_string());
+        let conflict = RoutingConflict::from_status(&status);
+        assert!(conflict.has_conflict);
+        assert!(conflict.message.is_some());
+    }
+}
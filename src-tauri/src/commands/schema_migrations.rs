@@ -0,0 +1,214 @@
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::utils::sqlite;
+
+/// A single schema upgrade of the agents database. Each migration must be idempotent: tables
+/// created by `init_database` already have the latest columns, so on a fresh database every
+/// migration runs against a schema it has nothing to change in.
+struct SchemaMigration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// Migrations in ascending version order; append new ones at the end
+const MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        version: 1,
+        description: "Add agent columns introduced after the first release",
+        apply: add_agent_columns,
+    },
+    SchemaMigration {
+        version: 2,
+        description: "Add agent run status and process columns, backfilling status",
+        apply: add_agent_run_columns,
+    },
+    SchemaMigration {
+        version: 3,
+        description: "Add relay station display order",
+        apply: add_relay_station_display_order,
+    },
+];
+
+fn table_exists(tx: &Transaction, table: &str) -> rusqlite::Result<bool> {
+    tx.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![table],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|row| row.is_some())
+}
+
+fn column_exists(tx: &Transaction, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(columns.iter().any(|name| name == column))
+}
+
+/// Adds a column unless it is already there; any other failure is returned, not ignored
+fn add_column(
+    tx: &Transaction,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    if !column_exists(tx, table, column)? {
+        tx.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn add_agent_columns(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column(tx, "agents", "default_task", "TEXT")?;
+    add_column(
+        tx,
+        "agents",
+        "model",
+        "TEXT DEFAULT 'claude-sonnet-4-20250514'",
+    )?;
+    add_column(tx, "agents", "hooks", "TEXT")?;
+    add_column(tx, "agents", "enable_file_read", "BOOLEAN DEFAULT 1")?;
+    add_column(tx, "agents", "enable_file_write", "BOOLEAN DEFAULT 1")?;
+    add_column(tx, "agents", "enable_network", "BOOLEAN DEFAULT 0")?;
+    add_column(tx, "agents", "env", "TEXT")
+}
+
+fn add_agent_run_columns(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column(tx, "agent_runs", "session_id", "TEXT")?;
+    add_column(tx, "agent_runs", "status", "TEXT DEFAULT 'pending'")?;
+    add_column(tx, "agent_runs", "pid", "INTEGER")?;
+    add_column(tx, "agent_runs", "process_started_at", "TEXT")?;
+    add_column(
+        tx,
+        "agent_runs",
+        "run_trigger",
+        "TEXT NOT NULL DEFAULT 'manual'",
+    )?;
+
+    // Older rows predate the session_id and status columns
+    tx.execute(
+        "UPDATE agent_runs SET session_id = '' WHERE session_id IS NULL",
+        [],
+    )?;
+    tx.execute("UPDATE agent_runs SET status = 'completed' WHERE status IS NULL AND completed_at IS NOT NULL", [])?;
+    tx.execute("UPDATE agent_runs SET status = 'failed' WHERE status IS NULL AND completed_at IS NOT NULL AND session_id = ''", [])?;
+    tx.execute(
+        "UPDATE agent_runs SET status = 'pending' WHERE status IS NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+/// The relay stations table is created lazily with display_order included, so there is
+/// nothing to do when it doesn't exist yet
+fn add_relay_station_display_order(tx: &Transaction) -> rusqlite::Result<()> {
+    if table_exists(tx, "relay_stations")? {
+        add_column(
+            tx,
+            "relay_stations",
+            "display_order",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+    }
+    Ok(())
+}
+
+/// Latest applied schema version; 0 when never migrated
+fn schema_version(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Upgrades the agents database schema, applying each pending migration in its own
+/// transaction together with its `schema_migrations` record. Returns the number applied.
+pub fn run_schema_migrations(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    let current = schema_version(conn)?;
+    let mut applied = 0;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = sqlite::begin_immediate(conn, "schema migration")?;
+        (migration.apply)(&tx)
+            .and_then(|_| {
+                tx.execute(
+                    "INSERT INTO schema_migrations (version, description) VALUES (?1, ?2)",
+                    params![migration.version, migration.description],
+                )
+            })
+            .and_then(|_| tx.commit())
+            .inspect_err(|e| {
+                log::error!(
+                    "Schema migration {} ({}) failed: {}",
+                    migration.version,
+                    migration.description,
+                    e
+                )
+            })?;
+        log::info!(
+            "Applied schema migration {}: {}",
+            migration.version,
+            migration.description
+        );
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", table))
+            .unwrap();
+        stmt.query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migrations_upgrade_old_schema_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE agents (id INTEGER PRIMARY KEY, name TEXT, icon TEXT, system_prompt TEXT);
+             CREATE TABLE agent_runs (id INTEGER PRIMARY KEY, agent_id INTEGER, completed_at TEXT);
+             INSERT INTO agent_runs (agent_id, completed_at) VALUES (1, '2024-01-01');",
+        )
+        .unwrap();
+
+        assert_eq!(run_schema_migrations(&conn).unwrap(), MIGRATIONS.len());
+        assert!(columns(&conn, "agents").contains(&"env".to_string()));
+        assert!(columns(&conn, "agent_runs").contains(&"run_trigger".to_string()));
+        let session_id: String = conn
+            .query_row("SELECT session_id FROM agent_runs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(session_id, "");
+
+        assert_eq!(run_schema_migrations(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migration_versions_are_ascending() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+    }
+}
@@ -0,0 +1,231 @@
+use glob::{MatchOptions, Pattern};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+use crate::commands::agents::AgentDb;
+
+/// app_settings key holding the user's extra ignore patterns as JSON
+const SEARCH_IGNORE_SETTING_KEY: &str = "search_ignore_patterns";
+
+/// Patterns skipped by project searches regardless of settings
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    "node_modules/",
+    "target/",
+    ".git/",
+    "dist/",
+    "build/",
+    ".next/",
+    "__pycache__/",
+];
+
+/// User-configurable ignore rules for CLAUDE.md discovery and file search.
+///
+/// Patterns use `.gitignore` syntax: a trailing `/` matches directories only, a leading or
+/// inner `/` anchors the pattern to the project root, and `*`, `?` and `[...]` are globs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchIgnoreSettings {
+    /// Extra patterns, applied on top of the defaults
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Also apply the patterns in the project's top-level `.gitignore`
+    #[serde(default)]
+    pub use_gitignore: bool,
+}
+
+/// Settings as shown to the user, including the built-in defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIgnoreInfo {
+    pub default_patterns: Vec<String>,
+    pub patterns: Vec<String>,
+    pub use_gitignore: bool,
+}
+
+struct IgnoreRule {
+    pattern: Pattern,
+    dir_only: bool,
+    /// Matched against the path relative to the root instead of the file name
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    /// Parses one `.gitignore`-style line; comments, blanks and negations yield `None`
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            return None;
+        }
+
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        if line.is_empty() {
+            return None;
+        }
+
+        match Pattern::new(line) {
+            Ok(pattern) => Some(Self {
+                pattern,
+                dir_only,
+                anchored,
+            }),
+            Err(e) => {
+                log::warn!("Ignoring invalid search ignore pattern '{}': {}", line, e);
+                None
+            }
+        }
+    }
+}
+
+/// Decides which entries a project search skips
+pub struct IgnoreMatcher {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreMatcher {
+    /// Builds the matcher for a search rooted at `root`
+    pub fn new(settings: &SearchIgnoreSettings, root: &Path) -> Self {
+        let mut lines: Vec<String> = DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .chain(settings.patterns.iter().cloned())
+            .collect();
+
+        if settings.use_gitignore {
+            match fs::read_to_string(root.join(".gitignore")) {
+                Ok(content) => lines.extend(content.lines().map(String::from)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => log::warn!("Failed to read .gitignore in {:?}: {}", root, e),
+            }
+        }
+
+        Self {
+            rules: lines.iter().filter_map(|l| IgnoreRule::parse(l)).collect(),
+        }
+    }
+
+    /// Whether an entry at `relative` (to the search root) should be skipped
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let name = match relative.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name,
+            None => return false,
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        self.rules.iter().any(|rule| {
+            (is_dir || !rule.dir_only)
+                && if rule.anchored {
+                    rule.pattern.matches_with(&relative, options)
+                } else {
+                    rule.pattern.matches(name)
+                }
+        })
+    }
+}
+
+/// Reads the ignore settings; missing or unreadable settings fall back to defaults only
+pub fn load_search_ignore_settings(db: &AgentDb) -> SearchIgnoreSettings {
+    let value = match db.lock() {
+        Ok(conn) => conn
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = ?1",
+                params![SEARCH_IGNORE_SETTING_KEY],
+                |row| row.get::<_, String>(0),
+            )
+            .ok(),
+        Err(e) => {
+            log::warn!("Failed to read search ignore settings: {}", e);
+            None
+        }
+    };
+
+    value
+        .and_then(|v| {
+            serde_json::from_str(&v)
+                .inspect_err(|e| log::warn!("Invalid search ignore settings: {}", e))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Gets the ignore patterns used by CLAUDE.md discovery and file search
+#[tauri::command]
+pub async fn get_search_ignore_patterns(
+    db: State<'_, AgentDb>,
+) -> Result<SearchIgnoreInfo, String> {
+    let settings = load_search_ignore_settings(&db);
+    Ok(SearchIgnoreInfo {
+        default_patterns: DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+        patterns: settings.patterns,
+        use_gitignore: settings.use_gitignore,
+    })
+}
+
+/// Saves extra ignore patterns, which are merged with the defaults
+#[tauri::command]
+pub async fn set_search_ignore_patterns(
+    db: State<'_, AgentDb>,
+    patterns: Vec<String>,
+    use_gitignore: bool,
+) -> Result<(), String> {
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    for pattern in &patterns {
+        Pattern::new(pattern.trim_matches('/'))
+            .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+    }
+
+    let value = serde_json::to_string(&SearchIgnoreSettings {
+        patterns,
+        use_gitignore,
+    })
+    .map_err(|e| e.to_string())?;
+
+    let conn = db.lock()?;
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![SEARCH_IGNORE_SETTING_KEY, value],
+    )
+    .map_err(|e| format!("Failed to save search ignore patterns: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_matcher_rules() {
+        let settings = SearchIgnoreSettings {
+            patterns: vec![
+                "*.egg-info/".into(),
+                "/docs/generated".into(),
+                "*.log".into(),
+            ],
+            use_gitignore: false,
+        };
+        let matcher = IgnoreMatcher::new(&settings, Path::new("/nonexistent"));
+
+        assert!(matcher.is_ignored(Path::new("web/node_modules"), true));
+        assert!(!matcher.is_ignored(Path::new("build"), false));
+        assert!(matcher.is_ignored(Path::new("pkg.egg-info"), true));
+        assert!(matcher.is_ignored(Path::new("docs/generated"), true));
+        assert!(!matcher.is_ignored(Path::new("src/docs/generated"), true));
+        assert!(matcher.is_ignored(Path::new("logs/app.log"), false));
+        assert!(!matcher.is_ignored(Path::new("src/main.rs"), false));
+    }
+}
@@ -0,0 +1,488 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use super::claude::is_command_wrapper_message;
+use super::session_export::session_file;
+use super::usage::estimate_cost;
+use crate::utils::jsonl::JsonlReader;
+
+/// Largest LCS table built for a response diff or turn alignment; beyond it, responses are
+/// shown as replaced and turns are paired by position
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// One user prompt and everything the assistant did in reply
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionTurnSummary {
+    pub prompt: String,
+    /// Assistant text of the turn, tool calls excluded
+    pub response: String,
+    /// Tools called in this turn, in call order
+    pub tools: Vec<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl SessionTurnSummary {
+    fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Equal,
+    /// Only in session A
+    Removed,
+    /// Only in session B
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// A row of the side-by-side view; one side is missing when a session has an extra turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnComparison {
+    pub index_a: Option<usize>,
+    pub index_b: Option<usize>,
+    pub turn_a: Option<SessionTurnSummary>,
+    pub turn_b: Option<SessionTurnSummary>,
+    pub same_prompt: bool,
+    pub responded_differently: bool,
+    /// Line diff of the assistant responses, A to B
+    pub response_diff: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionTotals {
+    pub turns: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    /// Call count per tool
+    pub tools: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionComparison {
+    pub session_a: String,
+    pub session_b: String,
+    pub turns: Vec<TurnComparison>,
+    pub totals_a: SessionTotals,
+    pub totals_b: SessionTotals,
+    /// Session B minus session A
+    pub token_delta: i64,
+    pub cost_delta_usd: f64,
+    pub tools_only_in_a: Vec<String>,
+    pub tools_only_in_b: Vec<String>,
+    /// Rows where the responses differ or only one session has the turn
+    pub differing_turns: usize,
+}
+
+/// Text of the `text` blocks of a message content (a string or a list of blocks)
+fn content_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Splits a session into turns, one per user prompt. Tool results and command wrapper
+/// messages don't start a turn; usage is counted once per assistant message id.
+fn parse_session_turns(path: &Path) -> Result<Vec<SessionTurnSummary>, String> {
+    let mut reader =
+        JsonlReader::open(path).map_err(|e| format!("Failed to open session file: {}", e))?;
+    let mut turns: Vec<SessionTurnSummary> = Vec::new();
+    let mut counted_messages = HashSet::new();
+
+    while let Some((_, entry)) = reader
+        .next_value()
+        .map_err(|e| format!("Failed to read session file: {}", e))?
+    {
+        let Some(message) = entry.get("message") else {
+            continue;
+        };
+        match message.get("role").and_then(|r| r.as_str()) {
+            Some("user") => {
+                let prompt = content_text(message.get("content"));
+                if prompt.trim().is_empty() || is_command_wrapper_message(&prompt) {
+                    continue;
+                }
+                turns.push(SessionTurnSummary {
+                    prompt,
+                    ..Default::default()
+                });
+            }
+            Some("assistant") => {
+                if turns.is_empty() {
+                    turns.push(SessionTurnSummary::default());
+                }
+                let Some(turn) = turns.last_mut() else {
+                    continue;
+                };
+
+                let text = content_text(message.get("content"));
+                if !text.trim().is_empty() {
+                    if !turn.response.is_empty() {
+                        turn.response.push('\n');
+                    }
+                    turn.response.push_str(&text);
+                }
+                if let Some(Value::Array(blocks)) = message.get("content") {
+                    turn.tools.extend(
+                        blocks
+                            .iter()
+                            .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                            .map(|b| {
+                                b.get("name")
+                                    .and_then(|n| n.as_str())
+                                    .unwrap_or("tool")
+                                    .to_string()
+                            }),
+                    );
+                }
+
+                // Each content block is written as its own line repeating the message usage
+                let id = message.get("id").and_then(|i| i.as_str());
+                if id.is_some_and(|id| !counted_messages.insert(id.to_string())) {
+                    continue;
+                }
+                if let Some(usage) = message.get("usage") {
+                    let tokens = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                    let (input, output, cache_creation, cache_read) = (
+                        tokens("input_tokens"),
+                        tokens("output_tokens"),
+                        tokens("cache_creation_input_tokens"),
+                        tokens("cache_read_input_tokens"),
+                    );
+                    turn.input_tokens += input;
+                    turn.output_tokens += output;
+                    turn.cache_creation_tokens += cache_creation;
+                    turn.cache_read_tokens += cache_read;
+                    if let Some(model) = message.get("model").and_then(|m| m.as_str()) {
+                        turn.cost_usd +=
+                            estimate_cost(model, input, output, cache_creation, cache_read);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(turns)
+}
+
+/// The user prompts of a session in order, as they would be typed again
+pub(super) fn session_prompts(path: &Path) -> Result<Vec<String>, String> {
+    Ok(parse_session_turns(path)?
+        .into_iter()
+        .map(|turn| turn.prompt)
+        .filter(|prompt| !prompt.trim().is_empty())
+        .collect())
+}
+
+/// Longest common subsequence of `a` and `b` as matched index pairs, in order
+fn lcs_pairs<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i * width + j] = if a[i] == b[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Pairs turns with identical prompts, then pairs the turns between those anchors by
+/// position; turns left over on either side are paired with nothing. Sessions too long for
+/// the LCS table are paired by position only
+fn align_turns(
+    a: &[SessionTurnSummary],
+    b: &[SessionTurnSummary],
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let prompts_a: Vec<&str> = a.iter().map(|t| t.prompt.trim()).collect();
+    let prompts_b: Vec<&str> = b.iter().map(|t| t.prompt.trim()).collect();
+
+    let mut rows = Vec::new();
+    let (mut next_a, mut next_b) = (0, 0);
+    let anchors = if (a.len() + 1) * (b.len() + 1) > MAX_DIFF_CELLS {
+        Vec::new()
+    } else {
+        lcs_pairs(&prompts_a, &prompts_b)
+    };
+    for (anchor_a, anchor_b) in anchors
+        .into_iter()
+        .chain(std::iter::once((a.len(), b.len())))
+    {
+        let gap = (anchor_a - next_a).max(anchor_b - next_b);
+        for offset in 0..gap {
+            let i = next_a + offset;
+            let j = next_b + offset;
+            rows.push(((i < anchor_a).then_some(i), (j < anchor_b).then_some(j)));
+        }
+        if anchor_a < a.len() {
+            rows.push((Some(anchor_a), Some(anchor_b)));
+        }
+        next_a = anchor_a + 1;
+        next_b = anchor_b + 1;
+    }
+    rows
+}
+
+/// Line diff from `a` to `b`
+fn diff_lines(a: &str, b: &str) -> Vec<DiffLine> {
+    let lines_a: Vec<&str> = a.lines().collect();
+    let lines_b: Vec<&str> = b.lines().collect();
+    let line = |kind, text: &str| DiffLine {
+        kind,
+        text: text.to_string(),
+    };
+
+    if (lines_a.len() + 1) * (lines_b.len() + 1) > MAX_DIFF_CELLS {
+        return lines_a
+            .iter()
+            .map(|l| line(DiffLineKind::Removed, l))
+            .chain(lines_b.iter().map(|l| line(DiffLineKind::Added, l)))
+            .collect();
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for (match_a, match_b) in lcs_pairs(&lines_a, &lines_b) {
+        diff.extend(
+            lines_a[i..match_a]
+                .iter()
+                .map(|l| line(DiffLineKind::Removed, l)),
+        );
+        diff.extend(
+            lines_b[j..match_b]
+                .iter()
+                .map(|l| line(DiffLineKind::Added, l)),
+        );
+        diff.push(line(DiffLineKind::Equal, lines_a[match_a]));
+        i = match_a + 1;
+        j = match_b + 1;
+    }
+    diff.extend(lines_a[i..].iter().map(|l| line(DiffLineKind::Removed, l)));
+    diff.extend(lines_b[j..].iter().map(|l| line(DiffLineKind::Added, l)));
+    diff
+}
+
+fn session_totals(turns: &[SessionTurnSummary]) -> SessionTotals {
+    let mut totals = SessionTotals {
+        turns: turns.len(),
+        ..Default::default()
+    };
+    for turn in turns {
+        totals.input_tokens += turn.input_tokens;
+        totals.output_tokens += turn.output_tokens;
+        totals.cache_creation_tokens += turn.cache_creation_tokens;
+        totals.cache_read_tokens += turn.cache_read_tokens;
+        totals.total_tokens += turn.total_tokens();
+        totals.cost_usd += turn.cost_usd;
+        for tool in &turn.tools {
+            *totals.tools.entry(tool.clone()).or_default() += 1;
+        }
+    }
+    totals
+}
+
+fn compare_turns(
+    session_a: String,
+    session_b: String,
+    turns_a: Vec<SessionTurnSummary>,
+    turns_b: Vec<SessionTurnSummary>,
+) -> SessionComparison {
+    let totals_a = session_totals(&turns_a);
+    let totals_b = session_totals(&turns_b);
+
+    let turns: Vec<TurnComparison> = align_turns(&turns_a, &turns_b)
+        .into_iter()
+        .map(|(index_a, index_b)| {
+            let turn_a = index_a.map(|i| turns_a[i].clone());
+            let turn_b = index_b.map(|i| turns_b[i].clone());
+            fn response(turn: &Option<SessionTurnSummary>) -> &str {
+                turn.as_ref().map_or("", |t| t.response.as_str())
+            }
+            let response_diff = diff_lines(response(&turn_a), response(&turn_b));
+            let same_prompt = match (&turn_a, &turn_b) {
+                (Some(a), Some(b)) => a.prompt.trim() == b.prompt.trim(),
+                _ => false,
+            };
+            TurnComparison {
+                same_prompt,
+                responded_differently: turn_a.is_none()
+                    || turn_b.is_none()
+                    || response_diff.iter().any(|l| l.kind != DiffLineKind::Equal),
+                index_a,
+                index_b,
+                turn_a,
+                turn_b,
+                response_diff,
+            }
+        })
+        .collect();
+
+    let only_in = |x: &SessionTotals, y: &SessionTotals| {
+        x.tools
+            .keys()
+            .filter(|tool| !y.tools.contains_key(*tool))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    SessionComparison {
+        session_a,
+        session_b,
+        differing_turns: turns.iter().filter(|t| t.responded_differently).count(),
+        turns,
+        token_delta: totals_b.total_tokens as i64 - totals_a.total_tokens as i64,
+        cost_delta_usd: totals_b.cost_usd - totals_a.cost_usd,
+        tools_only_in_a: only_in(&totals_a, &totals_b),
+        tools_only_in_b: only_in(&totals_b, &totals_a),
+        totals_a,
+        totals_b,
+    }
+}
+
+/// Compares two sessions of a project turn by turn: turns are aligned on their prompts and
+/// the assistant responses are diffed line by line
+#[tauri::command]
+pub async fn compare_sessions(
+    session_a: String,
+    session_b: String,
+    project_id: String,
+) -> Result<SessionComparison, String> {
+    let path_a = session_file(&session_a, &project_id)?;
+    let path_b = session_file(&session_b, &project_id)?;
+
+    tokio::task::spawn_blocking(move || -> Result<SessionComparison, String> {
+        let turns_a = parse_session_turns(&path_a)?;
+        let turns_b = parse_session_turns(&path_b)?;
+        Ok(compare_turns(session_a, session_b, turns_a, turns_b))
+    })
+    .await
+    .map_err(|e| format!("Failed to compare sessions: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(prompt: &str, response: &str) -> SessionTurnSummary {
+        SessionTurnSummary {
+            prompt: prompt.to_string(),
+            response: response.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_align_turns_anchors_on_prompts() {
+        let a = vec![turn("one", ""), turn("two", ""), turn("three", "")];
+        let b = vec![
+            turn("one", ""),
+            turn("2", ""),
+            turn("extra", ""),
+            turn("three", ""),
+        ];
+        assert_eq!(
+            align_turns(&a, &b),
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(1)),
+                (None, Some(2)),
+                (Some(2), Some(3)),
+            ]
+        );
+        assert_eq!(align_turns(&a, &[]).len(), 3);
+    }
+
+    #[test]
+    fn test_align_turns_pairs_long_sessions_by_position() {
+        let a: Vec<_> = (0..1500).map(|i| turn(&i.to_string(), "")).collect();
+        let b: Vec<_> = (1..1000).map(|i| turn(&i.to_string(), "")).collect();
+        let rows = align_turns(&a, &b);
+        assert_eq!(rows.len(), 1500);
+        assert_eq!(rows[0], (Some(0), Some(0)));
+        assert_eq!(rows[1200], (Some(1200), None));
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let kinds: Vec<DiffLineKind> = diff_lines("a\nb\nc", "a\nx\nc\nd")
+            .into_iter()
+            .map(|l| l.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffLineKind::Equal,
+                DiffLineKind::Removed,
+                DiffLineKind::Added,
+                DiffLineKind::Equal,
+                DiffLineKind::Added,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_session_turns_groups_replies() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s.jsonl");
+        let lines = [
+            r#"{"message":{"role":"user","content":"Fix the bug"}}"#,
+            r#"{"message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Looking"}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+            r#"{"message":{"id":"m1","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","name":"Read","input":{}}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+            r#"{"message":{"role":"user","content":[{"type":"tool_result","content":"file"}]}}"#,
+            r#"{"message":{"id":"m2","role":"assistant","content":[{"type":"text","text":"Done"}],"usage":{"output_tokens":3}}}"#,
+            r#"{"message":{"role":"user","content":"Thanks"}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let turns = parse_session_turns(&path).unwrap();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].response, "Looking\nDone");
+        assert_eq!(turns[0].tools, vec!["Read"]);
+        assert_eq!(turns[0].input_tokens, 10);
+        assert_eq!(turns[0].output_tokens, 8);
+        assert!(turns[0].cost_usd > 0.0);
+        assert_eq!(turns[1].prompt, "Thanks");
+    }
+}
@@ -0,0 +1,612 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::path::PathBuf;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+use super::claude::{get_claude_dir, is_command_wrapper_message};
+use crate::utils::jsonl::JsonlReader;
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// Token budget of `build_context_from_session` when none is given
+const DEFAULT_CONTEXT_TOKENS: usize = 8000;
+
+/// A content block of a transcript turn
+enum TranscriptBlock {
+    Text(String),
+    ToolUse { name: String, input: Value },
+    ToolResult { content: String, is_error: bool },
+}
+
+/// A single user or assistant message of a session
+struct TranscriptTurn {
+    role: String,
+    timestamp: Option<String>,
+    blocks: Vec<TranscriptBlock>,
+}
+
+/// Resolves the JSONL file of a session
+pub(super) fn session_file(session_id: &str, project_id: &str) -> Result<PathBuf, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let session_path = claude_dir
+        .join("projects")
+        .join(project_id)
+        .join(format!("{}.jsonl", session_id));
+
+    if !session_path.exists() {
+        return Err(format!("Session file not found: {}", session_id));
+    }
+    Ok(session_path)
+}
+
+/// Flattens tool result content (a string or a list of text blocks) into plain text
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Parses a session JSONL into user/assistant turns, skipping command wrapper messages
+fn parse_transcript(
+    session_id: &str,
+    project_id: &str,
+    include_tool_output: bool,
+) -> Result<Vec<TranscriptTurn>, String> {
+    let path = session_file(session_id, project_id)?;
+    let mut reader =
+        JsonlReader::open(&path).map_err(|e| format!("Failed to open session file: {}", e))?;
+
+    let mut turns = Vec::new();
+    while let Some((_, entry)) = reader
+        .next_value()
+        .map_err(|e| format!("Failed to read session file: {}", e))?
+    {
+        let Some(message) = entry.get("message") else {
+            continue;
+        };
+        let role = match message.get("role").and_then(|r| r.as_str()) {
+            Some(role @ ("user" | "assistant")) => role.to_string(),
+            _ => continue,
+        };
+
+        let mut blocks = Vec::new();
+        match message.get("content") {
+            Some(Value::String(text)) => {
+                if role == "user" && is_command_wrapper_message(text) {
+                    continue;
+                }
+                blocks.push(TranscriptBlock::Text(text.clone()));
+            }
+            Some(Value::Array(items)) => {
+                for item in items {
+                    match item.get("type").and_then(|t| t.as_str()) {
+                        Some("text") => {
+                            let text = item.get("text").and_then(|t| t.as_str()).unwrap_or("");
+                            if role == "user" && is_command_wrapper_message(text) {
+                                continue;
+                            }
+                            blocks.push(TranscriptBlock::Text(text.to_string()));
+                        }
+                        Some("tool_use") => blocks.push(TranscriptBlock::ToolUse {
+                            name: item
+                                .get("name")
+                                .and_then(|n| n.as_str())
+                                .unwrap_or("tool")
+                                .to_string(),
+                            input: item.get("input").cloned().unwrap_or(Value::Null),
+                        }),
+                        Some("tool_result") if include_tool_output => {
+                            blocks.push(TranscriptBlock::ToolResult {
+                                content: tool_result_text(item.get("content")),
+                                is_error: item
+                                    .get("is_error")
+                                    .and_then(|e| e.as_bool())
+                                    .unwrap_or(false),
+                            })
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => continue,
+        }
+
+        blocks.retain(
+            |block| !matches!(block, TranscriptBlock::Text(text) if text.trim().is_empty()),
+        );
+        if blocks.is_empty() {
+            continue;
+        }
+
+        turns.push(TranscriptTurn {
+            role,
+            timestamp: entry
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .map(|t| t.to_string()),
+            blocks,
+        });
+    }
+
+    Ok(turns)
+}
+
+/// Whether a turn only carries tool results (Claude Code stores them as user messages)
+fn is_tool_result_turn(turn: &TranscriptTurn) -> bool {
+    turn.blocks
+        .iter()
+        .all(|block| matches!(block, TranscriptBlock::ToolResult { .. }))
+}
+
+/// Returns a backtick fence longer than any backtick run inside `content`
+fn code_fence(content: &str) -> String {
+    let longest_run = content
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    "`".repeat((longest_run + 1).max(3))
+}
+
+fn pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Renders the transcript as Markdown, with tool calls in collapsible sections
+fn render_markdown(session_id: &str, turns: &[TranscriptTurn]) -> String {
+    let mut out = format!("# Session {}\n\n", session_id);
+
+    for turn in turns {
+        if !is_tool_result_turn(turn) {
+            let heading = if turn.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            match &turn.timestamp {
+                Some(ts) => out.push_str(&format!("---\n\n### {} · {}\n\n", heading, ts)),
+                None => out.push_str(&format!("---\n\n### {}\n\n", heading)),
+            }
+        }
+
+        for block in &turn.blocks {
+            match block {
+                TranscriptBlock::Text(text) => {
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n");
+                }
+                TranscriptBlock::ToolUse { name, input } => {
+                    let input = pretty_json(input);
+                    let fence = code_fence(&input);
+                    out.push_str(&format!(
+                        "<details>\n<summary>Tool: {}</summary>\n\n{}json\n{}\n{}\n\n</details>\n\n",
+                        name, fence, input, fence
+                    ));
+                }
+                TranscriptBlock::ToolResult { content, is_error } => {
+                    let fence = code_fence(content);
+                    let summary = if *is_error {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    out.push_str(&format!(
+                        "<details>\n<summary>{}</summary>\n\n{}\n{}\n{}\n\n</details>\n\n",
+                        summary,
+                        fence,
+                        content.trim_end(),
+                        fence
+                    ));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// A piece of message text: either prose or a fenced code block
+#[derive(Debug, PartialEq)]
+enum TextSegment {
+    Prose(String),
+    Code { lang: String, code: String },
+}
+
+/// Splits message text into prose and fenced code blocks
+fn split_fenced_blocks(text: &str) -> Vec<TextSegment> {
+    let mut segments = Vec::new();
+    let mut prose = String::new();
+    let mut code = String::new();
+    let mut open_fence: Option<(String, String)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        match &open_fence {
+            Some((fence, lang)) => {
+                if trimmed.trim_end() == fence.as_str() {
+                    segments.push(TextSegment::Code {
+                        lang: lang.clone(),
+                        code: std::mem::take(&mut code),
+                    });
+                    open_fence = None;
+                } else {
+                    code.push_str(line);
+                    code.push('\n');
+                }
+            }
+            None => {
+                let fence_len = trimmed.chars().take_while(|c| *c == '`').count();
+                if fence_len >= 3 {
+                    if !prose.trim().is_empty() {
+                        segments.push(TextSegment::Prose(std::mem::take(&mut prose)));
+                    }
+                    prose.clear();
+                    let lang = trimmed[fence_len..].trim().to_string();
+                    open_fence = Some(("`".repeat(fence_len), lang));
+                } else {
+                    prose.push_str(line);
+                    prose.push('\n');
+                }
+            }
+        }
+    }
+
+    // An unterminated fence still renders as code
+    if let Some((_, lang)) = open_fence {
+        segments.push(TextSegment::Code { lang, code });
+    }
+    if !prose.trim().is_empty() {
+        segments.push(TextSegment::Prose(prose));
+    }
+    segments
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Highlights a code block, falling back to plain escaped text for unknown languages
+fn highlight_code(code: &str, lang: &str, theme: &Theme) -> String {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+
+    match highlighted_html_for_string(code, &SYNTAX_SET, syntax, theme) {
+        Ok(html) => html,
+        Err(e) => {
+            log::warn!("Failed to highlight {} code block: {}", lang, e);
+            format!("<pre><code>{}</code></pre>", escape_html(code))
+        }
+    }
+}
+
+fn render_text_html(text: &str, theme: &Theme) -> String {
+    split_fenced_blocks(text)
+        .into_iter()
+        .map(|segment| match segment {
+            TextSegment::Prose(prose) => {
+                format!("<div class=\"prose\">{}</div>\n", escape_html(prose.trim()))
+            }
+            TextSegment::Code { lang, code } => highlight_code(&code, &lang, theme),
+        })
+        .collect()
+}
+
+/// Maps the requested theme to a bundled syntect theme and the page colour scheme
+fn resolve_theme(theme: Option<&str>) -> (&'static Theme, bool) {
+    let name = match theme.unwrap_or("light") {
+        "dark" => "base16-ocean.dark",
+        "light" => "InspiredGitHub",
+        other => other,
+    };
+
+    match THEME_SET.themes.get(name) {
+        Some(theme) => (theme, name.contains("dark")),
+        None => {
+            log::warn!("Unknown export theme {}, falling back to light", name);
+            (&THEME_SET.themes["InspiredGitHub"], false)
+        }
+    }
+}
+
+const HTML_STYLE: &str = r#"
+:root { --bg: #ffffff; --fg: #1f2328; --muted: #656d76; --border: #d0d7de; --user: #eef6ff; --assistant: #f6f8fa; --tool: #fff8e6; --error: #ffebe9; }
+body.dark { --bg: #0d1117; --fg: #e6edf3; --muted: #8d96a0; --border: #30363d; --user: #102035; --assistant: #161b22; --tool: #2a2110; --error: #3a1618; }
+body { margin: 0; background: var(--bg); color: var(--fg); font: 15px/1.6 -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; }
+main { max-width: 920px; margin: 0 auto; padding: 32px 20px; }
+h1 { font-size: 22px; word-break: break-all; }
+.turn { border: 1px solid var(--border); border-radius: 8px; padding: 12px 16px; margin: 16px 0; }
+.turn.user { background: var(--user); }
+.turn.assistant { background: var(--assistant); }
+.turn-header { display: flex; justify-content: space-between; font-weight: 600; margin-bottom: 8px; }
+.turn-header time { font-weight: 400; color: var(--muted); font-size: 13px; }
+.prose { white-space: pre-wrap; word-wrap: break-word; margin: 8px 0; }
+pre { padding: 12px; border-radius: 6px; overflow-x: auto; font: 13px/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+details { border: 1px solid var(--border); border-left: 4px solid #d4a72c; border-radius: 6px; background: var(--tool); margin: 8px 0; padding: 4px 12px; }
+details.tool-result { border-left-color: #8c959f; }
+details.tool-error { border-left-color: #cf222e; background: var(--error); }
+summary { cursor: pointer; font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 13px; padding: 4px 0; }
+"#;
+
+/// Renders the transcript as a single self-contained HTML document
+fn render_html(session_id: &str, turns: &[TranscriptTurn], theme: Option<&str>) -> String {
+    let (syntax_theme, dark) = resolve_theme(theme);
+    let title = escape_html(&format!("Session {}", session_id));
+
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body class=\"{}\">\n<main>\n<h1>{}</h1>\n",
+        title,
+        HTML_STYLE,
+        if dark { "dark" } else { "light" },
+        title
+    );
+
+    let mut turn_open = false;
+    for turn in turns {
+        // Tool results belong visually to the preceding assistant turn
+        if !is_tool_result_turn(turn) || !turn_open {
+            if turn_open {
+                out.push_str("</section>\n");
+            }
+            let heading = if turn.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            out.push_str(&format!(
+                "<section class=\"turn {}\">\n<div class=\"turn-header\"><span>{}</span><time>{}</time></div>\n",
+                turn.role,
+                heading,
+                escape_html(turn.timestamp.as_deref().unwrap_or(""))
+            ));
+            turn_open = true;
+        }
+
+        for block in &turn.blocks {
+            match block {
+                TranscriptBlock::Text(text) => out.push_str(&render_text_html(text, syntax_theme)),
+                TranscriptBlock::ToolUse { name, input } => out.push_str(&format!(
+                    "<details class=\"tool-use\">\n<summary>Tool: {}</summary>\n{}</details>\n",
+                    escape_html(name),
+                    highlight_code(&pretty_json(input), "json", syntax_theme)
+                )),
+                TranscriptBlock::ToolResult { content, is_error } => {
+                    let (class, summary) = if *is_error {
+                        ("tool-error", "Tool error")
+                    } else {
+                        ("tool-result", "Tool result")
+                    };
+                    out.push_str(&format!(
+                        "<details class=\"{}\">\n<summary>{}</summary>\n<pre><code>{}</code></pre>\n</details>\n",
+                        class,
+                        summary,
+                        escape_html(content.trim_end())
+                    ));
+                }
+            }
+        }
+    }
+
+    if turn_open {
+        out.push_str("</section>\n");
+    }
+    out.push_str("</main>\n</body>\n</html>\n");
+    out
+}
+
+/// Exports a session as a Markdown transcript.
+/// Tool results are included unless `include_tool_output` is false.
+#[tauri::command]
+pub async fn export_session_markdown(
+    session_id: String,
+    project_id: String,
+    include_tool_output: Option<bool>,
+) -> Result<String, String> {
+    log::info!(
+        "Exporting session {} in project {} as Markdown",
+        session_id,
+        project_id
+    );
+
+    let turns = parse_transcript(
+        &session_id,
+        &project_id,
+        include_tool_output.unwrap_or(true),
+    )?;
+    Ok(render_markdown(&session_id, &turns))
+}
+
+/// Exports a session as a self-contained HTML file with syntax-highlighted code.
+/// `theme` is "light", "dark" or the name of a bundled syntect theme.
+#[tauri::command]
+pub async fn export_session_html(
+    session_id: String,
+    project_id: String,
+    theme: Option<String>,
+    include_tool_output: Option<bool>,
+) -> Result<String, String> {
+    log::info!(
+        "Exporting session {} in project {} as HTML",
+        session_id,
+        project_id
+    );
+
+    let turns = parse_transcript(
+        &session_id,
+        &project_id,
+        include_tool_output.unwrap_or(true),
+    )?;
+    Ok(render_html(&session_id, &turns, theme.as_deref()))
+}
+
+/// Rough token count, assuming about four characters per token
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Formats the newest user/assistant text that fits in `max_tokens` as a block to paste into
+/// a new session. Tool calls are left out and the oldest turns are dropped first.
+fn render_context(session_id: &str, turns: &[TranscriptTurn], max_tokens: usize) -> String {
+    let header = format!(
+        "Here is the conversation from a previous session ({}) for context:\n\n<previous_session>\n",
+        session_id
+    );
+    let footer = "</previous_session>\n";
+    let mut remaining =
+        max_tokens.saturating_sub(estimate_tokens(&header) + estimate_tokens(footer));
+
+    let messages: Vec<String> = turns
+        .iter()
+        .filter_map(|turn| {
+            let text = turn
+                .blocks
+                .iter()
+                .filter_map(|block| match block {
+                    TranscriptBlock::Text(text) => Some(text.trim()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            if text.is_empty() {
+                return None;
+            }
+            let speaker = if turn.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            Some(format!("{}: {}\n\n", speaker, text))
+        })
+        .collect();
+
+    let mut kept: Vec<String> = Vec::new();
+    for message in messages.iter().rev() {
+        let tokens = estimate_tokens(message);
+        if tokens <= remaining {
+            remaining -= tokens;
+            kept.push(message.clone());
+            continue;
+        }
+        // Keep the end of the newest message when even it alone is over budget
+        if kept.is_empty() && remaining > 0 {
+            let chars: Vec<char> = message.chars().collect();
+            let start = chars.len().saturating_sub(remaining * 4);
+            let tail: String = chars[start..].iter().collect();
+            kept.push(format!("…{}", tail));
+        }
+        break;
+    }
+
+    let mut out = header;
+    let omitted = messages.len() - kept.len();
+    if omitted > 0 {
+        out.push_str(&format!("({} earlier messages omitted)\n\n", omitted));
+    }
+    for message in kept.iter().rev() {
+        out.push_str(message);
+    }
+    out.push_str(footer);
+    out
+}
+
+/// Builds a context block from a session's user and assistant messages, trimmed to roughly
+/// `max_tokens` tokens, for seeding a new session without forking the JSONL
+#[tauri::command]
+pub async fn build_context_from_session(
+    session_id: String,
+    project_id: String,
+    max_tokens: Option<usize>,
+) -> Result<String, String> {
+    let turns = parse_transcript(&session_id, &project_id, false)?;
+    Ok(render_context(
+        &session_id,
+        &turns,
+        max_tokens.unwrap_or(DEFAULT_CONTEXT_TOKENS),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_fence_outgrows_embedded_backticks() {
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("has ```rust\nfn main() {}\n```"), "````");
+    }
+
+    fn turn(role: &str, blocks: Vec<TranscriptBlock>) -> TranscriptTurn {
+        TranscriptTurn {
+            role: role.to_string(),
+            timestamp: None,
+            blocks,
+        }
+    }
+
+    #[test]
+    fn render_context_drops_oldest_turns_and_tool_calls() {
+        let turns = vec![
+            turn("user", vec![TranscriptBlock::Text("a".repeat(400))]),
+            turn(
+                "assistant",
+                vec![
+                    TranscriptBlock::Text("Reading the file".to_string()),
+                    TranscriptBlock::ToolUse {
+                        name: "Grep".to_string(),
+                        input: Value::Null,
+                    },
+                ],
+            ),
+            turn(
+                "user",
+                vec![TranscriptBlock::Text("Now fix it".to_string())],
+            ),
+        ];
+
+        let full = render_context("s1", &turns, 1000);
+        assert!(full.contains("User: aaaa"));
+        assert!(full.contains("Assistant: Reading the file\n\nUser: Now fix it"));
+        assert!(!full.contains("Grep"));
+
+        let trimmed = render_context("s1", &turns, 60);
+        assert!(trimmed.contains("(1 earlier messages omitted)"));
+        assert!(!trimmed.contains("aaaa"));
+        assert!(trimmed.ends_with("User: Now fix it\n\n</previous_session>\n"));
+    }
+
+    #[test]
+    fn split_fenced_blocks_separates_code() {
+        let segments = split_fenced_blocks("Intro\n```rust\nfn main() {}\n```\nOutro");
+        assert_eq!(
+            segments,
+            vec![
+                TextSegment::Prose("Intro\n".to_string()),
+                TextSegment::Code {
+                    lang: "rust".to_string(),
+                    code: "fn main() {}\n".to_string()
+                },
+                TextSegment::Prose("Outro\n".to_string()),
+            ]
+        );
+    }
+}
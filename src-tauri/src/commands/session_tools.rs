@@ -0,0 +1,698 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::claude::get_claude_dir;
+use super::session_export::session_file;
+use crate::utils::jsonl::JsonlReader;
+
+/// A file the tool calls of a session referred to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTouchedFile {
+    pub path: String,
+    /// Calls per operation: "read", "write", "edit" or "bash"
+    pub operations: BTreeMap<String, u32>,
+    /// Calls of all operations
+    pub count: u32,
+}
+
+/// Calls and results of one tool
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsage {
+    pub name: String,
+    /// Server of an MCP tool (`mcp__<server>__<tool>`)
+    pub mcp_server: Option<String>,
+    pub calls: u64,
+    /// Results matched to a call of this tool
+    pub results: u64,
+    pub error_results: u64,
+    pub total_result_bytes: u64,
+    pub avg_result_bytes: f64,
+}
+
+/// Calls of the tools of one MCP server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpServerUsage {
+    pub server: String,
+    pub calls: u64,
+    pub tools: usize,
+}
+
+/// Tool calls of one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionToolUsage {
+    pub session_id: String,
+    pub total_calls: u64,
+    /// Calls per tool name
+    pub tools: BTreeMap<String, u64>,
+}
+
+/// Tool usage of a session, or of every session of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolUsageStats {
+    pub project_id: String,
+    /// Set when the stats cover a single session
+    pub session_id: Option<String>,
+    pub sessions_scanned: usize,
+    pub total_calls: u64,
+    /// Sorted by call count, most used first
+    pub tools: Vec<ToolUsage>,
+    pub mcp_servers: Vec<McpServerUsage>,
+    pub by_session: Vec<SessionToolUsage>,
+}
+
+/// Content blocks of an entry's message. Older transcripts lack `message.role`, so the
+/// entry `type` is used as well.
+fn message_blocks<'a>(entry: &'a Value, role: &str) -> impl Iterator<Item = &'a Value> {
+    let message = entry.get("message").filter(|m| {
+        m.get("role")
+            .or_else(|| entry.get("type"))
+            .and_then(|r| r.as_str())
+            == Some(role)
+    });
+    message
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+}
+
+/// `tool_use` blocks of an assistant message entry
+fn tool_use_blocks(entry: &Value) -> impl Iterator<Item = &Value> {
+    message_blocks(entry, "assistant")
+        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+}
+
+/// `tool_result` blocks of a user message entry
+fn tool_result_blocks(entry: &Value) -> impl Iterator<Item = &Value> {
+    message_blocks(entry, "user")
+        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result"))
+}
+
+/// Server name of an MCP tool name such as `mcp__github__create_issue`
+fn mcp_server(tool: &str) -> Option<&str> {
+    let rest = tool.strip_prefix("mcp__")?;
+    rest.split_once("__")
+        .map(|(server, _)| server)
+        .filter(|server| !server.is_empty())
+}
+
+/// Size of a tool result: the text of a string or of `text` blocks, and the raw size of
+/// anything else (images, structured output)
+fn tool_result_bytes(content: Option<&Value>) -> u64 {
+    match content {
+        None | Some(Value::Null) => 0,
+        Some(Value::String(text)) => text.len() as u64,
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .map(|block| match block.get("text").and_then(|t| t.as_str()) {
+                Some(text) => text.len() as u64,
+                None => block.to_string().len() as u64,
+            })
+            .sum(),
+        Some(other) => other.to_string().len() as u64,
+    }
+}
+
+/// Tool calls of one session file, with result sizes matched to calls by tool use id
+#[derive(Debug, Default)]
+struct SessionToolScan {
+    tools: HashMap<String, ToolUsage>,
+}
+
+impl SessionToolScan {
+    fn scan(path: &Path) -> Result<Self, String> {
+        let mut reader =
+            JsonlReader::open(path).map_err(|e| format!("Failed to open session file: {}", e))?;
+        let mut scan = Self::default();
+        let mut call_tools: HashMap<String, String> = HashMap::new();
+        let mut seen_results = HashSet::new();
+
+        while let Some((_, entry)) = reader
+            .next_value()
+            .map_err(|e| format!("Failed to read session file: {}", e))?
+        {
+            for block in tool_use_blocks(&entry) {
+                let name = block
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .filter(|n| !n.is_empty())
+                    .unwrap_or("unknown");
+                if let Some(id) = block.get("id").and_then(|i| i.as_str()) {
+                    if call_tools
+                        .insert(id.to_string(), name.to_string())
+                        .is_some()
+                    {
+                        continue;
+                    }
+                }
+                scan.tool(name).calls += 1;
+            }
+
+            for block in tool_result_blocks(&entry) {
+                let Some(id) = block.get("tool_use_id").and_then(|i| i.as_str()) else {
+                    continue;
+                };
+                let Some(name) = call_tools.get(id) else {
+                    continue;
+                };
+                if !seen_results.insert(id.to_string()) {
+                    continue;
+                }
+                let is_error = block.get("is_error").and_then(|e| e.as_bool()) == Some(true);
+                let bytes = tool_result_bytes(block.get("content"));
+                if let Some(tool) = scan.tools.get_mut(name) {
+                    tool.results += 1;
+                    tool.error_results += u64::from(is_error);
+                    tool.total_result_bytes += bytes;
+                }
+            }
+        }
+
+        Ok(scan)
+    }
+
+    fn tool(&mut self, name: &str) -> &mut ToolUsage {
+        self.tools
+            .entry(name.to_string())
+            .or_insert_with(|| ToolUsage {
+                name: name.to_string(),
+                mcp_server: mcp_server(name).map(str::to_string),
+                ..Default::default()
+            })
+    }
+
+    fn total_calls(&self) -> u64 {
+        self.tools.values().map(|t| t.calls).sum()
+    }
+}
+
+/// Sums per-session scans into project-level stats
+fn aggregate_tool_usage(
+    project_id: String,
+    session_id: Option<String>,
+    scans: Vec<(String, SessionToolScan)>,
+) -> ToolUsageStats {
+    let mut tools: HashMap<String, ToolUsage> = HashMap::new();
+    let mut by_session = Vec::with_capacity(scans.len());
+
+    for (id, scan) in &scans {
+        by_session.push(SessionToolUsage {
+            session_id: id.clone(),
+            total_calls: scan.total_calls(),
+            tools: scan
+                .tools
+                .values()
+                .map(|t| (t.name.clone(), t.calls))
+                .collect(),
+        });
+        for usage in scan.tools.values() {
+            let total = tools
+                .entry(usage.name.clone())
+                .or_insert_with(|| ToolUsage {
+                    name: usage.name.clone(),
+                    mcp_server: usage.mcp_server.clone(),
+                    ..Default::default()
+                });
+            total.calls += usage.calls;
+            total.results += usage.results;
+            total.error_results += usage.error_results;
+            total.total_result_bytes += usage.total_result_bytes;
+        }
+    }
+
+    let mut tools: Vec<ToolUsage> = tools.into_values().collect();
+    for tool in &mut tools {
+        if tool.results > 0 {
+            tool.avg_result_bytes = tool.total_result_bytes as f64 / tool.results as f64;
+        }
+    }
+    tools.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+
+    let mut servers: BTreeMap<String, McpServerUsage> = BTreeMap::new();
+    for tool in &tools {
+        if let Some(server) = &tool.mcp_server {
+            let usage = servers
+                .entry(server.clone())
+                .or_insert_with(|| McpServerUsage {
+                    server: server.clone(),
+                    calls: 0,
+                    tools: 0,
+                });
+            usage.calls += tool.calls;
+            usage.tools += 1;
+        }
+    }
+
+    by_session.sort_by_key(|s| std::cmp::Reverse(s.total_calls));
+    ToolUsageStats {
+        project_id,
+        session_id,
+        sessions_scanned: scans.len(),
+        total_calls: tools.iter().map(|t| t.calls).sum(),
+        tools,
+        mcp_servers: servers.into_values().collect(),
+        by_session,
+    }
+}
+
+/// Session files of a project as (session id, path)
+fn project_session_files(project_id: &str) -> Result<Vec<(String, PathBuf)>, String> {
+    let project_dir = get_claude_dir()
+        .map_err(|e| e.to_string())?
+        .join("projects")
+        .join(project_id);
+    let entries = std::fs::read_dir(&project_dir)
+        .map_err(|e| format!("Failed to read project directory: {}", e))?;
+
+    Ok(entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_str()?.to_string();
+            Some((id, path))
+        })
+        .collect())
+}
+
+/// Operation of a file tool and the input field holding the file path
+fn file_tool_operation(tool: &str) -> Option<(&'static str, &'static str)> {
+    match tool {
+        "Read" => Some(("read", "file_path")),
+        "Write" => Some(("write", "file_path")),
+        "Edit" | "MultiEdit" => Some(("edit", "file_path")),
+        "NotebookEdit" => Some(("edit", "notebook_path")),
+        _ => None,
+    }
+}
+
+/// Resolves `path` against the session working directory; `None` for a relative path
+/// when the working directory is unknown
+fn absolute_path(path: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        cwd.map(|cwd| cwd.join(path))
+    }
+}
+
+/// A token of a shell command, as far as `bash_command_files` needs to tell them apart
+#[derive(Debug, PartialEq)]
+enum ShellToken {
+    Word(String),
+    /// `<`, `>` or `>>`; the next word is the file redirected from or to
+    Redirect,
+    /// `;`, `|`, `&`, newline or a parenthesis: the next word starts a new command
+    Break,
+}
+
+/// Splits a command into words, honoring quotes but not expansions or escapes
+fn shell_tokens(command: &str) -> Vec<ShellToken> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    fn flush(tokens: &mut Vec<ShellToken>, word: &mut String, in_word: &mut bool) {
+        if *in_word {
+            tokens.push(ShellToken::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    }
+
+    for c in command.chars() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            } else {
+                word.push(c);
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            ';' | '|' | '&' | '\n' | '(' | ')' => {
+                flush(&mut tokens, &mut word, &mut in_word);
+                tokens.push(ShellToken::Break);
+            }
+            '<' | '>' => {
+                // A file descriptor number before the redirect, as in `2>`, isn't a word
+                if in_word && word.chars().all(|c| c.is_ascii_digit()) {
+                    word.clear();
+                    in_word = false;
+                }
+                flush(&mut tokens, &mut word, &mut in_word);
+                if tokens.last() != Some(&ShellToken::Redirect) {
+                    tokens.push(ShellToken::Redirect);
+                }
+            }
+            c if c.is_whitespace() => flush(&mut tokens, &mut word, &mut in_word),
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    flush(&mut tokens, &mut word, &mut in_word);
+    tokens
+}
+
+/// How a command that takes file operands is parsed
+struct FileCommand {
+    names: &'static [&'static str],
+    /// Leading operands that aren't files, such as the mode of `chmod` or the script of `sed`
+    leading_operands: usize,
+    /// Options followed by a value
+    value_options: &'static [&'static str],
+    /// Options that supply the script themselves, so there is no leading operand
+    script_options: &'static [&'static str],
+}
+
+const FILE_COMMANDS: &[FileCommand] = &[
+    FileCommand {
+        names: &[
+            "rm", "unlink", "touch", "cat", "less", "more", "wc", "diff", "stat", "tee", "cp",
+            "mv", "ln", "sort", "uniq", "nano", "vi", "vim",
+        ],
+        leading_operands: 0,
+        value_options: &["-o", "-t", "-S", "--suffix", "--target-directory"],
+        script_options: &[],
+    },
+    FileCommand {
+        names: &["head", "tail"],
+        leading_operands: 0,
+        value_options: &["-n", "-c"],
+        script_options: &[],
+    },
+    FileCommand {
+        names: &["truncate"],
+        leading_operands: 0,
+        value_options: &["-s", "-r", "--size", "--reference"],
+        script_options: &[],
+    },
+    FileCommand {
+        names: &["chmod", "chown", "chgrp"],
+        leading_operands: 1,
+        value_options: &["--reference"],
+        script_options: &[],
+    },
+    FileCommand {
+        names: &["sed"],
+        leading_operands: 1,
+        value_options: &["-e", "-f"],
+        script_options: &["-e", "-f"],
+    },
+    FileCommand {
+        names: &["grep"],
+        leading_operands: 1,
+        value_options: &["-e", "-f", "-m", "-A", "-B", "-C"],
+        script_options: &["-e", "-f"],
+    },
+    FileCommand {
+        names: &["awk"],
+        leading_operands: 1,
+        value_options: &["-f", "-F", "-v"],
+        script_options: &["-f"],
+    },
+];
+
+/// Files named by a shell command: the file operands of well-known commands such as `rm`,
+/// `mv`, `cat` and `sed`, and the targets of redirects. Files that no longer exist are
+/// reported too, so deletions show up.
+fn bash_command_files(command: &str, cwd: Option<&Path>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let tokens = shell_tokens(command);
+
+    for segment in tokens.split(|t| *t == ShellToken::Break) {
+        let mut words = Vec::new();
+        let mut iter = segment.iter();
+        while let Some(token) = iter.next() {
+            match token {
+                ShellToken::Redirect => {
+                    if let Some(ShellToken::Word(target)) = iter.next() {
+                        if !target.starts_with("/dev/") {
+                            files.push(target.as_str());
+                        }
+                    }
+                }
+                ShellToken::Word(word) => words.push(word.as_str()),
+                ShellToken::Break => {}
+            }
+        }
+
+        // Skip variable assignments and wrappers in front of the command
+        let mut words = words.into_iter().skip_while(|word| {
+            matches!(*word, "sudo" | "env" | "time" | "nohup" | "command")
+                || word.split_once('=').is_some_and(|(name, _)| {
+                    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                })
+        });
+        let Some(program) = words.next() else {
+            continue;
+        };
+        let program = Path::new(program)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(program);
+        let Some(spec) = FILE_COMMANDS.iter().find(|c| c.names.contains(&program)) else {
+            continue;
+        };
+
+        let args: Vec<&str> = words.collect();
+        let mut leading = spec.leading_operands;
+        if args.iter().any(|arg| spec.script_options.contains(arg)) {
+            leading = 0;
+        }
+        let mut options_done = false;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if !options_done && arg == "--" {
+                options_done = true;
+            } else if !options_done && arg.starts_with('-') && arg.len() > 1 {
+                if spec.value_options.contains(&arg) {
+                    args.next();
+                }
+            } else if leading > 0 {
+                leading -= 1;
+            } else {
+                files.push(arg);
+            }
+        }
+    }
+
+    files
+        .into_iter()
+        .filter_map(|word| absolute_path(word, cwd))
+        .collect()
+}
+
+/// Files touched by the Read, Write, Edit and Bash calls of a session, sorted by path.
+/// Calls are counted once per tool use id.
+fn collect_touched_files(path: &Path) -> Result<Vec<SessionTouchedFile>, String> {
+    let mut reader =
+        JsonlReader::open(path).map_err(|e| format!("Failed to open session file: {}", e))?;
+    let mut seen_calls = HashSet::new();
+    let mut files: BTreeMap<String, SessionTouchedFile> = BTreeMap::new();
+    let mut cwd: Option<PathBuf> = None;
+
+    while let Some((_, entry)) = reader
+        .next_value()
+        .map_err(|e| format!("Failed to read session file: {}", e))?
+    {
+        if let Some(dir) = entry.get("cwd").and_then(|c| c.as_str()) {
+            cwd = Some(PathBuf::from(dir));
+        }
+
+        for block in tool_use_blocks(&entry) {
+            if let Some(id) = block.get("id").and_then(|i| i.as_str()) {
+                if !seen_calls.insert(id.to_string()) {
+                    continue;
+                }
+            }
+            let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let input = block.get("input");
+            let field = |key: &str| input.and_then(|i| i.get(key)).and_then(|v| v.as_str());
+
+            let mut touched: Vec<(&str, PathBuf)> = Vec::new();
+            if let Some((operation, key)) = file_tool_operation(name) {
+                if let Some(path) = field(key).and_then(|p| absolute_path(p, cwd.as_deref())) {
+                    touched.push((operation, path));
+                }
+            } else if name == "Bash" {
+                if let Some(command) = field("command") {
+                    let mut paths = bash_command_files(command, cwd.as_deref());
+                    paths.sort();
+                    paths.dedup();
+                    touched.extend(paths.into_iter().map(|path| ("bash", path)));
+                }
+            }
+
+            for (operation, path) in touched {
+                let path = path.to_string_lossy().to_string();
+                let file = files
+                    .entry(path.clone())
+                    .or_insert_with(|| SessionTouchedFile {
+                        path,
+                        operations: BTreeMap::new(),
+                        count: 0,
+                    });
+                *file.operations.entry(operation.to_string()).or_insert(0) += 1;
+                file.count += 1;
+            }
+        }
+    }
+
+    Ok(files.into_values().collect())
+}
+
+/// Lists the files a session read or changed, derived from its tool calls rather than from
+/// file timestamps, with the number of calls per operation
+#[tauri::command]
+pub async fn get_session_touched_files(
+    session_id: String,
+    project_id: String,
+) -> Result<Vec<SessionTouchedFile>, String> {
+    let path = session_file(&session_id, &project_id)?;
+    tokio::task::spawn_blocking(move || collect_touched_files(&path))
+        .await
+        .map_err(|e| format!("Failed to read session: {}", e))?
+}
+
+/// Counts tool calls per tool name, with the average result size, for one session when
+/// `session_id` is given and for every session of the project otherwise. MCP tools are
+/// also summed per server.
+#[tauri::command]
+pub async fn get_tool_usage_stats(
+    project_id: String,
+    session_id: Option<String>,
+) -> Result<ToolUsageStats, String> {
+    let files = match &session_id {
+        Some(id) => vec![(id.clone(), session_file(id, &project_id)?)],
+        None => project_session_files(&project_id)?,
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let mut scans = Vec::with_capacity(files.len());
+        for (id, path) in files {
+            match SessionToolScan::scan(&path) {
+                Ok(scan) => scans.push((id, scan)),
+                // A single unreadable session shouldn't hide the rest of the project
+                Err(e) if session_id.is_none() => log::warn!("Skipping session {}: {}", id, e),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(aggregate_tool_usage(project_id, session_id, scans))
+    })
+    .await
+    .map_err(|e| format!("Failed to scan sessions: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_touched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("notes.txt"), "x").unwrap();
+
+        let cwd = project.to_string_lossy().replace('\\', "/");
+        let lines = [
+            format!(
+                r#"{{"cwd":"{}","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"t1","name":"Read","input":{{"file_path":"src/main.rs"}}}}]}}}}"#,
+                cwd
+            ),
+            // The same call repeated on a later line is counted once
+            r#"{"message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"src/main.rs"}}]}}"#.to_string(),
+            r#"{"message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"src/main.rs"}}]}}"#.to_string(),
+            r#"{"message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Bash","input":{"command":"cat notes.txt | grep -n x && ls -la"}}]}}"#.to_string(),
+            r#"{"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t3","content":"x"}]}}"#.to_string(),
+        ];
+        let session = dir.path().join("s.jsonl");
+        std::fs::write(&session, lines.join("\n")).unwrap();
+
+        let files = collect_touched_files(&session).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[0].path.ends_with("notes.txt"));
+        assert_eq!(files[0].operations.get("bash"), Some(&1));
+        assert!(files[1].path.ends_with("main.rs"));
+        assert_eq!(files[1].count, 2);
+        assert_eq!(files[1].operations.get("read"), Some(&1));
+        assert_eq!(files[1].operations.get("edit"), Some(&1));
+    }
+
+    #[test]
+    fn test_bash_command_files() {
+        let cwd = Path::new("/work");
+        let files = |command: &str| -> Vec<String> {
+            bash_command_files(command, Some(cwd))
+                .into_iter()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+
+        assert_eq!(
+            files("rm -f old.txt 'with space.md'"),
+            vec!["/work/old.txt", "/work/with space.md"]
+        );
+        assert_eq!(files("mv a.rs /tmp/b.rs"), vec!["/work/a.rs", "/tmp/b.rs"]);
+        assert_eq!(
+            files("sed -i 's/a/b/' src/lib.rs"),
+            vec!["/work/src/lib.rs"]
+        );
+        assert_eq!(files("sed -i -e 's/a/b/' x.rs"), vec!["/work/x.rs"]);
+        assert_eq!(files("head -n 20 log.txt"), vec!["/work/log.txt"]);
+        assert_eq!(files("chmod +x run.sh"), vec!["/work/run.sh"]);
+        assert_eq!(
+            files("cargo build 2>&1 | tee build.log > /dev/null"),
+            vec!["/work/build.log"]
+        );
+        assert_eq!(
+            files("echo hi >> notes.md; ls -la src"),
+            vec!["/work/notes.md"]
+        );
+        assert_eq!(files("FOO=1 sudo /bin/rm -- -weird"), vec!["/work/-weird"]);
+        assert!(files("git status && npm test").is_empty());
+    }
+
+    #[test]
+    fn test_tool_usage_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let lines = [
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"a","name":"Read","input":{}},{"type":"tool_use","id":"b","name":"mcp__github__get_issue","input":{}}]}}"#,
+            r#"{"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"a","content":"12345678"},{"type":"tool_result","tool_use_id":"b","is_error":true,"content":[{"type":"text","text":"nope"}]}]}}"#,
+            r#"{"message":{"role":"assistant","content":[{"type":"tool_use","id":"c","name":"Read","input":{}}]}}"#,
+            r#"{"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"c","content":"1234"}]}}"#,
+            r#"{"message":{"role":"user","content":"plain prompt"}}"#,
+        ];
+        let session = dir.path().join("s.jsonl");
+        std::fs::write(&session, lines.join("\n")).unwrap();
+
+        let scan = SessionToolScan::scan(&session).unwrap();
+        let stats = aggregate_tool_usage("p".to_string(), None, vec![("s".to_string(), scan)]);
+
+        assert_eq!(stats.total_calls, 3);
+        assert_eq!(stats.tools[0].name, "Read");
+        assert_eq!(stats.tools[0].calls, 2);
+        assert_eq!(stats.tools[0].avg_result_bytes, 6.0);
+        assert_eq!(stats.tools[1].mcp_server.as_deref(), Some("github"));
+        assert_eq!(stats.tools[1].error_results, 1);
+        assert_eq!(
+            stats.mcp_servers,
+            vec![McpServerUsage {
+                server: "github".to_string(),
+                calls: 1,
+                tools: 1,
+            }]
+        );
+        assert_eq!(stats.by_session[0].tools.get("Read"), Some(&2));
+    }
+}
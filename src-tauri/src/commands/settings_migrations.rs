@@ -0,0 +1,211 @@
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+/// app_settings key holding the version of the stored settings layout
+pub const SETTINGS_VERSION_KEY: &str = "settings_version";
+
+/// A single upgrade of the stored settings. Each migration must be idempotent, since a crash
+/// between applying it and recording the new version makes it run again.
+struct SettingsMigration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// Migrations in ascending version order; append new ones at the end
+const MIGRATIONS: &[SettingsMigration] = &[
+    SettingsMigration {
+        version: 1,
+        description: "Normalize proxy settings (boolean flag and trimmed URLs)",
+        apply: normalize_proxy_settings,
+    },
+    SettingsMigration {
+        version: 2,
+        description: "Strip whitespace and quotes from the stored Claude binary path",
+        apply: normalize_claude_binary_path,
+    },
+    SettingsMigration {
+        version: 3,
+        description: "Drop an unparsable live output limit",
+        apply: drop_invalid_live_output_limit,
+    },
+];
+
+fn get_setting(tx: &Transaction, key: &str) -> rusqlite::Result<Option<String>> {
+    tx.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn set_setting(tx: &Transaction, key: &str, value: &str) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = ?2",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+fn delete_setting(tx: &Transaction, key: &str) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM app_settings WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+/// Rewrites a setting through `f`, leaving it untouched when `f` returns the same value
+fn update_setting(tx: &Transaction, key: &str, f: impl Fn(&str) -> String) -> rusqlite::Result<()> {
+    if let Some(value) = get_setting(tx, key)? {
+        let updated = f(&value);
+        if updated != value {
+            set_setting(tx, key, &updated)?;
+        }
+    }
+    Ok(())
+}
+
+/// The proxy loader only treats the exact string "true" as enabled
+fn normalize_proxy_settings(tx: &Transaction) -> rusqlite::Result<()> {
+    update_setting(tx, "proxy_enabled", |value| {
+        let enabled = matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "true" | "1" | "yes" | "on"
+        );
+        enabled.to_string()
+    })?;
+    for key in ["proxy_http", "proxy_https", "proxy_no", "proxy_all"] {
+        update_setting(tx, key, |value| value.trim().to_string())?;
+    }
+    Ok(())
+}
+
+fn normalize_claude_binary_path(tx: &Transaction) -> rusqlite::Result<()> {
+    update_setting(tx, "claude_binary_path", |value| {
+        value
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string()
+    })
+}
+
+fn drop_invalid_live_output_limit(tx: &Transaction) -> rusqlite::Result<()> {
+    if let Some(value) = get_setting(tx, "live_output_limit_bytes")? {
+        if value.trim().parse::<usize>().is_err() {
+            delete_setting(tx, "live_output_limit_bytes")?;
+        } else if value.trim() != value {
+            set_setting(tx, "live_output_limit_bytes", value.trim())?;
+        }
+    }
+    Ok(())
+}
+
+/// Current version of the stored settings; 0 when never migrated
+fn settings_version(conn: &Connection) -> rusqlite::Result<u32> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![SETTINGS_VERSION_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.and_then(|v| v.parse().ok()).unwrap_or(0))
+}
+
+/// Upgrades stored settings to the latest layout, applying each pending migration in its own
+/// transaction together with the version bump. Returns the number of migrations applied.
+pub fn run_settings_migrations(conn: &mut Connection) -> Result<usize, String> {
+    let current =
+        settings_version(conn).map_err(|e| format!("Failed to read settings version: {}", e))?;
+    let mut applied = 0;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start settings migration: {}", e))?;
+        (migration.apply)(&tx)
+            .and_then(|_| set_setting(&tx, SETTINGS_VERSION_KEY, &migration.version.to_string()))
+            .and_then(|_| tx.commit())
+            .map_err(|e| {
+                format!(
+                    "Settings migration {} ({}) failed: {}",
+                    migration.version, migration.description, e
+                )
+            })?;
+        log::info!(
+            "Applied settings migration {}: {}",
+            migration.version,
+            migration.description
+        );
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_db(entries: &[(&str, &str)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)",
+            [],
+        )
+        .unwrap();
+        for (key, value) in entries {
+            conn.execute(
+                "INSERT INTO app_settings (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn setting(conn: &Connection, key: &str) -> Option<String> {
+        conn.query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_migrations_upgrade_old_values_once() {
+        let mut conn = settings_db(&[
+            ("proxy_enabled", "1"),
+            ("proxy_http", " http://127.0.0.1:7890 "),
+            ("claude_binary_path", "\"/usr/local/bin/claude\""),
+            ("live_output_limit_bytes", "lots"),
+        ]);
+
+        assert_eq!(
+            run_settings_migrations(&mut conn).unwrap(),
+            MIGRATIONS.len()
+        );
+        assert_eq!(setting(&conn, "proxy_enabled").as_deref(), Some("true"));
+        assert_eq!(
+            setting(&conn, "proxy_http").as_deref(),
+            Some("http://127.0.0.1:7890")
+        );
+        assert_eq!(
+            setting(&conn, "claude_binary_path").as_deref(),
+            Some("/usr/local/bin/claude")
+        );
+        assert_eq!(setting(&conn, "live_output_limit_bytes"), None);
+        assert_eq!(
+            setting(&conn, SETTINGS_VERSION_KEY),
+            Some(MIGRATIONS.len().to_string())
+        );
+
+        assert_eq!(run_settings_migrations(&mut conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migration_versions_are_ascending() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+    }
+}
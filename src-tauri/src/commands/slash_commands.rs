@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
-use dirs;
 use log::{debug, error, info};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Represents a custom slash command
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +34,9 @@ pub struct SlashCommand {
     pub has_file_references: bool,
     /// Whether the command uses $ARGUMENTS placeholder
     pub accepts_arguments: bool,
+    /// Whether the command is backed by a file the user can edit or delete (false for built-ins)
+    #[serde(default)]
+    pub editable: bool,
 }
 
 /// YAML frontmatter structure
@@ -82,19 +87,18 @@ fn parse_markdown_with_frontmatter(content: &str) -> Result<(Option<CommandFront
 }
 
 /// Extract command name and namespace from file path
+/// (e.g. `frontend/react/component.md` -> `component` in namespace `frontend:react`)
 fn extract_command_info(file_path: &Path, base_path: &Path) -> Result<(String, Option<String>)> {
     let relative_path = file_path
         .strip_prefix(base_path)
         .context("Failed to get relative path")?;
 
-    // Remove .md extension
-    let path_without_ext = relative_path
-        .with_extension("")
-        .to_string_lossy()
-        .to_string();
-
-    // Split into components
-    let components: Vec<&str> = path_without_ext.split('/').collect();
+    // Remove .md extension and split into components, independent of the platform separator
+    let path_without_ext = relative_path.with_extension("");
+    let components: Vec<String> = path_without_ext
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
 
     if components.is_empty() {
         return Err(anyhow::anyhow!("Invalid command path"));
@@ -102,15 +106,58 @@ fn extract_command_info(file_path: &Path, base_path: &Path) -> Result<(String, O
 
     if components.len() == 1 {
         // No namespace
-        Ok((components[0].to_string(), None))
+        Ok((components[0].clone(), None))
     } else {
         // Last component is the command name, rest is namespace
-        let command_name = components.last().unwrap().to_string();
+        let command_name = components.last().unwrap().clone();
         let namespace = components[..components.len() - 1].join(":");
         Ok((command_name, Some(namespace)))
     }
 }
 
+/// Split a possibly namespaced command name (`foo:bar`, optionally with a leading `/`)
+/// into its name and namespace, merging it with an explicitly given namespace
+fn split_namespaced_name(
+    name: &str,
+    namespace: Option<&str>,
+) -> Result<(String, Option<String>), String> {
+    let mut components: Vec<&str> = namespace
+        .map(|ns| ns.split(':').collect())
+        .unwrap_or_default();
+    components.extend(name.trim_start_matches('/').split(':'));
+
+    // Hidden segments are skipped by discovery, and separators would escape the namespace
+    for component in &components {
+        if component.is_empty() || component.starts_with('.') || component.contains(['/', '\\']) {
+            return Err(format!("Invalid command name segment: '{}'", component));
+        }
+    }
+
+    let command_name = components.pop().unwrap_or_default().to_string();
+    let namespace = if components.is_empty() {
+        None
+    } else {
+        Some(components.join(":"))
+    };
+
+    Ok((command_name, namespace))
+}
+
+/// Base directory of the `commands/` tree for a scope
+fn commands_base_dir(scope: &str, project_path: Option<&str>) -> Result<PathBuf, String> {
+    if scope == "project" {
+        if let Some(proj_path) = project_path {
+            Ok(PathBuf::from(proj_path).join(".claude").join("commands"))
+        } else {
+            Err("Project path required for project scope".to_string())
+        }
+    } else {
+        Ok(crate::commands::claude::get_claude_dir()
+            .map_err(|e| e.to_string())?
+            .join("commands"))
+    }
+}
+
 /// Load a single command from a markdown file
 fn load_command_from_file(file_path: &Path, base_path: &Path, scope: &str) -> Result<SlashCommand> {
     debug!("Loading command from: {:?}", file_path);
@@ -162,6 +209,7 @@ fn load_command_from_file(file_path: &Path, base_path: &Path, scope: &str) -> Re
         has_bash_commands,
         has_file_references,
         accepts_arguments,
+        editable: true,
     })
 }
 
@@ -196,52 +244,72 @@ fn find_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// Claude Code's built-in slash commands: (name, description, accepts arguments)
+const BUILTIN_COMMANDS: &[(&str, &str, bool)] = &[
+    ("add-dir", "Add additional working directories", true),
+    ("agents", "Manage custom AI subagents", false),
+    ("bug", "Report bugs to Anthropic", true),
+    ("clear", "Clear conversation history", false),
+    (
+        "compact",
+        "Compact conversation with optional focus instructions",
+        true,
+    ),
+    ("config", "View or modify configuration", false),
+    ("cost", "Show token usage statistics", false),
+    (
+        "doctor",
+        "Check the health of the Claude Code installation",
+        false,
+    ),
+    ("help", "Get usage help", false),
+    ("hooks", "Manage hook configurations for tool events", false),
+    ("init", "Initialize project with CLAUDE.md guide", false),
+    ("login", "Switch Anthropic accounts", false),
+    ("logout", "Sign out from your Anthropic account", false),
+    (
+        "mcp",
+        "Manage MCP server connections and OAuth authentication",
+        false,
+    ),
+    ("memory", "Edit CLAUDE.md memory files", false),
+    ("model", "Select or change the AI model", true),
+    ("permissions", "View or update permissions", false),
+    ("pr_comments", "View pull request comments", false),
+    ("review", "Request code review", true),
+    ("status", "View account and system statuses", false),
+    (
+        "terminal-setup",
+        "Install Shift+Enter key binding for newlines",
+        false,
+    ),
+    (
+        "vim",
+        "Enter vim mode for alternating insert and command modes",
+        false,
+    ),
+];
+
 /// Create default/built-in slash commands
 fn create_default_commands() -> Vec<SlashCommand> {
-    vec![
-        SlashCommand {
-            id: "default-add-dir".to_string(),
-            name: "add-dir".to_string(),
-            full_command: "/add-dir".to_string(),
-            scope: "default".to_string(),
-            namespace: None,
-            file_path: "".to_string(),
-            content: "Add additional working directories".to_string(),
-            description: Some("Add additional working directories".to_string()),
-            allowed_tools: vec![],
-            has_bash_commands: false,
-            has_file_references: false,
-            accepts_arguments: false,
-        },
-        SlashCommand {
-            id: "default-init".to_string(),
-            name: "init".to_string(),
-            full_command: "/init".to_string(),
+    BUILTIN_COMMANDS
+        .iter()
+        .map(|(name, description, accepts_arguments)| SlashCommand {
+            id: format!("default-{}", name),
+            name: name.to_string(),
+            full_command: format!("/{}", name),
             scope: "default".to_string(),
             namespace: None,
             file_path: "".to_string(),
-            content: "Initialize project with CLAUDE.md guide".to_string(),
-            description: Some("Initialize project with CLAUDE.md guide".to_string()),
+            content: description.to_string(),
+            description: Some(description.to_string()),
             allowed_tools: vec![],
             has_bash_commands: false,
             has_file_references: false,
-            accepts_arguments: false,
-        },
-        SlashCommand {
-            id: "default-review".to_string(),
-            name: "review".to_string(),
-            full_command: "/review".to_string(),
-            scope: "default".to_string(),
-            namespace: None,
-            file_path: "".to_string(),
-            content: "Request code review".to_string(),
-            description: Some("Request code review".to_string()),
-            allowed_tools: vec![],
-            has_bash_commands: false,
-            has_file_references: false,
-            accepts_arguments: false,
-        },
-    ]
+            accepts_arguments: *accepts_arguments,
+            editable: false,
+        })
+        .collect()
 }
 
 /// Discover all custom slash commands
@@ -281,8 +349,8 @@ pub async fn slash_commands_list(
     }
 
     // Load user commands
-    if let Some(home_dir) = dirs::home_dir() {
-        let user_commands_dir = home_dir.join(".claude").join("commands");
+    if let Ok(claude_dir) = crate::commands::claude::get_claude_dir() {
+        let user_commands_dir = claude_dir.join("commands");
         if user_commands_dir.exists() {
             debug!("Scanning user commands at: {:?}", user_commands_dir);
 
@@ -309,6 +377,42 @@ pub async fn slash_commands_list(
     Ok(commands)
 }
 
+/// List built-in, user and project commands merged into one set.
+/// When several scopes define the same command, project wins over user, and user over built-in.
+#[tauri::command]
+pub async fn list_all_slash_commands(
+    project_path: Option<String>,
+) -> Result<Vec<SlashCommand>, String> {
+    let commands = slash_commands_list(project_path).await?;
+
+    let precedence = |scope: &str| match scope {
+        "project" => 0,
+        "user" => 1,
+        _ => 2,
+    };
+
+    let mut merged: HashMap<String, SlashCommand> = HashMap::new();
+    for cmd in commands {
+        match merged.get(&cmd.full_command) {
+            Some(existing) if precedence(&existing.scope) <= precedence(&cmd.scope) => {
+                debug!(
+                    "Skipping {} command {} shadowed by {} scope",
+                    cmd.scope, cmd.full_command, existing.scope
+                );
+            }
+            _ => {
+                merged.insert(cmd.full_command.clone(), cmd);
+            }
+        }
+    }
+
+    let mut commands: Vec<SlashCommand> = merged.into_values().collect();
+    commands.sort_by(|a, b| a.full_command.cmp(&b.full_command));
+
+    info!("Merged into {} slash commands", commands.len());
+    Ok(commands)
+}
+
 /// Get a single slash command by ID
 #[tauri::command]
 pub async fn slash_command_get(command_id: String) -> Result<SlashCommand, String> {
@@ -352,19 +456,11 @@ pub async fn slash_command_save(
         return Err("Invalid scope. Must be 'project' or 'user'".to_string());
     }
 
+    // Accept namespaced names like "frontend:component" as well as an explicit namespace
+    let (name, namespace) = split_namespaced_name(&name, namespace.as_deref())?;
+
     // Determine base directory
-    let base_dir = if scope == "project" {
-        if let Some(proj_path) = project_path {
-            PathBuf::from(proj_path).join(".claude").join("commands")
-        } else {
-            return Err("Project path required for project scope".to_string());
-        }
-    } else {
-        dirs::home_dir()
-            .ok_or_else(|| "Could not find home directory".to_string())?
-            .join(".claude")
-            .join("commands")
-    };
+    let base_dir = commands_base_dir(&scope, project_path.as_deref())?;
 
     // Build file path
     let mut file_path = base_dir.clone();
@@ -429,7 +525,7 @@ pub async fn slash_command_delete(
     }
 
     // List all commands (including project commands if applicable)
-    let commands = slash_commands_list(project_path).await?;
+    let commands = slash_commands_list(project_path.clone()).await?;
 
     // Find the command by ID
     let command = commands
@@ -437,21 +533,29 @@ pub async fn slash_command_delete(
         .find(|cmd| cmd.id == command_id)
         .ok_or_else(|| format!("Command not found: {}", command_id))?;
 
+    if !command.editable {
+        return Err(format!(
+            "Built-in command {} cannot be deleted",
+            command.full_command
+        ));
+    }
+
     // Delete the file
     fs::remove_file(&command.file_path)
         .map_err(|e| format!("Failed to delete command file: {}", e))?;
 
-    // Clean up empty directories
+    // Clean up now-empty namespace directories, stopping at the commands/ root
     if let Some(parent) = Path::new(&command.file_path).parent() {
-        let _ = remove_empty_dirs(parent);
+        let base_dir = commands_base_dir(&command.scope, project_path.as_deref())?;
+        let _ = remove_empty_dirs(parent, &base_dir);
     }
 
     Ok(format!("Deleted command: {}", command.full_command))
 }
 
-/// Remove empty directories recursively
-fn remove_empty_dirs(dir: &Path) -> Result<()> {
-    if !dir.exists() {
+/// Remove empty directories recursively up to (but not including) `stop_at`
+fn remove_empty_dirs(dir: &Path, stop_at: &Path) -> Result<()> {
+    if !dir.exists() || dir == stop_at || !dir.starts_with(stop_at) {
         return Ok(());
     }
 
@@ -463,9 +567,258 @@ fn remove_empty_dirs(dir: &Path) -> Result<()> {
 
         // Try to remove parent if it's also empty
         if let Some(parent) = dir.parent() {
-            let _ = remove_empty_dirs(parent);
+            let _ = remove_empty_dirs(parent, stop_at);
         }
     }
 
     Ok(())
 }
+
+// GitHub slash command import functionality
+
+/// Default directory of command files inside a repository
+const GITHUB_COMMANDS_DIR: &str = ".claude/commands";
+
+/// Maximum directory depth walked when listing commands from GitHub
+const GITHUB_MAX_DEPTH: usize = 4;
+
+/// Cached GitHub directory listings keyed by URL, as (ETag, body)
+static GITHUB_LISTING_CACHE: Lazy<Mutex<HashMap<String, (String, String)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Represents a slash command file found in a GitHub repository
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitHubSlashCommandFile {
+    pub name: String,
+    pub path: String,
+    pub download_url: String,
+    pub size: i64,
+    pub sha: String,
+    /// Command as it will be invoked once imported (e.g. "/frontend:component")
+    pub full_command: String,
+}
+
+/// Represents the GitHub API response for directory contents
+#[derive(Debug, Deserialize)]
+struct GitHubContentEntry {
+    name: String,
+    path: String,
+    sha: String,
+    size: i64,
+    download_url: Option<String>,
+    #[serde(rename = "type")]
+    file_type: String,
+}
+
+/// Validate an "owner/repo" string
+fn validate_github_repo(repo: &str) -> Result<(), String> {
+    let parts: Vec<&str> = repo.split('/').collect();
+    let valid = parts.len() == 2
+        && parts.iter().all(|p| {
+            !p.is_empty()
+                && !p.starts_with('.')
+                && p.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid repository '{}', expected 'owner/repo'",
+            repo
+        ))
+    }
+}
+
+/// Validate a path inside a repository, rejecting traversal and absolute paths
+fn validate_github_path(path: &str) -> Result<(), String> {
+    if path.starts_with('/')
+        || path.contains('\\')
+        || path
+            .split('/')
+            .any(|c| c.is_empty() || c == "." || c == "..")
+    {
+        return Err(format!("Invalid repository path: '{}'", path));
+    }
+    Ok(())
+}
+
+/// Derive the colon-namespaced command name from a file path in the repository,
+/// relative to the commands directory when the file lives inside one
+fn github_command_name(path: &str, base_dir: &str) -> String {
+    let relative = if base_dir.is_empty() {
+        path
+    } else {
+        path.strip_prefix(&format!("{}/", base_dir))
+            .unwrap_or_else(|| path.rsplit('/').next().unwrap_or(path))
+    };
+    relative
+        .trim_end_matches(".md")
+        .split('/')
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Fetch a GitHub directory listing, reusing the cached body when the ETag still matches
+async fn fetch_github_listing(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Vec<GitHubContentEntry>, String> {
+    let cached = GITHUB_LISTING_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(url).cloned());
+
+    let mut request = client
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "Claudia-App");
+    if let Some((etag, _)) = &cached {
+        request = request.header("If-None-Match", etag);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch from GitHub: {}", e))?;
+
+    let body = if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        debug!("GitHub listing not modified, using cache: {}", url);
+        cached.map(|(_, body)| body).unwrap_or_default()
+    } else if response.status().is_success() {
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read GitHub response: {}", e))?;
+        if let (Some(etag), Ok(mut cache)) = (etag, GITHUB_LISTING_CACHE.lock()) {
+            cache.insert(url.to_string(), (etag, body.clone()));
+        }
+        body
+    } else {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("GitHub API error ({}): {}", status, error_text));
+    };
+
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse GitHub response: {}", e))
+}
+
+/// List slash command files in a GitHub repository
+/// `path` defaults to `.claude/commands`; nested directories become namespaces
+#[tauri::command]
+pub async fn fetch_github_slash_commands(
+    repo: String,
+    path: Option<String>,
+) -> Result<Vec<GitHubSlashCommandFile>, String> {
+    validate_github_repo(&repo)?;
+    let base_dir = path.unwrap_or_else(|| GITHUB_COMMANDS_DIR.to_string());
+    let base_dir = base_dir.trim_matches('/').to_string();
+    if !base_dir.is_empty() {
+        validate_github_path(&base_dir)?;
+    }
+
+    info!("Fetching slash commands from GitHub: {}/{}", repo, base_dir);
+
+    let client = reqwest::Client::new();
+    let mut files = Vec::new();
+    let mut pending = vec![(base_dir.clone(), 0usize)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        let url = format!("https://api.github.com/repos/{}/contents/{}", repo, dir);
+        for entry in fetch_github_listing(&client, &url).await? {
+            // Hidden files/directories are skipped, matching local discovery
+            if entry.name.starts_with('.') {
+                continue;
+            }
+            match entry.file_type.as_str() {
+                "dir" if depth < GITHUB_MAX_DEPTH => pending.push((entry.path, depth + 1)),
+                "file" if entry.name.ends_with(".md") => {
+                    if let Some(download_url) = entry.download_url {
+                        let full_command =
+                            format!("/{}", github_command_name(&entry.path, &base_dir));
+                        files.push(GitHubSlashCommandFile {
+                            name: entry.name,
+                            path: entry.path,
+                            download_url,
+                            size: entry.size,
+                            sha: entry.sha,
+                            full_command,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.full_command.cmp(&b.full_command));
+    info!("Found {} slash commands on GitHub", files.len());
+    Ok(files)
+}
+
+/// Import a single slash command file from a GitHub repository into the given scope
+#[tauri::command]
+pub async fn import_slash_command_from_github(
+    repo: String,
+    path: String,
+    scope: String,
+    base_path: Option<String>,
+    project_path: Option<String>,
+) -> Result<SlashCommand, String> {
+    validate_github_repo(&repo)?;
+    validate_github_path(&path)?;
+    if !path.ends_with(".md") {
+        return Err("Only markdown (.md) command files can be imported".to_string());
+    }
+
+    info!("Importing slash command from GitHub: {}/{}", repo, path);
+
+    let url = format!("https://raw.githubusercontent.com/{}/HEAD/{}", repo, path);
+    let client = reqwest::Client::new();
+    let response = client
+        .get(&url)
+        .header("User-Agent", "Claudia-App")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download command: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download command: HTTP {}",
+            response.status()
+        ));
+    }
+
+    let content = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    let (frontmatter, body) = parse_markdown_with_frontmatter(&content)
+        .map_err(|e| format!("Failed to parse command file: {}", e))?;
+    let (description, allowed_tools) = match frontmatter {
+        Some(fm) => (fm.description, fm.allowed_tools.unwrap_or_default()),
+        None => (None, Vec::new()),
+    };
+
+    // The namespaced name is validated by slash_command_save, so it can't escape commands/
+    let base_dir = base_path.unwrap_or_else(|| GITHUB_COMMANDS_DIR.to_string());
+    let name = github_command_name(&path, base_dir.trim_matches('/'));
+
+    slash_command_save(
+        scope,
+        name,
+        None,
+        body.trim_start().to_string(),
+        description,
+        allowed_tools,
+        project_path,
+    )
+    .await
+}
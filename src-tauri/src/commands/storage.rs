@@ -1,11 +1,69 @@
-use super::agents::AgentDb;
+use super::agents::{AgentDb, DatabaseRecovery};
+use crate::utils::sqlite;
 use anyhow::Result;
+use once_cell::sync::Lazy;
 use rusqlite::{params, types::ValueRef, Connection, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value as JsonValue};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, State};
 
+/// A keyset-paginated page of table data, ordered by rowid
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TablePage {
+    pub table_name: String,
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Map<String, JsonValue>>,
+    /// Rowid to pass as `afterRowid` for the next page, `None` on the last page
+    pub next_cursor: Option<i64>,
+    pub total_rows: i64,
+    pub page_size: i64,
+    pub total_pages: i64,
+}
+
+/// Largest page `storage_read_table_page` returns
+const MAX_PAGE_SIZE: i64 = 1000;
+
+/// How long a cached row count is trusted before it is recomputed
+const ROW_COUNT_TTL: Duration = Duration::from_secs(30);
+
+/// Cached `COUNT(*)` per table, so paging through a large table doesn't rescan it every page
+static ROW_COUNT_CACHE: Lazy<Mutex<HashMap<String, (i64, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A single row edit applied by `storage_execute_batch`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StorageOp {
+    Insert {
+        table_name: String,
+        values: HashMap<String, JsonValue>,
+    },
+    Update {
+        table_name: String,
+        primary_key_values: HashMap<String, JsonValue>,
+        updates: HashMap<String, JsonValue>,
+    },
+    Delete {
+        table_name: String,
+        primary_key_values: HashMap<String, JsonValue>,
+    },
+}
+
+/// Outcome of one operation in a committed batch
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageOpResult {
+    pub index: usize,
+    pub rows_affected: usize,
+    pub last_insert_rowid: Option<i64>,
+}
+
+/// Phrase the caller must pass to `storage_reset_database`
+const RESET_CONFIRMATION: &str = "RESET";
+
 /// Represents metadata about a database table
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TableInfo {
@@ -49,7 +107,7 @@ pub struct QueryResult {
 /// List all tables in the database
 #[tauri::command]
 pub async fn storage_list_tables(db: State<'_, AgentDb>) -> Result<Vec<TableInfo>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Query for all tables
     let mut stmt = conn
@@ -75,24 +133,7 @@ pub async fn storage_list_tables(db: State<'_, AgentDb>) -> Result<Vec<TableInfo
             .unwrap_or(0);
 
         // Get column information
-        let mut pragma_stmt = conn
-            .prepare(&format!("PRAGMA table_info({})", table_name))
-            .map_err(|e| e.to_string())?;
-
-        let columns: Vec<ColumnInfo> = pragma_stmt
-            .query_map([], |row| {
-                Ok(ColumnInfo {
-                    cid: row.get(0)?,
-                    name: row.get(1)?,
-                    type_name: row.get(2)?,
-                    notnull: row.get::<_, i32>(3)? != 0,
-                    dflt_value: row.get(4)?,
-                    pk: row.get::<_, i32>(5)? != 0,
-                })
-            })
-            .map_err(|e| e.to_string())?
-            .collect::<SqliteResult<Vec<_>>>()
-            .map_err(|e| e.to_string())?;
+        let columns = table_columns(&conn, &table_name)?;
 
         tables.push(TableInfo {
             name: table_name,
@@ -104,6 +145,22 @@ pub async fn storage_list_tables(db: State<'_, AgentDb>) -> Result<Vec<TableInfo
     Ok(tables)
 }
 
+/// Describe the columns of a table (declared type, nullability, primary key and default)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage_describe_table(
+    db: State<'_, AgentDb>,
+    tableName: String,
+) -> Result<Vec<ColumnInfo>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    if !is_valid_table_name(&conn, &tableName)? {
+        return Err("Invalid table name".to_string());
+    }
+
+    table_columns(&conn, &tableName)
+}
+
 /// Read table data with pagination
 #[tauri::command]
 #[allow(non_snake_case)]
@@ -114,7 +171,7 @@ pub async fn storage_read_table(
     pageSize: i64,
     searchQuery: Option<String>,
 ) -> Result<TableData, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
 
     // Validate table name to prevent SQL injection
     if !is_valid_table_name(&conn, &tableName)? {
@@ -122,26 +179,7 @@ pub async fn storage_read_table(
     }
 
     // Get column information
-    let mut pragma_stmt = conn
-        .prepare(&format!("PRAGMA table_info({})", tableName))
-        .map_err(|e| e.to_string())?;
-
-    let columns: Vec<ColumnInfo> = pragma_stmt
-        .query_map([], |row| {
-            Ok(ColumnInfo {
-                cid: row.get(0)?,
-                name: row.get(1)?,
-                type_name: row.get(2)?,
-                notnull: row.get::<_, i32>(3)? != 0,
-                dflt_value: row.get(4)?,
-                pk: row.get::<_, i32>(5)? != 0,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<SqliteResult<Vec<_>>>()
-        .map_err(|e| e.to_string())?;
-
-    drop(pragma_stmt);
+    let columns = table_columns(&conn, &tableName)?;
 
     // Build query with optional search
     let (query, count_query) = if let Some(search) = &searchQuery {
@@ -191,23 +229,7 @@ pub async fn storage_read_table(
             let mut row_map = Map::new();
 
             for (idx, col) in columns.iter().enumerate() {
-                let value = match row.get_ref(idx)? {
-                    ValueRef::Null => JsonValue::Null,
-                    ValueRef::Integer(i) => JsonValue::Number(serde_json::Number::from(i)),
-                    ValueRef::Real(f) => {
-                        if let Some(n) = serde_json::Number::from_f64(f) {
-                            JsonValue::Number(n)
-                        } else {
-                            JsonValue::String(f.to_string())
-                        }
-                    }
-                    ValueRef::Text(s) => JsonValue::String(String::from_utf8_lossy(s).to_string()),
-                    ValueRef::Blob(b) => JsonValue::String(base64::Engine::encode(
-                        &base64::engine::general_purpose::STANDARD,
-                        b,
-                    )),
-                };
-                row_map.insert(col.name.clone(), value);
+                row_map.insert(col.name.clone(), sql_value_to_json(row.get_ref(idx)?));
             }
 
             Ok(row_map)
@@ -227,6 +249,118 @@ pub async fn storage_read_table(
     })
 }
 
+/// Read a page of rows after the given rowid. Unlike `storage_read_table` this never
+/// uses OFFSET, so later pages of large tables such as `usage_entries` stay cheap.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage_read_table_page(
+    db: State<'_, AgentDb>,
+    tableName: String,
+    afterRowid: Option<i64>,
+    limit: i64,
+) -> Result<TablePage, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    read_table_page(&conn, &tableName, afterRowid, limit)
+}
+
+fn read_table_page(
+    conn: &Connection,
+    table_name: &str,
+    after_rowid: Option<i64>,
+    limit: i64,
+) -> Result<TablePage, String> {
+    // Validate table name to prevent SQL injection
+    if !is_valid_table_name(conn, table_name)? {
+        return Err("Invalid table name".to_string());
+    }
+
+    let columns = table_columns(conn, table_name)?;
+    let page_size = limit.clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one extra row to know whether another page follows
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT rowid, * FROM {} WHERE rowid > ?1 ORDER BY rowid LIMIT ?2",
+            table_name
+        ))
+        .map_err(|e| {
+            format!(
+                "Cannot page through {} by rowid (WITHOUT ROWID table?): {}",
+                table_name, e
+            )
+        })?;
+
+    let mut rows: Vec<(i64, Map<String, JsonValue>)> = stmt
+        .query_map(
+            params![after_rowid.unwrap_or(i64::MIN), page_size + 1],
+            |row| {
+                let mut row_map = Map::new();
+                for (idx, col) in columns.iter().enumerate() {
+                    row_map.insert(col.name.clone(), sql_value_to_json(row.get_ref(idx + 1)?));
+                }
+                Ok((row.get(0)?, row_map))
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let has_more = rows.len() as i64 > page_size;
+    rows.truncate(page_size as usize);
+    let next_cursor = if has_more {
+        rows.last().map(|(rowid, _)| *rowid)
+    } else {
+        None
+    };
+
+    let total_rows = cached_row_count(conn, table_name)?;
+    let total_pages = (total_rows as f64 / page_size as f64).ceil() as i64;
+
+    Ok(TablePage {
+        table_name: table_name.to_string(),
+        columns,
+        rows: rows.into_iter().map(|(_, row)| row).collect(),
+        next_cursor,
+        total_rows,
+        page_size,
+        total_pages,
+    })
+}
+
+/// Returns the table's row count, reusing a recent count when available
+fn cached_row_count(conn: &Connection, table_name: &str) -> Result<i64, String> {
+    if let Ok(cache) = ROW_COUNT_CACHE.lock() {
+        if let Some((count, at)) = cache.get(table_name) {
+            if at.elapsed() < ROW_COUNT_TTL {
+                return Ok(*count);
+            }
+        }
+    }
+
+    let count: i64 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM {}", table_name), [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(mut cache) = ROW_COUNT_CACHE.lock() {
+        cache.insert(table_name.to_string(), (count, Instant::now()));
+    }
+    Ok(count)
+}
+
+/// Drops the cached row count for one table, or for all tables when `None`
+fn invalidate_row_count(table_name: Option<&str>) {
+    if let Ok(mut cache) = ROW_COUNT_CACHE.lock() {
+        match table_name {
+            Some(name) => {
+                cache.remove(name);
+            }
+            None => cache.clear(),
+        }
+    }
+}
+
 /// Update a row in a table
 #[tauri::command]
 #[allow(non_snake_case)]
@@ -236,29 +370,131 @@ pub async fn storage_update_row(
     primaryKeyValues: HashMap<String, JsonValue>,
     updates: HashMap<String, JsonValue>,
 ) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    update_row(&conn, &tableName, &primaryKeyValues, &updates)?;
+    Ok(())
+}
+
+/// Delete a row from a table
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage_delete_row(
+    db: State<'_, AgentDb>,
+    tableName: String,
+    primaryKeyValues: HashMap<String, JsonValue>,
+) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    delete_row(&conn, &tableName, &primaryKeyValues)?;
+    Ok(())
+}
+
+/// Insert a new row into a table
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage_insert_row(
+    db: State<'_, AgentDb>,
+    tableName: String,
+    values: HashMap<String, JsonValue>,
+) -> Result<i64, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    insert_row(&conn, &tableName, &values)
+}
 
+/// Apply several row edits atomically. Any failure rolls back every operation.
+#[tauri::command]
+pub async fn storage_execute_batch(
+    db: State<'_, AgentDb>,
+    operations: Vec<StorageOp>,
+) -> Result<Vec<StorageOpResult>, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    execute_batch(&mut conn, &operations)
+}
+
+/// Runs the operations inside a single transaction, which is rolled back on drop if any fails
+fn execute_batch(
+    conn: &mut Connection,
+    operations: &[StorageOp],
+) -> Result<Vec<StorageOpResult>, String> {
+    let tx = sqlite::begin_immediate(conn, "storage batch")
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let mut results = Vec::with_capacity(operations.len());
+    for (index, op) in operations.iter().enumerate() {
+        let result = match op {
+            StorageOp::Insert { table_name, values } => {
+                insert_row(&tx, table_name, values).map(|rowid| StorageOpResult {
+                    index,
+                    rows_affected: 1,
+                    last_insert_rowid: Some(rowid),
+                })
+            }
+            StorageOp::Update {
+                table_name,
+                primary_key_values,
+                updates,
+            } => update_row(&tx, table_name, primary_key_values, updates).map(|rows| {
+                StorageOpResult {
+                    index,
+                    rows_affected: rows,
+                    last_insert_rowid: None,
+                }
+            }),
+            StorageOp::Delete {
+                table_name,
+                primary_key_values,
+            } => delete_row(&tx, table_name, primary_key_values).map(|rows| StorageOpResult {
+                index,
+                rows_affected: rows,
+                last_insert_rowid: None,
+            }),
+        };
+        results.push(result.map_err(|e| {
+            format!(
+                "Operation {} failed, no changes were applied: {}",
+                index + 1,
+                e
+            )
+        })?);
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(results)
+}
+
+/// Updates the row matching the primary key values, returning the number of rows changed
+fn update_row(
+    conn: &Connection,
+    table_name: &str,
+    primary_key_values: &HashMap<String, JsonValue>,
+    updates: &HashMap<String, JsonValue>,
+) -> Result<usize, String> {
     // Validate table name
-    if !is_valid_table_name(&conn, &tableName)? {
+    if !is_valid_table_name(conn, table_name)? {
         return Err("Invalid table name".to_string());
     }
 
+    // Validate and coerce values against the column types
+    let columns = table_columns(conn, table_name)?;
+    let updates = validate_row_values(&columns, updates, RowWrite::Update)?;
+    let primary_key_values = validate_row_values(&columns, primary_key_values, RowWrite::Key)?;
+
     // Build UPDATE query
     let set_clauses: Vec<String> = updates
-        .keys()
+        .iter()
         .enumerate()
-        .map(|(idx, key)| format!("{} = ?{}", key, idx + 1))
+        .map(|(idx, (key, _))| format!("{} = ?{}", key, idx + 1))
         .collect();
 
-    let where_clauses: Vec<String> = primaryKeyValues
-        .keys()
+    let where_clauses: Vec<String> = primary_key_values
+        .iter()
         .enumerate()
-        .map(|(idx, key)| format!("{} = ?{}", key, idx + updates.len() + 1))
+        .map(|(idx, (key, _))| format!("{} = ?{}", key, idx + updates.len() + 1))
         .collect();
 
     let query = format!(
         "UPDATE {} SET {} WHERE {}",
-        tableName,
+        table_name,
         set_clauses.join(", "),
         where_clauses.join(" AND ")
     );
@@ -267,12 +503,12 @@ pub async fn storage_update_row(
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     // Add update values
-    for value in updates.values() {
+    for (_, value) in &updates {
         params.push(json_to_sql_value(value)?);
     }
 
     // Add where clause values
-    for value in primaryKeyValues.values() {
+    for (_, value) in &primary_key_values {
         params.push(json_to_sql_value(value)?);
     }
 
@@ -281,89 +517,88 @@ pub async fn storage_update_row(
         &query,
         rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
     )
-    .map_err(|e| format!("Failed to update row: {}", e))?;
-
-    Ok(())
+    .map_err(|e| format!("Failed to update row: {}", e))
 }
 
-/// Delete a row from a table
-#[tauri::command]
-#[allow(non_snake_case)]
-pub async fn storage_delete_row(
-    db: State<'_, AgentDb>,
-    tableName: String,
-    primaryKeyValues: HashMap<String, JsonValue>,
-) -> Result<(), String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-
+/// Deletes the row matching the primary key values, returning the number of rows removed
+fn delete_row(
+    conn: &Connection,
+    table_name: &str,
+    primary_key_values: &HashMap<String, JsonValue>,
+) -> Result<usize, String> {
     // Validate table name
-    if !is_valid_table_name(&conn, &tableName)? {
+    if !is_valid_table_name(conn, table_name)? {
         return Err("Invalid table name".to_string());
     }
 
+    // Only known columns may appear in the WHERE clause, and an empty key would match every row
+    if primary_key_values.is_empty() {
+        return Err("Primary key values are required".to_string());
+    }
+    let columns = table_columns(conn, table_name)?;
+    let primary_key_values = validate_row_values(&columns, primary_key_values, RowWrite::Key)?;
+
     // Build DELETE query
-    let where_clauses: Vec<String> = primaryKeyValues
-        .keys()
+    let where_clauses: Vec<String> = primary_key_values
+        .iter()
         .enumerate()
-        .map(|(idx, key)| format!("{} = ?{}", key, idx + 1))
+        .map(|(idx, (key, _))| format!("{} = ?{}", key, idx + 1))
         .collect();
 
     let query = format!(
         "DELETE FROM {} WHERE {}",
-        tableName,
+        table_name,
         where_clauses.join(" AND ")
     );
 
     // Prepare parameters
-    let params: Vec<Box<dyn rusqlite::ToSql>> = primaryKeyValues
-        .values()
-        .map(json_to_sql_value)
+    let params: Vec<Box<dyn rusqlite::ToSql>> = primary_key_values
+        .iter()
+        .map(|(_, value)| json_to_sql_value(value))
         .collect::<Result<Vec<_>, _>>()?;
 
     // Execute delete
-    conn.execute(
-        &query,
-        rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
-    )
-    .map_err(|e| format!("Failed to delete row: {}", e))?;
+    let rows_affected = conn
+        .execute(
+            &query,
+            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+        )
+        .map_err(|e| format!("Failed to delete row: {}", e))?;
 
-    Ok(())
+    invalidate_row_count(Some(table_name));
+    Ok(rows_affected)
 }
 
-/// Insert a new row into a table
-#[tauri::command]
-#[allow(non_snake_case)]
-pub async fn storage_insert_row(
-    db: State<'_, AgentDb>,
-    tableName: String,
-    values: HashMap<String, JsonValue>,
+/// Inserts a row, returning its rowid
+fn insert_row(
+    conn: &Connection,
+    table_name: &str,
+    values: &HashMap<String, JsonValue>,
 ) -> Result<i64, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-
     // Validate table name
-    if !is_valid_table_name(&conn, &tableName)? {
+    if !is_valid_table_name(conn, table_name)? {
         return Err("Invalid table name".to_string());
     }
 
+    // Validate and coerce values against the column types
+    let table_columns = table_columns(conn, table_name)?;
+    let values = validate_row_values(&table_columns, values, RowWrite::Insert)?;
+
     // Build INSERT query
-    let columns: Vec<&String> = values.keys().collect();
+    let columns: Vec<&str> = values.iter().map(|(name, _)| name.as_str()).collect();
     let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
 
     let query = format!(
         "INSERT INTO {} ({}) VALUES ({})",
-        tableName,
-        columns
-            .iter()
-            .map(|c| c.as_str())
-            .collect::<Vec<_>>()
-            .join(", "),
+        table_name,
+        columns.join(", "),
         placeholders.join(", ")
     );
 
     // Prepare parameters
     let params: Vec<Box<dyn rusqlite::ToSql>> = values
-        .values()
-        .map(json_to_sql_value)
+        .iter()
+        .map(|(_, value)| json_to_sql_value(value))
         .collect::<Result<Vec<_>, _>>()?;
 
     // Execute insert
@@ -373,23 +608,51 @@ pub async fn storage_insert_row(
     )
     .map_err(|e| format!("Failed to insert row: {}", e))?;
 
+    invalidate_row_count(Some(table_name));
     Ok(conn.last_insert_rowid())
 }
 
-/// Execute a raw SQL query
+/// Execute a raw SQL query.
+/// Prefer `storage_execute_sql_params` when the query contains user-provided values.
 #[tauri::command]
 pub async fn storage_execute_sql(
     db: State<'_, AgentDb>,
     query: String,
 ) -> Result<QueryResult, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    execute_sql(&conn, &query, &[])
+}
+
+/// Execute a SQL query with `?` placeholders bound to the given JSON values
+#[tauri::command]
+pub async fn storage_execute_sql_params(
+    db: State<'_, AgentDb>,
+    query: String,
+    params: Vec<JsonValue>,
+) -> Result<QueryResult, String> {
+    let params: Vec<Box<dyn rusqlite::ToSql>> = params
+        .iter()
+        .map(json_to_sql_value)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    execute_sql(&conn, &query, &params)
+}
+
+/// Runs a query with bound parameters, returning rows for SELECT and change counts otherwise
+fn execute_sql(
+    conn: &Connection,
+    query: &str,
+    params: &[Box<dyn rusqlite::ToSql>],
+) -> Result<QueryResult, String> {
+    let params = rusqlite::params_from_iter(params.iter().map(|p| p.as_ref()));
 
     // Check if it's a SELECT query
     let is_select = query.trim().to_uppercase().starts_with("SELECT");
 
     if is_select {
         // Handle SELECT queries
-        let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(query).map_err(|e| e.to_string())?;
         let column_count = stmt.column_count();
 
         // Get column names
@@ -399,28 +662,10 @@ pub async fn storage_execute_sql(
 
         // Execute query and collect results
         let rows: Vec<Vec<JsonValue>> = stmt
-            .query_map([], |row| {
+            .query_map(params, |row| {
                 let mut row_values = Vec::new();
                 for i in 0..column_count {
-                    let value = match row.get_ref(i)? {
-                        ValueRef::Null => JsonValue::Null,
-                        ValueRef::Integer(n) => JsonValue::Number(serde_json::Number::from(n)),
-                        ValueRef::Real(f) => {
-                            if let Some(n) = serde_json::Number::from_f64(f) {
-                                JsonValue::Number(n)
-                            } else {
-                                JsonValue::String(f.to_string())
-                            }
-                        }
-                        ValueRef::Text(s) => {
-                            JsonValue::String(String::from_utf8_lossy(s).to_string())
-                        }
-                        ValueRef::Blob(b) => JsonValue::String(base64::Engine::encode(
-                            &base64::engine::general_purpose::STANDARD,
-                            b,
-                        )),
-                    };
-                    row_values.push(value);
+                    row_values.push(sql_value_to_json(row.get_ref(i)?));
                 }
                 Ok(row_values)
             })
@@ -436,7 +681,8 @@ pub async fn storage_execute_sql(
         })
     } else {
         // Handle non-SELECT queries (INSERT, UPDATE, DELETE, etc.)
-        let rows_affected = conn.execute(&query, []).map_err(|e| e.to_string())?;
+        let rows_affected = conn.execute(query, params).map_err(|e| e.to_string())?;
+        invalidate_row_count(None);
 
         Ok(QueryResult {
             columns: vec![],
@@ -447,13 +693,60 @@ pub async fn storage_execute_sql(
     }
 }
 
-/// Reset the entire database (with confirmation)
+/// Directory holding database backups, created on demand
+fn backups_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("backups");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create backups directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Writes a consistent copy of the database to the backups directory and returns its path
+fn backup_database(app: &AppHandle, conn: &Connection) -> Result<PathBuf, String> {
+    let path = backups_dir(app)?.join(format!(
+        "agents-{}.db",
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    conn.execute(
+        "VACUUM INTO ?1",
+        params![path.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("Failed to back up database: {}", e))?;
+    log::info!("Backed up database to {:?}", path);
+    Ok(path)
+}
+
+/// Refreshes in-memory state derived from app_settings after the database was replaced
+fn reload_settings_state(conn: &mut Connection) {
+    if let Err(e) = super::settings_migrations::run_settings_migrations(conn) {
+        log::error!("{}", e);
+    }
+    super::claude::load_claude_dir_override(conn);
+    super::relay_adapters::load_relay_timeout_defaults(conn);
+    super::proxy::apply_proxy_settings(&super::proxy::load_proxy_settings(conn));
+}
+
+/// Reset the entire database. Requires `confirm` to be "RESET" and backs up the
+/// database first, returning the backup path.
 #[tauri::command]
-pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
-    {
+pub async fn storage_reset_database(app: AppHandle, confirm: String) -> Result<String, String> {
+    if confirm != RESET_CONFIRMATION {
+        return Err(format!(
+            "Type {} to confirm resetting the database",
+            RESET_CONFIRMATION
+        ));
+    }
+
+    let backup_path = {
         // Drop all existing tables within a scoped block
         let db_state = app.state::<AgentDb>();
-        let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+        let conn = db_state.lock().map_err(|e| e.to_string())?;
+
+        let backup_path = backup_database(&app, &conn)?;
 
         // Disable foreign key constraints temporarily to allow dropping tables
         conn.execute("PRAGMA foreign_keys = OFF", [])
@@ -472,25 +765,154 @@ pub async fn storage_reset_database(app: AppHandle) -> Result<(), String> {
             .map_err(|e| format!("Failed to re-enable foreign keys: {}", e))?;
 
         // Connection is automatically dropped at end of scope
-    }
+        backup_path
+    };
 
     // Re-initialize the database which will recreate all tables empty
-    let new_conn = init_database(&app).map_err(|e| format!("Failed to reset database: {}", e))?;
+    let mut new_conn =
+        init_database(&app).map_err(|e| format!("Failed to reset database: {}", e))?;
+    reload_settings_state(&mut new_conn);
+    invalidate_row_count(None);
 
     // Update the managed state with the new connection
     {
         let db_state = app.state::<AgentDb>();
-        let mut conn_guard = db_state.0.lock().map_err(|e| e.to_string())?;
+        let mut conn_guard = db_state.lock().map_err(|e| e.to_string())?;
         *conn_guard = new_conn;
     }
 
     // Run VACUUM to optimize the database
     {
         let db_state = app.state::<AgentDb>();
-        let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+        let conn = db_state.lock().map_err(|e| e.to_string())?;
         conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
     }
 
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
+/// Checks that a file is an intact SQLite database containing the agents table
+fn validate_backup(path: &Path) -> Result<(), String> {
+    let backup = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+    let integrity: String = backup
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Backup is not a valid database: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Backup failed the integrity check: {}", integrity));
+    }
+    if !is_valid_table_name(&backup, "agents")? {
+        return Err("Backup does not contain an agents table".to_string());
+    }
+    Ok(())
+}
+
+/// Replace the database with a backup. The current database is backed up first;
+/// returns the path of that safety backup.
+#[tauri::command]
+pub async fn restore_database_from_backup(app: AppHandle, path: String) -> Result<String, String> {
+    let backup = PathBuf::from(&path);
+    if !backup.is_file() {
+        return Err(format!("Backup not found: {}", path));
+    }
+    validate_backup(&backup)?;
+
+    let db_path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("agents.db");
+
+    let db_state = app.state::<AgentDb>();
+    let mut conn_guard = db_state.lock().map_err(|e| e.to_string())?;
+    let safety_backup = backup_database(&app, &conn_guard)?;
+
+    // Close the current connection so the database file can be replaced
+    *conn_guard =
+        Connection::open_in_memory().map_err(|e| format!("Failed to close database: {}", e))?;
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(db_path.with_file_name(format!("agents.db{}", suffix)));
+    }
+
+    let copy_result =
+        std::fs::copy(&backup, &db_path).map_err(|e| format!("Failed to restore backup: {}", e));
+    if let Err(e) = &copy_result {
+        log::error!("{}, putting back the previous database", e);
+        let _ = std::fs::copy(&safety_backup, &db_path);
+    }
+
+    let mut new_conn = match init_database(&app) {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::error!(
+                "Failed to open restored database: {}, putting back the previous database",
+                e
+            );
+            for suffix in ["-wal", "-shm"] {
+                let _ =
+                    std::fs::remove_file(db_path.with_file_name(format!("agents.db{}", suffix)));
+            }
+            std::fs::copy(&safety_backup, &db_path).map_err(|copy_err| {
+                format!(
+                    "Failed to open restored database: {}; putting back the previous database also failed: {}",
+                    e, copy_err
+                )
+            })?;
+            let mut previous = init_database(&app).map_err(|reopen_err| {
+                format!(
+                    "Failed to open restored database: {}; reopening the previous database also failed: {}",
+                    e, reopen_err
+                )
+            })?;
+            reload_settings_state(&mut previous);
+            invalidate_row_count(None);
+            *conn_guard = previous;
+            return Err(format!("Failed to open restored database: {}", e));
+        }
+    };
+    reload_settings_state(&mut new_conn);
+    invalidate_row_count(None);
+    *conn_guard = new_conn;
+    copy_result?;
+
+    log::info!("Restored database from {:?}", backup);
+    Ok(safety_backup.to_string_lossy().to_string())
+}
+
+/// Recovery of a corrupt database at startup, reported to the user once
+#[derive(Default)]
+pub struct DatabaseRecoveryState(pub Mutex<Option<DatabaseRecovery>>);
+
+/// Returns the startup database recovery, if any, and clears it so it is only shown once
+#[tauri::command]
+pub async fn take_database_recovery_notice(
+    state: State<'_, DatabaseRecoveryState>,
+) -> Result<Option<DatabaseRecovery>, String> {
+    let mut recovery = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(recovery.take())
+}
+
+/// Reopen the agents database, replacing the current connection even if its lock is poisoned.
+/// Re-runs the `init_database` migrations and reapplies the state derived from settings
+/// (Claude dir, relay timeouts, proxy). Lets users recover from a broken connection, or pick
+/// up a database file replaced outside the app, without restarting.
+#[tauri::command]
+pub async fn reset_db_connection(app: AppHandle) -> Result<(), String> {
+    let db_state = app.state::<AgentDb>();
+
+    // Holding the lock waits for in-flight commands and keeps new ones out until the swap
+    let mut conn_guard = db_state.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let mut new_conn =
+        init_database(&app).map_err(|e| format!("Failed to reopen database: {}", e))?;
+    reload_settings_state(&mut new_conn);
+
+    // Dropping the old connection closes it and rolls back anything left uncommitted
+    *conn_guard = new_conn;
+    db_state.0.clear_poison();
+    invalidate_row_count(None);
+
+    log::info!("Reopened agents database connection");
     Ok(())
 }
 
@@ -507,6 +929,170 @@ fn is_valid_table_name(conn: &Connection, table_name: &str) -> Result<bool, Stri
     Ok(count > 0)
 }
 
+/// Helper function to read the column definitions of a table
+fn table_columns(conn: &Connection, table_name: &str) -> Result<Vec<ColumnInfo>, String> {
+    let mut pragma_stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table_name))
+        .map_err(|e| e.to_string())?;
+
+    let columns = pragma_stmt
+        .query_map([], |row| {
+            Ok(ColumnInfo {
+                cid: row.get(0)?,
+                name: row.get(1)?,
+                type_name: row.get(2)?,
+                notnull: row.get::<_, i32>(3)? != 0,
+                dflt_value: row.get(4)?,
+                pk: row.get::<_, i32>(5)? != 0,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    Ok(columns)
+}
+
+/// SQLite type affinity derived from a declared column type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Affinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+/// Applies SQLite's affinity rules (https://www.sqlite.org/datatype3.html) to a declared type
+fn column_affinity(type_name: &str) -> Affinity {
+    let declared = type_name.to_uppercase();
+    if declared.contains("INT") {
+        Affinity::Integer
+    } else if ["CHAR", "CLOB", "TEXT"]
+        .iter()
+        .any(|t| declared.contains(t))
+    {
+        Affinity::Text
+    } else if declared.is_empty() || declared.contains("BLOB") {
+        Affinity::Blob
+    } else if ["REAL", "FLOA", "DOUB"]
+        .iter()
+        .any(|t| declared.contains(t))
+    {
+        Affinity::Real
+    } else {
+        Affinity::Numeric
+    }
+}
+
+/// Coerces a JSON value to the column's type, e.g. "42" into an INTEGER column.
+/// Empty strings become NULL for non-text columns.
+fn coerce_value(column: &ColumnInfo, value: &JsonValue) -> Result<JsonValue, String> {
+    let expected = if column.type_name.is_empty() {
+        "ANY"
+    } else {
+        column.type_name.as_str()
+    };
+    let mismatch = || format!("expects {}, got {}", expected, value);
+
+    let text = match value {
+        JsonValue::Array(_) | JsonValue::Object(_) => return Err(mismatch()),
+        JsonValue::String(text) => Some(text.trim()),
+        _ => None,
+    };
+    if matches!(text, Some("")) && column_affinity(&column.type_name) != Affinity::Text {
+        return Ok(JsonValue::Null);
+    }
+
+    match (column_affinity(&column.type_name), value) {
+        (_, JsonValue::Null) => Ok(JsonValue::Null),
+        (Affinity::Text | Affinity::Blob, _) => Ok(value.clone()),
+        (Affinity::Integer, JsonValue::Bool(b)) => Ok(JsonValue::from(*b as i64)),
+        (Affinity::Integer, JsonValue::Number(n)) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => Ok(JsonValue::from(i)),
+            (None, Some(f)) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => {
+                Ok(JsonValue::from(f as i64))
+            }
+            _ => Err(mismatch()),
+        },
+        (Affinity::Integer, JsonValue::String(_)) => text
+            .and_then(|t| t.parse::<i64>().ok())
+            .map(JsonValue::from)
+            .ok_or_else(mismatch),
+        (Affinity::Real, JsonValue::Bool(b)) => Ok(JsonValue::from(*b as i64 as f64)),
+        (Affinity::Real, JsonValue::Number(_)) => Ok(value.clone()),
+        (Affinity::Real, JsonValue::String(_)) => text
+            .and_then(|t| t.parse::<f64>().ok())
+            .and_then(serde_json::Number::from_f64)
+            .map(JsonValue::Number)
+            .ok_or_else(mismatch),
+        // BOOLEAN columns only take booleans, 0/1 and their string forms
+        (Affinity::Numeric, _) if column.type_name.to_uppercase().contains("BOOL") => match value {
+            JsonValue::Bool(b) => Ok(JsonValue::from(*b as i64)),
+            JsonValue::Number(n) if matches!(n.as_i64(), Some(0 | 1)) => Ok(value.clone()),
+            _ => match text.map(|t| t.to_lowercase()).as_deref() {
+                Some("true" | "1") => Ok(JsonValue::from(1)),
+                Some("false" | "0") => Ok(JsonValue::from(0)),
+                _ => Err(mismatch()),
+            },
+        },
+        (Affinity::Numeric, JsonValue::Bool(b)) => Ok(JsonValue::from(*b as i64)),
+        (Affinity::Numeric, _) => Ok(value.clone()),
+        (Affinity::Integer | Affinity::Real, JsonValue::Array(_) | JsonValue::Object(_)) => {
+            Err(mismatch())
+        }
+    }
+}
+
+/// What a set of column values is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowWrite {
+    Insert,
+    Update,
+    /// Primary key values identifying a row
+    Key,
+}
+
+/// Checks column names, NOT NULL constraints and types for a row write, returning the
+/// coerced values. All problems are reported at once, one line per field.
+fn validate_row_values(
+    columns: &[ColumnInfo],
+    values: &HashMap<String, JsonValue>,
+    write: RowWrite,
+) -> Result<Vec<(String, JsonValue)>, String> {
+    let mut errors = Vec::new();
+    let mut validated = Vec::new();
+
+    for (name, value) in values {
+        let Some(column) = columns.iter().find(|c| &c.name == name) else {
+            errors.push(format!("{}: unknown column", name));
+            continue;
+        };
+        match coerce_value(column, value) {
+            // An INTEGER PRIMARY KEY left NULL on insert is assigned automatically
+            Ok(JsonValue::Null) if column.notnull || (column.pk && write != RowWrite::Insert) => {
+                errors.push(format!("{}: cannot be NULL", name))
+            }
+            Ok(coerced) => validated.push((name.clone(), coerced)),
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+
+    if write == RowWrite::Insert {
+        for column in columns {
+            let required = column.notnull && column.dflt_value.is_none() && !column.pk;
+            if required && !values.contains_key(&column.name) {
+                errors.push(format!("{}: value is required", column.name));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(validated)
+    } else {
+        Err(format!("Invalid values:\n{}", errors.join("\n")))
+    }
+}
+
 /// Helper function to convert JSON value to SQL value
 fn json_to_sql_value(value: &JsonValue) -> Result<Box<dyn rusqlite::ToSql>, String> {
     match value {
@@ -526,5 +1112,284 @@ fn json_to_sql_value(value: &JsonValue) -> Result<Box<dyn rusqlite::ToSql>, Stri
     }
 }
 
+/// Helper function to convert a SQL value to JSON (blobs become base64 strings)
+fn sql_value_to_json(value: ValueRef) -> JsonValue {
+    match value {
+        ValueRef::Null => JsonValue::Null,
+        ValueRef::Integer(i) => JsonValue::Number(serde_json::Number::from(i)),
+        ValueRef::Real(f) => {
+            if let Some(n) = serde_json::Number::from_f64(f) {
+                JsonValue::Number(n)
+            } else {
+                JsonValue::String(f.to_string())
+            }
+        }
+        ValueRef::Text(s) => JsonValue::String(String::from_utf8_lossy(s).to_string()),
+        ValueRef::Blob(b) => JsonValue::String(base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            b,
+        )),
+    }
+}
+
 /// Initialize the agents database (re-exported from agents module)
 use super::agents::init_database;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bind(values: &[JsonValue]) -> Vec<Box<dyn rusqlite::ToSql>> {
+        values
+            .iter()
+            .map(|v| json_to_sql_value(v).unwrap())
+            .collect()
+    }
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE people (id INTEGER PRIMARY KEY, name TEXT, note TEXT, score REAL)",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_execute_sql_binds_quotes_and_nulls() {
+        let conn = test_db();
+        let inserted = execute_sql(
+            &conn,
+            "INSERT INTO people (name, note, score) VALUES (?, ?, ?)",
+            &bind(&[
+                json!("O'Brien \"Bob\"; DROP TABLE people; --"),
+                JsonValue::Null,
+                json!(1.5),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(inserted.rows_affected, Some(1));
+
+        let result = execute_sql(
+            &conn,
+            "SELECT name, note, score FROM people WHERE name = ? AND note IS NULL",
+            &bind(&[json!("O'Brien \"Bob\"; DROP TABLE people; --")]),
+        )
+        .unwrap();
+        assert_eq!(result.columns, vec!["name", "note", "score"]);
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                json!("O'Brien \"Bob\"; DROP TABLE people; --"),
+                JsonValue::Null,
+                json!(1.5)
+            ]]
+        );
+    }
+
+    fn column(name: &str, type_name: &str, notnull: bool, pk: bool) -> ColumnInfo {
+        ColumnInfo {
+            cid: 0,
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            notnull,
+            dflt_value: None,
+            pk,
+        }
+    }
+
+    fn write_db(path: &Path, schema: &str) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(schema).unwrap();
+    }
+
+    #[test]
+    fn test_validate_backup_accepts_agents_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.db");
+        write_db(
+            &path,
+            "CREATE TABLE agents (id INTEGER PRIMARY KEY, name TEXT);",
+        );
+        assert!(validate_backup(&path).is_ok());
+    }
+
+    #[test]
+    fn test_validate_backup_requires_agents_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.db");
+        write_db(&path, "CREATE TABLE other (id INTEGER PRIMARY KEY);");
+        let err = validate_backup(&path).unwrap_err();
+        assert!(err.contains("agents table"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_backup_rejects_non_database_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.db");
+        std::fs::write(&path, vec![0xAB; 8192]).unwrap();
+        assert!(validate_backup(&path).is_err());
+    }
+
+    #[test]
+    fn test_column_affinity() {
+        assert_eq!(column_affinity("INTEGER"), Affinity::Integer);
+        assert_eq!(column_affinity("BIGINT"), Affinity::Integer);
+        assert_eq!(column_affinity("VARCHAR(20)"), Affinity::Text);
+        assert_eq!(column_affinity(""), Affinity::Blob);
+        assert_eq!(column_affinity("DOUBLE"), Affinity::Real);
+        assert_eq!(column_affinity("BOOLEAN"), Affinity::Numeric);
+    }
+
+    #[test]
+    fn test_validate_row_values_coerces_and_reports_each_field() {
+        let columns = vec![
+            column("id", "INTEGER", false, true),
+            column("name", "TEXT", true, false),
+            column("count", "INTEGER", false, false),
+            column("enabled", "BOOLEAN", false, false),
+        ];
+
+        let values = HashMap::from([
+            ("name".to_string(), json!("Agent")),
+            ("count".to_string(), json!(" 42 ")),
+            ("enabled".to_string(), json!(true)),
+        ]);
+        let mut validated = validate_row_values(&columns, &values, RowWrite::Insert).unwrap();
+        validated.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            validated,
+            vec![
+                ("count".to_string(), json!(42)),
+                ("enabled".to_string(), json!(1)),
+                ("name".to_string(), json!("Agent")),
+            ]
+        );
+
+        let values = HashMap::from([
+            ("name".to_string(), JsonValue::Null),
+            ("count".to_string(), json!("many")),
+            ("enabled".to_string(), json!("maybe")),
+            ("bogus".to_string(), json!(1)),
+        ]);
+        let err = validate_row_values(&columns, &values, RowWrite::Update).unwrap_err();
+        assert!(err.contains("name: cannot be NULL"));
+        assert!(err.contains("count: expects INTEGER, got \"many\""));
+        assert!(err.contains("enabled: expects BOOLEAN"));
+        assert!(err.contains("bogus: unknown column"));
+
+        let err = validate_row_values(&columns, &HashMap::new(), RowWrite::Insert).unwrap_err();
+        assert!(err.contains("name: value is required"));
+    }
+
+    #[test]
+    fn test_execute_batch_rolls_back_on_failure() {
+        let mut conn = test_db();
+        let insert = |name: &str| StorageOp::Insert {
+            table_name: "people".to_string(),
+            values: HashMap::from([("name".to_string(), json!(name))]),
+        };
+
+        let err = execute_batch(
+            &mut conn,
+            &[
+                insert("Ada"),
+                StorageOp::Update {
+                    table_name: "people".to_string(),
+                    primary_key_values: HashMap::from([("id".to_string(), json!(1))]),
+                    updates: HashMap::from([("score".to_string(), json!("high"))]),
+                },
+            ],
+        )
+        .unwrap_err();
+        assert!(err.starts_with("Operation 2 failed"));
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM people", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+
+        let results = execute_batch(
+            &mut conn,
+            &[
+                insert("Ada"),
+                insert("Grace"),
+                StorageOp::Delete {
+                    table_name: "people".to_string(),
+                    primary_key_values: HashMap::from([("id".to_string(), json!(1))]),
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(results[1].last_insert_rowid, Some(2));
+        assert_eq!(results[2].rows_affected, 1);
+        let names: Vec<String> = conn
+            .prepare("SELECT name FROM people")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<SqliteResult<_>>()
+            .unwrap();
+        assert_eq!(names, vec!["Grace".to_string()]);
+    }
+
+    #[test]
+    fn test_execute_batch_rejects_unknown_delete_keys() {
+        let mut conn = test_db();
+        let insert = StorageOp::Insert {
+            table_name: "people".to_string(),
+            values: HashMap::from([("name".to_string(), json!("Ada"))]),
+        };
+        execute_batch(&mut conn, &[insert]).unwrap();
+
+        let delete = |key: &str| StorageOp::Delete {
+            table_name: "people".to_string(),
+            primary_key_values: HashMap::from([(key.to_string(), json!(1))]),
+        };
+        let err = execute_batch(&mut conn, &[delete("1=1 OR id")]).unwrap_err();
+        assert!(err.contains("unknown column"));
+
+        let err = execute_batch(
+            &mut conn,
+            &[StorageOp::Delete {
+                table_name: "people".to_string(),
+                primary_key_values: HashMap::new(),
+            }],
+        )
+        .unwrap_err();
+        assert!(err.contains("Primary key values are required"));
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM people", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_read_table_page_follows_cursor() {
+        let conn = test_db();
+        for name in ["a", "b", "c", "d", "e"] {
+            conn.execute("INSERT INTO people (name) VALUES (?1)", [name])
+                .unwrap();
+        }
+
+        let first = read_table_page(&conn, "people", None, 2).unwrap();
+        assert_eq!(first.rows.len(), 2);
+        assert_eq!(first.next_cursor, Some(2));
+        assert_eq!(first.total_rows, 5);
+        assert_eq!(first.total_pages, 3);
+
+        let last = read_table_page(&conn, "people", Some(4), 2).unwrap();
+        assert_eq!(last.rows.len(), 1);
+        assert_eq!(last.rows[0]["name"], json!("e"));
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn test_execute_sql_rejects_wrong_parameter_count() {
+        let conn = test_db();
+        assert!(execute_sql(&conn, "SELECT * FROM people WHERE id = ?", &[]).is_err());
+        assert!(json_to_sql_value(&json!({"nested": true})).is_err());
+    }
+}
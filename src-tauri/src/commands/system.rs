@@ -1,4 +1,20 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use tauri::{AppHandle, Manager, State};
+
+use super::agents::AgentDb;
+
+/// Default number of log lines returned by `read_recent_logs`
+const DEFAULT_LOG_TAIL_LINES: usize = 200;
+/// Upper bound on the number of log lines returned at once
+const MAX_LOG_TAIL_LINES: usize = 5000;
+
+/// app_settings key holding the preferred editor command or path
+pub const EDITOR_SETTING_KEY: &str = "editor_command";
+/// Editor tried when no preference is set
+const DEFAULT_EDITOR: &str = "code";
 
 /// Flush system DNS cache across platforms
 #[tauri::command]
@@ -73,3 +89,215 @@ pub async fn flush_dns() -> Result<String, String> {
         Err("No supported DNS flush method succeeded on this Linux system".into())
     }
 }
+
+/// Directory tauri-plugin-log writes the app logs to
+pub(crate) fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))
+}
+
+/// The most recently modified `.log` file, i.e. the one currently written after rotation
+pub(crate) fn newest_log_file(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("log"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Read the last `lines` lines of a file without loading all of it
+pub(crate) fn read_tail_lines(path: &Path, lines: usize) -> std::io::Result<String> {
+    const CHUNK: u64 = 64 * 1024;
+
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut start = len;
+    let mut buffer: Vec<u8> = Vec::new();
+
+    // Walk backwards until the buffer holds more line breaks than requested lines
+    while start > 0 && buffer.iter().filter(|b| **b == b'\n').count() <= lines {
+        let read_from = start.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (start - read_from) as usize];
+        file.seek(SeekFrom::Start(read_from))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+        start = read_from;
+    }
+
+    let text = String::from_utf8_lossy(&buffer);
+    let all: Vec<&str> = text.lines().collect();
+    let skip = all.len().saturating_sub(lines);
+    Ok(all[skip..].join("\n"))
+}
+
+/// Get the directory containing the app's log files
+#[tauri::command]
+pub async fn get_log_dir_path(app: AppHandle) -> Result<String, String> {
+    Ok(log_dir(&app)?.to_string_lossy().to_string())
+}
+
+/// Read the last lines of the current app log file
+#[tauri::command]
+pub async fn read_recent_logs(app: AppHandle, lines: Option<usize>) -> Result<String, String> {
+    let lines = lines
+        .unwrap_or(DEFAULT_LOG_TAIL_LINES)
+        .clamp(1, MAX_LOG_TAIL_LINES);
+    let dir = log_dir(&app)?;
+    let Some(path) = newest_log_file(&dir) else {
+        return Ok(String::new());
+    };
+    read_tail_lines(&path, lines).map_err(|e| format!("Failed to read log file: {}", e))
+}
+
+/// Open the log directory in the system file manager
+#[tauri::command]
+pub async fn reveal_log_dir(app: AppHandle) -> Result<(), String> {
+    let dir = log_dir(&app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+
+    Command::new(system_open_program())
+        .arg(&dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to open log directory: {}", e))?;
+    Ok(())
+}
+
+/// The OS command that opens a file or directory with its default application
+fn system_open_program() -> &'static str {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    let program = "xdg-open";
+    program
+}
+
+/// Finds an editor executable, either as a path or by name in the given PATH-style list
+fn resolve_editor(editor: &str, search_path: &str) -> Option<PathBuf> {
+    let as_path = Path::new(editor);
+    if as_path.components().count() > 1 {
+        return as_path.is_file().then(|| as_path.to_path_buf());
+    }
+
+    #[cfg(target_os = "windows")]
+    let names = [
+        format!("{}.cmd", editor),
+        format!("{}.exe", editor),
+        editor.to_string(),
+    ];
+    #[cfg(not(target_os = "windows"))]
+    let names = [editor.to_string()];
+
+    std::env::split_paths(search_path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Open a directory in the user's editor: the given editor, else the `editor_command`
+/// preference, else VS Code, else the OS default application
+#[tauri::command]
+pub async fn open_in_editor(
+    path: String,
+    editor: Option<String>,
+    db: State<'_, AgentDb>,
+) -> Result<(), String> {
+    if !Path::new(&path).exists() {
+        return Err(format!("Path does not exist: {}", path));
+    }
+
+    let preference = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            rusqlite::params![EDITOR_SETTING_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+    };
+    let requested = editor
+        .or(preference)
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty());
+
+    // Packaged apps start with a minimal PATH, so search the same locations as for claude
+    let search_path = crate::claude_binary::build_enhanced_path();
+    let program = match &requested {
+        Some(editor) => Some(resolve_editor(editor, &search_path).ok_or_else(|| {
+            format!(
+                "Editor '{}' was not found. Install its command-line launcher (for VS Code, run \
+                 \"Shell Command: Install 'code' command in PATH\") or set the '{}' setting to \
+                 the editor's full path.",
+                editor, EDITOR_SETTING_KEY
+            )
+        })?),
+        None => resolve_editor(DEFAULT_EDITOR, &search_path),
+    };
+
+    let mut cmd = match &program {
+        Some(program) => {
+            log::info!("Opening {} in editor {:?}", path, program);
+            Command::new(program)
+        }
+        None => {
+            log::info!("No editor found, opening {} with the system default", path);
+            Command::new(system_open_program())
+        }
+    };
+    cmd.arg(&path)
+        .env("PATH", &search_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_tail_lines_returns_last_lines() {
+        let path = std::env::temp_dir().join(format!("claudia-tail-{}.log", std::process::id()));
+        let content: String = (1..=1000).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(&path, content).unwrap();
+
+        let tail = read_tail_lines(&path, 3).unwrap();
+        let all = read_tail_lines(&path, 5000).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(tail, "line 998\nline 999\nline 1000");
+        assert_eq!(all.lines().count(), 1000);
+    }
+
+    #[test]
+    fn test_resolve_editor_searches_path_list() {
+        let dir = std::env::temp_dir().join(format!("claudia-editor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let editor = dir.join("my-editor");
+        std::fs::write(&editor, "").unwrap();
+
+        let search_path = std::env::join_paths([Path::new("/nonexistent"), dir.as_path()])
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        let by_name = resolve_editor("my-editor", &search_path);
+        let by_path = resolve_editor(&editor.to_string_lossy(), "");
+        let missing = resolve_editor("no-such-editor", &search_path);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(by_name, Some(editor.clone()));
+        assert_eq!(by_path, Some(editor));
+        assert_eq!(missing, None);
+    }
+}
@@ -6,6 +6,8 @@ use std::fs;
 use std::path::PathBuf;
 use tauri::command;
 
+use crate::utils::jsonl::{self, JsonlStats};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UsageEntry {
     pub timestamp: String,
@@ -19,7 +21,7 @@ pub struct UsageEntry {
     pub project_path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UsageStats {
     pub total_cost: f64,
     pub total_tokens: u64,
@@ -33,7 +35,7 @@ pub struct UsageStats {
     pub by_project: Vec<ProjectUsage>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelUsage {
     pub model: String,
     pub total_cost: f64,
@@ -45,7 +47,7 @@ pub struct ModelUsage {
     pub session_count: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyUsage {
     pub date: String,
     pub total_cost: f64,
@@ -59,7 +61,7 @@ pub struct DailyUsage {
     pub models_used: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProjectUsage {
     pub project_path: String,
     pub project_name: String,
@@ -174,6 +176,25 @@ fn calculate_cost(model: &str, usage: &UsageData) -> f64 {
     cost
 }
 
+// 按模型估算一组令牌用量的成本（供会话实时指标使用）
+pub(crate) fn estimate_cost(
+    model: &str,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+) -> f64 {
+    calculate_cost(
+        model,
+        &UsageData {
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(output_tokens),
+            cache_creation_input_tokens: Some(cache_creation_tokens),
+            cache_read_input_tokens: Some(cache_read_tokens),
+        },
+    )
+}
+
 // 独立的模型价格匹配函数，更精确的模型识别
 fn match_model_prices(model_lower: &str) -> (f64, f64, f64, f64) {
     // Claude Opus 系列
@@ -303,158 +324,153 @@ pub fn parse_jsonl_file(
     encoded_project_name: &str,
     processed_hashes: &mut HashSet<String>,
 ) -> Vec<UsageEntry> {
+    parse_jsonl_file_with_stats(path, encoded_project_name, processed_hashes).0
+}
+
+/// 流式解析单个 JSONL 文件，同时返回逐行统计（超长行不会被读入内存）
+pub fn parse_jsonl_file_with_stats(
+    path: &PathBuf,
+    encoded_project_name: &str,
+    processed_hashes: &mut HashSet<String>,
+) -> (Vec<UsageEntry>, JsonlStats) {
     let mut entries = Vec::new();
     let mut actual_project_path: Option<String> = None;
 
-    if let Ok(content) = fs::read_to_string(path) {
-        // Extract session ID from the file path
-        let session_id = path
-            .parent()
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-
-        for line in content.lines() {
-            if line.trim().is_empty() {
-                continue;
+    // Extract session ID from the file path
+    let session_id = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let result = jsonl::for_each_json_line_in_file(path, |_, json_value| {
+        // Extract the actual project path from cwd if we haven't already
+        if actual_project_path.is_none() {
+            if let Some(cwd) = json_value.get("cwd").and_then(|v| v.as_str()) {
+                actual_project_path = Some(cwd.to_string());
             }
+        }
 
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(line) {
-                // Extract the actual project path from cwd if we haven't already
-                if actual_project_path.is_none() {
-                    if let Some(cwd) = json_value.get("cwd").and_then(|v| v.as_str()) {
-                        actual_project_path = Some(cwd.to_string());
+        // Try to parse as JsonlEntry for usage data
+        if let Ok(entry) = serde_json::from_value::<JsonlEntry>(json_value) {
+            if let Some(message) = &entry.message {
+                if let Some(usage) = &message.usage {
+                    // 跳过所有令牌数为0的记录（根据文档规范）
+                    let has_tokens = usage.input_tokens.unwrap_or(0) > 0
+                        || usage.output_tokens.unwrap_or(0) > 0
+                        || usage.cache_creation_input_tokens.unwrap_or(0) > 0
+                        || usage.cache_read_input_tokens.unwrap_or(0) > 0;
+
+                    if !has_tokens {
+                        return;
                     }
-                }
-
-                // Try to parse as JsonlEntry for usage data
-                if let Ok(entry) = serde_json::from_value::<JsonlEntry>(json_value) {
-                    if let Some(message) = &entry.message {
-                        if let Some(usage) = &message.usage {
-                            // 跳过所有令牌数为0的记录（根据文档规范）
-                            let has_tokens = usage.input_tokens.unwrap_or(0) > 0
-                                || usage.output_tokens.unwrap_or(0) > 0
-                                || usage.cache_creation_input_tokens.unwrap_or(0) > 0
-                                || usage.cache_read_input_tokens.unwrap_or(0) > 0;
-
-                            if !has_tokens {
-                                continue;
-                            }
 
-                            // 智能去重策略
-                            let has_io_tokens = usage.input_tokens.unwrap_or(0) > 0
-                                || usage.output_tokens.unwrap_or(0) > 0;
-                            let has_cache_tokens = usage.cache_creation_input_tokens.unwrap_or(0)
-                                > 0
-                                || usage.cache_read_input_tokens.unwrap_or(0) > 0;
-
-                            let should_skip = if has_io_tokens {
-                                // 输入输出令牌：使用 session_id + message_id 严格去重
-                                if let Some(msg_id) = &message.id {
-                                    let unique_hash = format!("io:{}:{}", &session_id, msg_id);
-                                    if processed_hashes.contains(&unique_hash) {
-                                        true
-                                    } else {
-                                        processed_hashes.insert(unique_hash);
-                                        false
-                                    }
-                                } else {
-                                    false
-                                }
-                            } else if has_cache_tokens {
-                                // 缓存令牌：使用 message_id + request_id 宽松去重
-                                if let (Some(msg_id), Some(req_id)) =
-                                    (&message.id, &entry.request_id)
-                                {
-                                    let unique_hash = format!("cache:{}:{}", msg_id, req_id);
-                                    if processed_hashes.contains(&unique_hash) {
-                                        true
-                                    } else {
-                                        processed_hashes.insert(unique_hash);
-                                        false
-                                    }
-                                } else {
-                                    false
-                                }
+                    // 智能去重策略
+                    let has_io_tokens =
+                        usage.input_tokens.unwrap_or(0) > 0 || usage.output_tokens.unwrap_or(0) > 0;
+                    let has_cache_tokens = usage.cache_creation_input_tokens.unwrap_or(0) > 0
+                        || usage.cache_read_input_tokens.unwrap_or(0) > 0;
+
+                    let should_skip = if has_io_tokens {
+                        // 输入输出令牌：使用 session_id + message_id 严格去重
+                        if let Some(msg_id) = &message.id {
+                            let unique_hash = format!("io:{}:{}", &session_id, msg_id);
+                            if processed_hashes.contains(&unique_hash) {
+                                true
                             } else {
+                                processed_hashes.insert(unique_hash);
                                 false
-                            };
-
-                            if should_skip {
-                                continue;
                             }
-
-                            // 始终重新计算成本，不信任JSONL中的costUSD字段
-                            // 因为可能存在价格变化或计算错误
-                            let cost = if let Some(model_str) = &message.model {
-                                calculate_cost(model_str, usage)
+                        } else {
+                            false
+                        }
+                    } else if has_cache_tokens {
+                        // 缓存令牌：使用 message_id + request_id 宽松去重
+                        if let (Some(msg_id), Some(req_id)) = (&message.id, &entry.request_id) {
+                            let unique_hash = format!("cache:{}:{}", msg_id, req_id);
+                            if processed_hashes.contains(&unique_hash) {
+                                true
                             } else {
-                                0.0
-                            };
-
-                            // Use actual project path if found, otherwise use encoded name
-                            let project_path = actual_project_path
-                                .clone()
-                                .unwrap_or_else(|| encoded_project_name.to_string());
-
-                            // 转换时间戳为本地时间格式
-                            let local_timestamp =
-                                if let Ok(dt) = DateTime::parse_from_rfc3339(&entry.timestamp) {
-                                    // 转换为本地时区并格式化为 ISO 格式
-                                    dt.with_timezone(&Local)
-                                        .format("%Y-%m-%d %H:%M:%S%.3f")
-                                        .to_string()
-                                } else {
-                                    // 如果解析失败，保留原始时间戳
-                                    entry.timestamp.clone()
-                                };
-
-                            entries.push(UsageEntry {
-                                timestamp: local_timestamp,
-                                model: message
-                                    .model
-                                    .clone()
-                                    .unwrap_or_else(|| "unknown".to_string()),
-                                input_tokens: usage.input_tokens.unwrap_or(0),
-                                output_tokens: usage.output_tokens.unwrap_or(0),
-                                cache_creation_tokens: usage
-                                    .cache_creation_input_tokens
-                                    .unwrap_or(0),
-                                cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
-                                cost,
-                                session_id: entry.session_id.unwrap_or_else(|| session_id.clone()),
-                                project_path,
-                            });
+                                processed_hashes.insert(unique_hash);
+                                false
+                            }
+                        } else {
+                            false
                         }
+                    } else {
+                        false
+                    };
+
+                    if should_skip {
+                        return;
                     }
+
+                    // 始终重新计算成本，不信任JSONL中的costUSD字段
+                    // 因为可能存在价格变化或计算错误
+                    let cost = if let Some(model_str) = &message.model {
+                        calculate_cost(model_str, usage)
+                    } else {
+                        0.0
+                    };
+
+                    // Use actual project path if found, otherwise use encoded name
+                    let project_path = actual_project_path
+                        .clone()
+                        .unwrap_or_else(|| encoded_project_name.to_string());
+
+                    // 转换时间戳为本地时间格式
+                    let local_timestamp =
+                        if let Ok(dt) = DateTime::parse_from_rfc3339(&entry.timestamp) {
+                            // 转换为本地时区并格式化为 ISO 格式
+                            dt.with_timezone(&Local)
+                                .format("%Y-%m-%d %H:%M:%S%.3f")
+                                .to_string()
+                        } else {
+                            // 如果解析失败，保留原始时间戳
+                            entry.timestamp.clone()
+                        };
+
+                    entries.push(UsageEntry {
+                        timestamp: local_timestamp,
+                        model: message
+                            .model
+                            .clone()
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        input_tokens: usage.input_tokens.unwrap_or(0),
+                        output_tokens: usage.output_tokens.unwrap_or(0),
+                        cache_creation_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                        cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                        cost,
+                        session_id: entry.session_id.unwrap_or_else(|| session_id.clone()),
+                        project_path,
+                    });
                 }
             }
         }
-    }
+    });
 
-    entries
+    let stats = result.unwrap_or_else(|e| {
+        log::warn!("Failed to read usage file {:?}: {}", path, e);
+        JsonlStats::default()
+    });
+    (entries, stats)
 }
 
 fn get_earliest_timestamp(path: &PathBuf) -> Option<String> {
-    if let Ok(content) = fs::read_to_string(path) {
-        let mut earliest_timestamp: Option<String> = None;
-        for line in content.lines() {
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(line) {
-                if let Some(timestamp_str) = json_value.get("timestamp").and_then(|v| v.as_str()) {
-                    if let Some(current_earliest) = &earliest_timestamp {
-                        if timestamp_str < current_earliest.as_str() {
-                            earliest_timestamp = Some(timestamp_str.to_string());
-                        }
-                    } else {
-                        earliest_timestamp = Some(timestamp_str.to_string());
-                    }
-                }
+    let mut earliest_timestamp: Option<String> = None;
+    jsonl::for_each_json_line_in_file(path, |_, json_value| {
+        if let Some(timestamp_str) = json_value.get("timestamp").and_then(|v| v.as_str()) {
+            if earliest_timestamp
+                .as_deref()
+                .is_none_or(|current| timestamp_str < current)
+            {
+                earliest_timestamp = Some(timestamp_str.to_string());
             }
         }
-        return earliest_timestamp;
-    }
-    None
+    })
+    .ok()?;
+    earliest_timestamp
 }
 
 pub fn get_all_usage_entries(claude_path: &PathBuf) -> Vec<UsageEntry> {
@@ -498,9 +514,7 @@ pub fn get_all_usage_entries(claude_path: &PathBuf) -> Vec<UsageEntry> {
 
 #[command]
 pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     let all_entries = get_all_usage_entries(&claude_path);
 
@@ -726,9 +740,7 @@ pub fn get_usage_stats(days: Option<u32>) -> Result<UsageStats, String> {
 
 #[command]
 pub fn get_usage_by_date_range(start_date: String, end_date: String) -> Result<UsageStats, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     let all_entries = get_all_usage_entries(&claude_path);
 
@@ -965,9 +977,7 @@ pub fn get_usage_details(
     project_path: Option<String>,
     date: Option<String>,
 ) -> Result<Vec<UsageEntry>, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     let mut all_entries = get_all_usage_entries(&claude_path);
 
@@ -1003,9 +1013,7 @@ pub fn get_session_stats(
     until: Option<String>,
     order: Option<String>,
 ) -> Result<Vec<ProjectUsage>, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     let all_entries = get_all_usage_entries(&claude_path);
 
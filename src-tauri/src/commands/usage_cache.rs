@@ -1,15 +1,21 @@
 use chrono::{Local, Utc};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::{command, State};
 use walkdir::WalkDir;
 
+use crate::utils::jsonl::{self, JsonlStats};
+use crate::utils::sqlite;
+
 use super::usage::{
-    parse_jsonl_file, DailyUsage, ModelUsage, ProjectUsage, UsageEntry, UsageStats,
+    parse_jsonl_file_with_stats, DailyUsage, ModelUsage, ProjectUsage, UsageEntry, UsageStats,
 };
 
 #[derive(Default)]
@@ -25,6 +31,8 @@ pub struct ScanResult {
     pub entries_added: u32,
     pub entries_skipped: u32,
     pub scan_time_ms: u64,
+    /// Line statistics summed over the scanned files
+    pub line_stats: JsonlStats,
 }
 
 fn db_path() -> PathBuf {
@@ -44,8 +52,22 @@ pub fn init_cache_db() -> rusqlite::Result<Connection> {
     let path = db_path();
     ensure_parent_dir(&path).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
-    let conn = Connection::open(path)?;
+    let conn = sqlite::open_connection(path)?;
     conn.pragma_update(None, "journal_mode", &"WAL")?;
+    create_cache_schema(&conn)?;
+    Ok(conn)
+}
+
+fn create_cache_schema(conn: &Connection) -> rusqlite::Result<()> {
+    let table_exists = |name: &str| -> rusqlite::Result<bool> {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = ?1",
+            [name],
+            |row| row.get(0),
+        )
+    };
+    let has_message_index = table_exists("message_fts")?;
+    let has_message_files = table_exists("message_fts_files")?;
 
     // Create schema
     conn.execute_batch(
@@ -83,10 +105,147 @@ pub fn init_cache_db() -> rusqlite::Result<Connection> {
         CREATE INDEX IF NOT EXISTS idx_entries_project ON usage_entries(project_path);
         CREATE INDEX IF NOT EXISTS idx_entries_hash ON usage_entries(unique_hash);
         CREATE INDEX IF NOT EXISTS idx_entries_model ON usage_entries(model);
+
+        -- Full-text index over session message content
+        CREATE VIRTUAL TABLE IF NOT EXISTS message_fts USING fts5(
+          content,
+          role UNINDEXED,
+          project_id UNINDEXED,
+          session_id UNINDEXED,
+          message_index UNINDEXED,
+          timestamp UNINDEXED,
+          file_path UNINDEXED,
+          tokenize = 'unicode61'
+        );
+
+        -- Maps each indexed message to its file, so a file's messages are deleted by rowid
+        CREATE TABLE IF NOT EXISTS message_fts_files (
+          fts_rowid INTEGER PRIMARY KEY,
+          file_path TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_message_fts_files_path ON message_fts_files(file_path);
         "#,
     )?;
 
-    Ok(conn)
+    // Files scanned before the message index (or its file mapping) existed must be read
+    // again to populate it
+    if !has_message_index || !has_message_files {
+        conn.execute("DELETE FROM message_fts", [])?;
+        conn.execute("DELETE FROM scanned_files", [])?;
+    }
+
+    Ok(())
+}
+
+/// Adds one message of `file_path` to the full-text index
+fn index_message(
+    conn: &Connection,
+    message: &SearchableMessage,
+    project_id: &str,
+    session_id: &str,
+    file_path: &str,
+) -> rusqlite::Result<()> {
+    conn.prepare_cached(
+        "INSERT INTO message_fts (content, role, project_id, session_id, message_index, timestamp, file_path)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?
+    .execute(params![
+        message.text,
+        message.role,
+        project_id,
+        session_id,
+        message.index,
+        message.timestamp,
+        file_path,
+    ])?;
+    conn.prepare_cached("INSERT INTO message_fts_files (fts_rowid, file_path) VALUES (?1, ?2)")?
+        .execute(params![conn.last_insert_rowid(), file_path])?;
+    Ok(())
+}
+
+/// Removes the messages of `file_path` from the full-text index. `file_path` is an
+/// unindexed FTS column, so rows are looked up through `message_fts_files` and deleted by rowid.
+fn delete_indexed_messages(conn: &Connection, file_path: &str) -> rusqlite::Result<()> {
+    conn.prepare_cached(
+        "DELETE FROM message_fts WHERE rowid IN
+           (SELECT fts_rowid FROM message_fts_files WHERE file_path = ?1)",
+    )?
+    .execute(params![file_path])?;
+    conn.prepare_cached("DELETE FROM message_fts_files WHERE file_path = ?1")?
+        .execute(params![file_path])?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionSearchResult {
+    pub project_id: String,
+    pub session_id: String,
+    pub message_index: i64,
+    pub timestamp: Option<String>,
+    pub role: String,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+/// A user/assistant message extracted from a session JSONL for the search index
+struct SearchableMessage {
+    index: i64,
+    timestamp: Option<String>,
+    role: String,
+    text: String,
+}
+
+/// Extracts the text of user and assistant messages (line index is the message index)
+fn extract_searchable_messages(path: &Path) -> Vec<SearchableMessage> {
+    let mut messages = Vec::new();
+    let result = jsonl::for_each_json_line_in_file(path, |index, json| {
+        let role = match json.get("type").and_then(|t| t.as_str()) {
+            Some(role @ ("user" | "assistant")) => role.to_string(),
+            _ => return,
+        };
+
+        let text = match json.get("message").and_then(|m| m.get("content")) {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(serde_json::Value::Array(blocks)) => blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => return,
+        };
+        if text.trim().is_empty() {
+            return;
+        }
+
+        messages.push(SearchableMessage {
+            index: index as i64,
+            timestamp: json
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .map(|t| t.to_string()),
+            role,
+            text,
+        });
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to read session file {:?}: {}", path, e);
+    }
+    messages
+}
+
+/// Turns free-form user input into an FTS5 query matching all terms (quoted, so
+/// FTS syntax characters in the input are treated literally)
+pub(crate) fn build_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
 }
 
 fn get_file_mtime_ms(path: &Path) -> i64 {
@@ -121,6 +280,150 @@ fn generate_unique_hash(entry: &UsageEntry, has_io_tokens: bool, has_cache_token
     }
 }
 
+const USAGE_ENTRY_INSERT_SQL: &str = "INSERT INTO usage_entries (
+        timestamp, model, input_tokens, output_tokens,
+        cache_creation_tokens, cache_read_tokens, cost,
+        session_id, project_path, file_path, unique_hash
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+    ON CONFLICT(unique_hash) DO NOTHING";
+
+/// Inserts one usage entry with a statement prepared from `USAGE_ENTRY_INSERT_SQL`;
+/// returns 0 when an entry with the same hash already exists
+fn insert_usage_entry(
+    stmt: &mut rusqlite::Statement,
+    entry: &UsageEntry,
+    file_path: &str,
+) -> rusqlite::Result<usize> {
+    let has_io_tokens = entry.input_tokens > 0 || entry.output_tokens > 0;
+    let has_cache_tokens = entry.cache_creation_tokens > 0 || entry.cache_read_tokens > 0;
+    let unique_hash = generate_unique_hash(entry, has_io_tokens, has_cache_tokens);
+
+    stmt.execute(params![
+        entry.timestamp,
+        entry.model,
+        entry.input_tokens as i64,
+        entry.output_tokens as i64,
+        entry.cache_creation_tokens as i64,
+        entry.cache_read_tokens as i64,
+        entry.cost,
+        entry.session_id,
+        entry.project_path,
+        file_path,
+        unique_hash,
+    ])
+}
+
+/// Upper bound on threads used to walk and parse session files
+const MAX_SCAN_WORKERS: usize = 8;
+/// Files parsed per batch; each batch is written before the next is parsed to bound memory
+const SCAN_BATCH_FILES: usize = 64;
+
+/// A session file that changed since the last scan
+struct ScanCandidate {
+    path: PathBuf,
+    project_name: String,
+    file_size: i64,
+    mtime_ms: i64,
+}
+
+/// Usage entries and searchable messages parsed from one session file
+struct ParsedFile {
+    entries: Vec<UsageEntry>,
+    messages: Vec<SearchableMessage>,
+    stats: JsonlStats,
+}
+
+fn scan_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_SCAN_WORKERS)
+}
+
+/// Applies `f` to every item on up to `workers` threads, returning results in input order
+fn parallel_map<T, R, F>(items: &[T], workers: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    if workers <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = items.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.min(items.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for handle in handles {
+            // A panicking worker leaves its slots empty; they are redone below
+            if let Ok(done) = handle.join() {
+                for (index, result) in done {
+                    results[index] = Some(result);
+                }
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .zip(items)
+        .map(|(result, item)| result.unwrap_or_else(|| f(item)))
+        .collect()
+}
+
+/// Lists the session files of each project directory with their size and mtime
+fn collect_session_files(project_dirs: &[(String, PathBuf)], workers: usize) -> Vec<ScanCandidate> {
+    parallel_map(project_dirs, workers, |(project_name, project_path)| {
+        WalkDir::new(project_path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+            .map(|entry| {
+                let path = entry.path().to_path_buf();
+                ScanCandidate {
+                    file_size: get_file_size(&path),
+                    mtime_ms: get_file_mtime_ms(&path),
+                    project_name: project_name.clone(),
+                    path,
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Parses session files in parallel. Duplicate messages are only tracked within a file;
+/// duplicates across files are dropped by the `unique_hash` constraint on insert.
+fn parse_session_files(files: &[ScanCandidate], workers: usize) -> Vec<ParsedFile> {
+    parallel_map(files, workers, |file| {
+        let mut processed_hashes = HashSet::new();
+        let (entries, stats) =
+            parse_jsonl_file_with_stats(&file.path, &file.project_name, &mut processed_hashes);
+        ParsedFile {
+            entries,
+            messages: extract_searchable_messages(&file.path),
+            stats,
+        }
+    })
+}
+
 #[command]
 pub async fn usage_scan_update(state: State<'_, UsageCacheState>) -> Result<ScanResult, String> {
     // 检查是否正在扫描
@@ -132,6 +435,7 @@ pub async fn usage_scan_update(state: State<'_, UsageCacheState>) -> Result<Scan
                 entries_added: 0,
                 entries_skipped: 0,
                 scan_time_ms: 0,
+                line_stats: JsonlStats::default(),
             });
         }
         *is_scanning = true;
@@ -163,9 +467,7 @@ pub async fn usage_scan_update(state: State<'_, UsageCacheState>) -> Result<Scan
     }
     let conn = conn_guard.as_mut().unwrap();
 
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
 
     let projects_dir = claude_path.join("projects");
 
@@ -192,108 +494,119 @@ pub async fn usage_scan_update(state: State<'_, UsageCacheState>) -> Result<Scan
         }
     }
 
-    // Find all .jsonl files
-    let mut files_to_process = Vec::new();
-    let mut all_current_files = HashSet::new();
-
-    if let Ok(projects) = fs::read_dir(&projects_dir) {
-        for project in projects.flatten() {
-            if project.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-                let project_name = project.file_name().to_string_lossy().to_string();
-                let project_path = project.path();
+    // Find all .jsonl files, walking project directories in parallel
+    let workers = scan_workers();
+    let project_dirs: Vec<(String, PathBuf)> = fs::read_dir(&projects_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|project| project.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|project| {
+            (
+                project.file_name().to_string_lossy().to_string(),
+                project.path(),
+            )
+        })
+        .collect();
 
-                WalkDir::new(&project_path)
-                    .into_iter()
-                    .filter_map(Result::ok)
-                    .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
-                    .for_each(|entry| {
-                        let path = entry.path().to_path_buf();
-                        let path_str = path.to_string_lossy().to_string();
-                        all_current_files.insert(path_str.clone());
-
-                        // Check if file needs processing
-                        let current_size = get_file_size(&path);
-                        let current_mtime = get_file_mtime_ms(&path);
-
-                        let needs_processing = if let Some((stored_size, stored_mtime)) =
-                            existing_files.get(&path_str)
-                        {
-                            current_size != *stored_size || current_mtime != *stored_mtime
-                        } else {
-                            true // New file
-                        };
+    let mut all_current_files = HashSet::new();
+    let mut files_to_process = Vec::new();
+    for candidate in collect_session_files(&project_dirs, workers) {
+        let path_str = candidate.path.to_string_lossy().to_string();
 
-                        if needs_processing {
-                            files_to_process.push((path, project_name.clone()));
-                        }
-                    });
+        // Check if file needs processing
+        let needs_processing = match existing_files.get(&path_str) {
+            Some((stored_size, stored_mtime)) => {
+                candidate.file_size != *stored_size || candidate.mtime_ms != *stored_mtime
             }
+            None => true, // New file
+        };
+        all_current_files.insert(path_str);
+
+        if needs_processing {
+            files_to_process.push(candidate);
         }
     }
 
     let mut files_scanned = 0u32;
     let mut entries_added = 0u32;
     let mut entries_skipped = 0u32;
+    let mut line_stats = JsonlStats::default();
 
     // Process files that need updating
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let tx = sqlite::begin_immediate(conn, "usage scan").map_err(|e| e.to_string())?;
 
-    for (file_path, project_name) in files_to_process {
-        let path_str = file_path.to_string_lossy().to_string();
-        let file_size = get_file_size(&file_path);
-        let mtime_ms = get_file_mtime_ms(&file_path);
+    {
+        // Prepare the per-file and per-entry statements once for the whole scan
+        let mut upsert_file = tx
+            .prepare(
+                "INSERT INTO scanned_files (file_path, file_size, mtime_ms, last_scanned_ms, entry_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(file_path) DO UPDATE SET
+                    file_size = excluded.file_size,
+                    mtime_ms = excluded.mtime_ms,
+                    last_scanned_ms = excluded.last_scanned_ms,
+                    entry_count = excluded.entry_count",
+            )
+            .map_err(|e| e.to_string())?;
+        let mut insert_entry = tx
+            .prepare(USAGE_ENTRY_INSERT_SQL)
+            .map_err(|e| e.to_string())?;
 
-        // Parse the JSONL file and get entries
-        let mut processed_hashes = HashSet::new();
-        let entries = parse_jsonl_file(&file_path, &project_name, &mut processed_hashes);
-
-        // Insert or update file record
-        tx.execute(
-            "INSERT INTO scanned_files (file_path, file_size, mtime_ms, last_scanned_ms, entry_count) 
-             VALUES (?1, ?2, ?3, ?4, ?5)
-             ON CONFLICT(file_path) DO UPDATE SET 
-                file_size = excluded.file_size,
-                mtime_ms = excluded.mtime_ms,
-                last_scanned_ms = excluded.last_scanned_ms,
-                entry_count = excluded.entry_count",
-            params![path_str, file_size, mtime_ms, start_time, entries.len() as i64],
-        ).map_err(|e| e.to_string())?;
-
-        // Insert usage entries
-        for entry in entries {
-            let has_io_tokens = entry.input_tokens > 0 || entry.output_tokens > 0;
-            let has_cache_tokens = entry.cache_creation_tokens > 0 || entry.cache_read_tokens > 0;
-            let unique_hash = generate_unique_hash(&entry, has_io_tokens, has_cache_tokens);
-
-            let result = tx.execute(
-                "INSERT INTO usage_entries (
-                    timestamp, model, input_tokens, output_tokens, 
-                    cache_creation_tokens, cache_read_tokens, cost, 
-                    session_id, project_path, file_path, unique_hash
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-                ON CONFLICT(unique_hash) DO NOTHING",
-                params![
-                    entry.timestamp,
-                    entry.model,
-                    entry.input_tokens as i64,
-                    entry.output_tokens as i64,
-                    entry.cache_creation_tokens as i64,
-                    entry.cache_read_tokens as i64,
-                    entry.cost,
-                    entry.session_id,
-                    entry.project_path,
-                    path_str,
-                    unique_hash,
-                ],
-            );
+        for batch in files_to_process.chunks(SCAN_BATCH_FILES) {
+            // Parse the batch in parallel, then write it in file order on this thread
+            let parsed = parse_session_files(batch, workers);
+
+            for (file, parsed) in batch.iter().zip(parsed) {
+                let path_str = file.path.to_string_lossy().to_string();
+                let project_name = &file.project_name;
+                let entries = parsed.entries;
+                if parsed.stats.lines_oversized > 0 {
+                    log::warn!(
+                        "Skipped {} oversized lines in {}",
+                        parsed.stats.lines_oversized,
+                        path_str
+                    );
+                }
+                line_stats += parsed.stats;
+
+                // Insert or update file record
+                upsert_file
+                    .execute(params![
+                        path_str,
+                        file.file_size,
+                        file.mtime_ms,
+                        start_time,
+                        entries.len() as i64
+                    ])
+                    .map_err(|e| e.to_string())?;
+
+                // Re-index message content of this file for full-text search
+                let session_id = file
+                    .path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                // Files indexed for the first time have nothing to delete
+                if existing_files.contains_key(&path_str) {
+                    delete_indexed_messages(&tx, &path_str).map_err(|e| e.to_string())?;
+                }
+                for message in &parsed.messages {
+                    index_message(&tx, message, project_name, &session_id, &path_str)
+                        .map_err(|e| e.to_string())?;
+                }
 
-            match result {
-                Ok(n) if n > 0 => entries_added += 1,
-                _ => entries_skipped += 1,
+                // Insert usage entries
+                for entry in entries {
+                    match insert_usage_entry(&mut insert_entry, &entry, &path_str) {
+                        Ok(n) if n > 0 => entries_added += 1,
+                        _ => entries_skipped += 1,
+                    }
+                }
+
+                files_scanned += 1;
             }
         }
-
-        files_scanned += 1;
     }
 
     // Remove entries for files that no longer exist
@@ -309,6 +622,7 @@ pub async fn usage_scan_update(state: State<'_, UsageCacheState>) -> Result<Scan
                 params![old_path],
             )
             .map_err(|e| e.to_string())?;
+            delete_indexed_messages(&tx, &old_path).map_err(|e| e.to_string())?;
         }
     }
 
@@ -325,6 +639,7 @@ pub async fn usage_scan_update(state: State<'_, UsageCacheState>) -> Result<Scan
         entries_added,
         entries_skipped,
         scan_time_ms,
+        line_stats,
     })
 }
 
@@ -640,6 +955,270 @@ pub async fn usage_get_stats_cached(
     })
 }
 
+/// Aggregation applied by `export_usage_csv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UsageCsvGrouping {
+    Day,
+    Model,
+    Project,
+}
+
+impl UsageCsvGrouping {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "day" => Ok(Self::Day),
+            "model" => Ok(Self::Model),
+            "project" => Ok(Self::Project),
+            other => Err(format!(
+                "Unsupported group_by '{}', expected day, model or project",
+                other
+            )),
+        }
+    }
+
+    /// SQL expression and CSV column name of the grouping key
+    fn key(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Day => ("DATE(timestamp)", "date"),
+            Self::Model => ("model", "model"),
+            Self::Project => ("project_path", "project_path"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageCsvExport {
+    pub path: String,
+    /// Data rows written, excluding the header and the summary footer
+    pub rows: u64,
+    pub total_cost: f64,
+}
+
+/// Running totals for the CSV summary footer
+#[derive(Debug, Default)]
+struct UsageCsvTotals {
+    rows: u64,
+    requests: i64,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_creation_tokens: i64,
+    cache_read_tokens: i64,
+    cost: f64,
+}
+
+impl UsageCsvTotals {
+    fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Streams usage entries at or after `cutoff` as CSV rows, one per entry or one per group,
+/// followed by a `TOTAL` footer row
+fn write_usage_csv<W: Write>(
+    conn: &Connection,
+    out: &mut W,
+    cutoff: &str,
+    grouping: Option<UsageCsvGrouping>,
+) -> Result<UsageCsvTotals, String> {
+    let io_err = |e: std::io::Error| format!("Failed to write CSV: {}", e);
+    let mut totals = UsageCsvTotals::default();
+
+    match grouping {
+        None => {
+            writeln!(
+                out,
+                "timestamp,model,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,cost_usd,session_id,project_path"
+            )
+            .map_err(io_err)?;
+
+            // Ordered by the timestamp index, so rows are read straight from the index
+            let mut stmt = conn
+                .prepare(
+                    "SELECT timestamp, model, input_tokens, output_tokens, cache_creation_tokens,
+                            cache_read_tokens, cost, session_id, project_path
+                     FROM usage_entries
+                     WHERE timestamp >= ?1
+                     ORDER BY timestamp",
+                )
+                .map_err(|e| e.to_string())?;
+            let mut rows = stmt.query(params![cutoff]).map_err(|e| e.to_string())?;
+            while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+                let timestamp: String = row.get(0).map_err(|e| e.to_string())?;
+                let model: String = row.get(1).map_err(|e| e.to_string())?;
+                let input: i64 = row.get(2).map_err(|e| e.to_string())?;
+                let output: i64 = row.get(3).map_err(|e| e.to_string())?;
+                let cache_creation: i64 = row.get(4).map_err(|e| e.to_string())?;
+                let cache_read: i64 = row.get(5).map_err(|e| e.to_string())?;
+                let cost: f64 = row.get(6).map_err(|e| e.to_string())?;
+                let session_id: String = row.get(7).map_err(|e| e.to_string())?;
+                let project_path: String = row.get(8).map_err(|e| e.to_string())?;
+
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{:.6},{},{}",
+                    csv_field(&timestamp),
+                    csv_field(&model),
+                    input,
+                    output,
+                    cache_creation,
+                    cache_read,
+                    cost,
+                    csv_field(&session_id),
+                    csv_field(&project_path)
+                )
+                .map_err(io_err)?;
+
+                totals.rows += 1;
+                totals.requests += 1;
+                totals.input_tokens += input;
+                totals.output_tokens += output;
+                totals.cache_creation_tokens += cache_creation;
+                totals.cache_read_tokens += cache_read;
+                totals.cost += cost;
+            }
+
+            writeln!(
+                out,
+                "TOTAL,,{},{},{},{},{:.6},,",
+                totals.input_tokens,
+                totals.output_tokens,
+                totals.cache_creation_tokens,
+                totals.cache_read_tokens,
+                totals.cost
+            )
+            .map_err(io_err)?;
+        }
+        Some(grouping) => {
+            let (key_expr, key_name) = grouping.key();
+            writeln!(
+                out,
+                "{},requests,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,total_tokens,cost_usd,sessions",
+                key_name
+            )
+            .map_err(io_err)?;
+
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT {} AS group_key, COUNT(*), SUM(input_tokens), SUM(output_tokens),
+                            SUM(cache_creation_tokens), SUM(cache_read_tokens), SUM(cost),
+                            COUNT(DISTINCT session_id)
+                     FROM usage_entries
+                     WHERE timestamp >= ?1
+                     GROUP BY group_key
+                     ORDER BY group_key",
+                    key_expr
+                ))
+                .map_err(|e| e.to_string())?;
+            let mut rows = stmt.query(params![cutoff]).map_err(|e| e.to_string())?;
+            while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+                let key: Option<String> = row.get(0).map_err(|e| e.to_string())?;
+                let requests: i64 = row.get(1).map_err(|e| e.to_string())?;
+                let input: i64 = row.get(2).map_err(|e| e.to_string())?;
+                let output: i64 = row.get(3).map_err(|e| e.to_string())?;
+                let cache_creation: i64 = row.get(4).map_err(|e| e.to_string())?;
+                let cache_read: i64 = row.get(5).map_err(|e| e.to_string())?;
+                let cost: f64 = row.get(6).map_err(|e| e.to_string())?;
+                let sessions: i64 = row.get(7).map_err(|e| e.to_string())?;
+
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{:.6},{}",
+                    csv_field(key.as_deref().unwrap_or("")),
+                    requests,
+                    input,
+                    output,
+                    cache_creation,
+                    cache_read,
+                    input + output + cache_creation + cache_read,
+                    cost,
+                    sessions
+                )
+                .map_err(io_err)?;
+
+                totals.rows += 1;
+                totals.requests += requests;
+                totals.input_tokens += input;
+                totals.output_tokens += output;
+                totals.cache_creation_tokens += cache_creation;
+                totals.cache_read_tokens += cache_read;
+                totals.cost += cost;
+            }
+
+            // Session counts overlap between groups, so the footer leaves them blank
+            writeln!(
+                out,
+                "TOTAL,{},{},{},{},{},{},{:.6},",
+                totals.requests,
+                totals.input_tokens,
+                totals.output_tokens,
+                totals.cache_creation_tokens,
+                totals.cache_read_tokens,
+                totals.total_tokens(),
+                totals.cost
+            )
+            .map_err(io_err)?;
+        }
+    }
+
+    Ok(totals)
+}
+
+/// Exports usage entries from the last `range_days` days (all when omitted) to a CSV file,
+/// one row per entry or, with `group_by` ("day", "model" or "project"), one row per group
+#[command]
+pub async fn export_usage_csv(
+    range_days: Option<u32>,
+    dest_path: String,
+    group_by: Option<String>,
+    state: State<'_, UsageCacheState>,
+) -> Result<UsageCsvExport, String> {
+    let grouping = group_by
+        .as_deref()
+        .map(UsageCsvGrouping::parse)
+        .transpose()?;
+
+    let needs_init = {
+        let conn_guard = state.conn.lock().map_err(|e| e.to_string())?;
+        conn_guard.is_none()
+    };
+    if needs_init {
+        usage_scan_update(state.clone()).await?;
+    }
+
+    let conn_guard = state.conn.lock().map_err(|e| e.to_string())?;
+    let conn = conn_guard.as_ref().ok_or("Database not initialized")?;
+
+    // Every timestamp sorts after "", so no range exports everything
+    let cutoff = range_days
+        .map(|d| {
+            let cutoff = Local::now().naive_local().date() - chrono::Duration::days(d as i64);
+            cutoff.format("%Y-%m-%d").to_string()
+        })
+        .unwrap_or_default();
+
+    let file = fs::File::create(&dest_path)
+        .map_err(|e| format!("Failed to create {}: {}", dest_path, e))?;
+    let mut out = BufWriter::new(file);
+    let totals = write_usage_csv(conn, &mut out, &cutoff, grouping)?;
+    out.flush()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+
+    Ok(UsageCsvExport {
+        path: dest_path,
+        rows: totals.rows,
+        total_cost: totals.cost,
+    })
+}
+
 #[command]
 pub async fn usage_clear_cache(state: State<'_, UsageCacheState>) -> Result<String, String> {
     let mut conn_guard = state.conn.lock().map_err(|e| e.to_string())?;
@@ -649,6 +1228,10 @@ pub async fn usage_clear_cache(state: State<'_, UsageCacheState>) -> Result<Stri
             .map_err(|e| e.to_string())?;
         conn.execute("DELETE FROM scanned_files", params![])
             .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM message_fts", params![])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM message_fts_files", params![])
+            .map_err(|e| e.to_string())?;
 
         // 重置last scan time
         let mut last_scan = state.last_scan_time.lock().map_err(|e| e.to_string())?;
@@ -660,14 +1243,61 @@ pub async fn usage_clear_cache(state: State<'_, UsageCacheState>) -> Result<Stri
     Ok("No cache to clear.".to_string())
 }
 
+/// Full-text search over message content of all projects and sessions.
+/// Uses the index built by the usage scan, so results reflect the last scan.
+#[command]
+pub async fn search_all_sessions(
+    query: String,
+    limit: Option<u32>,
+    state: State<'_, UsageCacheState>,
+) -> Result<Vec<SessionSearchResult>, String> {
+    let Some(fts_query) = build_fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(50).clamp(1, 500);
+
+    let mut conn_guard = state.conn.lock().map_err(|e| e.to_string())?;
+    if conn_guard.is_none() {
+        *conn_guard = Some(init_cache_db().map_err(|e| e.to_string())?);
+    }
+    let conn = conn_guard.as_ref().unwrap();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT project_id, session_id, message_index, timestamp, role,
+                    snippet(message_fts, 0, '[', ']', '…', 16), bm25(message_fts) AS score
+             FROM message_fts
+             WHERE message_fts MATCH ?1
+             ORDER BY score
+             LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let results = stmt
+        .query_map(params![fts_query, limit], |row| {
+            Ok(SessionSearchResult {
+                project_id: row.get(0)?,
+                session_id: row.get(1)?,
+                message_index: row.get(2)?,
+                timestamp: row.get(3)?,
+                role: row.get(4)?,
+                snippet: row.get(5)?,
+                rank: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(results)
+}
+
 // 快速检查文件是否变化（不解析内容）
 pub async fn check_files_changed(state: &State<'_, UsageCacheState>) -> Result<bool, String> {
     let conn_guard = state.conn.lock().map_err(|e| e.to_string())?;
     let conn = conn_guard.as_ref().ok_or("Database not initialized")?;
 
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
     let projects_dir = claude_path.join("projects");
 
     // 获取已知文件的修改时间和大小
@@ -733,3 +1363,227 @@ pub async fn usage_check_updates(state: State<'_, UsageCacheState>) -> Result<bo
     // 检查是否有文件更新
     check_files_changed(&state).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage_entries_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE usage_entries (
+               id INTEGER PRIMARY KEY AUTOINCREMENT,
+               timestamp TEXT NOT NULL, model TEXT NOT NULL,
+               input_tokens INTEGER, output_tokens INTEGER,
+               cache_creation_tokens INTEGER, cache_read_tokens INTEGER,
+               cost REAL NOT NULL, session_id TEXT NOT NULL, project_path TEXT NOT NULL,
+               file_path TEXT NOT NULL, unique_hash TEXT NOT NULL UNIQUE
+             );",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_input_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let doubled = parallel_map(&items, 4, |n| n * 2);
+        assert_eq!(doubled, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_write_usage_csv_rows_and_groups() {
+        let conn = usage_entries_db();
+        for (i, (timestamp, model, project)) in [
+            ("2025-01-01 10:00:00.000", "sonnet", "/work/a"),
+            ("2025-01-01 11:00:00.000", "opus", "/work/a,b"),
+            ("2025-01-02 09:00:00.000", "sonnet", "/work/a"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            conn.execute(
+                "INSERT INTO usage_entries (timestamp, model, input_tokens, output_tokens,
+                    cache_creation_tokens, cache_read_tokens, cost, session_id, project_path,
+                    file_path, unique_hash)
+                 VALUES (?1, ?2, 10, 20, 0, 5, 0.5, ?3, ?4, 'f.jsonl', ?5)",
+                params![
+                    timestamp,
+                    model,
+                    format!("s{}", i % 2),
+                    project,
+                    i.to_string()
+                ],
+            )
+            .unwrap();
+        }
+
+        let mut out = Vec::new();
+        let totals = write_usage_csv(&conn, &mut out, "2025-01-01", None).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(totals.rows, 3);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[2].ends_with(",s1,\"/work/a,b\""));
+        assert_eq!(lines[4], "TOTAL,,30,60,0,15,1.500000,,");
+
+        let mut out = Vec::new();
+        let totals =
+            write_usage_csv(&conn, &mut out, "2025-01-02", Some(UsageCsvGrouping::Day)).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(totals.rows, 1);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("2025-01-02,1,10,20,0,5,35,0.500000,1")
+        );
+
+        let mut out = Vec::new();
+        write_usage_csv(&conn, &mut out, "", Some(UsageCsvGrouping::Model)).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv.lines().nth(2),
+            Some("sonnet,2,20,40,0,10,70,1.000000,1")
+        );
+        assert_eq!(csv.lines().last(), Some("TOTAL,3,30,60,0,15,105,1.500000,"));
+    }
+
+    #[test]
+    fn test_delete_indexed_messages_only_removes_file_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_cache_schema(&conn).unwrap();
+        let message = |index: i64, text: &str| SearchableMessage {
+            index,
+            role: "user".to_string(),
+            text: text.to_string(),
+            timestamp: None,
+        };
+        for (file, text) in [
+            ("a.jsonl", "alpha"),
+            ("b.jsonl", "beta"),
+            ("a.jsonl", "gamma"),
+        ] {
+            index_message(&conn, &message(0, text), "project", "session", file).unwrap();
+        }
+
+        delete_indexed_messages(&conn, "a.jsonl").unwrap();
+
+        let remaining: Vec<String> = conn
+            .prepare("SELECT content FROM message_fts")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["beta"]);
+        let mapped: i64 = conn
+            .query_row("SELECT COUNT(*) FROM message_fts_files", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(mapped, 1);
+    }
+
+    /// Compares sequential and parallel parsing on a generated history:
+    /// `cargo test --release usage_scan_parse_benchmark -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn usage_scan_parse_benchmark() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("-tmp-bench");
+        fs::create_dir_all(&project_dir).unwrap();
+        for file in 0..300 {
+            let lines: Vec<String> = (0..2000)
+                .map(|i| {
+                    serde_json::json!({
+                        "type": "assistant",
+                        "cwd": "/tmp/bench",
+                        "timestamp": "2025-01-01T00:00:00Z",
+                        "requestId": format!("req-{}-{}", file, i),
+                        "message": {
+                            "id": format!("msg-{}-{}", file, i),
+                            "model": "claude-sonnet-4-20250514",
+                            "content": [{"type": "text", "text": "benchmark message"}],
+                            "usage": {"input_tokens": 10, "output_tokens": 20}
+                        }
+                    })
+                    .to_string()
+                })
+                .collect();
+            fs::write(
+                project_dir.join(format!("session-{}.jsonl", file)),
+                lines.join("\n"),
+            )
+            .unwrap();
+        }
+
+        let project_dirs = vec![("-tmp-bench".to_string(), project_dir)];
+        let files = collect_session_files(&project_dirs, scan_workers());
+        let count = |parsed: Vec<ParsedFile>| parsed.iter().map(|p| p.entries.len()).sum::<usize>();
+
+        let started = std::time::Instant::now();
+        let sequential = count(parse_session_files(&files, 1));
+        let sequential_time = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let parallel = count(parse_session_files(&files, scan_workers()));
+        let parallel_time = started.elapsed();
+
+        assert_eq!(sequential, parallel);
+        println!(
+            "{} files, {} entries: sequential {:?}, parallel ({} workers) {:?}",
+            files.len(),
+            parallel,
+            sequential_time,
+            scan_workers(),
+            parallel_time
+        );
+    }
+
+    /// Compares re-parsing the insert SQL per entry with reusing one prepared statement:
+    /// `cargo test --release usage_insert_prepared_benchmark -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn usage_insert_prepared_benchmark() {
+        const ENTRIES: usize = 50_000;
+        let entry = |i: usize| UsageEntry {
+            timestamp: format!("2025-01-01T00:00:{:06}Z", i),
+            model: "claude-sonnet-4-20250514".to_string(),
+            input_tokens: 10,
+            output_tokens: 20,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            cost: 0.001,
+            session_id: format!("session-{}", i % 100),
+            project_path: "/tmp/bench".to_string(),
+        };
+
+        let conn = usage_entries_db();
+        let tx = conn.unchecked_transaction().unwrap();
+        let started = std::time::Instant::now();
+        for i in 0..ENTRIES {
+            // Previous approach: the SQL is compiled again for every entry
+            let mut stmt = tx.prepare(USAGE_ENTRY_INSERT_SQL).unwrap();
+            insert_usage_entry(&mut stmt, &entry(i), "bench.jsonl").unwrap();
+        }
+        let per_call_time = started.elapsed();
+        tx.commit().unwrap();
+
+        let conn = usage_entries_db();
+        let tx = conn.unchecked_transaction().unwrap();
+        let started = std::time::Instant::now();
+        {
+            let mut stmt = tx.prepare(USAGE_ENTRY_INSERT_SQL).unwrap();
+            for i in 0..ENTRIES {
+                insert_usage_entry(&mut stmt, &entry(i), "bench.jsonl").unwrap();
+            }
+        }
+        let prepared_time = started.elapsed();
+        tx.commit().unwrap();
+
+        println!(
+            "{} inserts: per-call execute {:?}, prepared {:?}",
+            ENTRIES, per_call_time, prepared_time
+        );
+        assert!(prepared_time < per_call_time);
+    }
+}
@@ -257,9 +257,9 @@ pub async fn usage_scan_update(
     if !force.unwrap_or(false) {
         if !should_scan(conn) {
             // 快速检查是否有文件变化
-            let projects_dir = dirs::home_dir()
-                .ok_or("Failed to get home directory")?
-                .join(".claude/projects");
+            let projects_dir = crate::commands::claude::get_claude_dir()
+                .map_err(|e| e.to_string())?
+                .join("projects");
                 
             let has_changes = quick_check_changes(conn, &projects_dir)?;
             
@@ -289,9 +289,7 @@ pub async fn usage_scan_update(
 
 // 实际的扫描逻辑（从原来的 usage_scan_update 中提取）
 fn perform_scan(conn: &mut Connection, start_time: i64) -> Result<ScanResult, String> {
-    let claude_path = dirs::home_dir()
-        .ok_or("Failed to get home directory")?
-        .join(".claude");
+    let claude_path = crate::commands::claude::get_claude_dir().map_err(|e| e.to_string())?;
     
     let projects_dir = claude_path.join("projects");
     
@@ -0,0 +1,278 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{command, State};
+
+use super::usage::UsageStats;
+use super::usage_cache::{usage_get_stats_cached, UsageCacheState};
+use crate::http_client;
+
+/// Public USD-based exchange rate feed, refreshed daily by the provider
+const EXCHANGE_RATE_URL: &str = "https://open.er-api.com/v6/latest/USD";
+
+/// How long a fetched or provided rate is used before fetching again
+const RATE_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+struct CachedRate {
+    rate: f64,
+    cached_at: Instant,
+    /// Unix seconds, reported back to the UI
+    cached_at_unix: u64,
+}
+
+/// USD to currency rates by upper-case currency code
+static RATE_CACHE: Lazy<Mutex<HashMap<String, CachedRate>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Where the exchange rate of a conversion came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateSource {
+    /// USD requested, no conversion needed
+    Identity,
+    /// Passed in by the caller
+    Provided,
+    /// Cached within the TTL
+    Cached,
+    /// Fetched from the exchange rate feed
+    Fetched,
+    /// Cached past the TTL, used because fetching failed
+    Stale,
+    /// No rate available; values are in USD
+    Unavailable,
+}
+
+/// Usage stats converted to another currency, with the raw USD values alongside
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurrencyUsageStats {
+    /// Currency of `stats`; "USD" when falling back
+    pub currency: String,
+    /// Currency that was asked for
+    pub requested_currency: String,
+    /// USD to `currency` multiplier applied to every cost
+    pub rate: f64,
+    pub rate_source: RateSource,
+    /// When the rate was cached or fetched (unix seconds)
+    pub rate_updated_at: Option<u64>,
+    /// True when no rate was available and `stats` are in USD
+    pub fell_back_to_usd: bool,
+    /// Stats with every cost converted, unrounded
+    pub stats: UsageStats,
+    /// The same stats in USD, as computed from the pricing table
+    pub usd_stats: UsageStats,
+}
+
+#[derive(Deserialize)]
+struct ExchangeRateResponse {
+    rates: HashMap<String, f64>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Normalizes an ISO 4217 code such as "eur" to "EUR"
+fn normalize_currency(currency: &str) -> Result<String, String> {
+    let code = currency.trim().to_ascii_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(code)
+    } else {
+        Err(format!("Invalid currency code '{}'", currency))
+    }
+}
+
+fn validate_rate(rate: f64) -> Result<f64, String> {
+    if rate.is_finite() && rate > 0.0 {
+        Ok(rate)
+    } else {
+        Err(format!("Invalid exchange rate {}", rate))
+    }
+}
+
+fn cache_rates(rates: impl IntoIterator<Item = (String, f64)>) {
+    if let Ok(mut cache) = RATE_CACHE.lock() {
+        let now = Instant::now();
+        let now_unix = unix_now();
+        for (code, rate) in rates {
+            cache.insert(
+                code,
+                CachedRate {
+                    rate,
+                    cached_at: now,
+                    cached_at_unix: now_unix,
+                },
+            );
+        }
+    }
+}
+
+/// Cached rate for `currency` as (rate, cached at, still within the TTL)
+fn cached_rate(currency: &str) -> Option<(f64, u64, bool)> {
+    let cache = RATE_CACHE.lock().ok()?;
+    cache.get(currency).map(|cached| {
+        (
+            cached.rate,
+            cached.cached_at_unix,
+            cached.cached_at.elapsed() < RATE_TTL,
+        )
+    })
+}
+
+/// Fetches the latest USD rates and caches all of them
+async fn fetch_rates() -> Result<(), String> {
+    let client = http_client::default_client().map_err(|e| e.to_string())?;
+    let response = client
+        .get(EXCHANGE_RATE_URL)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch exchange rates: {}", e))?;
+    let body: ExchangeRateResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid exchange rate response: {}", e))?;
+
+    cache_rates(
+        body.rates
+            .into_iter()
+            .filter(|(_, rate)| rate.is_finite() && *rate > 0.0)
+            .map(|(code, rate)| (code.to_ascii_uppercase(), rate)),
+    );
+    Ok(())
+}
+
+/// Picks the rate for `currency`: provided, then fresh cache, then the feed, then a stale
+/// cache entry. Returns `None` when nothing is available.
+async fn resolve_rate(
+    currency: &str,
+    provided: Option<f64>,
+) -> Result<Option<(f64, RateSource, Option<u64>)>, String> {
+    if currency == "USD" {
+        return Ok(Some((1.0, RateSource::Identity, None)));
+    }
+    if let Some(rate) = provided {
+        let rate = validate_rate(rate)?;
+        cache_rates([(currency.to_string(), rate)]);
+        return Ok(Some((rate, RateSource::Provided, Some(unix_now()))));
+    }
+
+    if let Some((rate, at, true)) = cached_rate(currency) {
+        return Ok(Some((rate, RateSource::Cached, Some(at))));
+    }
+
+    match fetch_rates().await {
+        Ok(()) => {
+            if let Some((rate, at, _)) = cached_rate(currency) {
+                return Ok(Some((rate, RateSource::Fetched, Some(at))));
+            }
+            log::warn!("Exchange rate feed has no rate for {}", currency);
+        }
+        Err(e) => log::warn!("{}", e),
+    }
+
+    Ok(cached_rate(currency).map(|(rate, at, _)| (rate, RateSource::Stale, Some(at))))
+}
+
+/// Multiplies every cost in `stats` by `rate`, leaving tokens and counts unchanged
+fn convert_usage_stats(stats: &UsageStats, rate: f64) -> UsageStats {
+    let mut converted = stats.clone();
+    converted.total_cost *= rate;
+    for model in &mut converted.by_model {
+        model.total_cost *= rate;
+    }
+    for day in &mut converted.by_date {
+        day.total_cost *= rate;
+    }
+    for project in &mut converted.by_project {
+        project.total_cost *= rate;
+    }
+    converted
+}
+
+/// Gets usage stats with costs in `currency`. Uses `rate` (USD to currency) when given,
+/// otherwise a cached or fetched rate; falls back to USD when none is available.
+#[command]
+pub async fn get_usage_stats_in_currency(
+    days: Option<u32>,
+    currency: String,
+    rate: Option<f64>,
+    state: State<'_, UsageCacheState>,
+) -> Result<CurrencyUsageStats, String> {
+    let requested_currency = normalize_currency(&currency)?;
+    let resolved = resolve_rate(&requested_currency, rate).await?;
+    let usd_stats = usage_get_stats_cached(days, state).await?;
+
+    Ok(match resolved {
+        Some((rate, rate_source, rate_updated_at)) => CurrencyUsageStats {
+            currency: requested_currency.clone(),
+            requested_currency,
+            rate,
+            rate_source,
+            rate_updated_at,
+            fell_back_to_usd: false,
+            stats: convert_usage_stats(&usd_stats, rate),
+            usd_stats,
+        },
+        None => CurrencyUsageStats {
+            currency: "USD".to_string(),
+            requested_currency,
+            rate: 1.0,
+            rate_source: RateSource::Unavailable,
+            rate_updated_at: None,
+            fell_back_to_usd: true,
+            stats: usd_stats.clone(),
+            usd_stats,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::usage::ModelUsage;
+
+    #[test]
+    fn test_convert_usage_stats_scales_costs_only() {
+        let usd = UsageStats {
+            total_cost: 2.0,
+            total_tokens: 300,
+            total_input_tokens: 100,
+            total_output_tokens: 200,
+            total_cache_creation_tokens: 0,
+            total_cache_read_tokens: 0,
+            total_sessions: 1,
+            by_model: vec![ModelUsage {
+                model: "sonnet".to_string(),
+                total_cost: 2.0,
+                total_tokens: 300,
+                input_tokens: 100,
+                output_tokens: 200,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                session_count: 1,
+            }],
+            by_date: Vec::new(),
+            by_project: Vec::new(),
+        };
+
+        let converted = convert_usage_stats(&usd, 0.5);
+        assert_eq!(converted.total_cost, 1.0);
+        assert_eq!(converted.by_model[0].total_cost, 1.0);
+        assert_eq!(converted.total_tokens, 300);
+        assert_eq!(usd.total_cost, 2.0);
+    }
+
+    #[test]
+    fn test_normalize_currency() {
+        assert_eq!(normalize_currency(" eur ").unwrap(), "EUR");
+        assert!(normalize_currency("euro").is_err());
+        assert!(normalize_currency("E1R").is_err());
+        assert!(validate_rate(0.0).is_err());
+        assert!(validate_rate(f64::NAN).is_err());
+    }
+}
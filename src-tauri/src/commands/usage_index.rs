@@ -10,6 +10,8 @@ use std::sync::{Arc, Mutex};
 use tauri::State;
 use walkdir::WalkDir;
 
+use crate::utils::sqlite;
+
 #[derive(Default)]
 pub struct UsageIndexState {
     pub jobs: Arc<Mutex<HashMap<String, ScanProgress>>>, // job_id -> progress
@@ -52,7 +54,7 @@ fn ensure_parent_dir(p: &Path) -> std::io::Result<()> {
 fn open_db(project_root: &Path) -> rusqlite::Result<Connection> {
     let path = db_path_for(project_root);
     ensure_parent_dir(&path).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-    let conn = Connection::open(path)?;
+    let conn = sqlite::open_connection(path)?;
     conn.pragma_update(None, "journal_mode", &"WAL")?;
     // schema
     conn.execute_batch(
@@ -170,7 +172,7 @@ pub async fn usage_scan_index(
     let job_id_task = job_id.clone();
     let job_id_ret = job_id.clone();
     tauri::async_runtime::spawn(async move {
-        let mut conn = match open_db(&project) {
+        let conn = match open_db(&project) {
             Ok(c) => c,
             Err(e) => {
                 log::error!("DB open error: {}", e);
@@ -220,7 +222,7 @@ pub async fn usage_scan_index(
 
         let mut seen: HashSet<String> = HashSet::new();
         let now = Utc::now().timestamp_millis();
-        let tx = conn.transaction();
+        let tx = sqlite::begin_immediate(&conn, "usage index scan");
         let mut processed: u64 = 0;
         if let Ok(tx) = tx {
             for entry in WalkDir::new(&project).into_iter().filter_map(Result::ok) {
@@ -445,12 +447,12 @@ struct ExternalDiff {
 #[tauri::command]
 pub fn usage_import_diffs(project_root: String, path: String) -> Result<ImportResult, String> {
     let project = PathBuf::from(project_root);
-    let mut conn = open_db(&project).map_err(|e| e.to_string())?;
+    let conn = open_db(&project).map_err(|e| e.to_string())?;
     let data = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
     let mut inserted = 0u64;
     let mut skipped = 0u64;
     let mut errors = 0u64;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let tx = sqlite::begin_immediate(&conn, "external diff import").map_err(|e| e.to_string())?;
     // try as JSON array
     let mut diffs: Vec<ExternalDiff> = Vec::new();
     match serde_json::from_str::<serde_json::Value>(&data) {
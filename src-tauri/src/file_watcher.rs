@@ -1,9 +1,17 @@
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::checkpoint::state::CheckpointState;
+use crate::commands::agents::AgentDb;
+use crate::commands::search_ignore::{
+    load_search_ignore_settings, IgnoreMatcher, SearchIgnoreSettings,
+};
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct FileChangeEvent {
@@ -12,11 +20,161 @@ pub struct FileChangeEvent {
     pub timestamp: u64,
 }
 
+/// 一个防抖间隔内合并后的全部文件变更
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FilesChangedEvent {
+    pub changes: Vec<FileChangeEvent>,
+    pub timestamp: u64,
+}
+
+/// CLAUDE.md 变更事件（携带最新内容，文件被删除时 content 为空）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ClaudeMdChangeEvent {
+    pub path: String,
+    pub change_type: String,
+    pub content: Option<String>,
+    pub timestamp: u64,
+}
+
+/// 项目目录中出现新会话文件时发送的事件
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionCreatedEvent {
+    pub session_id: String,
+    pub project_id: String,
+    pub path: String,
+    /// 第一条用户消息，等待超时仍未写入时为空
+    pub first_message: Option<String>,
+    pub message_timestamp: Option<String>,
+    pub timestamp: u64,
+}
+
+// 新会话文件创建后等待第一条用户消息写入的最长时间及检查间隔
+const SESSION_FIRST_MESSAGE_WAIT: Duration = Duration::from_secs(2);
+const SESSION_FIRST_MESSAGE_POLL: Duration = Duration::from_millis(200);
+
+// 文件变更默认防抖间隔，及可配置的范围
+const DEFAULT_FILE_CHANGE_DEBOUNCE_MS: u64 = 200;
+pub const FILE_CHANGE_DEBOUNCE_RANGE_MS: std::ops::RangeInclusive<u64> = 10..=5000;
+
+// 轮询模式默认扫描间隔，及可配置的范围
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2000;
+pub const POLL_INTERVAL_RANGE_MS: std::ops::RangeInclusive<u64> = 250..=60_000;
+
+// 同一路径持续变更时的最长发送延迟，保证实时会话的更新不被无限推迟；
+// 防抖间隔配置得更长时以防抖间隔为准
+const FILE_CHANGE_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// 是否为 Claude 项目目录下的会话文件，即 `<claude_dir>/projects/<id>/<session>.jsonl`
+fn is_session_file(path: &Path) -> bool {
+    if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+        return false;
+    }
+    let Some(projects_dir) = path.parent().and_then(Path::parent) else {
+        return false;
+    };
+    let Some(claude_projects) = crate::commands::claude::resolve_claude_dir()
+        .ok()
+        .and_then(|(dir, _)| dir.join("projects").canonicalize().ok())
+    else {
+        return false;
+    };
+    projects_dir
+        .canonicalize()
+        .is_ok_and(|dir| dir == claude_projects)
+}
+
+/// 等待发送的单个路径变更
+struct PendingChange {
+    change_type: &'static str,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+#[derive(Default)]
+struct PendingChanges {
+    changes: HashMap<PathBuf, PendingChange>,
+    // 是否已有线程在等待发送
+    flushing: bool,
+}
+
+/// 合并同一路径的两次变更：删除优先；新建后的修改仍为新建；删除后重建视为修改
+fn merge_change_type(previous: &'static str, next: &'static str) -> &'static str {
+    match (previous, next) {
+        (_, "deleted") => "deleted",
+        ("deleted", "created") => "modified",
+        ("created", _) => "created",
+        (_, next) => next,
+    }
+}
+
+// CLAUDE.md 防抖间隔：连续保存在此时间内合并为一次通知
+const CLAUDE_MD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 监听方式：系统原生事件，或定时扫描修改时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchMode {
+    Native,
+    Polling,
+}
+
+/// 监听的事件交给哪个处理函数
+#[derive(Debug, Clone, Copy)]
+enum WatchKind {
+    Files,
+    ClaudeMd,
+}
+
+/// 一个监听根路径，多个使用方共享，引用计数归零时才真正停止
+struct WatchEntry {
+    // 仅持有以保持监听，drop 时停止
+    _watcher: Box<dyn Watcher + Send>,
+    mode: WatchMode,
+    kind: WatchKind,
+    root: PathBuf,
+    // 该路径经检测原生事件不可用，始终轮询
+    prefer_polling: bool,
+    ref_count: usize,
+    recursive: bool,
+    project_id: Option<String>,
+}
+
+/// 当前监听的根路径及其对应的项目
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WatchedPath {
+    pub path: String,
+    /// ~/.claude/projects 下的项目 ID，非项目目录时为空
+    pub project_id: Option<String>,
+    /// 仍需要该监听的使用方数量
+    pub ref_count: usize,
+    pub recursive: bool,
+    pub mode: WatchMode,
+}
+
+/// 监听方式的当前状态，用于向用户解释实时更新为何有延迟
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WatcherModeStatus {
+    /// 设置中强制使用轮询
+    pub force_polling: bool,
+    pub poll_interval_ms: u64,
+    /// 是否有路径正在轮询（原生监听失败或被强制）
+    pub polling_active: bool,
+    pub paths: Vec<WatchedPath>,
+}
+
 pub struct FileWatcherManager {
-    watchers: Arc<Mutex<HashMap<String, RecommendedWatcher>>>,
+    watchers: Arc<Mutex<HashMap<String, WatchEntry>>>,
     app_handle: AppHandle,
-    // 用于去重，避免短时间内重复事件
-    last_events: Arc<Mutex<HashMap<PathBuf, SystemTime>>>,
+    // 等待防抖结束的文件变更
+    pending_changes: Arc<Mutex<PendingChanges>>,
+    // 防抖间隔（毫秒），可在运行时调整
+    debounce_ms: Arc<AtomicU64>,
+    // 等待防抖结束的 CLAUDE.md 路径及其最近一次事件时间
+    claude_md_pending: Arc<Mutex<HashMap<PathBuf, Instant>>>,
+    // 强制轮询（原生监听不可用的文件系统，如 NFS、WSL）
+    force_polling: AtomicBool,
+    // 轮询间隔（毫秒）
+    poll_interval_ms: AtomicU64,
 }
 
 impl FileWatcherManager {
@@ -24,12 +182,60 @@ impl FileWatcherManager {
         Self {
             watchers: Arc::new(Mutex::new(HashMap::new())),
             app_handle,
-            last_events: Arc::new(Mutex::new(HashMap::new())),
+            pending_changes: Arc::new(Mutex::new(PendingChanges::default())),
+            debounce_ms: Arc::new(AtomicU64::new(DEFAULT_FILE_CHANGE_DEBOUNCE_MS)),
+            claude_md_pending: Arc::new(Mutex::new(HashMap::new())),
+            force_polling: AtomicBool::new(false),
+            poll_interval_ms: AtomicU64::new(DEFAULT_POLL_INTERVAL_MS),
         }
     }
 
-    /// 监听指定路径（文件或目录）
-    pub fn watch_path(&self, path: &str, recursive: bool) -> Result<(), String> {
+    /// CLAUDE.md 监听在 watchers 中使用的键
+    pub fn claude_md_watch_key(project_path: &str) -> String {
+        format!("claude-md:{}", project_path)
+    }
+
+    /// 递归监听项目中的 CLAUDE.md 文件（包括之后新建的），变更经防抖后发送到前端
+    pub fn watch_claude_md(&self, project_path: &str) -> Result<(), String> {
+        let path_buf = PathBuf::from(project_path);
+        if !path_buf.is_dir() {
+            return Err(format!("Path does not exist: {}", project_path));
+        }
+
+        let key = Self::claude_md_watch_key(project_path);
+        if self.retain_existing(&key, None, false).is_some() {
+            log::debug!("Already watching CLAUDE.md files in: {}", project_path);
+            return Ok(());
+        }
+
+        let (watcher, mode) = self.create_watcher(WatchKind::ClaudeMd, &path_buf, true, false)?;
+        self.insert_watcher(
+            key,
+            WatchEntry {
+                _watcher: watcher,
+                mode,
+                kind: WatchKind::ClaudeMd,
+                root: path_buf,
+                prefer_polling: false,
+                ref_count: 1,
+                recursive: true,
+                project_id: None,
+            },
+        );
+
+        log::info!("Started watching CLAUDE.md files in: {}", project_path);
+        Ok(())
+    }
+
+    /// 监听指定路径，并记录其对应的项目 ID；已在监听时只增加引用计数。
+    /// `prefer_polling` 用于已检测出原生事件不会触发的路径，已按原生方式监听时改为轮询重建
+    pub fn watch_path_for(
+        &self,
+        path: &str,
+        recursive: bool,
+        project_id: Option<&str>,
+        prefer_polling: bool,
+    ) -> Result<WatchMode, String> {
         let path_buf = PathBuf::from(path);
 
         // 检查路径是否存在
@@ -38,79 +244,198 @@ impl FileWatcherManager {
         }
 
         // 检查是否已经在监听
-        {
-            let watchers = self.watchers.lock().unwrap();
-            if watchers.contains_key(path) {
-                log::debug!("Already watching path: {}", path);
-                return Ok(());
-            }
+        if let Some(mode) = self.retain_existing(path, project_id, prefer_polling) {
+            log::debug!("Already watching path: {}", path);
+            return Ok(mode);
         }
 
+        let (watcher, mode) =
+            self.create_watcher(WatchKind::Files, &path_buf, recursive, prefer_polling)?;
+
+        // 存储监听器
+        self.insert_watcher(
+            path.to_string(),
+            WatchEntry {
+                _watcher: watcher,
+                mode,
+                kind: WatchKind::Files,
+                root: path_buf,
+                prefer_polling,
+                ref_count: 1,
+                recursive,
+                project_id: project_id.map(str::to_string),
+            },
+        );
+
+        log::info!(
+            "Started watching path: {} (recursive: {}, mode: {:?})",
+            path,
+            recursive,
+            mode
+        );
+        Ok(mode)
+    }
+
+    /// 按类型创建的事件处理函数
+    fn event_handler(
+        &self,
+        kind: WatchKind,
+        root: &Path,
+    ) -> impl Fn(Result<Event, notify::Error>) + Clone + Send + 'static {
         let app_handle = self.app_handle.clone();
-        let last_events = self.last_events.clone();
-        let watch_path = path.to_string();
-
-        // 创建文件监听器
-        let mut watcher = RecommendedWatcher::new(
-            move |res: Result<Event, notify::Error>| match res {
-                Ok(event) => {
-                    Self::handle_event(event, &app_handle, &last_events);
-                }
-                Err(e) => {
-                    log::error!("Watch error: {:?}", e);
-                }
+        let pending = self.pending_changes.clone();
+        let debounce_ms = self.debounce_ms.clone();
+        let claude_md_pending = self.claude_md_pending.clone();
+        // CLAUDE.md 与项目搜索使用同一套忽略规则（创建监听时读取）
+        let settings = self
+            .app_handle
+            .try_state::<AgentDb>()
+            .map(|db| load_search_ignore_settings(&db))
+            .unwrap_or_else(SearchIgnoreSettings::default);
+        let ignore = Arc::new(IgnoreMatcher::new(&settings, root));
+        let root = root.to_path_buf();
+        move |res: Result<Event, notify::Error>| match res {
+            Ok(event) => match kind {
+                WatchKind::Files => Self::handle_event(event, &app_handle, &pending, &debounce_ms),
+                WatchKind::ClaudeMd => Self::handle_claude_md_event(
+                    event,
+                    &app_handle,
+                    &claude_md_pending,
+                    &root,
+                    &ignore,
+                ),
             },
-            Config::default()
-                .with_poll_interval(Duration::from_secs(1))
-                .with_compare_contents(false),
-        )
-        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+            Err(e) => {
+                log::error!("Watch error ({:?}): {:?}", kind, e);
+            }
+        }
+    }
 
-        // 开始监听
+    /// 创建并启动监听器：优先使用原生事件，失败或设置强制时退回轮询
+    fn create_watcher(
+        &self,
+        kind: WatchKind,
+        root: &Path,
+        recursive: bool,
+        prefer_polling: bool,
+    ) -> Result<(Box<dyn Watcher + Send>, WatchMode), String> {
         let mode = if recursive {
             RecursiveMode::Recursive
         } else {
             RecursiveMode::NonRecursive
         };
+        let handler = self.event_handler(kind, root);
 
+        if !prefer_polling && !self.force_polling.load(Ordering::Relaxed) {
+            let native = RecommendedWatcher::new(
+                handler.clone(),
+                Config::default()
+                    .with_poll_interval(Duration::from_secs(1))
+                    .with_compare_contents(false),
+            )
+            .and_then(|mut watcher| watcher.watch(root, mode).map(|_| watcher));
+            match native {
+                Ok(watcher) => return Ok((Box::new(watcher), WatchMode::Native)),
+                Err(e) => log::warn!(
+                    "Native watching failed for {}, falling back to polling: {}",
+                    root.display(),
+                    e
+                ),
+            }
+        }
+
+        let poll_interval = Duration::from_millis(self.poll_interval_ms.load(Ordering::Relaxed));
+        let mut watcher = PollWatcher::new(
+            handler,
+            Config::default()
+                .with_poll_interval(poll_interval)
+                .with_compare_contents(false),
+        )
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
         watcher
-            .watch(&path_buf, mode)
+            .watch(root, mode)
             .map_err(|e| format!("Failed to watch path: {}", e))?;
+        Ok((Box::new(watcher), WatchMode::Polling))
+    }
 
-        // 存储监听器
+    /// 已在监听时增加引用计数并补充项目 ID，返回其监听方式；
+    /// `prefer_polling` 时把原生监听改为轮询重建
+    fn retain_existing(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+        prefer_polling: bool,
+    ) -> Option<WatchMode> {
         let mut watchers = self.watchers.lock().unwrap();
-        watchers.insert(watch_path, watcher);
+        match watchers.get_mut(key) {
+            Some(entry) => {
+                entry.ref_count += 1;
+                if entry.project_id.is_none() {
+                    entry.project_id = project_id.map(str::to_string);
+                }
+                if prefer_polling && !entry.prefer_polling {
+                    entry.prefer_polling = true;
+                    if entry.mode == WatchMode::Native {
+                        match self.create_watcher(entry.kind, &entry.root, entry.recursive, true) {
+                            Ok((watcher, mode)) => {
+                                entry._watcher = watcher;
+                                entry.mode = mode;
+                            }
+                            Err(e) => log::warn!("Failed to switch {} to polling: {}", key, e),
+                        }
+                    }
+                }
+                Some(entry.mode)
+            }
+            None => None,
+        }
+    }
 
-        log::info!("Started watching path: {} (recursive: {})", path, recursive);
-        Ok(())
+    /// 存储新建的监听器；若同时有其他调用已创建，则合并为一次引用
+    fn insert_watcher(&self, key: String, entry: WatchEntry) {
+        let mut watchers = self.watchers.lock().unwrap();
+        watchers
+            .entry(key)
+            .and_modify(|existing| existing.ref_count += 1)
+            .or_insert(entry);
     }
 
-    /// 停止监听指定路径
+    /// 停止监听指定路径：减少引用计数，归零时才真正停止
     pub fn unwatch_path(&self, path: &str) -> Result<(), String> {
         let mut watchers = self.watchers.lock().unwrap();
 
-        if watchers.remove(path).is_some() {
+        let Some(entry) = watchers.get_mut(path) else {
+            return Err(format!("Path not being watched: {}", path));
+        };
+        entry.ref_count -= 1;
+        if entry.ref_count == 0 {
+            watchers.remove(path);
             log::info!("Stopped watching path: {}", path);
-            Ok(())
         } else {
-            Err(format!("Path not being watched: {}", path))
+            log::debug!(
+                "Released watch on {} ({} users remain)",
+                path,
+                entry.ref_count
+            );
         }
+        Ok(())
     }
 
-    /// 停止所有监听
-    #[allow(dead_code)]
-    pub fn unwatch_all(&self) {
+    /// 停止所有监听（忽略引用计数），返回停止的路径数量
+    pub fn unwatch_all(&self) -> usize {
         let mut watchers = self.watchers.lock().unwrap();
         let count = watchers.len();
         watchers.clear();
         log::info!("Stopped watching {} paths", count);
+        count
     }
 
-    /// 处理文件系统事件
+    /// 处理文件系统事件：记入待发送队列，同一路径在防抖间隔内的多次事件合并为一次
     fn handle_event(
         event: Event,
         app_handle: &AppHandle,
-        last_events: &Arc<Mutex<HashMap<PathBuf, SystemTime>>>,
+        pending: &Arc<Mutex<PendingChanges>>,
+        debounce_ms: &Arc<AtomicU64>,
     ) {
         // 过滤不需要的事件
         let change_type = match event.kind {
@@ -120,58 +445,502 @@ impl FileWatcherManager {
             _ => return, // 忽略其他事件（包括 Access 等）
         };
 
-        // 处理每个受影响的路径
-        for path in event.paths {
-            // 去重：检查是否在短时间内已经发送过相同路径的事件
-            let now = SystemTime::now();
-            let should_emit = {
-                let mut last_events = last_events.lock().unwrap();
-
-                if let Some(last_time) = last_events.get(&path) {
-                    // 如果距离上次事件不到500ms，忽略
-                    if now.duration_since(*last_time).unwrap_or(Duration::ZERO)
-                        < Duration::from_millis(500)
-                    {
-                        false
-                    } else {
-                        last_events.insert(path.clone(), now);
-                        true
+        // 新建的路径在释放锁后再检查是否为会话文件，避免持锁做文件系统操作
+        let mut created = Vec::new();
+        let start_flush = {
+            let mut pending = pending.lock().unwrap();
+            let now = Instant::now();
+            for path in event.paths {
+                match pending.changes.entry(path) {
+                    Entry::Occupied(mut entry) => {
+                        let change = entry.get_mut();
+                        change.change_type = merge_change_type(change.change_type, change_type);
+                        change.last_seen = now;
                     }
-                } else {
-                    last_events.insert(path.clone(), now);
-                    true
+                    Entry::Vacant(entry) => {
+                        // 同一路径在一个防抖间隔内只通知一次
+                        if change_type == "created" {
+                            created.push(entry.key().clone());
+                        }
+                        entry.insert(PendingChange {
+                            change_type,
+                            first_seen: now,
+                            last_seen: now,
+                        });
+                    }
+                }
+            }
+            !std::mem::replace(&mut pending.flushing, true)
+        };
+
+        for path in &created {
+            Self::notify_session_created(app_handle, path);
+        }
+
+        // 每个管理器同一时间只有一个发送线程
+        if start_flush {
+            let app_handle = app_handle.clone();
+            let pending = pending.clone();
+            let debounce_ms = debounce_ms.clone();
+            std::thread::spawn(move || Self::flush_pending(&app_handle, &pending, &debounce_ms));
+        }
+    }
+
+    /// 等待防抖间隔后发送已稳定的变更，直到队列清空
+    fn flush_pending(
+        app_handle: &AppHandle,
+        pending: &Arc<Mutex<PendingChanges>>,
+        debounce_ms: &Arc<AtomicU64>,
+    ) {
+        loop {
+            let window = Duration::from_millis(debounce_ms.load(Ordering::Relaxed));
+            let max_delay = FILE_CHANGE_MAX_DELAY.max(window);
+            std::thread::sleep(window);
+
+            let (ready, done): (Vec<(PathBuf, &'static str)>, bool) = {
+                let mut pending = pending.lock().unwrap();
+                let ready: Vec<PathBuf> = pending
+                    .changes
+                    .iter()
+                    .filter(|(_, change)| {
+                        // 持续写入的文件也至少每 max_delay 发送一次
+                        change.last_seen.elapsed() >= window
+                            || change.first_seen.elapsed() >= max_delay
+                    })
+                    .map(|(path, _)| path.clone())
+                    .collect();
+                let ready = ready
+                    .into_iter()
+                    .filter_map(|path| {
+                        pending
+                            .changes
+                            .remove(&path)
+                            .map(|change| (path, change.change_type))
+                    })
+                    .collect();
+                // 队列清空时在同一把锁内退出，之后的事件会启动新的发送线程
+                let done = pending.changes.is_empty();
+                if done {
+                    pending.flushing = false;
                 }
+                (ready, done)
             };
 
-            if should_emit {
-                let change_event = FileChangeEvent {
-                    path: path.to_string_lossy().to_string(),
-                    change_type: change_type.to_string(),
-                    timestamp: now
-                        .duration_since(SystemTime::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs(),
-                };
-
-                // 发送事件到前端
-                if let Err(e) = app_handle.emit("file-system-change", &change_event) {
-                    log::error!("Failed to emit file change event: {}", e);
+            if !ready.is_empty() {
+                Self::emit_changes(app_handle, ready);
+            }
+            if done {
+                break;
+            }
+        }
+    }
+
+    /// 发送合并后的变更：一次 files-changed 批量事件
+    fn emit_changes(app_handle: &AppHandle, ready: Vec<(PathBuf, &'static str)>) {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut changes = Vec::with_capacity(ready.len());
+        for (path, change_type) in ready {
+            if change_type != "deleted" {
+                Self::sync_checkpoint_tracking(app_handle, &path);
+            }
+
+            changes.push(FileChangeEvent {
+                path: path.to_string_lossy().to_string(),
+                change_type: change_type.to_string(),
+                timestamp,
+            });
+        }
+
+        log::debug!("Emitting {} coalesced file changes", changes.len());
+        let batch = FilesChangedEvent { changes, timestamp };
+        if let Err(e) = app_handle.emit("files-changed", &batch) {
+            log::error!("Failed to emit files changed event: {}", e);
+        }
+    }
+
+    /// 新建的会话 JSONL：等待第一条用户消息写入后发送 session-created 事件
+    fn notify_session_created(app_handle: &AppHandle, path: &Path) {
+        if !is_session_file(path) {
+            return;
+        }
+        let (Some(session_id), Some(project_id)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.parent()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str()),
+        ) else {
+            return;
+        };
+
+        let session_id = session_id.to_string();
+        let project_id = project_id.to_string();
+        let path = path.to_path_buf();
+        let app_handle = app_handle.clone();
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            let (first_message, message_timestamp) = loop {
+                let (message, timestamp) =
+                    crate::commands::claude::extract_first_user_message(&path);
+                if message.is_some() || started.elapsed() >= SESSION_FIRST_MESSAGE_WAIT {
+                    break (message, timestamp);
+                }
+                std::thread::sleep(SESSION_FIRST_MESSAGE_POLL);
+            };
+
+            let created_event = SessionCreatedEvent {
+                session_id,
+                project_id: project_id.clone(),
+                path: path.to_string_lossy().to_string(),
+                first_message,
+                message_timestamp,
+                timestamp: SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            };
+
+            // 通用事件 + 按项目区分的事件，会话列表只需监听当前项目
+            let scoped_event = format!("session-created:{}", Self::event_safe_path(&project_id));
+            for event_name in ["session-created", scoped_event.as_str()] {
+                if let Err(e) = app_handle.emit(event_name, &created_event) {
+                    log::error!("Failed to emit session created event: {}", e);
+                }
+            }
+            log::debug!(
+                "Emitted session created event: {} in {}",
+                created_event.session_id,
+                project_id
+            );
+        });
+    }
+
+    /// 会话 JSONL 变更时，让该会话已有的检查点管理器与磁盘内容同步
+    fn sync_checkpoint_tracking(app_handle: &AppHandle, path: &Path) {
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            return;
+        }
+        let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            return;
+        };
+
+        let session_id = session_id.to_string();
+        let path = path.to_path_buf();
+        let checkpoint_state = app_handle.state::<CheckpointState>().inner().clone();
+        tauri::async_runtime::spawn(async move {
+            // 只同步已打开的会话，不为每个变更的文件创建管理器
+            let Some(manager) = checkpoint_state.get_manager(&session_id).await else {
+                return;
+            };
+            match manager.sync_messages_from_file(&path).await {
+                Ok(synced) if synced > 0 => log::debug!(
+                    "Synced {} new checkpoint messages for session {}",
+                    synced,
+                    session_id
+                ),
+                Ok(_) => {}
+                Err(e) => log::warn!(
+                    "Failed to sync checkpoint tracking for session {}: {}",
+                    session_id,
+                    e
+                ),
+            }
+        });
+    }
+
+    /// 判断路径是否为需要关注的 CLAUDE.md（跳过搜索忽略规则命中的目录）
+    fn is_watched_claude_md(path: &Path, root: &Path, ignore: &IgnoreMatcher) -> bool {
+        let is_claude_md = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| name.eq_ignore_ascii_case("CLAUDE.md"));
+        if !is_claude_md {
+            return false;
+        }
+
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        !relative
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .any(|dir| ignore.is_ignored(dir, true))
+    }
+
+    /// 将路径转换为合法的事件名片段（Tauri 事件名只允许字母数字和 - / : _）
+    fn event_safe_path(path: &str) -> String {
+        path.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_') {
+                    c
                 } else {
-                    log::debug!(
-                        "Emitted file change event: {} ({})",
-                        change_event.path,
-                        change_event.change_type
-                    );
+                    '_'
                 }
+            })
+            .collect()
+    }
+
+    /// 处理 CLAUDE.md 事件：记录最近事件时间，每个路径只启动一个防抖线程
+    fn handle_claude_md_event(
+        event: Event,
+        app_handle: &AppHandle,
+        pending: &Arc<Mutex<HashMap<PathBuf, Instant>>>,
+        root: &Path,
+        ignore: &IgnoreMatcher,
+    ) {
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+
+        for path in event.paths {
+            if !Self::is_watched_claude_md(&path, root, ignore) {
+                continue;
+            }
+
+            let already_pending = {
+                let mut pending = pending.lock().unwrap();
+                pending.insert(path.clone(), Instant::now()).is_some()
+            };
+            if already_pending {
+                continue;
             }
+
+            let app_handle = app_handle.clone();
+            let pending = pending.clone();
+            std::thread::spawn(move || {
+                // 等待直到该路径在防抖间隔内没有新的事件
+                loop {
+                    std::thread::sleep(CLAUDE_MD_DEBOUNCE);
+                    let mut pending = pending.lock().unwrap();
+                    match pending.get(&path) {
+                        Some(last) if last.elapsed() < CLAUDE_MD_DEBOUNCE => continue,
+                        _ => {
+                            pending.remove(&path);
+                            break;
+                        }
+                    }
+                }
+
+                Self::emit_claude_md_change(&app_handle, &path);
+            });
+        }
+    }
+
+    /// 读取 CLAUDE.md 最新内容并发送 claude-md-changed 事件
+    fn emit_claude_md_change(app_handle: &AppHandle, path: &Path) {
+        let content = std::fs::read_to_string(path).ok();
+        let path_str = path.to_string_lossy().to_string();
+        let change_event = ClaudeMdChangeEvent {
+            path: path_str.clone(),
+            change_type: if content.is_some() {
+                "modified"
+            } else {
+                "deleted"
+            }
+            .to_string(),
+            content,
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+
+        // 通用事件 + 按路径区分的事件，前端可只监听正在编辑的文件
+        let scoped_event = format!("claude-md-changed:{}", Self::event_safe_path(&path_str));
+        for event_name in ["claude-md-changed", scoped_event.as_str()] {
+            if let Err(e) = app_handle.emit(event_name, &change_event) {
+                log::error!("Failed to emit CLAUDE.md change event: {}", e);
+            }
+        }
+        log::debug!(
+            "Emitted CLAUDE.md change event: {} ({})",
+            change_event.path,
+            change_event.change_type
+        );
+    }
+
+    /// 设置文件变更的防抖间隔（毫秒）
+    pub fn set_debounce_ms(&self, debounce_ms: u64) -> Result<(), String> {
+        if !FILE_CHANGE_DEBOUNCE_RANGE_MS.contains(&debounce_ms) {
+            return Err(format!(
+                "Debounce must be between {} and {} ms",
+                FILE_CHANGE_DEBOUNCE_RANGE_MS.start(),
+                FILE_CHANGE_DEBOUNCE_RANGE_MS.end()
+            ));
+        }
+        self.debounce_ms.store(debounce_ms, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 获取文件变更的防抖间隔（毫秒）
+    pub fn debounce_ms(&self) -> u64 {
+        self.debounce_ms.load(Ordering::Relaxed)
+    }
+
+    /// 设置是否强制轮询及轮询间隔，并按新设置重建已有的监听
+    pub fn set_polling(&self, force: bool, interval_ms: Option<u64>) -> Result<(), String> {
+        if let Some(interval_ms) = interval_ms {
+            if !POLL_INTERVAL_RANGE_MS.contains(&interval_ms) {
+                return Err(format!(
+                    "Poll interval must be between {} and {} ms",
+                    POLL_INTERVAL_RANGE_MS.start(),
+                    POLL_INTERVAL_RANGE_MS.end()
+                ));
+            }
+            self.poll_interval_ms.store(interval_ms, Ordering::Relaxed);
+        }
+        self.force_polling.store(force, Ordering::Relaxed);
+
+        let mut watchers = self.watchers.lock().unwrap();
+        for (key, entry) in watchers.iter_mut() {
+            match self.create_watcher(
+                entry.kind,
+                &entry.root,
+                entry.recursive,
+                entry.prefer_polling,
+            ) {
+                Ok((watcher, mode)) => {
+                    entry._watcher = watcher;
+                    entry.mode = mode;
+                }
+                Err(e) => log::warn!("Failed to rebuild watch on {}: {}", key, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取监听方式的当前状态
+    pub fn mode_status(&self) -> WatcherModeStatus {
+        let paths = self.get_watched_paths();
+        WatcherModeStatus {
+            force_polling: self.force_polling.load(Ordering::Relaxed),
+            poll_interval_ms: self.poll_interval_ms.load(Ordering::Relaxed),
+            polling_active: paths.iter().any(|p| p.mode == WatchMode::Polling),
+            paths,
         }
     }
 
     /// 获取当前监听的路径列表
-    pub fn get_watched_paths(&self) -> Vec<String> {
+    pub fn get_watched_paths(&self) -> Vec<WatchedPath> {
         let watchers = self.watchers.lock().unwrap();
-        watchers.keys().cloned().collect()
+        let mut paths: Vec<WatchedPath> = watchers
+            .iter()
+            .map(|(path, entry)| WatchedPath {
+                path: path.clone(),
+                project_id: entry.project_id.clone(),
+                ref_count: entry.ref_count,
+                recursive: entry.recursive,
+                mode: entry.mode,
+            })
+            .collect();
+        paths.sort_by(|a, b| a.path.cmp(&b.path));
+        paths
+    }
+}
+
+/// 路径的监听能力检测结果
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WatchCapability {
+    /// 实际检测的目录（传入文件时为其所在目录）
+    pub path: String,
+    /// 能否创建原生监听
+    pub native_supported: bool,
+    /// 写入测试文件后原生事件是否在超时内触发
+    pub native_events_fired: bool,
+    pub recommended_mode: WatchMode,
+    /// 无法完成检测的原因
+    pub error: Option<String>,
+}
+
+// 检测时等待原生事件的最长时间
+const WATCH_PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// 检测路径能否使用原生监听：创建临时监听，写入并删除一个标记文件，看事件是否触发。
+/// 会阻塞至多 WATCH_PROBE_TIMEOUT，应在阻塞线程中调用
+pub fn probe_watch_capability(path: &Path) -> WatchCapability {
+    let dir = if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    let mut capability = WatchCapability {
+        path: dir.to_string_lossy().to_string(),
+        native_supported: false,
+        native_events_fired: false,
+        recommended_mode: WatchMode::Polling,
+        error: None,
+    };
+    if !dir.is_dir() {
+        capability.error = Some(format!("Path does not exist: {}", path.display()));
+        return capability;
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let watcher = RecommendedWatcher::new(
+        move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        },
+        Config::default(),
+    )
+    .and_then(|mut watcher| {
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map(|_| watcher)
+    });
+    let _watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            capability.error = Some(format!("Native watching unavailable: {}", e));
+            return capability;
+        }
+    };
+    capability.native_supported = true;
+
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let marker = dir.join(format!(
+        ".claudia-watch-probe-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+    if let Err(e) = std::fs::write(&marker, b"probe") {
+        // 无法写入时无法验证，能创建原生监听就按原生处理
+        capability.recommended_mode = WatchMode::Native;
+        capability.error = Some(format!("Could not write test file: {}", e));
+        return capability;
+    }
+
+    let deadline = Instant::now() + WATCH_PROBE_TIMEOUT;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match rx.recv_timeout(remaining) {
+            Ok(event)
+                if event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == marker.file_name()) =>
+            {
+                capability.native_events_fired = true;
+                break;
+            }
+            Ok(_) => continue,
+            Err(_) => break,
+        }
     }
+    let _ = std::fs::remove_file(&marker);
+
+    if capability.native_events_fired {
+        capability.recommended_mode = WatchMode::Native;
+    }
+    capability
 }
 
 // 全局文件监听管理器状态
@@ -198,3 +967,38 @@ impl FileWatcherState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_change_type() {
+        assert_eq!(merge_change_type("created", "modified"), "created");
+        assert_eq!(merge_change_type("created", "deleted"), "deleted");
+        assert_eq!(merge_change_type("deleted", "created"), "modified");
+        assert_eq!(merge_change_type("modified", "modified"), "modified");
+    }
+
+    #[test]
+    fn test_probe_watch_capability_in_tempdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let capability = probe_watch_capability(dir.path());
+        // 原生事件在容器、NFS、WSL 上可能不触发，只检查结果是否自洽
+        assert_eq!(
+            capability.recommended_mode == WatchMode::Native,
+            capability.native_events_fired
+        );
+        assert!(!capability.native_events_fired || capability.native_supported);
+        // 探测文件用完即删除
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_probe_watch_capability_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let capability = probe_watch_capability(&dir.path().join("missing"));
+        assert!(!capability.native_supported);
+        assert!(capability.error.is_some());
+    }
+}
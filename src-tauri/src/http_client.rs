@@ -4,7 +4,7 @@
 /// 支持多种预设配置和自定义配置
 
 use anyhow::Result;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use std::time::Duration;
 
 /// HTTP 客户端配置
@@ -12,6 +12,10 @@ use std::time::Duration;
 pub struct ClientConfig {
     /// 超时时间（秒）
     pub timeout_secs: u64,
+    /// 精确到毫秒的总超时，设置后覆盖 `timeout_secs`
+    pub timeout_ms: Option<u64>,
+    /// 建立连接的超时（毫秒），未设置时只受总超时限制
+    pub connect_timeout_ms: Option<u64>,
     /// 是否接受无效证书（用于开发/测试）
     pub accept_invalid_certs: bool,
     /// 是否使用系统代理
@@ -24,6 +28,8 @@ impl Default for ClientConfig {
     fn default() -> Self {
         Self {
             timeout_secs: 10,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             accept_invalid_certs: false,
             use_proxy: true,
             user_agent: Some("Claudia/1.0".to_string()),
@@ -43,6 +49,18 @@ impl ClientConfig {
         self
     }
 
+    /// 设置毫秒级总超时
+    pub fn timeout_ms(mut self, ms: u64) -> Self {
+        self.timeout_ms = Some(ms);
+        self
+    }
+
+    /// 设置连接超时（毫秒）
+    pub fn connect_timeout_ms(mut self, ms: u64) -> Self {
+        self.connect_timeout_ms = Some(ms);
+        self
+    }
+
     /// 设置是否接受无效证书
     pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
         self.accept_invalid_certs = accept;
@@ -74,7 +92,15 @@ impl ClientConfig {
 /// let client = create_client(config)?;
 /// ```
 pub fn create_client(config: ClientConfig) -> Result<Client> {
-    let mut builder = Client::builder().timeout(Duration::from_secs(config.timeout_secs));
+    let timeout = config
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or_else(|| Duration::from_secs(config.timeout_secs));
+    let mut builder = Client::builder().timeout(timeout);
+
+    if let Some(ms) = config.connect_timeout_ms {
+        builder = builder.connect_timeout(Duration::from_millis(ms));
+    }
 
     if config.accept_invalid_certs {
         builder = builder.danger_accept_invalid_certs(true);
@@ -132,7 +158,7 @@ pub fn fast_client() -> Result<Client> {
     )
 }
 
-/// 创建安全客户端（用于 PackyCode API）
+/// 安全客户端配置（用于 PackyCode API），可在创建客户端前再按中转站覆盖超时
 ///
 /// 配置:
 /// - 超时: 30 秒
@@ -142,17 +168,15 @@ pub fn fast_client() -> Result<Client> {
 ///
 /// # Example
 /// ```
-/// use claudia_lib::http_client::secure_client;
+/// use claudia_lib::http_client::{create_client, secure_config};
 ///
-/// let client = secure_client()?;
+/// let client = create_client(secure_config())?;
 /// ```
-pub fn secure_client() -> Result<Client> {
-    create_client(
-        ClientConfig::default()
-            .timeout(30)
-            .use_proxy(false)
-            .user_agent("Claudia"),
-    )
+pub fn secure_config() -> ClientConfig {
+    ClientConfig::default()
+        .timeout(30)
+        .use_proxy(false)
+        .user_agent("Claudia")
 }
 
 /// 创建长超时客户端（用于大文件传输等）
@@ -167,6 +191,115 @@ pub fn long_timeout_client() -> Result<Client> {
     create_client(ClientConfig::default().timeout(60))
 }
 
+/// 重试策略（仅用于幂等的 GET 请求，不要用于创建/修改/删除等写操作）
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（含首次请求）
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    pub base_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 创建新的重试策略
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置最大尝试次数（至少 1 次）
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// 设置首次重试前的等待时间
+    pub fn base_delay_ms(mut self, delay_ms: u64) -> Self {
+        self.base_delay_ms = delay_ms;
+        self
+    }
+
+    /// 第 `attempt` 次请求失败后的等待时间（指数退避）
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_millis(self.base_delay_ms.saturating_mul(factor))
+    }
+}
+
+/// 带重试发送的结果
+#[derive(Debug)]
+pub struct RetryOutcome {
+    /// 最后一次请求的结果；可重试的状态码在用尽次数后仍以响应形式返回
+    pub result: reqwest::Result<Response>,
+    /// 实际发送的请求次数
+    pub attempts: u32,
+}
+
+/// 是否为可重试的状态码（5xx、408、429）
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 是否为可重试的网络错误（超时、连接失败、连接被重置）
+pub fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// 按重试策略发送幂等请求，遇到超时、连接错误或 5xx 时指数退避重试；
+/// 400/401/403 等客户端错误直接返回，不会重试
+pub async fn send_with_retry(request: RequestBuilder, policy: &RetryPolicy) -> RetryOutcome {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        // 请求体为流时无法复制，只能发送一次
+        let next = if attempts < policy.max_attempts {
+            request.try_clone()
+        } else {
+            None
+        };
+        let Some(current) = next else {
+            let result = request.send().await;
+            return RetryOutcome { result, attempts };
+        };
+
+        let result = current.send().await;
+        let retryable = match &result {
+            Ok(response) => is_retryable_status(response.status()),
+            Err(e) => is_retryable_error(e),
+        };
+        if !retryable {
+            return RetryOutcome { result, attempts };
+        }
+
+        let delay = policy.delay_after(attempts);
+        match &result {
+            Ok(response) => log::warn!(
+                "Request attempt {} returned HTTP {}, retrying in {:?}",
+                attempts,
+                response.status(),
+                delay
+            ),
+            Err(e) => log::warn!(
+                "Request attempt {} failed: {}, retrying in {:?}",
+                attempts,
+                e,
+                delay
+            ),
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,8 +341,39 @@ mod tests {
 
     #[test]
     fn test_create_secure_client() {
-        let result = secure_client();
-        assert!(result.is_ok());
+        let config = secure_config();
+        assert_eq!(config.timeout_secs, 30);
+        assert!(!config.use_proxy);
+        assert!(create_client(config).is_ok());
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy::new().base_delay_ms(100);
+        assert_eq!(policy.max_attempts, 3);
+        assert_eq!(policy.delay_after(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_after(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_after(3), Duration::from_millis(400));
+        assert_eq!(RetryPolicy::new().max_attempts(0).max_attempts, 1);
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(StatusCode::FORBIDDEN));
+        assert!(!is_retryable_status(StatusCode::OK));
+    }
+
+    #[test]
+    fn test_millisecond_timeouts() {
+        let config = ClientConfig::new().timeout_ms(2500).connect_timeout_ms(800);
+        assert_eq!(config.timeout_ms, Some(2500));
+        assert_eq!(config.connect_timeout_ms, Some(800));
+        assert!(create_client(config).is_ok());
     }
 
     #[test]
@@ -64,12 +64,31 @@ impl SimpleI18n {
             ("en-US", "relay_adapter.parse_error") => "Failed to parse response".to_string(),
             ("en-US", "relay_adapter.http_error") => "HTTP request failed".to_string(),
             ("en-US", "relay_adapter.network_error") => "Network connection failed".to_string(),
+            ("en-US", "relay_adapter.list_models_failed") => {
+                "Failed to list models offered by the relay station".to_string()
+            }
             ("en-US", "relay_station.enabled_success") => {
                 "Relay station enabled successfully".to_string()
             }
             ("en-US", "relay_station.disabled_success") => {
                 "Relay station disabled successfully".to_string()
             }
+            ("en-US", "relay_station.test_failed_not_applied") => {
+                "Connection test failed, relay station was not enabled".to_string()
+            }
+            ("en-US", "relay_station.ccr_conflict") => {
+                "CCR service is running and controls Claude's API config. Stop CCR or enable the relay station anyway".to_string()
+            }
+            ("en-US", "ccr.relay_station_conflict") => {
+                "A relay station is enabled and controls Claude's API config. Disable it or start CCR anyway".to_string()
+            }
+            ("en-US", "routing.conflict") => {
+                "Both CCR and a relay station are active and will overwrite each other's Claude config. Please keep only one".to_string()
+            }
+            ("en-US", "claude_md_template.rust") => "Rust crate or Cargo workspace".to_string(),
+            ("en-US", "claude_md_template.node") => "Node.js / TypeScript project".to_string(),
+            ("en-US", "claude_md_template.python") => "Python package".to_string(),
+            ("en-US", "claude_md_template.monorepo") => "Monorepo with multiple apps and packages".to_string(),
             ("en-US", "relay_station.name_required") => "Station name is required".to_string(),
             ("en-US", "relay_station.api_url_required") => "API URL is required".to_string(),
             ("en-US", "relay_station.invalid_url") => "Invalid URL format".to_string(),
@@ -110,8 +129,25 @@ impl SimpleI18n {
             ("zh-CN", "relay_adapter.parse_error") => "解析响应失败".to_string(),
             ("zh-CN", "relay_adapter.http_error") => "HTTP 请求失败".to_string(),
             ("zh-CN", "relay_adapter.network_error") => "网络连接失败".to_string(),
+            ("zh-CN", "relay_adapter.list_models_failed") => "获取中转站模型列表失败".to_string(),
             ("zh-CN", "relay_station.enabled_success") => "中转站启用成功".to_string(),
             ("zh-CN", "relay_station.disabled_success") => "中转站禁用成功".to_string(),
+            ("zh-CN", "relay_station.test_failed_not_applied") => {
+                "连接测试失败，未启用该中转站".to_string()
+            }
+            ("zh-CN", "relay_station.ccr_conflict") => {
+                "CCR 服务正在运行并接管 Claude API 配置，请先停止 CCR 或强制启用中转站".to_string()
+            }
+            ("zh-CN", "ccr.relay_station_conflict") => {
+                "已启用的中转站正在接管 Claude API 配置，请先禁用中转站或强制启动 CCR".to_string()
+            }
+            ("zh-CN", "routing.conflict") => {
+                "CCR 与中转站同时生效，会互相覆盖 Claude 配置，请只保留其中一个".to_string()
+            }
+            ("zh-CN", "claude_md_template.rust") => "Rust crate 或 Cargo 工作区".to_string(),
+            ("zh-CN", "claude_md_template.node") => "Node.js / TypeScript 项目".to_string(),
+            ("zh-CN", "claude_md_template.python") => "Python 包".to_string(),
+            ("zh-CN", "claude_md_template.monorepo") => "包含多个应用和包的 Monorepo".to_string(),
             ("zh-CN", "relay_station.name_required") => "中转站名称不能为空".to_string(),
             ("zh-CN", "relay_station.api_url_required") => "API地址不能为空".to_string(),
             ("zh-CN", "relay_station.invalid_url") => "无效的URL格式".to_string(),
@@ -13,30 +13,50 @@ mod types;
 mod utils;
 
 use checkpoint::state::CheckpointState;
+use commands::agent_notifications::{
+    get_agent_notification_config, set_agent_notification_config, test_agent_webhook,
+};
+use commands::agent_schedules::{
+    clear_agent_schedule, get_agent_schedule, get_next_run_time, pause_schedule, resume_schedule,
+    run_agent_scheduler, set_agent_schedule,
+};
 use commands::agents::{
-    cleanup_finished_processes, create_agent, delete_agent, execute_agent, export_agent,
-    export_agent_to_file, fetch_github_agent_content, fetch_github_agents, get_agent,
-    get_agent_run, get_agent_run_with_real_time_metrics, get_claude_binary_path,
-    get_live_session_output, get_model_mappings, get_session_output, get_session_status,
-    import_agent, import_agent_from_file, import_agent_from_github, init_database,
-    kill_agent_session, list_agent_runs, list_agent_runs_with_metrics, list_agents,
-    list_claude_installations, list_running_sessions, load_agent_session_history,
-    set_claude_binary_path, stream_session_output, update_agent, update_model_mapping, AgentDb,
+    cleanup_finished_processes, create_agent, delete_agent, duplicate_agent, execute_agent,
+    export_agent, export_agent_to_file, fetch_github_agent_content, fetch_github_agents, get_agent,
+    get_agent_aggregate_metrics, get_agent_run, get_agent_run_with_real_time_metrics,
+    get_claude_binary_path, get_live_output_limit, get_live_session_output, get_model_mappings,
+    get_session_output, get_session_status, import_agent, import_agent_from_file,
+    import_agent_from_github, init_database_with_recovery, kill_agent_session, list_agent_runs,
+    list_agent_runs_with_metrics, list_agents, list_claude_installations, list_running_sessions,
+    load_agent_session_history, load_live_output_limit, reconcile_interrupted_runs,
+    refresh_claude_binary_cache, search_agent_runs, set_claude_binary_path, set_live_output_limit,
+    stream_session_output, update_agent, update_model_mapping, AgentDb,
 };
 use commands::claude::{
-    cancel_claude_execution, check_auto_checkpoint, check_claude_version, cleanup_old_checkpoints,
-    clear_checkpoint_manager, continue_claude_code, create_checkpoint, execute_claude_code,
-    find_claude_md_files, fork_from_checkpoint, get_checkpoint_diff, get_checkpoint_settings,
-    get_checkpoint_state_stats, get_claude_session_output, get_claude_settings,
+    attach_session, cancel_claude_execution, check_auto_checkpoint, check_claude_version,
+    cleanup_old_checkpoints, cleanup_project_checkpoints, clear_checkpoint_manager,
+    continue_claude_code, create_checkpoint, debug_project_resolution, evict_idle_managers,
+    execute_claude_code, find_claude_md_files, find_duplicate_projects, fork_from_checkpoint,
+    get_checkpoint_diff, get_checkpoint_settings, get_checkpoint_state_stats,
+    get_claude_config_dir, get_claude_output_since, get_claude_session_output, get_claude_settings,
     get_claude_settings_backup, get_hooks_config, get_project_sessions,
-    get_recently_modified_files, get_session_timeline, get_system_prompt, list_checkpoints,
-    list_directory_contents, list_projects, list_running_claude_sessions, load_session_history,
-    open_new_session, read_claude_md_file, restore_checkpoint, resume_claude_code,
+    get_recently_modified_files, get_session_timeline, get_system_prompt, grep_files,
+    list_checkpoints, list_directory_contents, list_projects, list_running_claude_sessions,
+    load_claude_dir_override, load_session_history, merge_projects, open_new_session,
+    preview_restore_checkpoint, read_claude_md_file, repair_session_jsonl, replay_session,
+    restore_checkpoint, restore_checkpoint_files, resume_claude_code, run_claude_diagnostic,
     save_claude_md_file, save_claude_settings, save_claude_settings_backup, save_system_prompt,
-    search_files, track_checkpoint_message, track_session_messages,
-    unwatch_claude_project_directory, update_checkpoint_settings, update_hooks_config,
-    validate_hook_command, watch_claude_project_directory, ClaudeProcessState,
+    search_files, set_claude_config_dir, set_project_color, set_project_favorite,
+    set_session_output_paused, sync_checkpoint_tracking_from_file, track_checkpoint_message,
+    track_session_messages, unwatch_claude_project_directory, unwatch_projects,
+    update_checkpoint_settings, update_hooks_config, validate_hook_command,
+    watch_claude_project_directory, watch_projects, ClaudeProcessState,
+};
+use commands::claude_md::{
+    get_effective_claude_md, list_claude_md_templates, scaffold_claude_md, unwatch_claude_md_files,
+    watch_claude_md_files,
 };
+use commands::diagnostics::{export_diagnostics, run_self_test};
 use commands::mcp::{
     mcp_add, mcp_add_from_claude_desktop, mcp_add_json, mcp_export_servers, mcp_get,
     mcp_get_server_status, mcp_list, mcp_read_project_config, mcp_remove,
@@ -44,47 +64,71 @@ use commands::mcp::{
 };
 
 use commands::ccr::{
-    check_ccr_installation, get_ccr_config_path, get_ccr_service_status, get_ccr_version,
-    open_ccr_ui, restart_ccr_service, start_ccr_service, stop_ccr_service,
-};
-use commands::prompt_files::{
-    prompt_file_apply, prompt_file_create, prompt_file_deactivate, prompt_file_delete,
-    prompt_file_export, prompt_file_get, prompt_file_import_from_claude_md,
-    prompt_file_update, prompt_files_import_batch, prompt_files_list, 
-    prompt_files_update_order,
+    check_ccr_installation, follow_ccr_logs, get_ccr_config_path, get_ccr_log_path,
+    get_ccr_service_status, get_ccr_version, install_ccr, open_ccr_ui, read_ccr_config,
+    refresh_ccr_path, restart_ccr_service, start_ccr_service, stop_ccr_service,
+    stop_following_ccr_logs, write_ccr_config,
 };
 use commands::filesystem::{
-    get_file_info, get_file_tree, get_watched_paths, read_directory_tree, read_file,
-    search_files_by_name, unwatch_directory, watch_directory, write_file,
+    can_watch_path, get_claude_disk_usage, get_directory_size, get_file_info, get_file_tree,
+    get_file_watcher_debounce, get_file_watcher_mode, get_watched_paths, load_file_watcher_polling,
+    read_directory_tree, read_file, search_files_by_name, set_file_watcher_debounce,
+    set_file_watcher_polling, unwatch_all, unwatch_directory, watch_directory, write_file,
 };
 use commands::git::{
-    get_git_branches, get_git_commits, get_git_diff, get_git_history, get_git_status,
+    get_git_branches, get_git_commits, get_git_diff, get_git_history, get_git_remote_status,
+    get_git_status, git_checkout, git_commit, git_create_branch, git_diff_since, git_stage,
+    git_unstage, start_isolated_session,
 };
 use commands::language::{get_current_language, get_supported_languages, set_language};
 use commands::packycode_nodes::{
-    auto_select_best_node, get_packycode_nodes, test_all_packycode_nodes,
+    auto_select_best_node, get_packycode_nodes, get_packycode_nodes_with_status,
+    set_packycode_node_override, test_all_packycode_nodes,
+};
+use commands::project_archive::{archive_project, unarchive_project};
+use commands::project_budgets::{get_project_budget, set_project_budget};
+use commands::prompt_files::{
+    prompt_file_apply, prompt_file_create, prompt_file_deactivate, prompt_file_delete,
+    prompt_file_export, prompt_file_get, prompt_file_import_from_claude_md, prompt_file_update,
+    prompt_files_import_batch, prompt_files_list, prompt_files_update_order,
 };
 use commands::proxy::{apply_proxy_settings, get_proxy_settings, save_proxy_settings};
 use commands::relay_adapters::{
-    packycode_get_user_quota, relay_station_create_token, relay_station_delete_token,
-    relay_station_get_info, relay_station_get_usage_logs, relay_station_get_user_info,
-    relay_station_list_tokens, relay_station_test_connection, relay_station_update_token,
+    check_packycode_budget, get_packycode_budget_threshold, load_relay_timeout_defaults,
+    packycode_get_user_quota, relay_get_timeout_settings, relay_set_timeout_settings,
+    relay_station_create_token, relay_station_delete_token, relay_station_get_info,
+    relay_station_get_usage_logs, relay_station_get_user_info, relay_station_list_tokens,
+    relay_station_test_connection, relay_station_test_models, relay_station_update_token,
+    set_packycode_budget_threshold,
 };
 use commands::relay_stations::{
-    relay_station_create, relay_station_delete, relay_station_get,
-    relay_station_get_current_config, relay_station_restore_config, relay_station_sync_config,
-    relay_station_toggle_enable, relay_station_update, relay_station_update_order,
-    relay_stations_export, relay_stations_import, relay_stations_list,
+    list_claude_config_backups, migrate_tokens_to_keychain, relay_station_create,
+    relay_station_delete, relay_station_get, relay_station_get_current_config,
+    relay_station_get_token_keychain, relay_station_restore_config, relay_station_reveal_token,
+    relay_station_set_token_keychain, relay_station_sync_config, relay_station_toggle_enable,
+    relay_station_update, relay_station_update_order, relay_stations_export, relay_stations_import,
+    relay_stations_list, restore_claude_config_at,
 };
+use commands::routing::{check_routing_conflict, get_active_routing};
+use commands::search_ignore::{get_search_ignore_patterns, set_search_ignore_patterns};
+use commands::session_compare::compare_sessions;
+use commands::session_export::{
+    build_context_from_session, export_session_html, export_session_markdown,
+};
+use commands::session_tools::{get_session_touched_files, get_tool_usage_stats};
 use commands::smart_sessions::{
     cleanup_old_smart_sessions_command, create_smart_quick_start_session, get_smart_session_config,
     list_smart_sessions_command, toggle_smart_session_mode, update_smart_session_config,
 };
 use commands::storage::{
-    storage_delete_row, storage_execute_sql, storage_insert_row, storage_list_tables,
-    storage_read_table, storage_reset_database, storage_update_row,
+    reset_db_connection, restore_database_from_backup, storage_delete_row, storage_describe_table,
+    storage_execute_batch, storage_execute_sql, storage_execute_sql_params, storage_insert_row,
+    storage_list_tables, storage_read_table, storage_read_table_page, storage_reset_database,
+    storage_update_row, take_database_recovery_notice, DatabaseRecoveryState,
+};
+use commands::system::{
+    flush_dns, get_log_dir_path, open_in_editor, read_recent_logs, reveal_log_dir,
 };
-use commands::system::flush_dns;
 use commands::terminal::{
     cleanup_terminal_sessions, close_terminal_session, create_terminal_session,
     list_terminal_sessions, resize_terminal, send_terminal_input, TerminalState,
@@ -93,9 +137,10 @@ use commands::usage::{
     get_session_stats, get_usage_by_date_range, get_usage_details, get_usage_stats,
 };
 use commands::usage_cache::{
-    usage_check_updates, usage_clear_cache, usage_force_scan, usage_get_stats_cached,
-    usage_scan_update, UsageCacheState,
+    export_usage_csv, search_all_sessions, usage_check_updates, usage_clear_cache,
+    usage_force_scan, usage_get_stats_cached, usage_scan_update, UsageCacheState,
 };
+use commands::usage_currency::get_usage_stats_in_currency;
 use commands::usage_index::{
     usage_get_summary, usage_import_diffs, usage_scan_index, usage_scan_progress, UsageIndexState,
 };
@@ -114,6 +159,7 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_log::Builder::new()
                 .level(log::LevelFilter::Debug)
@@ -225,97 +271,79 @@ fn main() {
         })
         .setup(|app| {
             // Initialize agents database
-            let conn = init_database(&app.handle()).expect("Failed to initialize agents database");
+            let (mut conn, database_recovery) = init_database_with_recovery(app.handle())?;
+            app.manage(DatabaseRecoveryState(Mutex::new(database_recovery)));
+
+            // Upgrade settings stored by older versions before anything reads them
+            if let Err(e) = commands::settings_migrations::run_settings_migrations(&mut conn) {
+                log::error!("{}", e);
+            }
 
             // Initialize API nodes database
-            commands::api_nodes::init_nodes_db()
-                .expect("Failed to initialize API nodes database");
+            commands::api_nodes::init_nodes_db().expect("Failed to initialize API nodes database");
 
             // Load and apply proxy settings from the database
-            {
-                let db = AgentDb(Mutex::new(conn));
-                let proxy_settings = match db.0.lock() {
-                    Ok(conn) => {
-                        // Directly query proxy settings from the database
-                        let mut settings = commands::proxy::ProxySettings::default();
-
-                        let keys = vec![
-                            ("proxy_enabled", "enabled"),
-                            ("proxy_http", "http_proxy"),
-                            ("proxy_https", "https_proxy"),
-                            ("proxy_no", "no_proxy"),
-                            ("proxy_all", "all_proxy"),
-                        ];
-
-                        for (db_key, field) in keys {
-                            if let Ok(value) = conn.query_row(
-                                "SELECT value FROM app_settings WHERE key = ?1",
-                                rusqlite::params![db_key],
-                                |row| row.get::<_, String>(0),
-                            ) {
-                                match field {
-                                    "enabled" => settings.enabled = value == "true",
-                                    "http_proxy" => {
-                                        settings.http_proxy = Some(value).filter(|s| !s.is_empty())
-                                    }
-                                    "https_proxy" => {
-                                        settings.https_proxy = Some(value).filter(|s| !s.is_empty())
-                                    }
-                                    "no_proxy" => {
-                                        settings.no_proxy = Some(value).filter(|s| !s.is_empty())
-                                    }
-                                    "all_proxy" => {
-                                        settings.all_proxy = Some(value).filter(|s| !s.is_empty())
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-
-                        log::info!("Loaded proxy settings: enabled={}", settings.enabled);
-                        settings
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to lock database for proxy settings: {}", e);
-                        commands::proxy::ProxySettings::default()
-                    }
-                };
-
-                // Apply the proxy settings
-                apply_proxy_settings(&proxy_settings);
+            let proxy_settings = commands::proxy::load_proxy_settings(&conn);
+            log::info!("Loaded proxy settings: enabled={}", proxy_settings.enabled);
+            apply_proxy_settings(&proxy_settings);
+
+            // Runs still marked as running belong to a previous app session
+            match reconcile_interrupted_runs(&conn) {
+                Ok(runs) if !runs.is_empty() => {
+                    log::info!("Marked {} stale agent run(s) as interrupted", runs.len())
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to reconcile stale agent runs: {}", e),
             }
 
-            // Re-open the connection for the app to manage
-            let conn = init_database(&app.handle()).expect("Failed to initialize agents database");
+            // Move plaintext relay station tokens into the OS keychain, if enabled in settings
+            match migrate_tokens_to_keychain(&conn) {
+                Ok(count) if count > 0 => {
+                    log::info!("Moved {} relay station token(s) to the OS keychain", count)
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to migrate relay station tokens: {}", e),
+            }
+
+            let live_output_limit = load_live_output_limit(&conn);
+            let (force_polling, poll_interval_ms) = load_file_watcher_polling(&conn);
+            load_claude_dir_override(&conn);
+            load_relay_timeout_defaults(&conn);
             app.manage(AgentDb(Mutex::new(conn)));
 
             // Initialize checkpoint state
             let checkpoint_state = CheckpointState::new();
 
             // Set the Claude directory path
-            if let Ok(claude_dir) = dirs::home_dir()
-                .ok_or_else(|| "Could not find home directory")
-                .and_then(|home| {
-                    let claude_path = home.join(".claude");
-                    claude_path
-                        .canonicalize()
-                        .map_err(|_| "Could not find ~/.claude directory")
-                })
-            {
-                let state_clone = checkpoint_state.clone();
-                tauri::async_runtime::spawn(async move {
-                    state_clone.set_claude_dir(claude_dir).await;
-                });
+            match commands::claude::get_claude_dir() {
+                Ok(claude_dir) => {
+                    let state_clone = checkpoint_state.clone();
+                    tauri::async_runtime::spawn(async move {
+                        state_clone.set_claude_dir(claude_dir).await;
+                    });
+                }
+                Err(e) => log::error!("Checkpoints are unavailable: {}", e),
             }
 
             app.manage(checkpoint_state);
 
             // Initialize process registry
-            app.manage(ProcessRegistryState::default());
+            let process_registry = ProcessRegistryState::default();
+            if let Some(limit) = live_output_limit {
+                let _ = process_registry.0.set_live_output_limit(limit);
+            }
+            app.manage(process_registry);
 
             // Initialize file watcher state
             let file_watcher_state = FileWatcherState::new();
             file_watcher_state.init(app.handle().clone());
+            if force_polling || poll_interval_ms.is_some() {
+                if let Err(e) = file_watcher_state
+                    .with_manager(|manager| manager.set_polling(force_polling, poll_interval_ms))
+                {
+                    log::warn!("Ignoring saved file watcher polling setting: {}", e);
+                }
+            }
             app.manage(file_watcher_state);
 
             // Initialize Claude process state
@@ -328,6 +356,9 @@ fn main() {
             // Initialize Terminal state
             app.manage(TerminalState::default());
 
+            // Start the cron scheduler for agents once all state it needs is managed
+            tauri::async_runtime::spawn(run_agent_scheduler(app.handle().clone()));
+
             // Optionally auto-open DevTools if env var is set (works in packaged builds)
             if std::env::var("TAURI_OPEN_DEVTOOLS").ok().as_deref() == Some("1") {
                 if let Some(win) = app.get_webview_window("main") {
@@ -339,8 +370,19 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // Claude & Project Management
             list_projects,
+            set_project_favorite,
+            set_project_color,
             get_project_sessions,
+            find_duplicate_projects,
+            merge_projects,
+            debug_project_resolution,
+            archive_project,
+            unarchive_project,
+            set_project_budget,
+            get_project_budget,
             get_claude_settings,
+            get_claude_config_dir,
+            set_claude_config_dir,
             get_claude_settings_backup,
             open_new_session,
             get_system_prompt,
@@ -350,18 +392,40 @@ fn main() {
             save_claude_settings_backup,
             watch_claude_project_directory,
             unwatch_claude_project_directory,
+            watch_projects,
+            unwatch_projects,
             find_claude_md_files,
             read_claude_md_file,
             save_claude_md_file,
+            list_claude_md_templates,
+            scaffold_claude_md,
+            get_effective_claude_md,
+            watch_claude_md_files,
+            unwatch_claude_md_files,
             load_session_history,
+            repair_session_jsonl,
+            export_session_markdown,
+            export_session_html,
+            build_context_from_session,
+            compare_sessions,
+            get_session_touched_files,
+            get_tool_usage_stats,
             execute_claude_code,
             continue_claude_code,
+            replay_session,
             resume_claude_code,
             cancel_claude_execution,
             list_running_claude_sessions,
+            set_session_output_paused,
             get_claude_session_output,
+            get_claude_output_since,
+            attach_session,
+            run_claude_diagnostic,
             list_directory_contents,
             search_files,
+            grep_files,
+            get_search_ignore_patterns,
+            set_search_ignore_patterns,
             get_recently_modified_files,
             get_hooks_config,
             update_hooks_config,
@@ -369,6 +433,8 @@ fn main() {
             // Checkpoint Management
             create_checkpoint,
             restore_checkpoint,
+            preview_restore_checkpoint,
+            restore_checkpoint_files,
             list_checkpoints,
             fork_from_checkpoint,
             get_session_timeline,
@@ -376,22 +442,37 @@ fn main() {
             get_checkpoint_diff,
             track_checkpoint_message,
             track_session_messages,
+            sync_checkpoint_tracking_from_file,
             check_auto_checkpoint,
             cleanup_old_checkpoints,
+            cleanup_project_checkpoints,
             get_checkpoint_settings,
             clear_checkpoint_manager,
             get_checkpoint_state_stats,
+            evict_idle_managers,
             // Agent Management
             list_agents,
             create_agent,
             update_agent,
             delete_agent,
+            duplicate_agent,
+            set_agent_schedule,
+            get_agent_schedule,
+            clear_agent_schedule,
+            pause_schedule,
+            resume_schedule,
+            get_next_run_time,
+            get_agent_notification_config,
+            set_agent_notification_config,
+            test_agent_webhook,
             get_agent,
             execute_agent,
             list_agent_runs,
             get_agent_run,
+            search_agent_runs,
             list_agent_runs_with_metrics,
             get_agent_run_with_real_time_metrics,
+            get_agent_aggregate_metrics,
             list_running_sessions,
             kill_agent_session,
             get_session_status,
@@ -402,6 +483,9 @@ fn main() {
             load_agent_session_history,
             get_claude_binary_path,
             set_claude_binary_path,
+            refresh_claude_binary_cache,
+            get_live_output_limit,
+            set_live_output_limit,
             list_claude_installations,
             export_agent,
             export_agent_to_file,
@@ -428,6 +512,9 @@ fn main() {
             usage_clear_cache,
             usage_force_scan,
             usage_check_updates,
+            search_all_sessions,
+            export_usage_csv,
+            get_usage_stats_in_currency,
             // MCP (Model Context Protocol)
             mcp_add,
             mcp_list,
@@ -444,12 +531,19 @@ fn main() {
             mcp_export_servers,
             // Storage Management
             storage_list_tables,
+            storage_describe_table,
             storage_read_table,
+            storage_read_table_page,
             storage_update_row,
             storage_delete_row,
             storage_insert_row,
+            storage_execute_batch,
             storage_execute_sql,
+            storage_execute_sql_params,
             storage_reset_database,
+            restore_database_from_backup,
+            reset_db_connection,
+            take_database_recovery_notice,
             // Smart Sessions Management
             create_smart_quick_start_session,
             get_smart_session_config,
@@ -459,9 +553,12 @@ fn main() {
             cleanup_old_smart_sessions_command,
             // Slash Commands
             commands::slash_commands::slash_commands_list,
+            commands::slash_commands::list_all_slash_commands,
             commands::slash_commands::slash_command_get,
             commands::slash_commands::slash_command_save,
             commands::slash_commands::slash_command_delete,
+            commands::slash_commands::fetch_github_slash_commands,
+            commands::slash_commands::import_slash_command_from_github,
             // Prompt Files Management (Database Based)
             prompt_files_list,
             prompt_file_get,
@@ -484,29 +581,44 @@ fn main() {
             // Relay Stations
             relay_stations_list,
             relay_station_get,
+            relay_station_reveal_token,
+            relay_station_get_token_keychain,
+            relay_station_set_token_keychain,
             relay_station_create,
             relay_station_update,
             relay_station_delete,
             relay_station_toggle_enable,
             relay_station_sync_config,
             relay_station_restore_config,
+            list_claude_config_backups,
+            restore_claude_config_at,
             relay_station_get_current_config,
             relay_stations_export,
             relay_stations_import,
             relay_station_update_order,
+            get_active_routing,
+            check_routing_conflict,
             relay_station_get_info,
             relay_station_get_user_info,
             relay_station_test_connection,
+            relay_station_test_models,
+            relay_get_timeout_settings,
+            relay_set_timeout_settings,
             relay_station_get_usage_logs,
             relay_station_list_tokens,
             relay_station_create_token,
             relay_station_update_token,
             relay_station_delete_token,
             packycode_get_user_quota,
+            check_packycode_budget,
+            get_packycode_budget_threshold,
+            set_packycode_budget_threshold,
             // PackyCode Nodes
             test_all_packycode_nodes,
             auto_select_best_node,
             get_packycode_nodes,
+            get_packycode_nodes_with_status,
+            set_packycode_node_override,
             // API Nodes Management
             commands::api_nodes::init_default_nodes,
             commands::api_nodes::list_api_nodes,
@@ -517,20 +629,36 @@ fn main() {
             commands::api_nodes::test_all_api_nodes,
             // File System
             read_directory_tree,
+            get_directory_size,
+            get_claude_disk_usage,
             search_files_by_name,
             get_file_info,
             watch_directory,
+            can_watch_path,
             unwatch_directory,
             get_watched_paths,
+            unwatch_all,
+            set_file_watcher_debounce,
+            get_file_watcher_debounce,
+            set_file_watcher_polling,
+            get_file_watcher_mode,
             read_file,
             write_file,
             get_file_tree,
             // Git
             get_git_status,
+            get_git_remote_status,
             get_git_history,
             get_git_branches,
             get_git_diff,
             get_git_commits,
+            git_stage,
+            git_unstage,
+            git_commit,
+            git_create_branch,
+            git_checkout,
+            git_diff_since,
+            start_isolated_session,
             // Terminal
             create_terminal_session,
             send_terminal_input,
@@ -540,16 +668,74 @@ fn main() {
             cleanup_terminal_sessions,
             // CCR (Claude Code Router)
             check_ccr_installation,
+            refresh_ccr_path,
+            install_ccr,
             get_ccr_version,
             get_ccr_service_status,
             start_ccr_service,
             stop_ccr_service,
             restart_ccr_service,
             open_ccr_ui,
+            get_ccr_log_path,
+            follow_ccr_logs,
+            stop_following_ccr_logs,
             get_ccr_config_path,
+            read_ccr_config,
+            write_ccr_config,
             // System utilities
             flush_dns,
+            get_log_dir_path,
+            read_recent_logs,
+            reveal_log_dir,
+            open_in_editor,
+            export_diagnostics,
+            run_self_test,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                terminate_tracked_processes(app_handle);
+            }
+        });
+}
+
+/// Grace period for each tracked process when the app is quitting
+const EXIT_KILL_GRACE_PERIOD_MS: u64 = 1500;
+
+/// Terminate all tracked Claude/agent processes so they don't outlive the app
+fn terminate_tracked_processes(app_handle: &tauri::AppHandle) {
+    let registry = app_handle.state::<ProcessRegistryState>().0.clone();
+    let terminated = tauri::async_runtime::block_on(async move {
+        registry
+            .terminate_all(std::time::Duration::from_millis(EXIT_KILL_GRACE_PERIOD_MS))
+            .await
+    });
+
+    if terminated.is_empty() {
+        return;
+    }
+
+    log::info!(
+        "Terminated {} tracked process(es) on exit",
+        terminated.len()
+    );
+
+    // Agent runs killed here won't complete, so record them as interrupted
+    if let Ok(conn) = app_handle.state::<AgentDb>().lock() {
+        for (info, signal) in &terminated {
+            if let process::ProcessType::AgentRun { .. } = info.process_type {
+                log::info!(
+                    "Agent run {} (PID {}) terminated on exit ({:?})",
+                    info.run_id,
+                    info.pid,
+                    signal
+                );
+                let _ = conn.execute(
+                    "UPDATE agent_runs SET status = 'interrupted', completed_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = 'running'",
+                    rusqlite::params![info.run_id],
+                );
+            }
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use tokio::process::Child;
 
@@ -11,6 +11,24 @@ pub enum ProcessType {
     ClaudeSession { session_id: String },
 }
 
+/// Default grace period between the graceful signal and the forced kill
+pub const DEFAULT_KILL_GRACE_PERIOD_MS: u64 = 3000;
+
+/// Which signal ultimately terminated a process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminationSignal {
+    /// Exited after SIGTERM (graceful `taskkill` on Windows)
+    Term,
+    /// Needed SIGKILL (`taskkill /F` on Windows)
+    Kill,
+    /// Was no longer running when the kill was attempted
+    NotRunning,
+}
+
+/// Default cap on the live output kept in memory per run
+pub const DEFAULT_LIVE_OUTPUT_LIMIT_BYTES: usize = 8 * 1024 * 1024;
+
 /// Information about a running agent process
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
@@ -21,6 +39,180 @@ pub struct ProcessInfo {
     pub project_path: String,
     pub task: String,
     pub model: String,
+    /// Bytes of live output currently buffered for this process
+    #[serde(default)]
+    pub live_output_bytes: usize,
+    /// Number of oldest lines dropped from the live output buffer
+    #[serde(default)]
+    pub live_output_truncated_lines: usize,
+    /// Running input tokens (including cache reads and writes) from the stream-json output
+    #[serde(default)]
+    pub input_tokens: u64,
+    /// Running output tokens from the stream-json output
+    #[serde(default)]
+    pub output_tokens: u64,
+    /// Running cost estimate in USD; Claude's reported cost once the run finished
+    #[serde(default)]
+    pub estimated_cost_usd: f64,
+}
+
+/// Line-based ring buffer holding the most recent output of a process
+#[derive(Debug)]
+pub struct LiveOutputBuffer {
+    lines: VecDeque<String>,
+    bytes: usize,
+    truncated_lines: usize,
+    max_bytes: usize,
+}
+
+impl LiveOutputBuffer {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            bytes: 0,
+            truncated_lines: 0,
+            max_bytes,
+        }
+    }
+
+    /// Append a line, dropping the oldest lines once the byte limit is exceeded.
+    /// The newest line is always kept, even if it alone exceeds the limit.
+    /// Returns the cursor of the appended line.
+    pub fn push_line(&mut self, line: &str) -> usize {
+        let cursor = self.next_cursor();
+        self.bytes += line.len() + 1;
+        self.lines.push_back(line.to_string());
+
+        while self.bytes > self.max_bytes && self.lines.len() > 1 {
+            if let Some(dropped) = self.lines.pop_front() {
+                self.bytes -= dropped.len() + 1;
+                self.truncated_lines += 1;
+            }
+        }
+        cursor
+    }
+
+    /// Cursor the next appended line will get; equals the number of lines ever pushed
+    pub fn next_cursor(&self) -> usize {
+        self.truncated_lines + self.lines.len()
+    }
+
+    /// Lines from `cursor` onwards that are still buffered, plus how many lines after
+    /// `cursor` were already dropped and can no longer be returned
+    pub fn lines_since(&self, cursor: usize) -> (Vec<String>, usize) {
+        let start = cursor.saturating_sub(self.truncated_lines);
+        let dropped = self.truncated_lines.saturating_sub(cursor);
+        (self.lines.iter().skip(start).cloned().collect(), dropped)
+    }
+
+    /// Bytes currently buffered
+    pub fn len_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Number of lines dropped so far
+    pub fn truncated_lines(&self) -> usize {
+        self.truncated_lines
+    }
+
+    /// Render the buffer, prefixed with a marker if older lines were dropped
+    pub fn to_output(&self) -> String {
+        let marker = if self.truncated_lines > 0 {
+            format!("[...truncated {} lines...]\n", self.truncated_lines)
+        } else {
+            String::new()
+        };
+
+        let mut output = String::with_capacity(marker.len() + self.bytes);
+        output.push_str(&marker);
+        for line in &self.lines {
+            output.push_str(line);
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Output lines appended after a cursor, returned to a reconnecting frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveOutputChunk {
+    pub lines: Vec<String>,
+    /// Cursor to pass on the next call; also the cursor of the next emitted line
+    pub next_cursor: usize,
+    /// Lines after the requested cursor that had already been dropped from the buffer
+    pub dropped_lines: usize,
+}
+
+/// Snapshot returned when re-attaching to a running Claude session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionAttachment {
+    pub info: ProcessInfo,
+    /// Everything still buffered; `output.next_cursor` is the cursor of the next emitted line
+    pub output: LiveOutputChunk,
+    /// Whether output events were paused before attaching; attaching resumes them
+    pub was_paused: bool,
+}
+
+/// Maximum number of lines kept while a session's output is paused; older lines are
+/// still in the live output buffer but are not re-emitted on resume
+pub const PAUSED_OUTPUT_TAIL_LINES: usize = 500;
+
+/// A held back line and its live output cursor, if the process was registered
+#[derive(Debug, Clone)]
+pub struct PausedLine {
+    pub cursor: Option<usize>,
+    pub line: String,
+}
+
+/// Output held back from the frontend while a session's stream is paused
+#[derive(Debug, Default)]
+pub struct PausedOutput {
+    /// Base event name the lines were going to be emitted on (e.g. `claude-output`)
+    pub event: Option<String>,
+    pub lines: VecDeque<PausedLine>,
+    /// Number of lines buffered since the pause, including dropped ones
+    pub total_lines: usize,
+}
+
+impl PausedOutput {
+    /// Hold back a line, keeping only the most recent `PAUSED_OUTPUT_TAIL_LINES`
+    pub fn push_line(&mut self, event: &str, line: &str, cursor: Option<usize>) {
+        if self.event.is_none() {
+            self.event = Some(event.to_string());
+        }
+        self.total_lines += 1;
+        self.lines.push_back(PausedLine {
+            cursor,
+            line: line.to_string(),
+        });
+        if self.lines.len() > PAUSED_OUTPUT_TAIL_LINES {
+            self.lines.pop_front();
+        }
+    }
+
+    /// Marker emitted before the flushed lines on resume. Stream-json channels get a
+    /// `system` message so the frontend can parse it like any other line.
+    pub fn marker(&self) -> String {
+        if self.event.as_deref() == Some("claude-output") {
+            return serde_json::json!({
+                "type": "system",
+                "subtype": "output_buffered",
+                "buffered_lines": self.total_lines,
+                "shown_lines": self.lines.len(),
+            })
+            .to_string();
+        }
+
+        if self.total_lines > self.lines.len() {
+            format!(
+                "[buffered {} lines, showing last {}]",
+                self.total_lines,
+                self.lines.len()
+            )
+        } else {
+            format!("[buffered {} lines]", self.total_lines)
+        }
+    }
 }
 
 /// Information about a running process with handle
@@ -28,13 +220,15 @@ pub struct ProcessInfo {
 pub struct ProcessHandle {
     pub info: ProcessInfo,
     pub child: Arc<Mutex<Option<Child>>>,
-    pub live_output: Arc<Mutex<String>>,
+    pub live_output: Arc<Mutex<LiveOutputBuffer>>,
 }
 
 /// Registry for tracking active agent processes
 pub struct ProcessRegistry {
     processes: Arc<Mutex<HashMap<i64, ProcessHandle>>>, // run_id -> ProcessHandle
     next_id: Arc<Mutex<i64>>, // Auto-incrementing ID for non-agent processes
+    live_output_limit: Arc<Mutex<usize>>, // Per-run live output cap in bytes
+    paused_output: Arc<Mutex<HashMap<String, PausedOutput>>>, // session_id -> held back output
 }
 
 impl ProcessRegistry {
@@ -42,9 +236,30 @@ impl ProcessRegistry {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
             next_id: Arc::new(Mutex::new(1000000)), // Start at high number to avoid conflicts
+            live_output_limit: Arc::new(Mutex::new(DEFAULT_LIVE_OUTPUT_LIMIT_BYTES)),
+            paused_output: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Set the per-run live output cap; applies to processes registered afterwards
+    pub fn set_live_output_limit(&self, max_bytes: usize) -> Result<(), String> {
+        let mut limit = self.live_output_limit.lock().map_err(|e| e.to_string())?;
+        *limit = max_bytes;
+        Ok(())
+    }
+
+    /// Get the per-run live output cap in bytes
+    pub fn live_output_limit(&self) -> usize {
+        self.live_output_limit
+            .lock()
+            .map(|limit| *limit)
+            .unwrap_or(DEFAULT_LIVE_OUTPUT_LIMIT_BYTES)
+    }
+
+    fn new_live_output(&self) -> Arc<Mutex<LiveOutputBuffer>> {
+        Arc::new(Mutex::new(LiveOutputBuffer::new(self.live_output_limit())))
+    }
+
     /// Generate a unique ID for non-agent processes
     pub fn generate_id(&self) -> Result<i64, String> {
         let mut next_id = self.next_id.lock().map_err(|e| e.to_string())?;
@@ -76,6 +291,11 @@ impl ProcessRegistry {
             project_path,
             task,
             model,
+            live_output_bytes: 0,
+            live_output_truncated_lines: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            estimated_cost_usd: 0.0,
         };
 
         self.register_process_internal(run_id, process_info, child)
@@ -103,6 +323,11 @@ impl ProcessRegistry {
             project_path,
             task,
             model,
+            live_output_bytes: 0,
+            live_output_truncated_lines: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            estimated_cost_usd: 0.0,
         };
 
         // For sidecar processes, we register without the child handle since it's managed differently
@@ -111,7 +336,7 @@ impl ProcessRegistry {
         let process_handle = ProcessHandle {
             info: process_info,
             child: Arc::new(Mutex::new(None)), // No tokio::process::Child handle for sidecar
-            live_output: Arc::new(Mutex::new(String::new())),
+            live_output: self.new_live_output(),
         };
 
         processes.insert(run_id, process_handle);
@@ -137,6 +362,11 @@ impl ProcessRegistry {
             project_path,
             task,
             model,
+            live_output_bytes: 0,
+            live_output_truncated_lines: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            estimated_cost_usd: 0.0,
         };
 
         // Register without child - Claude sessions use ClaudeProcessState for process management
@@ -145,7 +375,7 @@ impl ProcessRegistry {
         let process_handle = ProcessHandle {
             info: process_info,
             child: Arc::new(Mutex::new(None)), // No child handle for Claude sessions
-            live_output: Arc::new(Mutex::new(String::new())),
+            live_output: self.new_live_output(),
         };
 
         processes.insert(run_id, process_handle);
@@ -164,7 +394,7 @@ impl ProcessRegistry {
         let process_handle = ProcessHandle {
             info: process_info,
             child: Arc::new(Mutex::new(Some(child))),
-            live_output: Arc::new(Mutex::new(String::new())),
+            live_output: self.new_live_output(),
         };
 
         processes.insert(run_id, process_handle);
@@ -198,6 +428,23 @@ impl ProcessRegistry {
             .map(|handle| handle.info.clone()))
     }
 
+    /// Update the running token and cost totals of a process
+    pub fn update_usage(
+        &self,
+        run_id: i64,
+        input_tokens: u64,
+        output_tokens: u64,
+        estimated_cost_usd: f64,
+    ) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        if let Some(handle) = processes.get_mut(&run_id) {
+            handle.info.input_tokens = input_tokens;
+            handle.info.output_tokens = output_tokens;
+            handle.info.estimated_cost_usd = estimated_cost_usd;
+        }
+        Ok(())
+    }
+
     /// Unregister a process (called when it completes)
     #[allow(dead_code)]
     pub fn unregister_process(&self, run_id: i64) -> Result<(), String> {
@@ -237,7 +484,24 @@ impl ProcessRegistry {
 
     /// Kill a running process with proper cleanup
     pub async fn kill_process(&self, run_id: i64) -> Result<bool, String> {
-        use log::{error, info, warn};
+        let signal = self
+            .terminate_process(
+                run_id,
+                std::time::Duration::from_millis(DEFAULT_KILL_GRACE_PERIOD_MS),
+            )
+            .await?;
+        Ok(signal.is_some())
+    }
+
+    /// Terminate a running process and its process group, sending SIGTERM first and
+    /// escalating to SIGKILL if it hasn't exited within `grace_period`.
+    /// Returns the signal that ended the process, or None if it isn't registered.
+    pub async fn terminate_process(
+        &self,
+        run_id: i64,
+        grace_period: std::time::Duration,
+    ) -> Result<Option<TerminationSignal>, String> {
+        use log::{info, warn};
 
         // First check if the process exists and get its PID
         let (pid, child_arc) = {
@@ -246,7 +510,7 @@ impl ProcessRegistry {
                 (handle.info.pid, handle.child.clone())
             } else {
                 warn!("Process {} not found in registry", run_id);
-                return Ok(false); // Process not found
+                return Ok(None); // Process not found
             }
         };
 
@@ -255,108 +519,43 @@ impl ProcessRegistry {
             run_id, pid
         );
 
-        // Send kill signal to the process
-        let kill_sent = {
-            let mut child_guard = child_arc.lock().map_err(|e| e.to_string())?;
-            if let Some(child) = child_guard.as_mut() {
-                match child.start_kill() {
-                    Ok(_) => {
-                        info!("Successfully sent kill signal to process {}", run_id);
-                        true
-                    }
-                    Err(e) => {
-                        error!("Failed to send kill signal to process {}: {}", run_id, e);
-                        // Don't return error here, try fallback method
+        // Prefer the child handle to check for exit (it also reaps the process);
+        // fall back to probing the PID for sessions registered without a handle
+        let is_alive = || match child_arc.lock() {
+            Ok(mut guard) => match guard.as_mut() {
+                Some(child) => match child.try_wait() {
+                    Ok(None) => true,
+                    _ => {
+                        *guard = None;
                         false
                     }
-                }
-            } else {
-                warn!(
-                    "No child handle available for process {} (PID: {}), attempting system kill",
-                    run_id, pid
-                );
-                false // Process handle not available, try fallback
-            }
+                },
+                None => is_pid_alive(pid),
+            },
+            Err(_) => is_pid_alive(pid),
         };
 
-        // If direct kill didn't work, try system command as fallback
-        if !kill_sent {
-            info!(
-                "Attempting fallback kill for process {} (PID: {})",
-                run_id, pid
-            );
-            match self.kill_process_by_pid(run_id, pid) {
-                Ok(true) => return Ok(true),
-                Ok(false) => warn!(
-                    "Fallback kill also failed for process {} (PID: {})",
-                    run_id, pid
-                ),
-                Err(e) => error!("Error during fallback kill: {}", e),
-            }
-            // Continue with the rest of the cleanup even if fallback failed
-        }
+        let signal = terminate_with_escalation(pid, grace_period, is_alive).await;
 
-        // Wait for the process to exit (with timeout)
-        let wait_result = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
-            loop {
-                // Check if process has exited
-                let status = {
-                    let mut child_guard = child_arc.lock().map_err(|e| e.to_string())?;
-                    if let Some(child) = child_guard.as_mut() {
-                        match child.try_wait() {
-                            Ok(Some(status)) => {
-                                info!("Process {} exited with status: {:?}", run_id, status);
-                                *child_guard = None; // Clear the child handle
-                                Some(Ok::<(), String>(()))
-                            }
-                            Ok(None) => {
-                                // Still running
-                                None
-                            }
-                            Err(e) => {
-                                error!("Error checking process status: {}", e);
-                                Some(Err(e.to_string()))
-                            }
-                        }
-                    } else {
-                        // Process already gone
-                        Some(Ok(()))
-                    }
-                };
-
-                match status {
-                    Some(result) => return result,
-                    None => {
-                        // Still running, wait a bit
-                        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                    }
+        if signal == TerminationSignal::Kill {
+            // Make sure the direct child is gone even if the group kill missed it
+            if let Ok(mut child_guard) = child_arc.lock() {
+                if let Some(child) = child_guard.as_mut() {
+                    let _ = child.start_kill();
                 }
-            }
-        })
-        .await;
-
-        match wait_result {
-            Ok(Ok(_)) => {
-                info!("Process {} exited gracefully", run_id);
-            }
-            Ok(Err(e)) => {
-                error!("Error waiting for process {}: {}", run_id, e);
-            }
-            Err(_) => {
-                warn!("Process {} didn't exit within 5 seconds after kill", run_id);
-                // Force clear the handle
-                if let Ok(mut child_guard) = child_arc.lock() {
-                    *child_guard = None;
-                }
-                // One more attempt with system kill
-                let _ = self.kill_process_by_pid(run_id, pid);
+                *child_guard = None;
             }
         }
 
+        info!(
+            "Process {} (PID: {}) terminated by {:?}",
+            run_id, pid, signal
+        );
+
         // Remove from registry after killing
         self.unregister_process(run_id)?;
 
-        Ok(true)
+        Ok(Some(signal))
     }
 
     /// Kill a process by PID using system commands (fallback method)
@@ -469,14 +668,37 @@ impl ProcessRegistry {
     }
 
     /// Append to live output for a process
-    pub fn append_live_output(&self, run_id: i64, output: &str) -> Result<(), String> {
-        let processes = self.processes.lock().map_err(|e| e.to_string())?;
-        if let Some(handle) = processes.get(&run_id) {
+    /// Returns the line's cursor, or `None` if the process is not registered.
+    pub fn append_live_output(&self, run_id: i64, output: &str) -> Result<Option<usize>, String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+        if let Some(handle) = processes.get_mut(&run_id) {
             let mut live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
-            live_output.push_str(output);
-            live_output.push('\n');
+            let cursor = live_output.push_line(output);
+            handle.info.live_output_bytes = live_output.len_bytes();
+            handle.info.live_output_truncated_lines = live_output.truncated_lines();
+            Ok(Some(cursor))
+        } else {
+            Ok(None)
         }
-        Ok(())
+    }
+
+    /// Get the live output lines appended at or after `cursor`
+    pub fn get_live_output_since(
+        &self,
+        run_id: i64,
+        cursor: usize,
+    ) -> Result<Option<LiveOutputChunk>, String> {
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let Some(handle) = processes.get(&run_id) else {
+            return Ok(None);
+        };
+        let live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
+        let (lines, dropped_lines) = live_output.lines_since(cursor);
+        Ok(Some(LiveOutputChunk {
+            lines,
+            next_cursor: live_output.next_cursor(),
+            dropped_lines,
+        }))
     }
 
     /// Get live output for a process
@@ -484,12 +706,90 @@ impl ProcessRegistry {
         let processes = self.processes.lock().map_err(|e| e.to_string())?;
         if let Some(handle) = processes.get(&run_id) {
             let live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
-            Ok(live_output.clone())
+            Ok(live_output.to_output())
         } else {
             Ok(String::new())
         }
     }
 
+    /// Pause or resume emitting a session's output to the frontend. Returns the output held
+    /// back while paused when resuming, so the caller can flush it.
+    pub fn set_output_paused(
+        &self,
+        session_id: &str,
+        paused: bool,
+    ) -> Result<Option<PausedOutput>, String> {
+        let mut paused_output = self.paused_output.lock().map_err(|e| e.to_string())?;
+        if paused {
+            paused_output.entry(session_id.to_string()).or_default();
+            Ok(None)
+        } else {
+            Ok(paused_output.remove(session_id))
+        }
+    }
+
+    /// Append a line to a process's live output and, under the pause lock, either hold it
+    /// back (if the session's output is paused) or pass its cursor to `emit`. Holding the lock
+    /// across the emit keeps `attach_claude_session` from snapshotting a line that is emitted
+    /// after the snapshot. Returns whether the line was held back.
+    pub fn append_session_output(
+        &self,
+        run_id: Option<i64>,
+        session_id: Option<&str>,
+        event: &str,
+        line: &str,
+        emit: impl FnOnce(Option<usize>),
+    ) -> Result<bool, String> {
+        let mut paused_output = self.paused_output.lock().map_err(|e| e.to_string())?;
+        let cursor = match run_id {
+            Some(run_id) => self.append_live_output(run_id, line)?,
+            None => None,
+        };
+        if let Some(buffer) = session_id.and_then(|id| paused_output.get_mut(id)) {
+            buffer.push_line(event, line, cursor);
+            return Ok(true);
+        }
+        emit(cursor);
+        Ok(false)
+    }
+
+    /// Snapshot a running Claude session's buffered output and resume its output events.
+    /// Output appended through `append_session_output` is either in the snapshot (emitted
+    /// before it, or held back while paused and discarded here) or emitted after it. Events
+    /// may still reach the frontend after the snapshot was taken, so clients should follow
+    /// `claude-output-seq:{session_id}` and drop cursors below `output.next_cursor`.
+    /// Returns `None` if the session is not running.
+    pub fn attach_claude_session(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<SessionAttachment>, String> {
+        let mut paused_output = self.paused_output.lock().map_err(|e| e.to_string())?;
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+        let Some(handle) = processes
+            .values()
+            .find(|handle| match &handle.info.process_type {
+                ProcessType::ClaudeSession { session_id: sid } => sid == session_id,
+                _ => false,
+            })
+        else {
+            return Ok(None);
+        };
+
+        let live_output = handle.live_output.lock().map_err(|e| e.to_string())?;
+        let (lines, dropped_lines) = live_output.lines_since(0);
+        let output = LiveOutputChunk {
+            lines,
+            next_cursor: live_output.next_cursor(),
+            dropped_lines,
+        };
+
+        Ok(Some(SessionAttachment {
+            info: handle.info.clone(),
+            output,
+            was_paused: paused_output.remove(session_id).is_some(),
+        }))
+    }
+
     /// Cleanup finished processes
     #[allow(dead_code)]
     pub async fn cleanup_finished_processes(&self) -> Result<Vec<i64>, String> {
@@ -519,6 +819,137 @@ impl ProcessRegistry {
 
         Ok(finished_runs)
     }
+
+    /// Terminate every tracked process concurrently, e.g. when the app is shutting down.
+    /// Returns the info of each process together with the signal that ended it.
+    pub async fn terminate_all(
+        &self,
+        grace_period: std::time::Duration,
+    ) -> Vec<(ProcessInfo, TerminationSignal)> {
+        let tracked = match self.get_running_processes() {
+            Ok(tracked) => tracked,
+            Err(e) => {
+                log::error!("Failed to list tracked processes for termination: {}", e);
+                return Vec::new();
+            }
+        };
+
+        if tracked.is_empty() {
+            return Vec::new();
+        }
+
+        log::info!("Terminating {} tracked process(es)", tracked.len());
+
+        let results = futures::future::join_all(tracked.into_iter().map(|info| async move {
+            match self.terminate_process(info.run_id, grace_period).await {
+                Ok(Some(signal)) => Some((info, signal)),
+                Ok(None) => None,
+                Err(e) => {
+                    log::error!("Failed to terminate process {}: {}", info.run_id, e);
+                    None
+                }
+            }
+        }))
+        .await;
+
+        results.into_iter().flatten().collect()
+    }
+}
+
+/// Check whether a PID refers to a live process.
+/// On Unix this is a `kill(pid, 0)` syscall, cheap enough for the escalation poll loop
+#[cfg(unix)]
+pub fn is_pid_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 performs only the existence and permission checks
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    // EPERM means the process exists but belongs to another user
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Check whether a PID refers to a live process
+#[cfg(not(unix))]
+pub fn is_pid_alive(pid: u32) -> bool {
+    match std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid)])
+        .args(["/FO", "CSV", "/NH"])
+        .output()
+    {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)),
+        Err(_) => false,
+    }
+}
+
+/// Send a graceful or forced termination signal to a process tree
+fn send_termination_signal(pid: u32, force: bool) {
+    if cfg!(target_os = "windows") {
+        // /T includes child processes (e.g. MCP servers spawned by Claude)
+        let pid_str = pid.to_string();
+        let mut args = vec!["/T", "/PID", pid_str.as_str()];
+        if force {
+            args.insert(0, "/F");
+        }
+        let _ = std::process::Command::new("taskkill").args(&args).output();
+    } else {
+        let signal = if force { "-KILL" } else { "-TERM" };
+        // Signal the whole process group first so MCP subprocesses don't linger,
+        // then the PID itself in case it isn't a group leader
+        let _ = std::process::Command::new("kill")
+            .args([signal, "--", &format!("-{}", pid)])
+            .output();
+        let _ = std::process::Command::new("kill")
+            .args([signal, &pid.to_string()])
+            .output();
+    }
+}
+
+/// Terminate a process tree with SIGTERM, escalating to SIGKILL after `grace_period`.
+/// `is_alive` reports whether the process is still running.
+pub async fn terminate_with_escalation<F>(
+    pid: u32,
+    grace_period: std::time::Duration,
+    mut is_alive: F,
+) -> TerminationSignal
+where
+    F: FnMut() -> bool,
+{
+    use log::{info, warn};
+
+    if !is_alive() {
+        return TerminationSignal::NotRunning;
+    }
+
+    info!("Sending graceful termination signal to PID {}", pid);
+    send_termination_signal(pid, false);
+
+    let deadline = std::time::Instant::now() + grace_period;
+    while std::time::Instant::now() < deadline {
+        if !is_alive() {
+            return TerminationSignal::Term;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    warn!(
+        "PID {} still running after {}ms grace period, sending SIGKILL",
+        pid,
+        grace_period.as_millis()
+    );
+    send_termination_signal(pid, true);
+
+    // Give the OS a moment to tear the process down
+    for _ in 0..20 {
+        if !is_alive() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    TerminationSignal::Kill
 }
 
 impl Default for ProcessRegistry {
@@ -535,3 +966,170 @@ impl Default for ProcessRegistryState {
         Self(Arc::new(ProcessRegistry::new()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_output_buffer_drops_oldest_lines() {
+        let mut buffer = LiveOutputBuffer::new(12);
+        buffer.push_line("aaaa");
+        buffer.push_line("bbbb");
+        assert_eq!(buffer.truncated_lines(), 0);
+
+        buffer.push_line("cccc");
+        assert_eq!(buffer.truncated_lines(), 1);
+        assert_eq!(buffer.len_bytes(), 10);
+        assert_eq!(
+            buffer.to_output(),
+            "[...truncated 1 lines...]\nbbbb\ncccc\n"
+        );
+    }
+
+    #[test]
+    fn test_paused_output_keeps_tail_and_counts_all_lines() {
+        let mut paused = PausedOutput::default();
+        assert_eq!(paused.marker(), "[buffered 0 lines]");
+
+        for i in 0..PAUSED_OUTPUT_TAIL_LINES + 2 {
+            paused.push_line("claude-text", &i.to_string(), Some(i));
+        }
+        assert_eq!(paused.event.as_deref(), Some("claude-text"));
+        assert_eq!(paused.lines.len(), PAUSED_OUTPUT_TAIL_LINES);
+        let first = paused.lines.front().unwrap();
+        assert_eq!((first.cursor, first.line.as_str()), (Some(2), "2"));
+        assert_eq!(
+            paused.marker(),
+            format!(
+                "[buffered {} lines, showing last {}]",
+                PAUSED_OUTPUT_TAIL_LINES + 2,
+                PAUSED_OUTPUT_TAIL_LINES
+            )
+        );
+    }
+
+    #[test]
+    fn test_paused_output_marker_is_stream_json_on_claude_output() {
+        let mut paused = PausedOutput::default();
+        paused.push_line("claude-output", "{}", Some(0));
+        paused.push_line("claude-output", "{}", Some(1));
+
+        let marker: serde_json::Value = serde_json::from_str(&paused.marker()).unwrap();
+        assert_eq!(marker["type"], "system");
+        assert_eq!(marker["subtype"], "output_buffered");
+        assert_eq!(marker["buffered_lines"], 2);
+        assert_eq!(marker["shown_lines"], 2);
+    }
+
+    #[test]
+    fn test_live_output_buffer_lines_since_cursor() {
+        let mut buffer = LiveOutputBuffer::new(12);
+        assert_eq!(buffer.push_line("aaaa"), 0);
+        assert_eq!(buffer.push_line("bbbb"), 1);
+        assert_eq!(buffer.push_line("cccc"), 2);
+        assert_eq!(buffer.next_cursor(), 3);
+
+        assert_eq!(buffer.lines_since(2), (vec!["cccc".to_string()], 0));
+        assert_eq!(
+            buffer.lines_since(0),
+            (vec!["bbbb".to_string(), "cccc".to_string()], 1)
+        );
+        assert_eq!(buffer.lines_since(3), (Vec::<String>::new(), 0));
+        assert_eq!(buffer.lines_since(7), (Vec::<String>::new(), 0));
+    }
+
+    #[test]
+    fn test_update_usage_shows_in_session_info() {
+        let registry = ProcessRegistry::new();
+        let run_id = registry
+            .register_claude_session(
+                "session-1".to_string(),
+                42,
+                "/tmp/project".to_string(),
+                "hello".to_string(),
+                "claude-opus-4".to_string(),
+            )
+            .unwrap();
+
+        registry.update_usage(run_id, 1200, 300, 0.42).unwrap();
+
+        let info = registry
+            .get_claude_session_by_id("session-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.model, "claude-opus-4");
+        assert_eq!(info.input_tokens, 1200);
+        assert_eq!(info.output_tokens, 300);
+        assert_eq!(info.estimated_cost_usd, 0.42);
+    }
+
+    #[test]
+    fn test_attach_claude_session_returns_backlog_and_resumes() {
+        let registry = ProcessRegistry::new();
+        assert!(registry
+            .attach_claude_session("session-1")
+            .unwrap()
+            .is_none());
+
+        let run_id = registry
+            .register_claude_session(
+                "session-1".to_string(),
+                42,
+                "/tmp/project".to_string(),
+                "hello".to_string(),
+                "claude-opus-4".to_string(),
+            )
+            .unwrap();
+        let append = |line: &str| {
+            let mut emitted = None;
+            let held = registry
+                .append_session_output(
+                    Some(run_id),
+                    Some("session-1"),
+                    "claude-output",
+                    line,
+                    |cursor| emitted = cursor,
+                )
+                .unwrap();
+            (held, emitted)
+        };
+        assert_eq!(append("first"), (false, Some(0)));
+        registry.set_output_paused("session-1", true).unwrap();
+        assert_eq!(append("second"), (true, None));
+
+        let attachment = registry
+            .attach_claude_session("session-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(attachment.info.run_id, run_id);
+        assert_eq!(attachment.output.lines, vec!["first", "second"]);
+        assert_eq!(attachment.output.next_cursor, 2);
+        assert!(attachment.was_paused);
+        assert_eq!(append("third"), (false, Some(2)));
+    }
+
+    #[test]
+    fn test_live_output_buffer_keeps_oversized_line() {
+        let mut buffer = LiveOutputBuffer::new(4);
+        buffer.push_line("a very long line");
+        assert_eq!(buffer.to_output(), "a very long line\n");
+    }
+
+    #[test]
+    fn test_is_pid_alive() {
+        assert!(is_pid_alive(std::process::id()));
+
+        let mut child = std::process::Command::new(if cfg!(windows) { "cmd" } else { "true" })
+            .args(if cfg!(windows) {
+                &["/C", "exit"][..]
+            } else {
+                &[][..]
+            })
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert!(!is_pid_alive(pid));
+    }
+}
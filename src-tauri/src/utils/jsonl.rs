@@ -0,0 +1,371 @@
+/// JSONL 流式读取工具
+///
+/// 逐行读取并限制单行长度，损坏文件中几百 MB 的"单行"会被跳过而不会整行读入内存
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// 单行允许的最大字节数，超过的行会被丢弃
+///
+/// 会话中的图片以 base64 内联，正常行可达数 MB，因此留出足够余量
+pub const MAX_JSONL_LINE_BYTES: usize = 32 * 1024 * 1024;
+
+/// 单个文件的逐行解析统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonlStats {
+    /// 成功解析为 JSON 的行数
+    pub lines_parsed: usize,
+    /// 不是合法 JSON（或不是 UTF-8）的行数，空行不计入
+    pub lines_skipped: usize,
+    /// 超过长度上限而被丢弃的行数
+    pub lines_oversized: usize,
+}
+
+impl std::ops::AddAssign for JsonlStats {
+    fn add_assign(&mut self, other: Self) {
+        self.lines_parsed += other.lines_parsed;
+        self.lines_skipped += other.lines_skipped;
+        self.lines_oversized += other.lines_oversized;
+    }
+}
+
+/// 一次 `read_capped_line` 读到的行
+struct CappedLine {
+    /// 未超过长度上限；超长行的内容已被跳过且 `buf` 为空
+    fits: bool,
+    /// 以换行符结尾（文件末尾的最后一行可能没有，也可能是写入到一半的行）
+    terminated: bool,
+    /// 该行占用的字节数，含换行符
+    bytes: usize,
+}
+
+/// 读取一行到 `buf`（不含换行符），最多保留 `max_bytes` 字节
+///
+/// 返回 `None` 表示已到文件末尾
+fn read_capped_line<R: BufRead>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    max_bytes: usize,
+) -> io::Result<Option<CappedLine>> {
+    buf.clear();
+    let mut bytes = 0;
+    let mut oversized = false;
+
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok((bytes > 0).then_some(CappedLine {
+                fits: !oversized,
+                terminated: false,
+                bytes,
+            }));
+        }
+
+        let newline = available.iter().position(|&b| b == b'\n');
+        let chunk = &available[..newline.unwrap_or(available.len())];
+        if !oversized {
+            if buf.len() + chunk.len() > max_bytes {
+                oversized = true;
+                buf.clear();
+            } else {
+                buf.extend_from_slice(chunk);
+            }
+        }
+
+        let consumed = newline.map_or(available.len(), |i| i + 1);
+        reader.consume(consumed);
+        bytes += consumed;
+        if newline.is_some() {
+            return Ok(Some(CappedLine {
+                fits: !oversized,
+                terminated: true,
+                bytes,
+            }));
+        }
+    }
+}
+
+/// `JsonlReader::next_checked` 读到的一行
+#[derive(Debug, Clone)]
+pub struct CheckedJsonlLine {
+    /// 行号（从 0 开始，计入所有物理行）
+    pub index: usize,
+    /// 该行在文件中的起始字节偏移
+    pub offset: u64,
+    /// 去除首尾空白后的内容，非 UTF-8 字节被替换；超长行为空
+    pub text: String,
+    /// 该行不是合法 JSON 时的错误信息
+    pub error: Option<String>,
+}
+
+/// 逐行读取 JSONL 的解析器，只返回合法的 JSON 行并记录统计
+///
+/// 空行被忽略，非法行和超长行只计入统计
+pub struct JsonlReader<R> {
+    reader: R,
+    max_line_bytes: usize,
+    buf: Vec<u8>,
+    next_index: usize,
+    stats: JsonlStats,
+    /// 已读完的完整行（以换行符结尾）的总字节数
+    complete_bytes: u64,
+    /// 已读取的总字节数，含末尾没有换行符的行
+    consumed_bytes: u64,
+    /// 是否忽略末尾没有换行符的行
+    complete_lines_only: bool,
+}
+
+impl<R: BufRead> JsonlReader<R> {
+    pub fn new(reader: R, max_line_bytes: usize) -> Self {
+        Self {
+            reader,
+            max_line_bytes,
+            buf: Vec::new(),
+            next_index: 0,
+            stats: JsonlStats::default(),
+            complete_bytes: 0,
+            consumed_bytes: 0,
+            complete_lines_only: false,
+        }
+    }
+
+    /// 忽略末尾没有换行符的行，用于读取仍在写入的文件：该行可能只写了一半
+    pub fn complete_lines_only(mut self) -> Self {
+        self.complete_lines_only = true;
+        self
+    }
+
+    /// 下一个非空的 UTF-8 行（已去除首尾空白）及其行号
+    fn next_text(&mut self) -> io::Result<Option<(usize, &str)>> {
+        while let Some(line) =
+            read_capped_line(&mut self.reader, &mut self.buf, self.max_line_bytes)?
+        {
+            if !line.terminated && self.complete_lines_only {
+                break;
+            }
+            if line.terminated {
+                self.complete_bytes += line.bytes as u64;
+            }
+            self.consumed_bytes += line.bytes as u64;
+            let index = self.next_index;
+            self.next_index += 1;
+
+            if !line.fits {
+                self.stats.lines_oversized += 1;
+                continue;
+            }
+            match std::str::from_utf8(&self.buf).map(|text| text.trim().is_empty()) {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(_) => {
+                    self.stats.lines_skipped += 1;
+                    continue;
+                }
+            }
+            // 确定要返回时才借用 buf，避免借用跨越下一次循环中对 buf 的写入
+            let text = std::str::from_utf8(&self.buf).unwrap_or_default().trim();
+            return Ok(Some((index, text)));
+        }
+        Ok(None)
+    }
+
+    /// 下一个合法的 JSON 行及其行号（从 0 开始，计入所有物理行）；到达末尾时返回 `None`
+    pub fn next_value(&mut self) -> io::Result<Option<(usize, serde_json::Value)>> {
+        loop {
+            let Some((index, line)) = self.next_text()? else {
+                return Ok(None);
+            };
+            match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(value) => {
+                    self.stats.lines_parsed += 1;
+                    return Ok(Some((index, value)));
+                }
+                Err(_) => self.stats.lines_skipped += 1,
+            }
+        }
+    }
+
+    /// 下一个非空行的原始文本及其行号；不解析 JSON，统计中只记录超长行和非 UTF-8 行。
+    /// 到达末尾时返回 `None`
+    pub fn next_line(&mut self) -> io::Result<Option<(usize, String)>> {
+        Ok(self
+            .next_text()?
+            .map(|(index, line)| (index, line.to_string())))
+    }
+
+    /// 下一个非空行的校验结果：与 `next_value` 不同，非法行、非 UTF-8 行和超长行也会返回，
+    /// 并附带起始字节偏移，供检查与修复文件使用。到达末尾时返回 `None`
+    pub fn next_checked(&mut self) -> io::Result<Option<CheckedJsonlLine>> {
+        loop {
+            let offset = self.consumed_bytes;
+            let Some(line) =
+                read_capped_line(&mut self.reader, &mut self.buf, self.max_line_bytes)?
+            else {
+                return Ok(None);
+            };
+            if !line.terminated && self.complete_lines_only {
+                return Ok(None);
+            }
+            if line.terminated {
+                self.complete_bytes += line.bytes as u64;
+            }
+            self.consumed_bytes += line.bytes as u64;
+            let index = self.next_index;
+            self.next_index += 1;
+
+            if !line.fits {
+                self.stats.lines_oversized += 1;
+                return Ok(Some(CheckedJsonlLine {
+                    index,
+                    offset,
+                    text: String::new(),
+                    error: Some(format!("line exceeds {} bytes", self.max_line_bytes)),
+                }));
+            }
+            let text = String::from_utf8_lossy(&self.buf).trim().to_string();
+            if text.is_empty() {
+                continue;
+            }
+            let error = match serde_json::from_slice::<serde_json::Value>(&self.buf) {
+                Ok(_) => {
+                    self.stats.lines_parsed += 1;
+                    None
+                }
+                Err(e) => {
+                    self.stats.lines_skipped += 1;
+                    Some(e.to_string())
+                }
+            };
+            return Ok(Some(CheckedJsonlLine {
+                index,
+                offset,
+                text,
+                error,
+            }));
+        }
+    }
+
+    /// 已读完的完整行（以换行符结尾）的总字节数，可作为下次增量读取的起始偏移
+    pub fn complete_bytes(&self) -> u64 {
+        self.complete_bytes
+    }
+
+    /// 目前为止的统计
+    pub fn stats(&self) -> JsonlStats {
+        self.stats
+    }
+}
+
+impl JsonlReader<BufReader<File>> {
+    /// 以默认长度上限打开文件
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Ok(Self::new(BufReader::new(file), MAX_JSONL_LINE_BYTES))
+    }
+}
+
+/// 逐行解析 JSONL，对每个合法的 JSON 行调用 `f(行号, 值)`；读取出错时返回错误
+pub fn for_each_json_line<R, F>(
+    reader: R,
+    max_line_bytes: usize,
+    mut f: F,
+) -> io::Result<JsonlStats>
+where
+    R: BufRead,
+    F: FnMut(usize, serde_json::Value),
+{
+    let mut reader = JsonlReader::new(reader, max_line_bytes);
+    while let Some((index, value)) = reader.next_value()? {
+        f(index, value);
+    }
+    Ok(reader.stats())
+}
+
+/// 打开文件并以默认长度上限逐行解析，参见 [`for_each_json_line`]
+pub fn for_each_json_line_in_file<F>(path: &Path, f: F) -> io::Result<JsonlStats>
+where
+    F: FnMut(usize, serde_json::Value),
+{
+    let file = File::open(path)?;
+    for_each_json_line(BufReader::new(file), MAX_JSONL_LINE_BYTES, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each_json_line_counts_and_caps_lines() {
+        let long = format!("{{\"text\":\"{}\"}}", "x".repeat(100));
+        let input = format!(
+            "{{\"a\":1}}\n\nnot json\n{}\n{{\"b\":2}}\r\n\u{0}\u{ff}\n{{\"c\":3}}",
+            long
+        );
+        let mut input = input.into_bytes();
+        input.extend_from_slice(b"\n\xff\xfe\n");
+
+        let mut seen = Vec::new();
+        // 缓冲区很小，使一行跨越多次 fill_buf
+        let reader = BufReader::with_capacity(8, input.as_slice());
+        let stats =
+            for_each_json_line(reader, 64, |index, value| seen.push((index, value))).unwrap();
+
+        let indexes: Vec<usize> = seen.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indexes, vec![0, 4, 6]);
+        assert_eq!(seen[1].1["b"], 2);
+        assert_eq!(
+            stats,
+            JsonlStats {
+                lines_parsed: 3,
+                lines_skipped: 3,
+                lines_oversized: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_complete_lines_only_stops_at_partial_line() {
+        let input = b"{\"a\":1}\n\n{\"b\":2}\n{\"c\":";
+        let mut reader = JsonlReader::new(BufReader::new(&input[..]), 64).complete_lines_only();
+
+        assert_eq!(
+            reader.next_line().unwrap(),
+            Some((0, "{\"a\":1}".to_string()))
+        );
+        assert_eq!(
+            reader.next_line().unwrap(),
+            Some((2, "{\"b\":2}".to_string()))
+        );
+        assert_eq!(reader.next_line().unwrap(), None);
+        assert_eq!(reader.complete_bytes(), 17);
+    }
+
+    #[test]
+    fn test_next_checked_reports_offsets_and_errors() {
+        let long = format!("\"{}\"", "x".repeat(100));
+        let input = format!("{{\"a\":1}}\n\nnot json\n{}\n{{\"c\":", long);
+        let mut reader = JsonlReader::new(BufReader::new(input.as_bytes()), 64);
+
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_checked().unwrap() {
+            lines.push(line);
+        }
+
+        let summary: Vec<(usize, u64, bool)> = lines
+            .iter()
+            .map(|line| (line.index, line.offset, line.error.is_some()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(0, 0, false), (2, 9, true), (3, 18, true), (4, 121, true)]
+        );
+        assert_eq!(lines[1].text, "not json");
+        assert!(lines[2].text.is_empty());
+        assert_eq!(lines[3].text, "{\"c\":");
+    }
+}
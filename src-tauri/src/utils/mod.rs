@@ -1,3 +1,5 @@
 /// 工具函数模块
 pub mod error;
+pub mod jsonl;
 pub mod node_tester;
+pub mod sqlite;
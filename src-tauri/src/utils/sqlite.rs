@@ -0,0 +1,141 @@
+/// SQLite 连接工具
+///
+/// 统一配置 busy_timeout，并为写操作提供遇到锁冲突（SQLITE_BUSY / SQLITE_LOCKED）时的重试
+use rusqlite::{Connection, ErrorCode, Transaction, TransactionBehavior};
+use std::path::Path;
+use std::time::Duration;
+
+/// 遇到锁时 SQLite 内部等待的最长时间
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// busy_timeout 耗尽后，写操作最多再尝试的次数
+const MAX_BUSY_RETRIES: u32 = 3;
+
+/// 重试的基础退避时间，每次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// 为连接设置 busy_timeout，避免 WAL checkpoint 或并发扫描时立即报 "database is locked"
+pub fn configure_connection(conn: &Connection) -> rusqlite::Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)
+}
+
+/// 打开数据库并设置 busy_timeout
+pub fn open_connection(path: impl AsRef<Path>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    configure_connection(&conn)?;
+    Ok(conn)
+}
+
+/// 判断错误是否为暂时性的锁冲突
+pub fn is_busy_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// 判断错误是否表示数据库文件已损坏（SQLITE_CORRUPT / SQLITE_NOTADB）
+pub fn is_corruption_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(e.code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+/// 执行写操作，遇到锁冲突时按指数退避重试
+///
+/// 每次重试都会记录警告日志，便于发现真正的死锁
+///
+/// # Example
+/// ```
+/// use claudia_lib::utils::sqlite::{open_connection, with_busy_retry};
+///
+/// let conn = open_connection(":memory:").unwrap();
+/// with_busy_retry("create table", || conn.execute("CREATE TABLE t (id INTEGER)", [])).unwrap();
+/// ```
+pub fn with_busy_retry<T, F>(operation: &str, mut f: F) -> rusqlite::Result<T>
+where
+    F: FnMut() -> rusqlite::Result<T>,
+{
+    let mut retries = 0;
+    loop {
+        match f() {
+            Err(e) if is_busy_error(&e) && retries < MAX_BUSY_RETRIES => {
+                let delay = RETRY_BASE_DELAY * 2u32.pow(retries);
+                retries += 1;
+                log::warn!(
+                    "Database busy during {} (retry {}/{} in {:?}): {}",
+                    operation,
+                    retries,
+                    MAX_BUSY_RETRIES,
+                    delay,
+                    e
+                );
+                std::thread::sleep(delay);
+            }
+            Err(e) => {
+                if retries > 0 {
+                    log::error!("{} failed after {} busy retries: {}", operation, retries, e);
+                }
+                return Err(e);
+            }
+            Ok(value) => {
+                if retries > 0 {
+                    log::info!("{} succeeded after {} busy retries", operation, retries);
+                }
+                return Ok(value);
+            }
+        }
+    }
+}
+
+/// 以 IMMEDIATE 模式开启写事务，锁冲突时重试
+///
+/// 开始时即获取写锁，事务内后续写入不会因锁冲突中途失败，重试也不会重复执行任何写入
+pub fn begin_immediate<'a>(
+    conn: &'a Connection,
+    operation: &str,
+) -> rusqlite::Result<Transaction<'a>> {
+    with_busy_retry(operation, || {
+        Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy() -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None)
+    }
+
+    #[test]
+    fn test_is_busy_error() {
+        assert!(is_busy_error(&busy()));
+        assert!(!is_busy_error(&rusqlite::Error::QueryReturnedNoRows));
+    }
+
+    #[test]
+    fn test_with_busy_retry_retries_only_busy_errors() {
+        let mut calls = 0;
+        let result = with_busy_retry("test", || {
+            calls += 1;
+            if calls < 3 {
+                Err(busy())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = with_busy_retry("test", || {
+            calls += 1;
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -139,6 +139,19 @@ function AppContent() {
     initializeBackendLanguage();
   }, []); // Run once on app startup
 
+  // Tell the user once if a corrupt database was replaced at startup
+  useEffect(() => {
+    api.takeDatabaseRecoveryNotice()
+      .then((recovery) => {
+        if (!recovery) return;
+        const message = recovery.in_memory
+          ? t('messages.databaseRecoveredInMemory')
+          : t('messages.databaseRecovered', { path: recovery.moved_to ?? '' });
+        setToast({ message, type: "error" });
+      })
+      .catch(() => {});
+  }, []);
+
   // Update document title based on current language
   useEffect(() => {
     try {
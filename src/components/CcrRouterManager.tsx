@@ -5,7 +5,7 @@ import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
 import { Toast, ToastContainer } from "@/components/ui/toast";
-import { ccrApi, type CcrServiceStatus } from "@/lib/api";
+import { api, ccrApi, type CcrServiceStatus } from "@/lib/api";
 import { open } from '@tauri-apps/plugin-shell';
 import { useTranslation } from '@/hooks/useTranslation';
 
@@ -53,10 +53,45 @@ export function CcrRouterManager({ onBack }: CcrRouterManagerProps) {
     }
   };
 
+  // 已启用中转站时，CCR 会与其争夺 Claude 配置，需要用户确认
+  const confirmRelayConflict = async (): Promise<boolean> => {
+    try {
+      const conflict = await api.checkRoutingConflict();
+      if (!conflict.relay_station_enabled) return true;
+      return window.confirm(t('ccr.relayConflictConfirm', { name: conflict.relay_station_name ?? '' }));
+    } catch (error) {
+      console.error("Failed to check routing conflict:", error);
+      return true;
+    }
+  };
+
+  const handleInstall = async () => {
+    if (!window.confirm(t('ccr.installConfirm'))) return;
+    try {
+      setActionLoading(true);
+      setToast({ message: t('ccr.installing'), type: "info" });
+      const result = await ccrApi.install(true);
+      setToast({
+        message: result.success ? t('ccr.installSuccess') : t('ccr.installFailed', { error: result.message }),
+        type: result.success ? "success" : "error"
+      });
+      await loadServiceStatus();
+    } catch (error) {
+      console.error("Failed to install CCR:", error);
+      setToast({
+        message: t('ccr.installFailed', { error: String(error) }),
+        type: "error"
+      });
+    } finally {
+      setActionLoading(false);
+    }
+  };
+
   const handleStartService = async () => {
     try {
+      if (!(await confirmRelayConflict())) return;
       setActionLoading(true);
-      const result = await ccrApi.startService();
+      const result = await ccrApi.startService(true);
       setServiceStatus(result.status);
       setToast({ 
         message: result.message, 
@@ -415,10 +450,15 @@ export function CcrRouterManager({ onBack }: CcrRouterManagerProps) {
                     需要先安装 Claude Code Router 才能使用此功能
                   </p>
                   <Button
-                    onClick={() => open("https://github.com/musistudio/claude-code-router/tree/main")}
+                    onClick={handleInstall}
+                    disabled={actionLoading}
                     className="gap-2"
                   >
-                    <Download className="w-4 h-4" />
+                    {actionLoading ? (
+                      <Loader2 className="w-4 h-4 animate-spin" />
+                    ) : (
+                      <Download className="w-4 h-4" />
+                    )}
                     安装 CCR
                   </Button>
                 </div>
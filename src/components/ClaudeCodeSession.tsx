@@ -30,7 +30,7 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Popover } from "@/components/ui/popover";
 import { useTranslation } from "react-i18next";
-import { api, type Session } from "@/lib/api";
+import { api, type FilesChangedEvent, type Session } from "@/lib/api";
 import { cn } from "@/lib/utils";
 import { useTabState } from "@/hooks/useTabState";
 import { open } from "@tauri-apps/plugin-dialog";
@@ -242,49 +242,50 @@ export const ClaudeCodeSession: React.FC<ClaudeCodeSessionProps> = ({
       console.log('[FileMonitor] File watching started successfully');
       
       // 监听文件系统变化事件
-      const unlisten = await listen<any>('file-system-change', (event) => {
+      const unlisten = await listen<FilesChangedEvent>('files-changed', (event) => {
         if (!isMountedRef.current) return;
         
-        const { path, change_type } = event.payload;
-        console.log('[FileMonitor] File change detected:', { path, change_type });
+        for (const { path, change_type } of event.payload.changes) {
+          console.log('[FileMonitor] File change detected:', { path, change_type });
         
-        // 过滤掉隐藏文件和临时文件
-        const fileName = path.split('/').pop() || '';
-        if (fileName.startsWith('.') || fileName.includes('~') || fileName.endsWith('.tmp')) {
-          return;
-        }
+          // 过滤掉隐藏文件和临时文件
+          const fileName = path.split('/').pop() || '';
+          if (fileName.startsWith('.') || fileName.includes('~') || fileName.endsWith('.tmp')) {
+            continue;
+          }
         
-        // 通知文件同步管理器
-        fileSyncManager.notifyFileChange(path, change_type);
+          // 通知文件同步管理器
+          fileSyncManager.notifyFileChange(path, change_type);
         
-        // 判断是否是 Claude 项目文件变化
-        const isClaudeProjectFile = path.includes('/.claude/projects/');
-        const displayPath = isClaudeProjectFile 
-          ? path.replace(/.*\/\.claude\/projects\/[^/]+\//, '[Claude] ') // 简化 Claude 项目文件路径显示
-          : path.replace(projectPath + '/', ''); // 项目文件相对路径
+          // 判断是否是 Claude 项目文件变化
+          const isClaudeProjectFile = path.includes('/.claude/projects/');
+          const displayPath = isClaudeProjectFile 
+            ? path.replace(/.*\/\.claude\/projects\/[^/]+\//, '[Claude] ') // 简化 Claude 项目文件路径显示
+            : path.replace(projectPath + '/', ''); // 项目文件相对路径
         
-        const newChange: FileChange = {
-          path: displayPath,
-          changeType: change_type,
-          timestamp: Date.now(),
-        };
+          const newChange: FileChange = {
+            path: displayPath,
+            changeType: change_type,
+            timestamp: Date.now(),
+          };
         
-        setFileChanges(prev => {
-          // 限制最多保存100个变化记录
-          const updated = [newChange, ...prev].slice(0, 100);
-          return updated;
-        });
+          setFileChanges(prev => {
+            // 限制最多保存100个变化记录
+            const updated = [newChange, ...prev].slice(0, 100);
+            return updated;
+          });
         
-        // 如果是 Claude 项目文件变化且文件被修改，重新加载会话历史
-        if (isClaudeProjectFile && change_type === 'modified' && session) {
-          const fileName = path.split('/').pop() || '';
-          // 检查是否是当前会话的 JSONL 文件
-          if (fileName === `${session.id}.jsonl`) {
-            console.log('[FileMonitor] Claude session file updated, reloading history');
-            // 使用 setTimeout 避免频繁刷新
-            setTimeout(() => {
-              loadSessionHistory();
-            }, 500);
+          // 如果是 Claude 项目文件变化且文件被修改，重新加载会话历史
+          if (isClaudeProjectFile && change_type === 'modified' && session) {
+            const fileName = path.split('/').pop() || '';
+            // 检查是否是当前会话的 JSONL 文件
+            if (fileName === `${session.id}.jsonl`) {
+              console.log('[FileMonitor] Claude session file updated, reloading history');
+              // 使用 setTimeout 避免频繁刷新
+              setTimeout(() => {
+                loadSessionHistory();
+              }, 500);
+            }
           }
         }
       });
@@ -1,6 +1,7 @@
 import React, { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { FilesChangedEvent } from "@/lib/api";
 import {
   X,
   Save,
@@ -518,11 +519,13 @@ export const FileEditorEnhanced: React.FC<FileEditorEnhancedProps> = ({
         });
         
         // 监听文件变化事件（作为备用）
-        unlistenRef.current = await listen('file-system-change', (event: any) => {
-          const { path, change_type } = event.payload;
-          
+        unlistenRef.current = await listen<FilesChangedEvent>('files-changed', (event) => {
           // 检查是否是当前文件的变化
-          if (path === filePath && (change_type === 'modified' || change_type === 'created')) {
+          const change = event.payload.changes.find(({ path, change_type }) =>
+            path === filePath && (change_type === 'modified' || change_type === 'created')
+          );
+          if (change) {
+            const { path, change_type } = change;
             // 检查时间间隔，避免自己保存触发的事件
             const timeSinceLastSave = Date.now() - lastCheckTime;
             
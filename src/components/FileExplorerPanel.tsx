@@ -2,6 +2,7 @@ import React, { useState, useEffect, useCallback, useMemo } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { FilesChangedEvent } from "@/lib/api";
 import {
   Folder,
   FolderOpen,
@@ -323,8 +324,8 @@ export const FileExplorerPanel: React.FC<FileExplorerPanelProps> = ({
     const setupListener = async () => {
       try {
         // 监听文件系统变化事件
-        unlistenRef.current = await listen("file-system-change", (event) => {
-          console.log("File system changed:", event.payload);
+        unlistenRef.current = await listen<FilesChangedEvent>("files-changed", (event) => {
+          console.log("File system changed:", event.payload.changes);
           loadFileTree();
         });
 
@@ -303,7 +303,7 @@ export const FilePicker: React.FC<FilePickerProps> = ({
       }
       
       // Always fetch fresh results in background
-      const results = await api.searchFiles(basePath, query);
+      const { entries: results } = await api.searchFiles(basePath, query);
       console.log('[FilePicker] Fresh search results:', results.length, 'items');
       
       // Cache the results
@@ -9,7 +9,8 @@ import {
   MoreVertical,
   Search,
   X,
-  Plus
+  Plus,
+  Star
 } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card } from "@/components/ui/card";
@@ -86,8 +87,10 @@ export const ProjectList: React.FC<ProjectListProps> = ({
   
   // Sort and filter projects
   const filteredAndSortedProjects = useMemo(() => {
-    // First, sort by last_session_time in descending order (newest first)
-    let sorted = [...projects].sort((a, b) => b.last_session_time - a.last_session_time);
+    // First, sort favorites first, then by last_session_time in descending order (newest first)
+    let sorted = [...projects].sort((a, b) =>
+      Number(!!b.is_favorite) - Number(!!a.is_favorite) || b.last_session_time - a.last_session_time
+    );
     
     // Then filter by search query
     if (searchQuery.trim()) {
@@ -212,11 +215,17 @@ export const ProjectList: React.FC<ProjectListProps> = ({
                   <div className="flex items-start justify-between mb-3">
                     <div className="flex items-center gap-2 flex-1 min-w-0">
                       <div className="p-2 rounded-lg bg-primary/10 group-hover:bg-primary/20 transition-colors">
-                        <FolderOpen className="h-4 w-4 text-primary" />
+                        <FolderOpen
+                          className="h-4 w-4 text-primary"
+                          style={project.color ? { color: project.color } : undefined}
+                        />
                       </div>
                       <h3 className="font-semibold text-base truncate group-hover:text-primary transition-colors">
                         {getProjectName(project.path)}
                       </h3>
+                      {project.is_favorite && (
+                        <Star className="h-3.5 w-3.5 shrink-0 fill-yellow-400 text-yellow-400" />
+                      )}
                     </div>
                     {project.sessions.length > 0 && (
                       <Badge variant="secondary" className="shrink-0 ml-2 group-hover:bg-primary group-hover:text-primary-foreground transition-colors">
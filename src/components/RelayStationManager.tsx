@@ -488,13 +488,40 @@ const RelayStationManager: React.FC<RelayStationManagerProps> = ({ onBack }) =>
     }
   };
 
+  // 检查中转站是否提供已映射的模型，缺失时提示（不阻止启用）
+  const warnMissingModels = async (stationId: string): Promise<boolean> => {
+    try {
+      const mappings = await api.getModelMappings();
+      const requiredModels = mappings.map(m => m.model_name).filter(Boolean);
+      if (requiredModels.length === 0) return false;
+      const result = await api.relayStationTestModels(stationId, requiredModels);
+      if (!result.all_present) {
+        showToast(t('relayStation.missingModels', { models: result.missing.join(', ') }), "error");
+        return true;
+      }
+    } catch (error) {
+      // 部分中转站不支持 /v1/models，忽略检测失败
+      console.warn('Model availability check skipped:', error);
+    }
+    return false;
+  };
+
   // 切换启用状态
   const toggleEnableStatus = async (stationId: string, currentEnabled: boolean) => {
     try {
       setTogglingEnable(prev => ({ ...prev, [stationId]: true }));
       const newEnabled = !currentEnabled;
-      await api.relayStationToggleEnable(stationId, newEnabled);
-      showToast(newEnabled ? t('relayStation.enabledSuccess') : t('relayStation.disabledSuccess'), "success");
+      const warned = newEnabled && await warnMissingModels(stationId);
+      const result = await api.relayStationToggleEnable(stationId, newEnabled);
+      if (!result.applied) {
+        // 连接测试失败，配置未写入
+        const reason = result.test_result?.message;
+        showToast(reason ? `${result.message}: ${reason}` : result.message, "error");
+        return;
+      }
+      if (!warned) {
+        showToast(newEnabled ? t('relayStation.enabledSuccess') : t('relayStation.disabledSuccess'), "success");
+      }
       loadStations();
       loadCurrentConfig(); // 重新加载配置状态
     } catch (error) {
@@ -1559,6 +1586,32 @@ const EditStationDialog: React.FC<{
   });
   const [submitting, setSubmitting] = useState(false);
   const [formToast, setFormToast] = useState<{ message: string; type: "success" | "error" } | null>(null);
+  const [tokenRevealed, setTokenRevealed] = useState(false);
+
+  // 列表中的令牌已脱敏，仅在用户点击时获取完整令牌
+  const revealToken = async () => {
+    try {
+      const token = await api.relayStationRevealToken(station.id);
+      setFormData(prev => ({ ...prev, system_token: token }));
+      setTokenRevealed(true);
+    } catch (error) {
+      console.error('Failed to reveal token:', error);
+      setFormToast({ message: t('relayStation.revealTokenFailed'), type: "error" });
+    }
+  };
+
+  const revealTokenButton = !tokenRevealed && (
+    <Button
+      type="button"
+      variant="ghost"
+      size="sm"
+      className="h-auto p-1 text-xs text-muted-foreground hover:text-foreground"
+      onClick={revealToken}
+    >
+      <Eye className="w-3 h-3 mr-1" />
+      {t('relayStation.revealToken')}
+    </Button>
+  );
 
   const [customJson, setCustomJson] = useState<string>(() => {
     // 从 adapter_config 中提取自定义JSON
@@ -1981,10 +2034,11 @@ const EditStationDialog: React.FC<{
               <div className="space-y-2">
                 <div className="flex items-center justify-between">
                   <Label htmlFor="edit-system_token">{t('relayStation.systemToken')} *</Label>
+                  {revealTokenButton}
                 </div>
                 <Input
                   id="edit-system_token"
-                  type="password"
+                  type={tokenRevealed ? "text" : "password"}
                   value={formData.system_token}
                   onChange={(e) => setFormData(prev => ({ ...prev, system_token: e.target.value }))}
                   placeholder={t('relayStation.tokenPlaceholder')}
@@ -2016,6 +2070,7 @@ const EditStationDialog: React.FC<{
             <div className="space-y-2">
               <div className="flex items-center justify-between">
                 <Label htmlFor="edit-system_token">{t('relayStation.systemToken')} *</Label>
+                {revealTokenButton}
                 {getApiKeyUrl(formData.adapter) && (
                   <Button
                     type="button"
@@ -2034,7 +2089,7 @@ const EditStationDialog: React.FC<{
               </div>
               <Input
                 id="edit-system_token"
-                type="password"
+                type={tokenRevealed ? "text" : "password"}
                 value={formData.system_token}
                 onChange={(e) => setFormData(prev => ({ ...prev, system_token: e.target.value }))}
                 placeholder={formData.adapter === 'packycode' ? 'sk-xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx' : t('relayStation.tokenPlaceholder')}
@@ -10,6 +10,10 @@ import {
   BarChart3,
   Shield,
   Trash,
+  Stethoscope,
+  CheckCircle2,
+  AlertTriangle,
+  XCircle,
 } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
@@ -22,7 +26,8 @@ import {
   api, 
   type ClaudeSettings,
   type ClaudeInstallation,
-  type ModelMapping
+  type ModelMapping,
+  type SelfTestReport
 } from "@/lib/api";
 import { cn } from "@/lib/utils";
 import { Toast, ToastContainer } from "@/components/ui/toast";
@@ -105,6 +110,10 @@ export const Settings: React.FC<SettingsProps> = ({
   const [loadingMappings, setLoadingMappings] = useState(false);
   const [modelMappingsChanged, setModelMappingsChanged] = useState(false);
   
+  // Self-test state
+  const [selfTestReport, setSelfTestReport] = useState<SelfTestReport | null>(null);
+  const [runningSelfTest, setRunningSelfTest] = useState(false);
+  
   // Load settings on mount
   useEffect(() => {
     loadSettings();
@@ -124,6 +133,22 @@ export const Settings: React.FC<SettingsProps> = ({
     }
   };
 
+  /**
+   * Runs the backend self-test and shows its per-check results
+   */
+  const runSelfTest = async () => {
+    try {
+      setRunningSelfTest(true);
+      const report = await api.runSelfTest();
+      setSelfTestReport(report);
+    } catch (err) {
+      console.error("Failed to run self-test:", err);
+      setToast({ message: t('settings.advanced.selfTestFailed'), type: "error" });
+    } finally {
+      setRunningSelfTest(false);
+    }
+  };
+
   /**
    * Loads model mappings
    * @author yovinchen
@@ -983,6 +1008,65 @@ export const Settings: React.FC<SettingsProps> = ({
                       {t('settings.advanced.rawSettingsDesc')}
                     </p>
                   </div>
+                  
+                  {/* Self-test */}
+                  <div className="space-y-3">
+                    <div className="flex items-center justify-between gap-4">
+                      <div>
+                        <Label>{t('settings.advanced.selfTest')}</Label>
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t('settings.advanced.selfTestDesc')}
+                        </p>
+                      </div>
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        onClick={runSelfTest}
+                        disabled={runningSelfTest}
+                        className="gap-2"
+                      >
+                        {runningSelfTest ? (
+                          <Loader2 className="h-4 w-4 animate-spin" />
+                        ) : (
+                          <Stethoscope className="h-4 w-4" />
+                        )}
+                        {t('settings.advanced.runDiagnostics')}
+                      </Button>
+                    </div>
+                    {selfTestReport && (
+                      <div className="space-y-2">
+                        <p className="text-xs text-muted-foreground">
+                          {t('settings.advanced.selfTestSummary', {
+                            passed: selfTestReport.passed,
+                            warnings: selfTestReport.warnings,
+                            failures: selfTestReport.failures,
+                          })}
+                        </p>
+                        {selfTestReport.checks.map((check) => (
+                          <div
+                            key={check.name}
+                            className="flex items-start gap-2 p-2 rounded-md border text-sm"
+                          >
+                            {check.status === "pass" && (
+                              <CheckCircle2 className="h-4 w-4 mt-0.5 shrink-0 text-green-500" />
+                            )}
+                            {check.status === "warn" && (
+                              <AlertTriangle className="h-4 w-4 mt-0.5 shrink-0 text-yellow-500" />
+                            )}
+                            {check.status === "fail" && (
+                              <XCircle className="h-4 w-4 mt-0.5 shrink-0 text-destructive" />
+                            )}
+                            <div className="min-w-0">
+                              <p className="font-medium">
+                                {t(`settings.advanced.selfTestChecks.${check.name}`, { defaultValue: check.name })}
+                              </p>
+                              <p className="text-xs text-muted-foreground break-all">{check.message}</p>
+                            </div>
+                          </div>
+                        ))}
+                      </div>
+                    )}
+                  </div>
                 </div>
               </Card>
             </TabsContent>
@@ -97,6 +97,7 @@ export const StorageTab: React.FC = () => {
   const [newRow, setNewRow] = useState<Record<string, any> | null>(null);
   const [deletingRow, setDeletingRow] = useState<Record<string, any> | null>(null);
   const [showResetConfirm, setShowResetConfirm] = useState(false);
+  const [resetConfirmText, setResetConfirmText] = useState("");
   const [showSqlEditor, setShowSqlEditor] = useState(false);
   const [sqlQuery, setSqlQuery] = useState("");
   const [sqlResult, setSqlResult] = useState<QueryResult | null>(null);
@@ -281,13 +282,14 @@ export const StorageTab: React.FC = () => {
   const handleResetDatabase = async () => {
     try {
       setLoading(true);
-      await api.storageResetDatabase();
+      const backupPath = await api.storageResetDatabase(resetConfirmText);
       await loadTables();
       setSelectedTable("");
       setTableData(null);
       setShowResetConfirm(false);
+      setResetConfirmText("");
       setToast({
-        message: t('storageTab.resetSuccess'),
+        message: t('storageTab.resetSuccessWithBackup', { path: backupPath }),
         type: "success",
       });
     } catch (err) {
@@ -785,17 +787,30 @@ export const StorageTab: React.FC = () => {
               {t('storageTab.resetWarning')}
             </span>
           </div>
+          <div className="space-y-2">
+            <Label htmlFor="reset-confirm">{t('storageTab.resetConfirmLabel')}</Label>
+            <Input
+              id="reset-confirm"
+              value={resetConfirmText}
+              onChange={(e) => setResetConfirmText(e.target.value)}
+              placeholder="RESET"
+              autoComplete="off"
+            />
+          </div>
           <DialogFooter>
             <Button
               variant="outline"
-              onClick={() => setShowResetConfirm(false)}
+              onClick={() => {
+                setShowResetConfirm(false);
+                setResetConfirmText("");
+              }}
             >
               {t('app.cancel')}
             </Button>
             <Button
               variant="destructive"
               onClick={handleResetDatabase}
-              disabled={loading}
+              disabled={loading || resetConfirmText !== "RESET"}
             >
               {loading ? (
                 <Loader2 className="h-4 w-4 animate-spin" />
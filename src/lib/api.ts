@@ -15,6 +15,40 @@ export interface ProcessInfo {
   project_path: string;
   task: string;
   model: string;
+  /** Bytes of live output currently buffered */
+  live_output_bytes: number;
+  /** Number of oldest output lines dropped to stay within the buffer limit */
+  live_output_truncated_lines: number;
+  /** Running input tokens, including cache reads and writes (Claude sessions only) */
+  input_tokens: number;
+  /** Running output tokens (Claude sessions only) */
+  output_tokens: number;
+  /** Running cost estimate in USD; Claude's reported cost once the run finished */
+  estimated_cost_usd: number;
+}
+
+/** Output lines appended to a session's live output buffer since a cursor */
+export interface LiveOutputChunk {
+  lines: string[];
+  /** Cursor to pass on the next call; also the cursor of the next emitted line */
+  next_cursor: number;
+  /** Lines after the requested cursor already dropped from the buffer */
+  dropped_lines: number;
+}
+
+/** Snapshot returned when re-attaching to a running Claude session */
+export interface SessionAttachment {
+  info: ProcessInfo;
+  /** Everything still buffered; `output.next_cursor` is the cursor of the next emitted line */
+  output: LiveOutputChunk;
+  /** Whether output events were paused before attaching; attaching resumes them */
+  was_paused: boolean;
+}
+
+/** A claude-output line with its buffer cursor, emitted on `claude-output-seq:{session_id}` */
+export interface ClaudeOutputLine {
+  cursor: number;
+  line: string;
 }
 
 /**
@@ -31,6 +65,126 @@ export interface Project {
   created_at: number;
   /** Unix timestamp of the most recent session (last modified time of newest JSONL file) */
   last_session_time: number;
+  /** Whether the user pinned this project as a favorite */
+  is_favorite: boolean;
+  /** Optional user-chosen color tag (e.g. "#3b82f6") */
+  color?: string | null;
+}
+
+/**
+ * Project directories whose sessions resolve to the same working directory
+ */
+export interface DuplicateProjectGroup {
+  path: string;
+  project_ids: string[];
+}
+
+/**
+ * Outcome of merging project directories into one
+ */
+export interface MergeProjectsResult {
+  target_id: string;
+  moved_sessions: string[];
+  /** Source directories removed because they were empty after the move */
+  removed_projects: string[];
+}
+
+/**
+ * Outcome of archiving a project
+ */
+export interface ArchiveProjectResult {
+  archive_path: string;
+  session_count: number;
+  file_count: number;
+  /** Total size of the archived files */
+  original_bytes: number;
+  /** Size of the archive on disk */
+  archive_bytes: number;
+  removed_originals: boolean;
+}
+
+/**
+ * Outcome of restoring a project archive
+ */
+export interface UnarchiveProjectResult {
+  project_id: string;
+  session_count: number;
+  file_count: number;
+  restored_bytes: number;
+  archive_bytes: number;
+}
+
+/**
+ * Spend limits of a project
+ */
+export interface ProjectBudget {
+  project_id: string;
+  daily_usd?: number | null;
+  monthly_usd?: number | null;
+  /** Refuse to start sessions when over budget; otherwise only warn */
+  hard_limit: boolean;
+}
+
+/**
+ * A project budget with the spend it is checked against
+ */
+export interface ProjectBudgetStatus {
+  budget: ProjectBudget;
+  spent_today_usd: number;
+  spent_this_month_usd: number;
+}
+
+/**
+ * Payload of the `budget-exceeded` event emitted before starting a session
+ */
+export interface BudgetExceeded {
+  project_id: string;
+  project_path: string;
+  period: "daily" | "monthly";
+  limit_usd: number;
+  spent_usd: number;
+  /** Whether the session was refused */
+  enforced: boolean;
+}
+
+/**
+ * The cwd recorded by one session file of a project
+ */
+export interface SessionCwdInfo {
+  session_id: string;
+  cwd?: string | null;
+  /** Why no cwd could be read from this file */
+  error?: string | null;
+}
+
+/**
+ * How a project directory name was resolved to a path
+ */
+export interface ProjectResolutionDebug {
+  project_id: string;
+  /** Result of the lossy directory-name decoding fallback */
+  decoded_path: string;
+  /** Session files in the order resolution tries them */
+  sessions: SessionCwdInfo[];
+  chosen_session_id?: string | null;
+  resolved_path: string;
+  conflicting_cwds: boolean;
+}
+
+/**
+ * A full-text search hit in a session message
+ */
+export interface SessionSearchResult {
+  project_id: string;
+  session_id: string;
+  /** Line index of the message in the session JSONL */
+  message_index: number;
+  timestamp?: string | null;
+  role: string;
+  /** Matching excerpt with hits wrapped in [ ] */
+  snippet: string;
+  /** BM25 score, lower is more relevant */
+  rank: number;
 }
 
 /**
@@ -72,6 +226,31 @@ export interface ClaudeVersionStatus {
   output: string;
 }
 
+/**
+ * Output format requested from the Claude CLI.
+ * "stream-json" (default) is required for checkpoint message tracking and live metrics;
+ * "text" emits raw lines on `claude-text:{sessionId}` and reports completion via exit code.
+ */
+export type ClaudeOutputFormat = "stream-json" | "text";
+
+/**
+ * Result of running an allowlisted Claude CLI diagnostic command
+ */
+export interface DiagnosticResult {
+  /** The command line that was executed */
+  command: string;
+  /** Captured standard output */
+  stdout: string;
+  /** Captured standard error */
+  stderr: string;
+  /** Process exit code (null if killed or timed out) */
+  exit_code: number | null;
+  /** Whether the command was killed after exceeding the timeout */
+  timed_out: boolean;
+  /** Wall-clock duration in milliseconds */
+  duration_ms: number;
+}
+
 /**
  * Represents a CLAUDE.md file found in the project
  */
@@ -87,14 +266,559 @@ export interface ClaudeMdFile {
 }
 
 /**
- * Represents a file or directory entry
+ * Represents a CLAUDE.md template
+ */
+export interface ClaudeMdTemplate {
+  /** Template name used to scaffold it */
+  name: string;
+  /** Short description in the current locale */
+  description: string;
+  /** Where the template comes from */
+  source: "builtin" | "user";
+  /** Markdown content in the current locale */
+  content: string;
+}
+
+/**
+ * A CLAUDE.md file that applies to a directory
+ */
+export interface EffectiveClaudeMdFile {
+  /** "user" for ~/.claude/CLAUDE.md, "project" for files in the project tree */
+  scope: "user" | "project";
+  /** Absolute path to the file */
+  absolute_path: string;
+  /** Directory relative to the project root */
+  relative_dir: string;
+  /** File content */
+  content: string;
+}
+
+/**
+ * The chain of CLAUDE.md files applying to a directory
+ */
+export interface EffectiveClaudeMd {
+  /** Files in precedence order, closest directory first */
+  files: EffectiveClaudeMdFile[];
+  /** All files concatenated in load order */
+  merged: string;
+}
+
+/**
+ * Payload of the `claude-metrics:{session_id}` events: running totals of a streaming session
+ */
+export interface SessionMetrics {
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  estimated_cost_usd: number;
+  /** True once the final result arrived; the cost is then the one Claude reported */
+  is_final: boolean;
+}
+
+/**
+ * Payload of the `session-history-malformed` events emitted by loadSessionHistory
+ */
+export interface MalformedSessionLines {
+  session_id: string;
+  project_id: string;
+  malformed_lines: number;
+  /** Lines dropped for exceeding the line length limit */
+  oversized_lines: number;
+}
+
+/**
+ * One user prompt of a session and the assistant's reply
+ */
+export interface SessionTurnSummary {
+  prompt: string;
+  /** Assistant text of the turn, tool calls excluded */
+  response: string;
+  /** Tools called in this turn, in call order */
+  tools: string[];
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  cost_usd: number;
+}
+
+/**
+ * A line of a response diff; "removed" lines are only in session A, "added" only in B
+ */
+export interface DiffLine {
+  kind: "equal" | "removed" | "added";
+  text: string;
+}
+
+/**
+ * A row of the side-by-side comparison; one side is missing for extra turns
+ */
+export interface TurnComparison {
+  index_a?: number | null;
+  index_b?: number | null;
+  turn_a?: SessionTurnSummary | null;
+  turn_b?: SessionTurnSummary | null;
+  same_prompt: boolean;
+  responded_differently: boolean;
+  response_diff: DiffLine[];
+}
+
+export interface SessionTotals {
+  turns: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_tokens: number;
+  cache_read_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+  /** Call count per tool */
+  tools: Record<string, number>;
+}
+
+/**
+ * Result of comparing two sessions
+ */
+export interface SessionComparison {
+  session_a: string;
+  session_b: string;
+  turns: TurnComparison[];
+  totals_a: SessionTotals;
+  totals_b: SessionTotals;
+  /** Session B minus session A */
+  token_delta: number;
+  cost_delta_usd: number;
+  tools_only_in_a: string[];
+  tools_only_in_b: string[];
+  differing_turns: number;
+}
+
+/**
+ * A file the tool calls of a session referred to
+ */
+export interface SessionTouchedFile {
+  path: string;
+  /** Calls per operation: "read", "write", "edit" or "bash" */
+  operations: Record<string, number>;
+  count: number;
+}
+
+/**
+ * Calls and results of one tool
+ */
+export interface ToolUsage {
+  name: string;
+  /** Server of an MCP tool (`mcp__<server>__<tool>`) */
+  mcp_server?: string;
+  calls: number;
+  results: number;
+  error_results: number;
+  total_result_bytes: number;
+  avg_result_bytes: number;
+}
+
+/**
+ * Tool usage of a session, or of every session of a project
+ */
+export interface ToolUsageStats {
+  project_id: string;
+  /** Set when the stats cover a single session */
+  session_id?: string;
+  sessions_scanned: number;
+  total_calls: number;
+  /** Most used first */
+  tools: ToolUsage[];
+  mcp_servers: { server: string; calls: number; tools: number }[];
+  by_session: { session_id: string; total_calls: number; tools: Record<string, number> }[];
+}
+
+/**
+ * Result of pruning the checkpoints of every session of a project
+ */
+export interface ProjectCheckpointCleanup {
+  sessions: {
+    session_id: string;
+    removed: number;
+    bytes_freed: number;
+    /** Set when cleaning up this session failed */
+    error?: string;
+  }[];
+  total_removed: number;
+  total_bytes_freed: number;
+}
+
+/**
+ * Result of exporting usage data to CSV
+ */
+export interface UsageCsvExport {
+  path: string;
+  /** Data rows written, excluding the header and the summary footer */
+  rows: number;
+  total_cost: number;
+}
+
+/**
+ * Error of a git write operation, discriminated by `kind`
+ */
+export type GitError =
+  | { kind: "not_a_repository"; path: string }
+  | { kind: "invalid_input"; message: string }
+  | { kind: "nothing_to_commit" }
+  | { kind: "hook_rejected"; output: string }
+  | { kind: "uncommitted_changes"; paths: string[] }
+  | { kind: "failed"; message: string };
+
+/**
+ * Options of `gitCommit`
+ */
+export interface GitCommitOptions {
+  /** Add a Signed-off-by trailer */
+  sign_off?: boolean;
+  /** Override the configured author name */
+  author_name?: string;
+  /** Override the configured author email */
+  author_email?: string;
+}
+
+/**
+ * Sync state of the current branch with its upstream
+ */
+export interface GitRemoteStatus {
+  branch: string;
+  /** e.g. "origin/main"; undefined when no upstream is configured */
+  upstream?: string;
+  has_upstream: boolean;
+  /** Local commits not pushed yet */
+  ahead: number;
+  /** Upstream commits not pulled yet */
+  behind: number;
+  remote_url?: string;
+  /** Whether the working tree has uncommitted or untracked changes */
+  is_dirty: boolean;
+}
+
+/**
+ * Unified diff of one file
+ */
+export interface GitFileDiff {
+  path: string;
+  status: "added" | "modified" | "deleted" | "renamed";
+  insertions: number;
+  deletions: number;
+  patch: string;
+}
+
+/**
+ * Working tree changes since a point in time
+ */
+export interface GitDiffSince {
+  /** HEAD at that time; undefined when the repository had no commits yet */
+  base_commit?: string;
+  /** How the base was found: from the HEAD reflog or by commit date */
+  base_source: "reflog" | "history";
+  files: GitFileDiff[];
+}
+
+/**
+ * Result of `startIsolatedSession`
+ */
+export interface IsolatedSessionStart {
+  branch: string;
+  checkpoint: CheckpointResult;
+}
+
+/**
+ * A session file line that is not valid JSON
+ */
+export interface CorruptJsonlLine {
+  /** 1-based line number */
+  line_number: number;
+  preview: string;
+  error: string;
+}
+
+/**
+ * Result of scanning (and optionally repairing) a session file
+ */
+export interface SessionRepairReport {
+  total_lines: number;
+  corrupt_lines: CorruptJsonlLine[];
+  trailing_line_incomplete: boolean;
+  repaired: boolean;
+  backup_path?: string;
+}
+
+/**
+ * The Claude config directory in use
+ */
+export interface ClaudeDirInfo {
+  path: string;
+  /** "setting" (app override), "env" (CLAUDE_CONFIG_DIR) or "default" (~/.claude) */
+  source: "setting" | "env" | "default";
+  exists: boolean;
+}
+
+/**
+ * Result of a single self-test check
+ */
+export interface SelfTestCheck {
+  name: string;
+  status: "pass" | "warn" | "fail";
+  message: string;
+}
+
+/**
+ * Results of all self-test checks
+ */
+export interface SelfTestReport {
+  checks: SelfTestCheck[];
+  passed: number;
+  warnings: number;
+  failures: number;
+}
+
+/**
+ * A corrupt agents database that was replaced at startup
+ */
+export interface DatabaseRecovery {
+  /** Where the corrupt database file was moved, if moving it succeeded */
+  moved_to: string | null;
+  reason: string;
+  /** Whether the app is running on a temporary in-memory database */
+  in_memory: boolean;
+}
+
+/**
+ * A page of table rows read with a rowid cursor
+ */
+export interface TablePage {
+  table_name: string;
+  columns: any[];
+  rows: Record<string, any>[];
+  /** Pass as `afterRowid` to read the next page; null on the last page */
+  next_cursor: number | null;
+  total_rows: number;
+  page_size: number;
+  total_pages: number;
+}
+
+/**
+ * A single row edit applied by storageExecuteBatch
+ */
+export type StorageOp =
+  | { type: "insert"; table_name: string; values: Record<string, any> }
+  | {
+      type: "update";
+      table_name: string;
+      primary_key_values: Record<string, any>;
+      updates: Record<string, any>;
+    }
+  | { type: "delete"; table_name: string; primary_key_values: Record<string, any> };
+
+/**
+ * Outcome of one operation in a committed batch
+ */
+export interface StorageOpResult {
+  index: number;
+  rows_affected: number;
+  last_insert_rowid: number | null;
+}
+
+/**
+ * Payload of the `claude-md-changed` events
+ */
+export interface ClaudeMdChangeEvent {
+  /** Absolute path to the CLAUDE.md file */
+  path: string;
+  /** "modified" or "deleted" */
+  change_type: "modified" | "deleted";
+  /** New content, null when the file was deleted */
+  content: string | null;
+  /** Unix timestamp in seconds */
+  timestamp: number;
+}
+
+/**
+ * How a path is watched: native file events, or scanning modification times
+ */
+export type WatchMode = "native" | "polling";
+
+/**
+ * Result of testing whether a path supports native file watching
+ */
+export interface WatchCapability {
+  /** Directory that was tested */
+  path: string;
+  native_supported: boolean;
+  /** Whether writing a marker file produced a native event in time */
+  native_events_fired: boolean;
+  recommended_mode: WatchMode;
+  /** Why the check could not be completed */
+  error: string | null;
+}
+
+/**
+ * A path being watched by the file watcher
+ */
+export interface WatchedPath {
+  path: string;
+  /** Project directory name under ~/.claude/projects, if the path is one */
+  project_id: string | null;
+  /** Number of users still holding the watch */
+  ref_count: number;
+  recursive: boolean;
+  /** "polling" when native events are unavailable or polling is forced */
+  mode: WatchMode;
+}
+
+/**
+ * Which file watching mode is in use
+ */
+export interface WatcherModeStatus {
+  force_polling: boolean;
+  poll_interval_ms: number;
+  /** Whether any path is being polled; live updates lag by up to the interval */
+  polling_active: boolean;
+  paths: WatchedPath[];
+}
+
+/**
+ * A single file change from the file watcher
+ */
+export interface FileChangeEvent {
+  path: string;
+  change_type: "created" | "modified" | "deleted";
+  /** Unix timestamp in seconds */
+  timestamp: number;
+}
+
+/**
+ * Payload of the `files-changed` event: all changes coalesced within one debounce window
+ */
+export interface FilesChangedEvent {
+  changes: FileChangeEvent[];
+  /** Unix timestamp in seconds */
+  timestamp: number;
+}
+
+/**
+ * Payload of the `session-created` and `session-created:{project_id}` events, emitted
+ * while the project's session directory is watched
+ */
+export interface SessionCreatedEvent {
+  session_id: string;
+  project_id: string;
+  /** Absolute path to the session JSONL file */
+  path: string;
+  /** First user message, null if none was written shortly after creation */
+  first_message: string | null;
+  message_timestamp: string | null;
+  /** Unix timestamp in seconds */
+  timestamp: number;
+}
+
+/**
+ * Represents a file or directory entry
+ */
+export interface FileEntry {
+  name: string;
+  path: string;
+  is_directory: boolean;
+  size: number;
+  extension?: string;
+  /** Whether the name starts with a dot */
+  is_hidden: boolean;
+  /** Whether the entry itself is a symbolic link */
+  is_symlink: boolean;
+  /** Resolved target of a symbolic link */
+  symlink_target?: string | null;
+}
+
+/**
+ * Disk space used by a directory
+ */
+export interface DirectorySize {
+  bytes: number;
+  file_count: number;
+}
+
+/**
+ * Disk usage of ~/.claude and the Claudia cache
+ */
+export interface ClaudeDiskUsage {
+  /** Session files, excluding checkpoints */
+  projects: DirectorySize;
+  /** Checkpoint data under each project's .timelines */
+  checkpoints: DirectorySize;
+  todos: DirectorySize;
+  /** Everything else in ~/.claude */
+  other: DirectorySize;
+  /** ~/.claudia/cache */
+  claudia_cache: DirectorySize;
+  total: DirectorySize;
+}
+
+/**
+ * File search results along with how the search went
+ */
+export interface SearchFilesResult {
+  entries: FileEntry[];
+  /** Number of matches found before the search stopped, at most one past the result cap */
+  total_matched: number;
+  /** Whether the search stopped early because more entries matched than the result cap */
+  truncated: boolean;
+  dirs_scanned: number;
+  /** Whether some directories were too deep to be searched */
+  depth_limit_reached: boolean;
+  elapsed_ms: number;
+}
+
+/**
+ * Options for grepping file contents
+ */
+export interface GrepOptions {
+  case_insensitive?: boolean;
+  /** Stop after this many matching lines (default 500) */
+  max_matches?: number;
+  /** Only search files whose name or relative path matches one of these globs */
+  include?: string[];
+  /** Skip files whose name or relative path matches one of these globs */
+  exclude?: string[];
+  /** Enter symlinked directories, each real directory at most once */
+  follow_symlinks?: boolean;
+}
+
+/**
+ * All matching lines in one file
  */
-export interface FileEntry {
-  name: string;
+export interface GrepFileMatches {
   path: string;
-  is_directory: boolean;
-  size: number;
-  extension?: string;
+  relative_path: string;
+  matches: { line_number: number; line: string }[];
+}
+
+/**
+ * Result of grepping file contents
+ */
+export interface GrepResult {
+  files: GrepFileMatches[];
+  total_matches: number;
+  /** Whether more lines matched than max_matches and were left out */
+  truncated: boolean;
+  files_scanned: number;
+  /** Whether some files were not searched because of the depth or file count limit */
+  scan_limit_reached: boolean;
+  elapsed_ms: number;
+}
+
+/**
+ * Ignore patterns for CLAUDE.md discovery and file search (.gitignore syntax)
+ */
+export interface SearchIgnoreInfo {
+  /** Built-in patterns that always apply */
+  default_patterns: string[];
+  patterns: string[];
+  use_gitignore: boolean;
 }
 
 /**
@@ -122,6 +846,28 @@ export interface Agent {
   hooks?: string; // JSON string of HooksConfiguration
   created_at: string;
   updated_at: string;
+  env?: Record<string, string>; // Extra environment variables for each run
+}
+
+/**
+ * Cron schedule attached to an agent
+ */
+export interface AgentSchedule {
+  agent_id: number;
+  cron: string;
+  project_path: string;
+  task?: string; // Falls back to the agent's default task
+  paused: boolean;
+  last_run_at?: string;
+  next_run_at?: string;
+}
+
+/**
+ * How an agent reports finished runs
+ */
+export interface AgentNotificationConfig {
+  system_notification: boolean;
+  webhook_url?: string;
 }
 
 export interface AgentExport {
@@ -145,6 +891,21 @@ export interface GitHubAgentFile {
   sha: string;
 }
 
+/**
+ * A run whose stored output matched a search query
+ */
+export interface AgentRunSearchResult {
+  run_id: number;
+  agent_id: number;
+  agent_name: string;
+  agent_icon: string;
+  task: string;
+  status: string;
+  created_at: string;
+  completed_at?: string;
+  snippet: string;
+}
+
 export interface AgentRun {
   id?: number;
   agent_id: number;
@@ -154,7 +915,7 @@ export interface AgentRun {
   model: string;
   project_path: string;
   session_id: string;
-  status: string; // 'pending', 'running', 'completed', 'failed', 'cancelled'
+  status: string; // 'pending', 'running', 'completed', 'failed', 'cancelled', 'interrupted'
   pid?: number;
   process_started_at?: string;
   created_at: string;
@@ -177,7 +938,7 @@ export interface AgentRunWithMetrics {
   model: string;
   project_path: string;
   session_id: string;
-  status: string; // 'pending', 'running', 'completed', 'failed', 'cancelled'
+  status: string; // 'pending', 'running', 'completed', 'failed', 'cancelled', 'interrupted'
   pid?: number;
   process_started_at?: string;
   created_at: string;
@@ -186,6 +947,26 @@ export interface AgentRunWithMetrics {
   output?: string; // Real-time JSONL content
 }
 
+/** Cost and run count for one day of an agent's runs */
+export interface AgentDailyCost {
+  date: string; // YYYY-MM-DD
+  cost_usd: number;
+  run_count: number;
+}
+
+/** Lifetime metrics aggregated across all runs of an agent */
+export interface AgentAggregate {
+  agent_id: number;
+  run_count: number;
+  completed_count: number;
+  failed_count: number;
+  success_rate: number; // 0..1 over finished runs
+  total_tokens: number;
+  total_cost_usd: number;
+  average_duration_ms?: number;
+  daily_costs: AgentDailyCost[];
+}
+
 // Usage Dashboard types
 export interface UsageEntry {
   project: string;
@@ -244,6 +1025,26 @@ export interface UsageStats {
   by_project: ProjectUsage[];
 }
 
+/**
+ * Usage stats with costs converted to another currency
+ */
+export interface CurrencyUsageStats {
+  /** Currency of `stats`; "USD" when falling back */
+  currency: string;
+  requested_currency: string;
+  /** USD to `currency` multiplier applied to every cost */
+  rate: number;
+  rate_source: "identity" | "provided" | "cached" | "fetched" | "stale" | "unavailable";
+  /** When the rate was cached or fetched (unix seconds) */
+  rate_updated_at?: number | null;
+  /** True when no rate was available and `stats` are in USD */
+  fell_back_to_usd: boolean;
+  /** Stats with every cost converted, unrounded */
+  stats: UsageStats;
+  /** The same stats in USD */
+  usd_stats: UsageStats;
+}
+
 /**
  * Represents a checkpoint in the session timeline
  */
@@ -301,6 +1102,10 @@ export interface SessionTimeline {
   autoCheckpointEnabled: boolean;
   checkpointStrategy: CheckpointStrategy;
   totalCheckpoints: number;
+  /** Minimum seconds between automatic checkpoints, 0 when unlimited */
+  autoCheckpointMinIntervalSecs: number;
+  /** Tool uses accumulated before an automatic checkpoint, 0 when unlimited */
+  autoCheckpointMinToolUses: number;
 }
 
 /**
@@ -317,6 +1122,41 @@ export interface CheckpointResult {
   warnings: string[];
 }
 
+/**
+ * What restoring a checkpoint would change, computed without modifying anything
+ */
+export interface RestorePreview {
+  checkpoint: Checkpoint;
+  overwrittenFiles: string[];
+  createdFiles: string[];
+  deletedFiles: string[];
+  currentMessageCount: number;
+  restoredMessageCount: number;
+  messagesRemoved: number;
+}
+
+/**
+ * In-memory footprint of an active checkpoint manager
+ */
+export interface CheckpointManagerStats {
+  session_id: string;
+  project_id: string;
+  tracked_messages: number;
+  tracked_files: number;
+  estimated_bytes: number;
+  idle_secs: number;
+}
+
+/**
+ * Checkpoint manager statistics
+ */
+export interface CheckpointStateStats {
+  active_managers: number;
+  active_sessions: string[];
+  estimated_bytes: number;
+  managers: CheckpointManagerStats[];
+}
+
 /**
  * Diff between two checkpoints
  */
@@ -401,7 +1241,7 @@ export interface SlashCommand {
   name: string;
   /** Full command with prefix (e.g., "/project:optimize") */
   full_command: string;
-  /** Command scope: "project" or "user" */
+  /** Command scope: "project", "user" or "default" (built-in) */
   scope: string;
   /** Optional namespace (e.g., "frontend" in "/project:frontend:component") */
   namespace?: string;
@@ -419,6 +1259,19 @@ export interface SlashCommand {
   has_file_references: boolean;
   /** Whether the command uses $ARGUMENTS placeholder */
   accepts_arguments: boolean;
+  /** Whether the command can be edited or deleted (false for built-ins) */
+  editable: boolean;
+}
+
+/** A slash command file found in a GitHub repository */
+export interface GitHubSlashCommandFile {
+  name: string;
+  path: string;
+  download_url: string;
+  size: number;
+  sha: string;
+  /** Command as it will be invoked once imported (e.g. "/frontend:component") */
+  full_command: string;
 }
 
 /**
@@ -532,7 +1385,7 @@ export interface RelayStation {
   api_url: string;              // API 基础 URL
   adapter: RelayStationAdapter; // 适配器类型
   auth_method: AuthMethod;      // 认证方式
-  system_token: string;         // 系统令牌
+  system_token: string;         // 系统令牌（列表/详情中为脱敏值，完整值需调用 relayStationRevealToken）
   user_id?: string;             // 用户 ID（可选）
   adapter_config?: Record<string, any>; // 适配器特定配置
   enabled: boolean;             // 启用状态
@@ -589,12 +1442,76 @@ export interface UserInfo {
   metadata?: Record<string, any>;          // 原始数据
 }
 
+/** 中转站请求的全局默认超时（单站可通过 adapter_config 的 timeout_ms / connect_timeout_ms 覆盖） */
+export interface RelayTimeoutSettings {
+  timeout_ms?: number;            // 总超时（毫秒）
+  connect_timeout_ms?: number;    // 连接超时（毫秒），默认 5000
+}
+
 /** 连接测试结果 */
 export interface ConnectionTestResult {
   success: boolean;                // 连接是否成功
   response_time?: number;         // 响应时间（毫秒）
   message: string;                // 结果消息
   error?: string;                 // 错误信息
+  attempts?: number;              // 实际请求次数（含重试）
+}
+
+/** Claude 配置备份摘要 */
+export interface ClaudeConfigBackup {
+  timestamp: string;              // 备份标识（源文件备份为 "source"）
+  created_at: number;             // 创建时间（毫秒）
+  size: number;
+  is_source: boolean;             // 是否为首次启用中转站前的源文件备份
+  api_base_url?: string;          // 备份中的 ANTHROPIC_BASE_URL
+  model?: string;
+}
+
+/** 模型可用性测试结果 */
+export interface ModelAvailabilityResult {
+  available_models: string[];     // 中转站提供的全部模型
+  present: string[];              // 所需模型中已提供的
+  missing: string[];              // 所需模型中缺失的
+  all_present: boolean;
+}
+
+/** 切换中转站启用状态的结果 */
+export interface RelayToggleResult {
+  applied: boolean;               // 是否已切换并写入配置
+  message: string;                // 结果消息
+  test_result?: ConnectionTestResult; // 启用前的连接测试结果
+}
+
+/** Claude 请求的实际路由方式 */
+export type RoutingMode = 'direct' | 'relay_station' | 'ccr' | 'custom';
+
+/** 当前路由状态 */
+export interface RoutingStatus {
+  mode: RoutingMode;               // 实际生效的路由方式
+  relay_station_id?: string;       // 已启用的中转站 ID
+  relay_station_name?: string;     // 已启用的中转站名称
+  ccr_running: boolean;            // CCR 服务是否运行
+  ccr_port?: number;               // CCR 服务端口
+  base_url?: string;               // Claude 配置中的 base URL
+  masked_token?: string;           // Claude 配置中的令牌（已脱敏）
+}
+
+/**
+ * Per-run routing override for agent executions (set either field, not both)
+ */
+export interface RoutingOverride {
+  relay_station_id?: string;
+  ccr_port?: number;
+}
+
+/** CCR 与中转站的路由冲突检测结果 */
+export interface RoutingConflict {
+  has_conflict: boolean;           // CCR 与中转站是否同时生效
+  ccr_running: boolean;            // CCR 服务是否运行（启用中转站前需注意）
+  relay_station_enabled: boolean;  // 是否有启用的中转站（启动 CCR 前需注意）
+  relay_station_name?: string;     // 已启用的中转站名称
+  mode: RoutingMode;               // Claude 配置当前实际指向
+  message?: string;                // 冲突提示
 }
 
 /** 导入结果统计 */
@@ -644,6 +1561,18 @@ export interface PackycodeNode {
   available?: boolean;            // 是否可用
 }
 
+/** 节点状态（含最近测速结果与使用状态） */
+export interface PackycodeNodeStatus {
+  node_id: string;                // 节点 ID（节点 URL）
+  node: PackycodeNode;            // 节点信息
+  latency_ms?: number;            // 最近一次测速的延迟（毫秒）
+  reachable?: boolean;            // 最近一次测速是否可达，未测速时为空
+  measured_at?: number;           // 最近一次测速时间（Unix 秒）
+  active: boolean;                // 是否为当前启用的中转站所用节点
+  pinned: boolean;                // 是否为用户固定的节点
+  selection?: 'auto' | 'pinned';  // 当前使用节点的选择方式，非当前节点为空
+}
+
 /** 节点测速结果 */
 export interface NodeSpeedTestResult {
   node: PackycodeNode;            // 节点信息
@@ -652,6 +1581,9 @@ export interface NodeSpeedTestResult {
   error?: string;                 // 错误信息
 }
 
+/** PackyCode 服务类型（adapter_config 的 service_type）：公交车 / 滴滴车，未设置时根据 API 地址推断 */
+export type PackycodeServiceType = 'bus' | 'share';
+
 /** PackyCode 用户额度信息 */
 export interface PackycodeUserQuota {
   daily_budget_usd: number;              // 日预算（美元）
@@ -667,6 +1599,17 @@ export interface PackycodeUserQuota {
   opus_enabled?: boolean;                // 是否启用Opus模型
 }
 
+/** PackyCode 预算提醒（`budget-warning` 事件载荷） */
+export interface PackycodeBudgetWarning {
+  station_id: string;                    // 中转站 ID
+  station_name: string;                  // 中转站名称
+  period: 'daily' | 'monthly';           // 预算周期
+  spent_usd: number;                     // 已使用（美元）
+  budget_usd: number;                    // 预算（美元）
+  ratio: number;                         // 已用/预算
+  threshold: number;                     // 触发提醒的阈值
+}
+
 /**
  * API client for interacting with the Rust backend
  */
@@ -684,6 +1627,34 @@ export const api = {
     }
   },
 
+  /**
+   * Marks or unmarks a project as favorite (favorites are listed first)
+   * @param projectId - The project ID (encoded directory name)
+   * @param isFavorite - Whether the project is a favorite
+   */
+  async setProjectFavorite(projectId: string, isFavorite: boolean): Promise<void> {
+    try {
+      return await invoke<void>("set_project_favorite", { projectId, isFavorite });
+    } catch (error) {
+      console.error("Failed to set project favorite:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets or clears the color tag of a project
+   * @param projectId - The project ID (encoded directory name)
+   * @param color - Color in #RGB or #RRGGBB form, or null to clear
+   */
+  async setProjectColor(projectId: string, color: string | null): Promise<void> {
+    try {
+      return await invoke<void>("set_project_color", { projectId, color });
+    } catch (error) {
+      console.error("Failed to set project color:", error);
+      throw error;
+    }
+  },
+
   /**
    * Retrieves sessions for a specific project
    * @param projectId - The ID of the project to retrieve sessions for
@@ -698,6 +1669,120 @@ export const api = {
     }
   },
 
+  /**
+   * Finds project directories that resolve to the same working directory
+   * @returns Promise resolving to groups of more than one project ID
+   */
+  async findDuplicateProjects(): Promise<DuplicateProjectGroup[]> {
+    try {
+      return await invoke<DuplicateProjectGroup[]>('find_duplicate_projects');
+    } catch (error) {
+      console.error("Failed to find duplicate projects:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Moves the sessions and checkpoints of the source projects into the target project.
+   * Fails without changes if a session ID exists in more than one of them.
+   * @param targetId - The project to keep
+   * @param sourceIds - The projects to merge into it
+   */
+  async mergeProjects(targetId: string, sourceIds: string[]): Promise<MergeProjectsResult> {
+    try {
+      return await invoke<MergeProjectsResult>('merge_projects', { targetId, sourceIds });
+    } catch (error) {
+      console.error("Failed to merge projects:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Explains how a project directory resolves to its path
+   * @param projectId - The encoded project directory name
+   */
+  async debugProjectResolution(projectId: string): Promise<ProjectResolutionDebug> {
+    try {
+      return await invoke<ProjectResolutionDebug>('debug_project_resolution', { projectId });
+    } catch (error) {
+      console.error("Failed to debug project resolution:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Zips a project's sessions, checkpoints and todos into one archive
+   * @param projectId - The project to archive
+   * @param destPath - Archive file path, or a directory to create it in
+   * @param removeOriginals - Delete the project once the archive is verified
+   */
+  async archiveProject(
+    projectId: string,
+    destPath: string,
+    removeOriginals?: boolean
+  ): Promise<ArchiveProjectResult> {
+    try {
+      return await invoke<ArchiveProjectResult>('archive_project', {
+        projectId,
+        destPath,
+        removeOriginals,
+      });
+    } catch (error) {
+      console.error("Failed to archive project:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Restores a project archive
+   * @param archivePath - Archive created by archiveProject
+   * @param overwrite - Write into the project even if it already exists
+   */
+  async unarchiveProject(archivePath: string, overwrite?: boolean): Promise<UnarchiveProjectResult> {
+    try {
+      return await invoke<UnarchiveProjectResult>('unarchive_project', { archivePath, overwrite });
+    } catch (error) {
+      console.error("Failed to restore project archive:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets the spend limits of a project; clearing both limits removes the budget
+   * @param projectId - The encoded project directory name
+   * @param dailyUsd - Optional daily limit in USD
+   * @param monthlyUsd - Optional monthly limit in USD
+   * @param hardLimit - Refuse sessions when over budget (default true); otherwise only warn
+   */
+  async setProjectBudget(
+    projectId: string,
+    dailyUsd?: number | null,
+    monthlyUsd?: number | null,
+    hardLimit?: boolean
+  ): Promise<void> {
+    try {
+      return await invoke<void>('set_project_budget', { projectId, dailyUsd, monthlyUsd, hardLimit });
+    } catch (error) {
+      console.error("Failed to set project budget:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the budget of a project with today's and this month's spend
+   * @param projectId - The encoded project directory name
+   * @param projectPath - The project's path, as recorded in usage entries
+   * @returns Promise resolving to the budget status, or null when the project has no budget
+   */
+  async getProjectBudget(projectId: string, projectPath: string): Promise<ProjectBudgetStatus | null> {
+    try {
+      return await invoke<ProjectBudgetStatus | null>('get_project_budget', { projectId, projectPath });
+    } catch (error) {
+      console.error("Failed to get project budget:", error);
+      throw error;
+    }
+  },
+
   /**
    * Fetch list of agents from GitHub repository
    * @returns Promise resolving to list of available agents on GitHub
@@ -739,6 +1824,33 @@ export const api = {
     }
   },
 
+  /**
+   * Gets the Claude config directory in use and where its location came from
+   * @returns Promise resolving to the directory info
+   */
+  async getClaudeConfigDir(): Promise<ClaudeDirInfo> {
+    try {
+      return await invoke<ClaudeDirInfo>("get_claude_config_dir");
+    } catch (error) {
+      console.error("Failed to get Claude config directory:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Relocates the Claude config directory; pass null to fall back to CLAUDE_CONFIG_DIR or ~/.claude
+   * @param path - An existing, writable directory
+   * @returns Promise resolving to the directory info after the change
+   */
+  async setClaudeConfigDir(path: string | null): Promise<ClaudeDirInfo> {
+    try {
+      return await invoke<ClaudeDirInfo>("set_claude_config_dir", { path });
+    } catch (error) {
+      console.error("Failed to set Claude config directory:", error);
+      throw error;
+    }
+  },
+
   /**
    * Reads the Claude settings file
    * @returns Promise resolving to the settings object
@@ -802,6 +1914,21 @@ export const api = {
     }
   },
 
+  /**
+   * Runs an allowlisted Claude CLI command (e.g. `doctor`, `--version`) and captures its output
+   * @param args - Arguments passed to the claude binary
+   * @param timeoutSecs - Optional timeout in seconds (default 30)
+   * @returns Promise resolving to the captured output and exit code
+   */
+  async runClaudeDiagnostic(args: string[], timeoutSecs?: number): Promise<DiagnosticResult> {
+    try {
+      return await invoke<DiagnosticResult>("run_claude_diagnostic", { args, timeoutSecs });
+    } catch (error) {
+      console.error("Failed to run Claude diagnostic:", error);
+      throw error;
+    }
+  },
+
   /**
    * Saves the CLAUDE.md system prompt file
    * @param content - The new content for the system prompt
@@ -843,66 +1970,151 @@ export const api = {
         return result.data;
       }
 
-      return result as ClaudeSettings;
+      return result as ClaudeSettings;
+    } catch (error) {
+      console.error("Failed to get Claude settings backup:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Saves the Claude settings backup file
+   * @param settings - The backup settings object to save
+   * @returns Promise resolving when the backup settings are saved
+   */
+  async saveClaudeSettingsBackup(settings: ClaudeSettings): Promise<string> {
+    try {
+      return await invoke<string>("save_claude_settings_backup", { settings });
+    } catch (error) {
+      console.error("Failed to save Claude settings backup:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Finds all CLAUDE.md files in a project directory
+   * @param projectPath - The absolute path to the project
+   * @param followSymlinks - Enter symlinked directories (cycles are skipped)
+   * @returns Promise resolving to an array of CLAUDE.md files
+   */
+  async findClaudeMdFiles(projectPath: string, followSymlinks?: boolean): Promise<ClaudeMdFile[]> {
+    try {
+      return await invoke<ClaudeMdFile[]>("find_claude_md_files", { projectPath, followSymlinks });
+    } catch (error) {
+      console.error("Failed to find CLAUDE.md files:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Reads a specific CLAUDE.md file
+   * @param filePath - The absolute path to the file
+   * @returns Promise resolving to the file content
+   */
+  async readClaudeMdFile(filePath: string): Promise<string> {
+    try {
+      return await invoke<string>("read_claude_md_file", { filePath });
+    } catch (error) {
+      console.error("Failed to read CLAUDE.md file:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Saves a specific CLAUDE.md file
+   * @param filePath - The absolute path to the file
+   * @param content - The new content for the file
+   * @returns Promise resolving when the file is saved
+   */
+  async saveClaudeMdFile(filePath: string, content: string): Promise<string> {
+    try {
+      return await invoke<string>("save_claude_md_file", { filePath, content });
+    } catch (error) {
+      console.error("Failed to save CLAUDE.md file:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists bundled CLAUDE.md templates and those in ~/.claudia/templates
+   * @returns Promise resolving to the available templates
+   */
+  async listClaudeMdTemplates(): Promise<ClaudeMdTemplate[]> {
+    try {
+      return await invoke<ClaudeMdTemplate[]>("list_claude_md_templates");
     } catch (error) {
-      console.error("Failed to get Claude settings backup:", error);
+      console.error("Failed to list CLAUDE.md templates:", error);
       throw error;
     }
   },
 
   /**
-   * Saves the Claude settings backup file
-   * @param settings - The backup settings object to save
-   * @returns Promise resolving when the backup settings are saved
+   * Writes a CLAUDE.md template into a project
+   * @param projectPath - The project root
+   * @param templateName - Name of the template to use
+   * @param overwrite - Replace an existing CLAUDE.md
+   * @param inClaudeDir - Write to .claude/CLAUDE.md instead of the project root
+   * @returns Promise resolving to the path of the written file
    */
-  async saveClaudeSettingsBackup(settings: ClaudeSettings): Promise<string> {
+  async scaffoldClaudeMd(
+    projectPath: string,
+    templateName: string,
+    overwrite?: boolean,
+    inClaudeDir?: boolean
+  ): Promise<string> {
     try {
-      return await invoke<string>("save_claude_settings_backup", { settings });
+      return await invoke<string>("scaffold_claude_md", {
+        projectPath,
+        templateName,
+        overwrite,
+        inClaudeDir,
+      });
     } catch (error) {
-      console.error("Failed to save Claude settings backup:", error);
+      console.error("Failed to scaffold CLAUDE.md:", error);
       throw error;
     }
   },
 
   /**
-   * Finds all CLAUDE.md files in a project directory
-   * @param projectPath - The absolute path to the project
-   * @returns Promise resolving to an array of CLAUDE.md files
+   * Resolves which CLAUDE.md files apply to a directory and previews their merged content
+   * @param fileDir - The directory to resolve for
+   * @param projectPath - Optional project root (defaults to the nearest git root)
+   * @returns Promise resolving to the files in precedence order and the merged preview
    */
-  async findClaudeMdFiles(projectPath: string): Promise<ClaudeMdFile[]> {
+  async getEffectiveClaudeMd(fileDir: string, projectPath?: string): Promise<EffectiveClaudeMd> {
     try {
-      return await invoke<ClaudeMdFile[]>("find_claude_md_files", { projectPath });
+      return await invoke<EffectiveClaudeMd>("get_effective_claude_md", { fileDir, projectPath });
     } catch (error) {
-      console.error("Failed to find CLAUDE.md files:", error);
+      console.error("Failed to resolve effective CLAUDE.md:", error);
       throw error;
     }
   },
 
   /**
-   * Reads a specific CLAUDE.md file
-   * @param filePath - The absolute path to the file
-   * @returns Promise resolving to the file content
+   * Watches all CLAUDE.md files in a project, including newly created ones.
+   * Changes are emitted as `claude-md-changed` and `claude-md-changed:{path}` events
+   * (characters other than letters, digits, `-`, `/`, `:` and `_` are replaced with `_` in the path).
+   * @param projectPath - The project root
+   * @returns Promise resolving to the CLAUDE.md files currently in the project
    */
-  async readClaudeMdFile(filePath: string): Promise<string> {
+  async watchClaudeMdFiles(projectPath: string): Promise<ClaudeMdFile[]> {
     try {
-      return await invoke<string>("read_claude_md_file", { filePath });
+      return await invoke<ClaudeMdFile[]>("watch_claude_md_files", { projectPath });
     } catch (error) {
-      console.error("Failed to read CLAUDE.md file:", error);
+      console.error("Failed to watch CLAUDE.md files:", error);
       throw error;
     }
   },
 
   /**
-   * Saves a specific CLAUDE.md file
-   * @param filePath - The absolute path to the file
-   * @param content - The new content for the file
-   * @returns Promise resolving when the file is saved
+   * Stops watching CLAUDE.md files in a project
+   * @param projectPath - The project root
    */
-  async saveClaudeMdFile(filePath: string, content: string): Promise<string> {
+  async unwatchClaudeMdFiles(projectPath: string): Promise<void> {
     try {
-      return await invoke<string>("save_claude_md_file", { filePath, content });
+      return await invoke<void>("unwatch_claude_md_files", { projectPath });
     } catch (error) {
-      console.error("Failed to save CLAUDE.md file:", error);
+      console.error("Failed to stop watching CLAUDE.md files:", error);
       throw error;
     }
   },
@@ -930,6 +2142,8 @@ export const api = {
    * @param default_task - Optional default task
    * @param model - Optional model (defaults to 'sonnet')
    * @param hooks - Optional hooks configuration as JSON string
+   * @param env - Optional environment variables merged into each run
+   * @param allowProtectedEnv - Allow env to override PATH or HOME
    * @returns Promise resolving to the created agent
    */
   async createAgent(
@@ -938,7 +2152,9 @@ export const api = {
     system_prompt: string,
     default_task?: string,
     model?: string,
-    hooks?: string
+    hooks?: string,
+    env?: Record<string, string>,
+    allowProtectedEnv?: boolean
   ): Promise<Agent> {
     try {
       return await invoke<Agent>('create_agent', {
@@ -947,7 +2163,9 @@ export const api = {
         systemPrompt: system_prompt,
         defaultTask: default_task,
         model,
-        hooks
+        hooks,
+        env,
+        allowProtectedEnv
       });
     } catch (error) {
       console.error("Failed to create agent:", error);
@@ -964,6 +2182,8 @@ export const api = {
    * @param default_task - Optional default task
    * @param model - Optional model
    * @param hooks - Optional hooks configuration as JSON string
+   * @param env - Optional environment variables merged into each run
+   * @param allowProtectedEnv - Allow env to override PATH or HOME
    * @returns Promise resolving to the updated agent
    */
   async updateAgent(
@@ -973,7 +2193,9 @@ export const api = {
     system_prompt: string,
     default_task?: string,
     model?: string,
-    hooks?: string
+    hooks?: string,
+    env?: Record<string, string>,
+    allowProtectedEnv?: boolean
   ): Promise<Agent> {
     try {
       return await invoke<Agent>('update_agent', {
@@ -983,7 +2205,9 @@ export const api = {
         systemPrompt: system_prompt,
         defaultTask: default_task,
         model,
-        hooks
+        hooks,
+        env,
+        allowProtectedEnv
       });
     } catch (error) {
       console.error("Failed to update agent:", error);
@@ -1005,6 +2229,152 @@ export const api = {
     }
   },
 
+  /**
+   * Duplicates an agent under a new name
+   * @param agentId - The agent ID to copy
+   * @param newName - Name for the copy (" (copy)" is appended if it is taken)
+   * @returns Promise resolving to the new agent
+   */
+  async duplicateAgent(agentId: number, newName: string): Promise<Agent> {
+    try {
+      return await invoke<Agent>('duplicate_agent', { agentId, newName });
+    } catch (error) {
+      console.error("Failed to duplicate agent:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets (or replaces) the cron schedule of an agent
+   * @param agentId - The agent ID
+   * @param cron - Cron expression (5 or 6 fields, evaluated in local time)
+   * @param projectPath - Project the scheduled runs execute in
+   * @param task - Optional task (defaults to the agent's default task)
+   * @returns Promise resolving to the saved schedule
+   */
+  async setAgentSchedule(
+    agentId: number,
+    cron: string,
+    projectPath: string,
+    task?: string
+  ): Promise<AgentSchedule> {
+    try {
+      return await invoke<AgentSchedule>('set_agent_schedule', { agentId, cron, projectPath, task });
+    } catch (error) {
+      console.error("Failed to set agent schedule:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the schedule of an agent
+   * @param agentId - The agent ID
+   * @returns Promise resolving to the schedule, or null if the agent is not scheduled
+   */
+  async getAgentSchedule(agentId: number): Promise<AgentSchedule | null> {
+    try {
+      return await invoke<AgentSchedule | null>('get_agent_schedule', { agentId });
+    } catch (error) {
+      console.error("Failed to get agent schedule:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Removes the schedule of an agent
+   * @param agentId - The agent ID
+   */
+  async clearAgentSchedule(agentId: number): Promise<void> {
+    try {
+      return await invoke<void>('clear_agent_schedule', { agentId });
+    } catch (error) {
+      console.error("Failed to clear agent schedule:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Pauses the schedule of an agent
+   * @param agentId - The agent ID
+   */
+  async pauseSchedule(agentId: number): Promise<void> {
+    try {
+      return await invoke<void>('pause_schedule', { agentId });
+    } catch (error) {
+      console.error("Failed to pause schedule:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Resumes a paused schedule (runs missed while paused are skipped)
+   * @param agentId - The agent ID
+   */
+  async resumeSchedule(agentId: number): Promise<void> {
+    try {
+      return await invoke<void>('resume_schedule', { agentId });
+    } catch (error) {
+      console.error("Failed to resume schedule:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the next time an agent's schedule fires
+   * @param agentId - The agent ID
+   * @returns Promise resolving to an RFC 3339 timestamp, or null when unscheduled or paused
+   */
+  async getNextRunTime(agentId: number): Promise<string | null> {
+    try {
+      return await invoke<string | null>('get_next_run_time', { agentId });
+    } catch (error) {
+      console.error("Failed to get next run time:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets how an agent reports finished runs
+   * @param agentId - The agent ID
+   * @returns Promise resolving to the notification settings
+   */
+  async getAgentNotificationConfig(agentId: number): Promise<AgentNotificationConfig> {
+    try {
+      return await invoke<AgentNotificationConfig>('get_agent_notification_config', { agentId });
+    } catch (error) {
+      console.error("Failed to get agent notification config:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Saves how an agent reports finished runs
+   * @param agentId - The agent ID
+   * @param config - System notification and/or webhook settings
+   */
+  async setAgentNotificationConfig(agentId: number, config: AgentNotificationConfig): Promise<void> {
+    try {
+      return await invoke<void>('set_agent_notification_config', { agentId, config });
+    } catch (error) {
+      console.error("Failed to save agent notification config:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sends a sample run summary to a webhook URL
+   * @param url - The webhook URL
+   * @returns Promise resolving to a message with the HTTP status
+   */
+  async testAgentWebhook(url: string): Promise<string> {
+    try {
+      return await invoke<string>('test_agent_webhook', { url });
+    } catch (error) {
+      console.error("Failed to test agent webhook:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets a single agent by ID
    * @param id - The agent ID
@@ -1067,11 +2437,18 @@ export const api = {
    * @param projectPath - The project path to run the agent in
    * @param task - The task description
    * @param model - Optional model override
+   * @param routing - Optional relay station or CCR override for this run only
    * @returns Promise resolving to the run ID when execution starts
    */
-  async executeAgent(agentId: number, projectPath: string, task: string, model?: string): Promise<number> {
+  async executeAgent(
+    agentId: number,
+    projectPath: string,
+    task: string,
+    model?: string,
+    routing?: RoutingOverride
+  ): Promise<number> {
     try {
-      return await invoke<number>('execute_agent', { agentId, projectPath, task, model });
+      return await invoke<number>('execute_agent', { agentId, projectPath, task, model, routing });
     } catch (error) {
       console.error("Failed to execute agent:", error);
       // Return a sentinel value to indicate error
@@ -1108,6 +2485,21 @@ export const api = {
     }
   },
 
+  /**
+   * Searches the stored output of finished agent runs
+   * @param query - Search terms
+   * @param limit - Maximum number of results (capped at 100)
+   * @returns Promise resolving to matching runs with snippets
+   */
+  async searchAgentRuns(query: string, limit?: number): Promise<AgentRunSearchResult[]> {
+    try {
+      return await invoke<AgentRunSearchResult[]>('search_agent_runs', { query, limit });
+    } catch (error) {
+      console.error("Failed to search agent runs:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets a single agent run by ID with real-time metrics from JSONL
    * @param id - The run ID
@@ -1122,6 +2514,20 @@ export const api = {
     }
   },
 
+  /**
+   * Gets lifetime metrics aggregated across all runs of an agent
+   * @param agentId - The agent ID
+   * @returns Promise resolving to the aggregated metrics (zeroed if the agent has no runs)
+   */
+  async getAgentAggregateMetrics(agentId: number): Promise<AgentAggregate> {
+    try {
+      return await invoke<AgentAggregate>('get_agent_aggregate_metrics', { agentId });
+    } catch (error) {
+      console.error("Failed to get agent aggregate metrics:", error);
+      throw new Error(`Failed to get agent aggregate metrics: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
   /**
    * Lists all currently running agent sessions
    * @returns Promise resolving to list of running agent sessions
@@ -1135,96 +2541,284 @@ export const api = {
     }
   },
 
+  /**
+   * Gets the per-run live output buffer limit
+   * @returns Promise resolving to the limit in bytes
+   */
+  async getLiveOutputLimit(): Promise<number> {
+    try {
+      return await invoke<number>('get_live_output_limit');
+    } catch (error) {
+      console.error("Failed to get live output limit:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets the per-run live output buffer limit; oldest lines are dropped once exceeded
+   * @param limitBytes - The limit in bytes
+   */
+  async setLiveOutputLimit(limitBytes: number): Promise<void> {
+    try {
+      return await invoke('set_live_output_limit', { limitBytes });
+    } catch (error) {
+      console.error("Failed to set live output limit:", error);
+      throw error;
+    }
+  },
+
   /**
    * Kills a running agent session
    * @param runId - The run ID to kill
+   * @param gracePeriodMs - Optional time to wait after SIGTERM before sending SIGKILL
    * @returns Promise resolving to whether the session was successfully killed
    */
-  async killAgentSession(runId: number): Promise<boolean> {
+  async killAgentSession(runId: number, gracePeriodMs?: number): Promise<boolean> {
+    try {
+      return await invoke<boolean>('kill_agent_session', { runId, gracePeriodMs });
+    } catch (error) {
+      console.error("Failed to kill agent session:", error);
+      throw new Error(`Failed to kill agent session: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
+  /**
+   * Gets the status of a specific agent session
+   * @param runId - The run ID to check
+   * @returns Promise resolving to the session status or null if not found
+   */
+  async getSessionStatus(runId: number): Promise<string | null> {
+    try {
+      return await invoke<string | null>('get_session_status', { runId });
+    } catch (error) {
+      console.error("Failed to get session status:", error);
+      throw new Error(`Failed to get session status: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
+  /**
+   * Cleanup finished processes and update their status
+   * @returns Promise resolving to list of run IDs that were cleaned up
+   */
+  async cleanupFinishedProcesses(): Promise<number[]> {
+    try {
+      return await invoke<number[]>('cleanup_finished_processes');
+    } catch (error) {
+      console.error("Failed to cleanup finished processes:", error);
+      throw new Error(`Failed to cleanup finished processes: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
+  /**
+   * Get real-time output for a running session (with live output fallback)
+   * @param runId - The run ID to get output for
+   * @returns Promise resolving to the current session output (JSONL format)
+   */
+  async getSessionOutput(runId: number): Promise<string> {
+    try {
+      return await invoke<string>('get_session_output', { runId });
+    } catch (error) {
+      console.error("Failed to get session output:", error);
+      throw new Error(`Failed to get session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
+  /**
+   * Get live output directly from process stdout buffer
+   * @param runId - The run ID to get live output for
+   * @returns Promise resolving to the current live output
+   */
+  async getLiveSessionOutput(runId: number): Promise<string> {
+    try {
+      return await invoke<string>('get_live_session_output', { runId });
+    } catch (error) {
+      console.error("Failed to get live session output:", error);
+      throw new Error(`Failed to get live session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
+  /**
+   * Start streaming real-time output for a running session
+   * @param runId - The run ID to stream output for
+   * @returns Promise that resolves when streaming starts
+   */
+  async streamSessionOutput(runId: number): Promise<void> {
+    try {
+      return await invoke<void>('stream_session_output', { runId });
+    } catch (error) {
+      console.error("Failed to start streaming session output:", error);
+      throw new Error(`Failed to start streaming session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  },
+
+  /**
+   * Loads the JSONL history for a specific session
+   */
+  async loadSessionHistory(sessionId: string, projectId: string): Promise<any[]> {
+    return invoke("load_session_history", { sessionId, projectId });
+  },
+
+  /**
+   * Scans a session file for corrupt JSONL lines
+   * @param sessionId - The session ID (UUID)
+   * @param projectId - The project ID
+   * @param removeTrailing - Remove an incomplete last line, keeping a backup of the file
+   * @returns Promise resolving to the scan report
+   */
+  async repairSessionJsonl(
+    sessionId: string,
+    projectId: string,
+    removeTrailing?: boolean
+  ): Promise<SessionRepairReport> {
+    try {
+      return await invoke<SessionRepairReport>("repair_session_jsonl", {
+        sessionId,
+        projectId,
+        removeTrailing,
+      });
+    } catch (error) {
+      console.error("Failed to repair session file:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Exports a session as a Markdown transcript
+   * @param sessionId - The session ID (UUID)
+   * @param projectId - The project ID
+   * @param includeToolOutput - Whether to include tool results (defaults to true)
+   * @returns Promise resolving to the Markdown document
+   */
+  async exportSessionMarkdown(
+    sessionId: string,
+    projectId: string,
+    includeToolOutput?: boolean
+  ): Promise<string> {
     try {
-      return await invoke<boolean>('kill_agent_session', { runId });
+      return await invoke<string>("export_session_markdown", {
+        sessionId,
+        projectId,
+        includeToolOutput,
+      });
     } catch (error) {
-      console.error("Failed to kill agent session:", error);
-      throw new Error(`Failed to kill agent session: ${error instanceof Error ? error.message : 'Unknown error'}`);
+      console.error("Failed to export session as Markdown:", error);
+      throw error;
     }
   },
 
   /**
-   * Gets the status of a specific agent session
-   * @param runId - The run ID to check
-   * @returns Promise resolving to the session status or null if not found
+   * Exports a session as a self-contained HTML file with syntax-highlighted code
+   * @param sessionId - The session ID (UUID)
+   * @param projectId - The project ID
+   * @param theme - "light", "dark" or a bundled highlighter theme name
+   * @param includeToolOutput - Whether to include tool results (defaults to true)
+   * @returns Promise resolving to the HTML document
    */
-  async getSessionStatus(runId: number): Promise<string | null> {
+  async exportSessionHtml(
+    sessionId: string,
+    projectId: string,
+    theme?: string,
+    includeToolOutput?: boolean
+  ): Promise<string> {
     try {
-      return await invoke<string | null>('get_session_status', { runId });
+      return await invoke<string>("export_session_html", {
+        sessionId,
+        projectId,
+        theme,
+        includeToolOutput,
+      });
     } catch (error) {
-      console.error("Failed to get session status:", error);
-      throw new Error(`Failed to get session status: ${error instanceof Error ? error.message : 'Unknown error'}`);
+      console.error("Failed to export session as HTML:", error);
+      throw error;
     }
   },
 
   /**
-   * Cleanup finished processes and update their status
-   * @returns Promise resolving to list of run IDs that were cleaned up
+   * Builds a context block from a session's user and assistant messages to paste as the first
+   * message of a new session. Tool calls are excluded and the oldest messages dropped first.
+   * @param sessionId - The session ID (UUID)
+   * @param projectId - The project ID
+   * @param maxTokens - Approximate token budget (defaults to 8000)
+   * @returns Promise resolving to the context text
    */
-  async cleanupFinishedProcesses(): Promise<number[]> {
+  async buildContextFromSession(
+    sessionId: string,
+    projectId: string,
+    maxTokens?: number
+  ): Promise<string> {
     try {
-      return await invoke<number[]>('cleanup_finished_processes');
+      return await invoke<string>("build_context_from_session", {
+        sessionId,
+        projectId,
+        maxTokens,
+      });
     } catch (error) {
-      console.error("Failed to cleanup finished processes:", error);
-      throw new Error(`Failed to cleanup finished processes: ${error instanceof Error ? error.message : 'Unknown error'}`);
+      console.error("Failed to build context from session:", error);
+      throw error;
     }
   },
 
   /**
-   * Get real-time output for a running session (with live output fallback)
-   * @param runId - The run ID to get output for
-   * @returns Promise resolving to the current session output (JSONL format)
+   * Compares two sessions of a project turn by turn. Turns are aligned on their prompts and
+   * the assistant responses are diffed line by line.
+   * @param sessionA - The first session ID
+   * @param sessionB - The second session ID
+   * @param projectId - The project ID
+   * @returns Promise resolving to the side-by-side comparison
    */
-  async getSessionOutput(runId: number): Promise<string> {
+  async compareSessions(
+    sessionA: string,
+    sessionB: string,
+    projectId: string
+  ): Promise<SessionComparison> {
     try {
-      return await invoke<string>('get_session_output', { runId });
+      return await invoke<SessionComparison>("compare_sessions", {
+        sessionA,
+        sessionB,
+        projectId,
+      });
     } catch (error) {
-      console.error("Failed to get session output:", error);
-      throw new Error(`Failed to get session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
+      console.error("Failed to compare sessions:", error);
+      throw error;
     }
   },
 
   /**
-   * Get live output directly from process stdout buffer
-   * @param runId - The run ID to get live output for
-   * @returns Promise resolving to the current live output
+   * Lists the files a session read or changed, derived from its Read, Write, Edit and
+   * Bash tool calls
+   * @param sessionId - The session ID
+   * @param projectId - The project ID the session belongs to
    */
-  async getLiveSessionOutput(runId: number): Promise<string> {
+  async getSessionTouchedFiles(
+    sessionId: string,
+    projectId: string
+  ): Promise<SessionTouchedFile[]> {
     try {
-      return await invoke<string>('get_live_session_output', { runId });
+      return await invoke<SessionTouchedFile[]>("get_session_touched_files", {
+        sessionId,
+        projectId,
+      });
     } catch (error) {
-      console.error("Failed to get live session output:", error);
-      throw new Error(`Failed to get live session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
+      console.error("Failed to get session touched files:", error);
+      throw error;
     }
   },
 
   /**
-   * Start streaming real-time output for a running session
-   * @param runId - The run ID to stream output for
-   * @returns Promise that resolves when streaming starts
+   * Counts tool calls per tool with the average result size, for one session or for
+   * every session of a project
+   * @param projectId - The project ID
+   * @param sessionId - Optional session ID; all sessions of the project when omitted
    */
-  async streamSessionOutput(runId: number): Promise<void> {
+  async getToolUsageStats(projectId: string, sessionId?: string): Promise<ToolUsageStats> {
     try {
-      return await invoke<void>('stream_session_output', { runId });
+      return await invoke<ToolUsageStats>("get_tool_usage_stats", { projectId, sessionId });
     } catch (error) {
-      console.error("Failed to start streaming session output:", error);
-      throw new Error(`Failed to start streaming session output: ${error instanceof Error ? error.message : 'Unknown error'}`);
+      console.error("Failed to get tool usage stats:", error);
+      throw error;
     }
   },
 
-  /**
-   * Loads the JSONL history for a specific session
-   */
-  async loadSessionHistory(sessionId: string, projectId: string): Promise<any[]> {
-    return invoke("load_session_history", { sessionId, projectId });
-  },
-
   /**
    * Loads the JSONL history for a specific agent session
    * Similar to loadSessionHistory but searches across all project directories
@@ -1243,30 +2837,59 @@ export const api = {
   /**
    * Executes a new interactive Claude Code session with streaming output
    */
-  async executeClaudeCode(projectPath: string, prompt: string, model: string): Promise<void> {
-    return invoke("execute_claude_code", { projectPath, prompt, model });
+  async executeClaudeCode(projectPath: string, prompt: string, model: string, outputFormat?: ClaudeOutputFormat): Promise<void> {
+    return invoke("execute_claude_code", { projectPath, prompt, model, outputFormat });
   },
 
   /**
    * Continues an existing Claude Code conversation with streaming output
    */
-  async continueClaudeCode(projectPath: string, prompt: string, model: string): Promise<void> {
-    return invoke("continue_claude_code", { projectPath, prompt, model });
+  async continueClaudeCode(projectPath: string, prompt: string, model: string, outputFormat?: ClaudeOutputFormat): Promise<void> {
+    return invoke("continue_claude_code", { projectPath, prompt, model, outputFormat });
   },
 
   /**
    * Resumes an existing Claude Code session by ID with streaming output
    */
-  async resumeClaudeCode(projectPath: string, sessionId: string, prompt: string, model: string): Promise<void> {
-    return invoke("resume_claude_code", { projectPath, sessionId, prompt, model });
+  async resumeClaudeCode(projectPath: string, sessionId: string, prompt: string, model: string, outputFormat?: ClaudeOutputFormat): Promise<void> {
+    return invoke("resume_claude_code", { projectPath, sessionId, prompt, model, outputFormat });
+  },
+
+  /**
+   * Replays the user prompts of a session as a new session, one after another.
+   * Progress is emitted as `replay-progress`; cancelling the running session stops the replay.
+   * @param sourceSessionId - The session to replay
+   * @param projectId - The project ID the session belongs to
+   * @param projectPath - The project directory to run in
+   * @param model - The model to replay with
+   * @returns Promise resolving to the new session ID
+   */
+  async replaySession(
+    sourceSessionId: string,
+    projectId: string,
+    projectPath: string,
+    model: string
+  ): Promise<string> {
+    try {
+      return await invoke<string>("replay_session", {
+        sourceSessionId,
+        projectId,
+        projectPath,
+        model,
+      });
+    } catch (error) {
+      console.error("Failed to replay session:", error);
+      throw error;
+    }
   },
 
   /**
    * Cancels the currently running Claude Code execution
    * @param sessionId - Optional session ID to cancel a specific session
+   * @param gracePeriodMs - Optional time to wait after SIGTERM before sending SIGKILL
    */
-  async cancelClaudeExecution(sessionId?: string): Promise<void> {
-    return invoke("cancel_claude_execution", { sessionId });
+  async cancelClaudeExecution(sessionId?: string, gracePeriodMs?: number): Promise<void> {
+    return invoke("cancel_claude_execution", { sessionId, gracePeriodMs });
   },
 
   /**
@@ -1286,18 +2909,141 @@ export const api = {
     return invoke("get_claude_session_output", { sessionId });
   },
 
+  /**
+   * Gets a session's output lines from a cursor onwards, for reconnecting without loss.
+   * Subscribe to `claude-output-seq:{sessionId}` first, then fetch from the last cursor
+   * seen and ignore events whose cursor is below the returned `next_cursor`.
+   * @param sessionId - The session ID to get output for
+   * @param cursor - Cursor of the first line to return (0 for everything still buffered)
+   * @returns Promise resolving to the lines and the next cursor
+   */
+  async getClaudeOutputSince(sessionId: string, cursor: number): Promise<LiveOutputChunk> {
+    try {
+      return await invoke<LiveOutputChunk>("get_claude_output_since", { sessionId, cursor });
+    } catch (error) {
+      console.error("Failed to get Claude output since cursor:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Re-attaches to a running session: returns its buffered output and resumes its output
+   * events if paused. Rejects if the session is not running; fall back to the JSONL then.
+   * Lines can arrive both in the backlog and as events: subscribe to
+   * `claude-output-seq:{sessionId}` first and drop cursors below `output.next_cursor`.
+   * @param sessionId - The session ID to attach to
+   * @returns Promise resolving to the session info and buffered output
+   */
+  async attachSession(sessionId: string): Promise<SessionAttachment> {
+    try {
+      return await invoke<SessionAttachment>("attach_session", { sessionId });
+    } catch (error) {
+      console.error("Failed to attach to session:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Pauses or resumes emitting a session's output events. The session keeps running;
+   * on resume the held back lines are emitted after a marker: a `system` message with
+   * subtype `output_buffered` on claude-output, "[buffered N lines]" on claude-text.
+   * @param sessionId - The session ID
+   * @param paused - Whether output events should be held back
+   */
+  async setSessionOutputPaused(sessionId: string, paused: boolean): Promise<void> {
+    return invoke("set_session_output_paused", { sessionId, paused });
+  },
+
   /**
    * Lists files and directories in a given path
+   * @param showHidden - Include dotfiles, except those matching the search ignore patterns
+   */
+  async listDirectoryContents(directoryPath: string, showHidden?: boolean): Promise<FileEntry[]> {
+    return invoke("list_directory_contents", { directoryPath, showHidden });
+  },
+
+  /**
+   * Computes the size of a directory, skipping the search ignore patterns.
+   * Results are cached briefly.
+   * @param path - Directory to measure
+   */
+  async getDirectorySize(path: string): Promise<DirectorySize> {
+    try {
+      return await invoke<DirectorySize>("get_directory_size", { path });
+    } catch (error) {
+      console.error("Failed to get directory size:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Breaks down the disk space used by ~/.claude and the Claudia cache
    */
-  async listDirectoryContents(directoryPath: string): Promise<FileEntry[]> {
-    return invoke("list_directory_contents", { directoryPath });
+  async getClaudeDiskUsage(): Promise<ClaudeDiskUsage> {
+    try {
+      return await invoke<ClaudeDiskUsage>("get_claude_disk_usage");
+    } catch (error) {
+      console.error("Failed to get Claude disk usage:", error);
+      throw error;
+    }
   },
 
   /**
    * Searches for files and directories matching a pattern
+   * @param maxDepth - Directory depth to search below basePath (default 5)
+   * @param maxResults - Maximum number of entries returned (default 50)
+   * @param followSymlinks - Enter symlinked directories (cycles are skipped)
+   */
+  async searchFiles(
+    basePath: string,
+    query: string,
+    maxDepth?: number,
+    maxResults?: number,
+    followSymlinks?: boolean
+  ): Promise<SearchFilesResult> {
+    return invoke("search_files", { basePath, query, maxDepth, maxResults, followSymlinks });
+  },
+
+  /**
+   * Searches file contents for a regex, honoring the search ignore patterns
+   * @param basePath - Directory to search
+   * @param pattern - Regular expression (Rust regex syntax)
+   * @param options - Case sensitivity, match cap and include/exclude globs
+   */
+  async grepFiles(basePath: string, pattern: string, options?: GrepOptions): Promise<GrepResult> {
+    try {
+      return await invoke<GrepResult>("grep_files", { basePath, pattern, options });
+    } catch (error) {
+      console.error("Failed to grep files:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the ignore patterns used by CLAUDE.md discovery and file search
+   * @returns Promise resolving to the built-in and user-configured patterns
+   */
+  async getSearchIgnorePatterns(): Promise<SearchIgnoreInfo> {
+    try {
+      return await invoke<SearchIgnoreInfo>("get_search_ignore_patterns");
+    } catch (error) {
+      console.error("Failed to get search ignore patterns:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Saves extra ignore patterns (.gitignore syntax), merged with the defaults
+   * @param patterns - Extra patterns to skip
+   * @param useGitignore - Also apply the project's top-level .gitignore
    */
-  async searchFiles(basePath: string, query: string): Promise<FileEntry[]> {
-    return invoke("search_files", { basePath, query });
+  async setSearchIgnorePatterns(patterns: string[], useGitignore: boolean): Promise<void> {
+    try {
+      return await invoke<void>("set_search_ignore_patterns", { patterns, useGitignore });
+    } catch (error) {
+      console.error("Failed to save search ignore patterns:", error);
+      throw error;
+    }
   },
 
   /**
@@ -1400,6 +3146,66 @@ export const api = {
     }
   },
 
+  /**
+   * Gets usage statistics with costs converted to another currency
+   * @param days - Optional number of days to look back
+   * @param currency - ISO 4217 currency code, e.g. "EUR"
+   * @param rate - Optional USD to currency rate; a cached or fetched rate is used when omitted
+   * @returns Promise resolving to converted and raw USD statistics
+   */
+  async getUsageStatsInCurrency(
+    days: number | undefined,
+    currency: string,
+    rate?: number
+  ): Promise<CurrencyUsageStats> {
+    try {
+      return await invoke<CurrencyUsageStats>("get_usage_stats_in_currency", {
+        days,
+        currency,
+        rate,
+      });
+    } catch (error) {
+      console.error("Failed to get usage stats in currency:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Exports usage entries to a CSV file with a TOTAL footer row
+   * @param rangeDays - Optional number of days to look back (all entries when omitted)
+   * @param destPath - File to write
+   * @param groupBy - Optional aggregation: one row per day, model or project
+   * @returns Promise resolving to the written file and row count
+   */
+  async exportUsageCsv(
+    rangeDays: number | undefined,
+    destPath: string,
+    groupBy?: "day" | "model" | "project"
+  ): Promise<UsageCsvExport> {
+    try {
+      return await invoke<UsageCsvExport>("export_usage_csv", { rangeDays, destPath, groupBy });
+    } catch (error) {
+      console.error("Failed to export usage CSV:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Full-text search across message content of all projects and sessions
+   * (uses the index built by the usage scan)
+   * @param query - Search terms; all terms must match
+   * @param limit - Maximum number of results (default 50)
+   * @returns Promise resolving to ranked search results
+   */
+  async searchAllSessions(query: string, limit?: number): Promise<SessionSearchResult[]> {
+    try {
+      return await invoke<SessionSearchResult[]>("search_all_sessions", { query, limit });
+    } catch (error) {
+      console.error("Failed to search sessions:", error);
+      throw error;
+    }
+  },
+
   /**
    * Check if there are usage data updates available
    * @returns Promise resolving to boolean indicating if updates are available
@@ -1449,6 +3255,42 @@ export const api = {
     });
   },
 
+  /**
+   * Previews the files and messages restoring a checkpoint would change, without changing them
+   */
+  async previewRestoreCheckpoint(
+    checkpointId: string,
+    sessionId: string,
+    projectId: string,
+    projectPath: string
+  ): Promise<RestorePreview> {
+    return invoke("preview_restore_checkpoint", {
+      checkpointId,
+      sessionId,
+      projectId,
+      projectPath
+    });
+  },
+
+  /**
+   * Restores only the given files from a checkpoint, leaving the session and other files as they are
+   */
+  async restoreCheckpointFiles(
+    checkpointId: string,
+    sessionId: string,
+    projectId: string,
+    projectPath: string,
+    files: string[]
+  ): Promise<CheckpointResult> {
+    return invoke("restore_checkpoint_files", {
+      checkpointId,
+      sessionId,
+      projectId,
+      projectPath,
+      files
+    });
+  },
+
   /**
    * Lists all checkpoints for a session
    */
@@ -1508,14 +3350,18 @@ export const api = {
     projectId: string,
     projectPath: string,
     autoCheckpointEnabled: boolean,
-    checkpointStrategy: CheckpointStrategy
+    checkpointStrategy: CheckpointStrategy,
+    minIntervalSecs?: number,
+    minToolUses?: number
   ): Promise<void> {
     return invoke("update_checkpoint_settings", {
       sessionId,
       projectId,
       projectPath,
       autoCheckpointEnabled,
-      checkpointStrategy
+      checkpointStrategy,
+      minIntervalSecs,
+      minToolUses
     });
   },
 
@@ -1607,6 +3453,31 @@ export const api = {
     }
   },
 
+  /**
+   * Prunes the checkpoints of every session of a project, keeping the most recent ones.
+   * Fails while any of those sessions is running.
+   * @param projectId - The project ID
+   * @param projectPath - The project directory
+   * @param keepPerSession - Checkpoints to keep per session
+   * @returns Promise resolving to a per-session summary and the bytes freed
+   */
+  async cleanupProjectCheckpoints(
+    projectId: string,
+    projectPath: string,
+    keepPerSession: number
+  ): Promise<ProjectCheckpointCleanup> {
+    try {
+      return await invoke<ProjectCheckpointCleanup>("cleanup_project_checkpoints", {
+        projectId,
+        projectPath,
+        keepPerSession,
+      });
+    } catch (error) {
+      console.error("Failed to clean up project checkpoints:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets checkpoint settings for a session
    */
@@ -1619,6 +3490,10 @@ export const api = {
     checkpoint_strategy: CheckpointStrategy;
     total_checkpoints: number;
     current_checkpoint_id?: string;
+    /** Minimum seconds between automatic checkpoints, 0 when unlimited */
+    min_interval_secs: number;
+    /** Tool uses accumulated before an automatic checkpoint, 0 when unlimited */
+    min_tool_uses: number;
   }> {
     try {
       return await invoke("get_checkpoint_settings", {
@@ -1639,22 +3514,66 @@ export const api = {
     try {
       await invoke("clear_checkpoint_manager", { sessionId });
     } catch (error) {
-      console.error("Failed to clear checkpoint manager:", error);
+      console.error("Failed to clear checkpoint manager:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets checkpoint manager statistics, including the memory footprint of each active manager
+   */
+  async getCheckpointStateStats(): Promise<CheckpointStateStats> {
+    try {
+      return await invoke<CheckpointStateStats>("get_checkpoint_state_stats");
+    } catch (error) {
+      console.error("Failed to get checkpoint state stats:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Drops checkpoint managers idle for at least `idleSecs`; returns the evicted session IDs
+   */
+  async evictIdleManagers(idleSecs: number): Promise<string[]> {
+    try {
+      return await invoke<string[]>("evict_idle_managers", { idleSecs });
+    } catch (error) {
+      console.error("Failed to evict idle checkpoint managers:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Tracks a batch of messages for a session for checkpointing
+   */
+  trackSessionMessages: (
+    sessionId: string,
+    projectId: string,
+    projectPath: string,
+    messages: string[]
+  ): Promise<void> =>
+    invoke("track_session_messages", { sessionId, projectId, projectPath, messages }),
+
+  /**
+   * Syncs checkpoint tracking with the session file on disk; returns the number of new messages
+   */
+  async syncCheckpointTrackingFromFile(
+    sessionId: string,
+    projectId: string,
+    projectPath: string
+  ): Promise<number> {
+    try {
+      return await invoke<number>("sync_checkpoint_tracking_from_file", {
+        sessionId,
+        projectId,
+        projectPath
+      });
+    } catch (error) {
+      console.error("Failed to sync checkpoint tracking from file:", error);
       throw error;
     }
   },
 
-  /**
-   * Tracks a batch of messages for a session for checkpointing
-   */
-  trackSessionMessages: (
-    sessionId: string,
-    projectId: string,
-    projectPath: string,
-    messages: string[]
-  ): Promise<void> =>
-    invoke("track_session_messages", { sessionId, projectId, projectPath, messages }),
-
   /**
    * Adds a new MCP server
    */
@@ -1881,6 +3800,19 @@ export const api = {
     }
   },
 
+  /**
+   * Re-runs Claude binary discovery, e.g. after installing or updating Claude Code
+   * @returns Promise resolving to the newly resolved binary path
+   */
+  async refreshClaudeBinaryCache(): Promise<string> {
+    try {
+      return await invoke<string>("refresh_claude_binary_cache");
+    } catch (error) {
+      console.error("Failed to refresh Claude binary cache:", error);
+      throw error;
+    }
+  },
+
   // Storage API methods
 
   /**
@@ -1896,6 +3828,20 @@ export const api = {
     }
   },
 
+  /**
+   * Describes the columns of a table
+   * @param tableName - Name of the table
+   * @returns Promise resolving to each column's name, declared type, nullability, primary-key flag and default
+   */
+  async storageDescribeTable(tableName: string): Promise<any[]> {
+    try {
+      return await invoke<any[]>("storage_describe_table", { tableName });
+    } catch (error) {
+      console.error("Failed to describe table:", error);
+      throw error;
+    }
+  },
+
   /**
    * Reads table data with pagination
    * @param tableName - Name of the table to read
@@ -1923,6 +3869,30 @@ export const api = {
     }
   },
 
+  /**
+   * Reads the page of rows following a rowid cursor, ordered by rowid
+   * @param tableName - Name of the table to read
+   * @param afterRowid - Cursor returned by the previous page, or null for the first page
+   * @param limit - Maximum number of rows to return (capped at 1000)
+   * @returns Promise resolving to the page, its next cursor and the total row count
+   */
+  async storageReadTablePage(
+    tableName: string,
+    afterRowid: number | null,
+    limit: number
+  ): Promise<TablePage> {
+    try {
+      return await invoke<TablePage>("storage_read_table_page", {
+        tableName,
+        afterRowid,
+        limit,
+      });
+    } catch (error) {
+      console.error("Failed to read table page:", error);
+      throw error;
+    }
+  },
+
   /**
    * Updates a row in a table
    * @param tableName - Name of the table
@@ -1990,7 +3960,21 @@ export const api = {
   },
 
   /**
-   * Executes a raw SQL query
+   * Applies several row edits in a single transaction; nothing is applied if any edit fails
+   * @param operations - Inserts, updates and deletes to apply in order
+   * @returns Promise resolving to one result per operation
+   */
+  async storageExecuteBatch(operations: StorageOp[]): Promise<StorageOpResult[]> {
+    try {
+      return await invoke<StorageOpResult[]>("storage_execute_batch", { operations });
+    } catch (error) {
+      console.error("Failed to execute storage batch:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Executes a raw SQL query. Prefer storageExecuteSqlParams when the query includes user input.
    * @param query - SQL query string
    * @returns Promise resolving to query result
    */
@@ -2004,18 +3988,79 @@ export const api = {
   },
 
   /**
-   * Resets the entire database
-   * @returns Promise resolving when the database is reset
+   * Executes a SQL query with `?` placeholders bound to the given values
+   * @param query - SQL query string with `?` placeholders
+   * @param params - Values for the placeholders (string, number, boolean or null)
+   * @returns Promise resolving to query result
+   */
+  async storageExecuteSqlParams(
+    query: string,
+    params: Array<string | number | boolean | null>
+  ): Promise<any> {
+    try {
+      return await invoke<any>("storage_execute_sql_params", { query, params });
+    } catch (error) {
+      console.error("Failed to execute SQL:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Resets the entire database after backing it up
+   * @param confirm - Must be "RESET"
+   * @returns Promise resolving to the path of the backup taken before the reset
    */
-  async storageResetDatabase(): Promise<void> {
+  async storageResetDatabase(confirm: string): Promise<string> {
     try {
-      return await invoke<void>("storage_reset_database");
+      return await invoke<string>("storage_reset_database", { confirm });
     } catch (error) {
       console.error("Failed to reset database:", error);
       throw error;
     }
   },
 
+  /**
+   * Replaces the database with a backup file, backing up the current database first
+   * @param path - Path of the backup .db file
+   * @returns Promise resolving to the path of the backup of the replaced database
+   */
+  async restoreDatabaseFromBackup(path: string): Promise<string> {
+    try {
+      return await invoke<string>("restore_database_from_backup", { path });
+    } catch (error) {
+      console.error("Failed to restore database from backup:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Reopens the agents database connection, re-running migrations and reapplying the
+   * settings-derived state. Use after an internal error left the connection unusable or
+   * after the database file was replaced outside the app.
+   * @returns Promise resolving when the new connection is in place
+   */
+  async resetDbConnection(): Promise<void> {
+    try {
+      await invoke("reset_db_connection");
+    } catch (error) {
+      console.error("Failed to reset database connection:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Returns the startup database recovery, if one happened; only the first call gets it
+   * @returns Promise resolving to the recovery details or null
+   */
+  async takeDatabaseRecoveryNotice(): Promise<DatabaseRecovery | null> {
+    try {
+      return await invoke<DatabaseRecovery | null>("take_database_recovery_notice");
+    } catch (error) {
+      console.error("Failed to get database recovery notice:", error);
+      throw error;
+    }
+  },
+
   // Theme settings helpers
 
   /**
@@ -2133,6 +4178,21 @@ export const api = {
 
   // Slash Commands API methods
 
+  /**
+   * Lists built-in, user and project slash commands merged and deduplicated by name.
+   * Project commands take precedence over user commands, which take precedence over built-ins.
+   * @param projectPath - Optional project path to include project-specific commands
+   * @returns Promise resolving to the merged command list
+   */
+  async listAllSlashCommands(projectPath?: string): Promise<SlashCommand[]> {
+    try {
+      return await invoke<SlashCommand[]>("list_all_slash_commands", { projectPath });
+    } catch (error) {
+      console.error("Failed to list all slash commands:", error);
+      throw error;
+    }
+  },
+
   /**
    * Lists all available slash commands
    * @param projectPath - Optional project path to include project-specific commands
@@ -2147,6 +4207,51 @@ export const api = {
     }
   },
 
+  /**
+   * Lists slash command files in a GitHub repository
+   * @param repo - Repository in "owner/repo" form
+   * @param path - Directory inside the repository (defaults to ".claude/commands")
+   * @returns Promise resolving to the command files found
+   */
+  async fetchGitHubSlashCommands(repo: string, path?: string): Promise<GitHubSlashCommandFile[]> {
+    try {
+      return await invoke<GitHubSlashCommandFile[]>("fetch_github_slash_commands", { repo, path });
+    } catch (error) {
+      console.error("Failed to fetch GitHub slash commands:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Imports a slash command file from a GitHub repository
+   * @param repo - Repository in "owner/repo" form
+   * @param path - Path of the .md file inside the repository
+   * @param scope - Command scope: "project" or "user"
+   * @param basePath - Commands directory the namespace is derived from (defaults to ".claude/commands")
+   * @param projectPath - Required for project scope commands
+   * @returns Promise resolving to the imported command
+   */
+  async importSlashCommandFromGitHub(
+    repo: string,
+    path: string,
+    scope: string,
+    basePath?: string,
+    projectPath?: string
+  ): Promise<SlashCommand> {
+    try {
+      return await invoke<SlashCommand>("import_slash_command_from_github", {
+        repo,
+        path,
+        scope,
+        basePath,
+        projectPath
+      });
+    } catch (error) {
+      console.error("Failed to import slash command from GitHub:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets a single slash command by ID
    * @param commandId - Unique identifier of the command
@@ -2164,7 +4269,7 @@ export const api = {
   /**
    * Creates or updates a slash command
    * @param scope - Command scope: "project" or "user"
-   * @param name - Command name (without prefix)
+   * @param name - Command name (without prefix); may be namespaced, e.g. "frontend:component"
    * @param namespace - Optional namespace for organization
    * @param content - Markdown content of the command
    * @param description - Optional description
@@ -2431,6 +4536,48 @@ export const api = {
     }
   },
 
+  /**
+   * Reveals the full token of a relay station (list/get responses only carry a masked token)
+   * @param id - The relay station ID
+   * @returns Promise resolving to the clear token
+   */
+  async relayStationRevealToken(id: string): Promise<string> {
+    try {
+      return await invoke<string>("relay_station_reveal_token", { id });
+    } catch (error) {
+      console.error("Failed to reveal relay station token:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets whether relay station tokens are stored in the OS keychain
+   * @returns Promise resolving to true when keychain storage is enabled
+   */
+  async relayStationGetTokenKeychain(): Promise<boolean> {
+    try {
+      return await invoke<boolean>("relay_station_get_token_keychain");
+    } catch (error) {
+      console.error("Failed to get relay station keychain setting:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Enables or disables storing relay station tokens in the OS keychain. Enabling moves
+   * existing plaintext tokens into the keychain; disabling only affects tokens saved later.
+   * @param enabled - Whether to store tokens in the OS keychain
+   * @returns Promise resolving to the number of tokens moved into the keychain
+   */
+  async relayStationSetTokenKeychain(enabled: boolean): Promise<number> {
+    try {
+      return await invoke<number>("relay_station_set_token_keychain", { enabled });
+    } catch (error) {
+      console.error("Failed to set relay station keychain setting:", error);
+      throw error;
+    }
+  },
+
   /**
    * Creates a new relay station
    * @param request - The relay station creation request
@@ -2477,11 +4624,12 @@ export const api = {
    * Toggles relay station enable status (ensures only one station is enabled)
    * @param id - The relay station ID
    * @param enabled - Whether to enable or disable the station
-   * @returns Promise resolving to success message
+   * @param force - Apply the station even if its connection test fails
+   * @returns Promise resolving to whether the change was applied, with the connection test result
    */
-  async relayStationToggleEnable(id: string, enabled: boolean): Promise<string> {
+  async relayStationToggleEnable(id: string, enabled: boolean, force?: boolean): Promise<RelayToggleResult> {
     try {
-      return await invoke<string>("relay_station_toggle_enable", { id, enabled });
+      return await invoke<RelayToggleResult>("relay_station_toggle_enable", { id, enabled, force });
     } catch (error) {
       console.error("Failed to toggle relay station enable status:", error);
       throw error;
@@ -2514,6 +4662,33 @@ export const api = {
     }
   },
 
+  /**
+   * Lists Claude config backups (the pristine source backup first, then newest first)
+   * @returns Promise resolving to backup summaries
+   */
+  async listClaudeConfigBackups(): Promise<ClaudeConfigBackup[]> {
+    try {
+      return await invoke<ClaudeConfigBackup[]>("list_claude_config_backups");
+    } catch (error) {
+      console.error("Failed to list config backups:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Restores Claude config from a specific backup
+   * @param timestamp - Backup identifier from listClaudeConfigBackups ("source" for the pre-relay config)
+   * @returns Promise resolving to restore result message
+   */
+  async restoreClaudeConfigAt(timestamp: string): Promise<string> {
+    try {
+      return await invoke<string>("restore_claude_config_at", { timestamp });
+    } catch (error) {
+      console.error("Failed to restore config backup:", error);
+      throw error;
+    }
+  },
+
   /**
    * Flush system DNS cache
    * @returns Promise resolving to success message
@@ -2527,6 +4702,87 @@ export const api = {
     }
   },
 
+  /**
+   * Gets the directory containing the app's log files
+   * @returns Promise resolving to the log directory path
+   */
+  async getLogDirPath(): Promise<string> {
+    try {
+      return await invoke<string>("get_log_dir_path");
+    } catch (error) {
+      console.error("Failed to get log directory:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Reads the last lines of the current app log file
+   * @param lines - Number of lines (defaults to 200, capped at 5000)
+   * @returns Promise resolving to the log tail
+   */
+  async readRecentLogs(lines?: number): Promise<string> {
+    try {
+      return await invoke<string>("read_recent_logs", { lines });
+    } catch (error) {
+      console.error("Failed to read recent logs:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Opens the log directory in the system file manager
+   */
+  async revealLogDir(): Promise<void> {
+    try {
+      return await invoke<void>("reveal_log_dir");
+    } catch (error) {
+      console.error("Failed to open log directory:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Opens a directory in the user's editor (the given editor, the `editor_command` setting,
+   * VS Code, or the OS default, in that order)
+   * @param path - Directory to open, e.g. a project path
+   * @param editor - Optional editor command or full path overriding the preference
+   */
+  async openInEditor(path: string, editor?: string): Promise<void> {
+    try {
+      return await invoke<void>("open_in_editor", { path, editor });
+    } catch (error) {
+      console.error("Failed to open in editor:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Writes a zip with redacted version, routing, proxy and log information for bug reports
+   * @param outputDir - Target directory (defaults to the Downloads folder)
+   * @returns Promise resolving to the path of the zip file
+   */
+  async exportDiagnostics(outputDir?: string): Promise<string> {
+    try {
+      return await invoke<string>("export_diagnostics", { outputDir });
+    } catch (error) {
+      console.error("Failed to export diagnostics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Checks the Claude binary, config directory, databases, CCR and proxy
+   * @returns Promise resolving to per-check pass/warn/fail results
+   */
+  async runSelfTest(): Promise<SelfTestReport> {
+    try {
+      return await invoke<SelfTestReport>("run_self_test");
+    } catch (error) {
+      console.error("Failed to run self-test:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets current API config from Claude settings
    * @returns Promise resolving to current config info
@@ -2540,6 +4796,32 @@ export const api = {
     }
   },
 
+  /**
+   * Detects how Claude traffic is currently routed (CCR, relay station or direct)
+   * @returns Promise resolving to the active routing status
+   */
+  async getActiveRouting(): Promise<RoutingStatus> {
+    try {
+      return await invoke<RoutingStatus>("get_active_routing");
+    } catch (error) {
+      console.error("Failed to get active routing:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Checks whether CCR and a relay station are both trying to control the Claude config
+   * @returns Promise resolving to the conflict status
+   */
+  async checkRoutingConflict(): Promise<RoutingConflict> {
+    try {
+      return await invoke<RoutingConflict>("check_routing_conflict");
+    } catch (error) {
+      console.error("Failed to check routing conflict:", error);
+      throw error;
+    }
+  },
+
   /**
    * Exports all relay stations configuration
    * @returns Promise resolving to array of relay stations
@@ -2603,15 +4885,56 @@ export const api = {
   },
 
   /**
-   * Tests relay station connection
-   * @param stationId - The relay station ID
-   * @returns Promise resolving to connection test.md result
+   * Tests relay station connection
+   * @param stationId - The relay station ID
+   * @returns Promise resolving to connection test.md result
+   */
+  async relayStationTestConnection(stationId: string): Promise<ConnectionTestResult> {
+    try {
+      return await invoke<ConnectionTestResult>("relay_station_test_connection", { stationId });
+    } catch (error) {
+      console.error("Failed to test.md connection:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Checks which of the required models a relay station offers
+   * @param stationId - The relay station ID
+   * @param requiredModels - Model names that must be available
+   * @returns Promise resolving to the present/missing models
+   */
+  async relayStationTestModels(stationId: string, requiredModels: string[]): Promise<ModelAvailabilityResult> {
+    try {
+      return await invoke<ModelAvailabilityResult>("relay_station_test_models", { stationId, requiredModels });
+    } catch (error) {
+      console.error("Failed to test relay station models:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the global default timeouts for relay station requests
+   * @returns Promise resolving to the timeout settings (unset values use built-in defaults)
+   */
+  async relayGetTimeoutSettings(): Promise<RelayTimeoutSettings> {
+    try {
+      return await invoke<RelayTimeoutSettings>("relay_get_timeout_settings");
+    } catch (error) {
+      console.error("Failed to get relay timeout settings:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Saves the global default timeouts for relay station requests
+   * @param settings - Total and connect timeouts in milliseconds; empty values clear the setting
    */
-  async relayStationTestConnection(stationId: string): Promise<ConnectionTestResult> {
+  async relaySetTimeoutSettings(settings: RelayTimeoutSettings): Promise<void> {
     try {
-      return await invoke<ConnectionTestResult>("relay_station_test_connection", { stationId });
+      return await invoke<void>("relay_set_timeout_settings", { settings });
     } catch (error) {
-      console.error("Failed to test.md connection:", error);
+      console.error("Failed to save relay timeout settings:", error);
       throw error;
     }
   },
@@ -2771,6 +5094,33 @@ export const api = {
     }
   },
 
+  /**
+   * Gets all PackyCode nodes with their last measured latency and active/pinned state
+   * @returns Promise resolving to the node status list (does not run a speed test)
+   */
+  async getPackycodeNodesWithStatus(): Promise<PackycodeNodeStatus[]> {
+    try {
+      return await invoke<PackycodeNodeStatus[]>("get_packycode_nodes_with_status");
+    } catch (error) {
+      console.error("Failed to get PackyCode node status:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Pins a PackyCode node and applies it to the enabled PackyCode station, or clears the pin
+   * @param nodeId - The node ID to pin, or null to re-enable auto-selection
+   * @returns Promise resolving to the pinned node, or null when auto-selection was restored
+   */
+  async setPackycodeNodeOverride(nodeId: string | null): Promise<PackycodeNode | null> {
+    try {
+      return await invoke<PackycodeNode | null>("set_packycode_node_override", { nodeId });
+    } catch (error) {
+      console.error("Failed to set PackyCode node override:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets PackyCode user quota information
    * @param stationId - The relay station ID
@@ -2785,17 +5135,62 @@ export const api = {
     }
   },
 
+  /**
+   * Checks PackyCode daily/monthly budgets and emits `budget-warning` events above the threshold
+   * @param stationId - The relay station ID
+   * @returns Promise resolving to the budgets that crossed the threshold
+   */
+  async checkPackycodeBudget(stationId: string): Promise<PackycodeBudgetWarning[]> {
+    try {
+      return await invoke<PackycodeBudgetWarning[]>("check_packycode_budget", { stationId });
+    } catch (error) {
+      console.error("Failed to check PackyCode budget:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the PackyCode budget warning threshold
+   * @returns Promise resolving to the spent/budget fraction that triggers a warning (0-1)
+   */
+  async getPackycodeBudgetThreshold(): Promise<number> {
+    try {
+      return await invoke<number>("get_packycode_budget_threshold");
+    } catch (error) {
+      console.error("Failed to get PackyCode budget threshold:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets the PackyCode budget warning threshold
+   * @param threshold - Spent/budget fraction that triggers a warning, e.g. 0.8
+   */
+  async setPackycodeBudgetThreshold(threshold: number): Promise<void> {
+    try {
+      return await invoke<void>("set_packycode_budget_threshold", { threshold });
+    } catch (error) {
+      console.error("Failed to set PackyCode budget threshold:", error);
+      throw error;
+    }
+  },
+
   // ============= File System Watching =============
 
   /**
    * Starts watching a directory for file system changes
    * @param directoryPath - The directory path to watch
    * @param recursive - Whether to watch subdirectories recursively
-   * @returns Promise resolving when watching starts
+   * @param check - Test native events first and poll the path if they don't fire
+   * @returns The watching mode in use
    */
-  async watchDirectory(directoryPath: string, recursive: boolean = true): Promise<void> {
+  async watchDirectory(
+    directoryPath: string,
+    recursive: boolean = true,
+    check: boolean = false
+  ): Promise<WatchMode> {
     try {
-      return await invoke<void>("watch_directory", { path: directoryPath, recursive });
+      return await invoke<WatchMode>("watch_directory", { path: directoryPath, recursive, check });
     } catch (error) {
       console.error("Failed to watch directory:", error);
       throw error;
@@ -2816,16 +5211,83 @@ export const api = {
     }
   },
 
+  /**
+   * Sets how long file change events are coalesced per path before being emitted
+   * @param debounceMs - Debounce window in milliseconds (10-5000)
+   */
+  async setFileWatcherDebounce(debounceMs: number): Promise<void> {
+    try {
+      return await invoke<void>("set_file_watcher_debounce", { debounceMs });
+    } catch (error) {
+      console.error("Failed to set file watcher debounce:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the file change debounce window in milliseconds
+   */
+  async getFileWatcherDebounce(): Promise<number> {
+    try {
+      return await invoke<number>("get_file_watcher_debounce");
+    } catch (error) {
+      console.error("Failed to get file watcher debounce:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Forces polling-based file watching (for NFS, WSL and other filesystems without native
+   * events) and rebuilds existing watches; native failures fall back to polling regardless
+   * @param force - Whether to always poll
+   * @param intervalMs - Poll interval in milliseconds (250-60000)
+   */
+  async setFileWatcherPolling(force: boolean, intervalMs?: number): Promise<WatcherModeStatus> {
+    try {
+      return await invoke<WatcherModeStatus>("set_file_watcher_polling", { force, intervalMs });
+    } catch (error) {
+      console.error("Failed to set file watcher polling:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets whether file watching uses native events or polling, per watched path
+   */
+  async getFileWatcherMode(): Promise<WatcherModeStatus> {
+    try {
+      return await invoke<WatcherModeStatus>("get_file_watcher_mode");
+    } catch (error) {
+      console.error("Failed to get file watcher mode:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Tests whether native file events fire for a path by briefly watching it and writing
+   * and removing a marker file; recommends polling when they don't
+   * @param path - Directory (or file, whose directory is tested) to check
+   */
+  async canWatchPath(path: string): Promise<WatchCapability> {
+    try {
+      return await invoke<WatchCapability>("can_watch_path", { path });
+    } catch (error) {
+      console.error("Failed to check watch capability:", error);
+      throw error;
+    }
+  },
+
   // ============= Claude Project Directory Watching =============
 
   /**
    * Starts watching Claude project directory for the given project path
    * @param projectPath - The project path to find the corresponding Claude directory
-   * @returns Promise resolving when watching starts
+   * @param check - Test native events first and poll the directory if they don't fire
+   * @returns The watching mode in use
    */
-  async watchClaudeProjectDirectory(projectPath: string): Promise<void> {
+  async watchClaudeProjectDirectory(projectPath: string, check: boolean = false): Promise<WatchMode> {
     try {
-      return await invoke<void>("watch_claude_project_directory", { projectPath });
+      return await invoke<WatchMode>("watch_claude_project_directory", { projectPath, check });
     } catch (error) {
       console.error("Failed to watch Claude project directory:", error);
       throw error;
@@ -2846,6 +5308,59 @@ export const api = {
     }
   },
 
+  /**
+   * Watches the session directories of several projects; each call takes a reference
+   * that must be released with `unwatchProjects`
+   * @param projectIds - Encoded project directory names
+   * @returns All currently watched paths
+   */
+  async watchProjects(projectIds: string[]): Promise<WatchedPath[]> {
+    try {
+      return await invoke<WatchedPath[]>("watch_projects", { projectIds });
+    } catch (error) {
+      console.error("Failed to watch projects:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Releases references taken by `watchProjects`
+   * @param projectIds - Encoded project directory names
+   */
+  async unwatchProjects(projectIds: string[]): Promise<void> {
+    try {
+      return await invoke<void>("unwatch_projects", { projectIds });
+    } catch (error) {
+      console.error("Failed to unwatch projects:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists watched paths with the project each maps to and how many users hold it
+   */
+  async getWatchedPaths(): Promise<WatchedPath[]> {
+    try {
+      return await invoke<WatchedPath[]>("get_watched_paths");
+    } catch (error) {
+      console.error("Failed to get watched paths:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Stops every watch regardless of reference counts
+   * @returns Number of paths that stopped being watched
+   */
+  async unwatchAll(): Promise<number> {
+    try {
+      return await invoke<number>("unwatch_all");
+    } catch (error) {
+      console.error("Failed to unwatch all paths:", error);
+      throw error;
+    }
+  },
+
   // ============= Terminal API =============
 
   /**
@@ -3066,6 +5581,154 @@ export const api = {
       console.error("Failed to cleanup old smart sessions:", error);
       throw error;
     }
+  },
+
+  /**
+   * Gets ahead/behind counts versus the upstream branch, the remote URL and whether the
+   * working tree is dirty
+   * @param repoPath - The repository directory
+   */
+  async getGitRemoteStatus(repoPath: string): Promise<GitRemoteStatus> {
+    try {
+      return await invoke<GitRemoteStatus>("get_git_remote_status", { repoPath });
+    } catch (error) {
+      console.error("Failed to get git remote status:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Stages paths in a git repository, including deletions
+   * @param repoPath - The repository directory
+   * @param paths - Paths to stage, relative to the repository
+   */
+  async gitStage(repoPath: string, paths: string[]): Promise<void> {
+    try {
+      return await invoke<void>("git_stage", { repoPath, paths });
+    } catch (error) {
+      console.error("Failed to stage files:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Unstages paths in a git repository, keeping the working tree changes
+   * @param repoPath - The repository directory
+   * @param paths - Paths to unstage, relative to the repository
+   */
+  async gitUnstage(repoPath: string, paths: string[]): Promise<void> {
+    try {
+      return await invoke<void>("git_unstage", { repoPath, paths });
+    } catch (error) {
+      console.error("Failed to unstage files:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Commits the staged changes. Rejects with a `GitError`.
+   * @param repoPath - The repository directory
+   * @param message - The commit message; may be empty when amending to keep the old one
+   * @param amend - Whether to amend the last commit
+   * @param options - Sign-off and author overrides
+   * @returns Promise resolving to the hash of the new commit
+   */
+  async gitCommit(
+    repoPath: string,
+    message: string,
+    amend: boolean,
+    options?: GitCommitOptions
+  ): Promise<string> {
+    try {
+      return await invoke<string>("git_commit", { repoPath, message, amend, options });
+    } catch (error) {
+      console.error("Failed to commit:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Creates a branch and switches to it. Rejects with a `GitError`.
+   * @param repoPath - The repository directory
+   * @param name - The new branch name
+   * @param from - Optional start point, defaults to HEAD
+   * @returns Promise resolving to the current branch
+   */
+  async gitCreateBranch(repoPath: string, name: string, from?: string): Promise<string> {
+    try {
+      return await invoke<string>("git_create_branch", { repoPath, name, from });
+    } catch (error) {
+      console.error("Failed to create branch:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Checks out a branch or commit. Refuses when there are uncommitted changes unless
+   * `force` discards them or `stash` stashes them first. Rejects with a `GitError`.
+   * @param repoPath - The repository directory
+   * @param reference - The branch or commit to check out
+   * @param force - Discard uncommitted changes
+   * @param stash - Stash uncommitted changes before checking out
+   * @returns Promise resolving to the current branch
+   */
+  async gitCheckout(
+    repoPath: string,
+    reference: string,
+    force: boolean,
+    stash?: boolean
+  ): Promise<string> {
+    try {
+      return await invoke<string>("git_checkout", { repoPath, reference, force, stash });
+    } catch (error) {
+      console.error("Failed to check out:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Diffs the working tree against the commit that was HEAD at a point in time, such as a
+   * checkpoint timestamp. Untracked files created since then are included.
+   * @param repoPath - The repository directory
+   * @param sinceTimestamp - RFC 3339 timestamp
+   * @returns Promise resolving to the per-file diffs
+   */
+  async gitDiffSince(repoPath: string, sinceTimestamp: string): Promise<GitDiffSince> {
+    try {
+      return await invoke<GitDiffSince>("git_diff_since", { repoPath, sinceTimestamp });
+    } catch (error) {
+      console.error("Failed to diff since timestamp:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Creates and switches to a branch for a session, then creates a checkpoint on it
+   * @param projectPath - The project (repository) directory
+   * @param branchName - The new branch name
+   * @param from - Optional start point, defaults to HEAD
+   * @param sessionId - The session to checkpoint
+   * @param projectId - The project ID the session belongs to
+   */
+  async startIsolatedSession(
+    projectPath: string,
+    branchName: string,
+    from: string | undefined,
+    sessionId: string,
+    projectId: string
+  ): Promise<IsolatedSessionStart> {
+    try {
+      return await invoke<IsolatedSessionStart>("start_isolated_session", {
+        projectPath,
+        branchName,
+        from,
+        sessionId,
+        projectId,
+      });
+    } catch (error) {
+      console.error("Failed to start isolated session:", error);
+      throw error;
+    }
   }
 };
 
@@ -3085,6 +5748,18 @@ export interface CcrServiceInfo {
   message: string;
 }
 
+export interface CcrInstallResult {
+  success: boolean;
+  message: string;
+  ccr_path?: string; // CCR path discovered after installing
+}
+
+export interface CcrLogSnapshot {
+  path: string;
+  is_running: boolean;
+  content: string; // Tail of the log, useful to see why the service failed to start
+}
+
 // CCR API methods
 export const ccrApi = {
   /**
@@ -3099,6 +5774,32 @@ export const ccrApi = {
     }
   },
 
+  /**
+   * Clear the cached CCR path and discover it again (after reinstalling or moving CCR)
+   * @returns Promise resolving to the discovered path, or null if CCR was not found
+   */
+  async refreshPath(): Promise<string | null> {
+    try {
+      return await invoke<string | null>("refresh_ccr_path");
+    } catch (error) {
+      console.error("Failed to refresh CCR path:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Install CCR globally with npm; install output is emitted on the `ccr-install-output` event
+   * @param confirmed - Must be true, the user has to confirm the global install first
+   */
+  async install(confirmed: boolean): Promise<CcrInstallResult> {
+    try {
+      return await invoke<CcrInstallResult>("install_ccr", { confirmed });
+    } catch (error) {
+      console.error("Failed to install CCR:", error);
+      throw error;
+    }
+  },
+
   /**
    * Get CCR version
    */
@@ -3125,10 +5826,11 @@ export const ccrApi = {
 
   /**
    * Start CCR service
+   * @param force - Start even if a relay station currently controls the Claude config
    */
-  async startService(): Promise<CcrServiceInfo> {
+  async startService(force?: boolean): Promise<CcrServiceInfo> {
     try {
-      return await invoke<CcrServiceInfo>("start_ccr_service");
+      return await invoke<CcrServiceInfo>("start_ccr_service", { force });
     } catch (error) {
       console.error("Failed to start CCR service:", error);
       throw error;
@@ -3181,6 +5883,70 @@ export const ccrApi = {
       console.error("Failed to get CCR config path:", error);
       throw error;
     }
+  },
+
+  /**
+   * Read CCR config.json (returns an empty skeleton when the file does not exist)
+   */
+  async readConfig(): Promise<Record<string, any>> {
+    try {
+      return await invoke<Record<string, any>>("read_ccr_config");
+    } catch (error) {
+      console.error("Failed to read CCR config:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Validate and save CCR config.json (the previous file is kept as config.json.bak)
+   * @param config - Full CCR config object
+   * @param restart - Restart the running CCR service so the changes take effect
+   */
+  async writeConfig(config: Record<string, any>, restart?: boolean): Promise<string> {
+    try {
+      return await invoke<string>("write_ccr_config", { config, restart });
+    } catch (error) {
+      console.error("Failed to write CCR config:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Get CCR log file path
+   */
+  async getLogPath(): Promise<string> {
+    try {
+      return await invoke<string>("get_ccr_log_path");
+    } catch (error) {
+      console.error("Failed to get CCR log path:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Start following the CCR log; new lines are emitted on the `ccr-log` event
+   * @returns Promise resolving to the current tail of the log
+   */
+  async followLogs(): Promise<CcrLogSnapshot> {
+    try {
+      return await invoke<CcrLogSnapshot>("follow_ccr_logs");
+    } catch (error) {
+      console.error("Failed to follow CCR logs:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Stop following the CCR log
+   * @returns Promise resolving to whether a follower was running
+   */
+  async stopFollowingLogs(): Promise<boolean> {
+    try {
+      return await invoke<boolean>("stop_following_ccr_logs");
+    } catch (error) {
+      console.error("Failed to stop following CCR logs:", error);
+      throw error;
+    }
   }
 };
 